@@ -0,0 +1,166 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Address, BlockId, H256, U256, U64},
+};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// The balance and nonce of an account at a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountState {
+    pub balance: U256,
+    pub nonce: U256,
+}
+
+/// A change of the state of a watched account, emitted by the
+/// [AccountCollector](AccountCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountChange {
+    pub account: Address,
+    /// The block at which the change was observed.
+    pub block_number: U64,
+    /// The state last emitted for the account, `None` the first time it is
+    /// observed.
+    pub previous: Option<AccountState>,
+    pub current: AccountState,
+}
+
+/// A collector that fetches the balance and nonce of a set of accounts at every new
+/// block, and generates a stream of [changes](AccountChange) beyond thresholds, so
+/// strategies and executors can react to funding changes, or to transactions sent
+/// from their own accounts by something else.
+///
+/// Changes are measured against the state last emitted, so changes below the
+/// thresholds accumulate until they cross them. The first observation of every
+/// account is emitted. Accounts that fail to be fetched are skipped until the next
+/// block.
+pub struct AccountCollector<M> {
+    provider: Arc<M>,
+    accounts: Vec<Address>,
+    balance_threshold: U256,
+    nonce_threshold: U256,
+    last: Mutex<HashMap<Address, AccountState>>,
+}
+
+impl<M> AccountCollector<M> {
+    pub fn new(provider: Arc<M>, accounts: Vec<Address>) -> Self {
+        Self {
+            provider,
+            accounts,
+            balance_threshold: U256::zero(),
+            nonce_threshold: U256::one(),
+            last: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Only emits balance changes of at least `threshold` wei, in either direction.
+    /// Defaults to any change.
+    pub fn with_balance_threshold(mut self, threshold: U256) -> Self {
+        self.balance_threshold = threshold;
+        self
+    }
+
+    /// Only emits nonce changes of at least `threshold` transactions. Defaults to 1.
+    pub fn with_nonce_threshold(mut self, threshold: u64) -> Self {
+        self.nonce_threshold = threshold.into();
+        self
+    }
+
+    /// Whether the change from `previous` to `current` crosses a threshold.
+    fn is_significant(&self, previous: &AccountState, current: &AccountState) -> bool {
+        let balance_delta = abs_diff(previous.balance, current.balance);
+        let nonce_delta = abs_diff(previous.nonce, current.nonce);
+        (!balance_delta.is_zero() && balance_delta >= self.balance_threshold)
+            || (!nonce_delta.is_zero() && nonce_delta >= self.nonce_threshold)
+    }
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<M> AccountCollector<M>
+where
+    M: Middleware,
+{
+    /// Returns the state of `account` at the block `block_hash`.
+    async fn state(
+        &self,
+        account: Address,
+        block_hash: H256,
+    ) -> std::result::Result<AccountState, M::Error> {
+        let block = Some(BlockId::Hash(block_hash));
+        let (balance, nonce) = futures::try_join!(
+            self.provider.get_balance(account, block),
+            self.provider.get_transaction_count(account, block),
+        )?;
+        Ok(AccountState { balance, nonce })
+    }
+
+    /// Returns the significant changes of the accounts at a new block.
+    async fn changes(&self, block_hash: H256, block_number: U64) -> Vec<AccountChange> {
+        let states = futures::future::join_all(
+            self.accounts
+                .iter()
+                .map(|account| self.state(*account, block_hash)),
+        )
+        .await;
+
+        let mut last = self.last.lock().unwrap();
+        let mut changes = vec![];
+        for (account, state) in self.accounts.iter().zip(states) {
+            let current = match state {
+                Ok(state) => state,
+                Err(e) => {
+                    warn!("error fetching state of account {:?}: {}", account, e);
+                    continue;
+                }
+            };
+            let previous = last.get(account).copied();
+            if previous.is_some_and(|previous| !self.is_significant(&previous, &current)) {
+                continue;
+            }
+            last.insert(*account, current);
+            changes.push(AccountChange {
+                account: *account,
+                block_number,
+                previous,
+                current,
+            });
+        }
+        changes
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [AccountCollector](AccountCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new blocks.
+#[async_trait]
+impl<M> Collector<AccountChange> for AccountCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, AccountChange>> {
+        let stream = self.provider.subscribe_blocks().await.retryable()?;
+        let stream = stream
+            .then(move |block| async move {
+                match (block.hash, block.number) {
+                    (Some(hash), Some(number)) => self.changes(hash, number).await,
+                    _ => vec![],
+                }
+            })
+            .flat_map(futures::stream::iter);
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,54 @@
+use alloy::providers::{IpcConnect, Provider, ProviderBuilder, RootProvider};
+use async_trait::async_trait;
+use ethers::types::{H256, U64};
+use futures::StreamExt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::collectors::block_collector::NewBlock;
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+
+/// A collector that listens for new blocks over an alloy pubsub provider, and
+/// generates a stream of the same [events](NewBlock) as the [BlockCollector](crate::collectors::block_collector::BlockCollector).
+///
+/// Bots running next to their node should prefer an IPC provider, see
+/// [connect_ipc](AlloyBlockCollector::connect_ipc), since new heads are delivered
+/// with materially lower latency than over a WebSocket.
+pub struct AlloyBlockCollector<P> {
+    provider: Arc<P>,
+}
+
+impl<P> AlloyBlockCollector<P> {
+    pub fn new(provider: Arc<P>) -> Self {
+        Self { provider }
+    }
+}
+
+impl AlloyBlockCollector<RootProvider> {
+    /// Connects to the IPC socket of a node, e.g. `/tmp/reth.ipc`.
+    pub async fn connect_ipc(path: impl Into<PathBuf>) -> Result<Self> {
+        let provider = ProviderBuilder::default()
+            .connect_ipc(IpcConnect::new(path.into()))
+            .await
+            .retryable()?;
+        Ok(Self::new(Arc::new(provider)))
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [AlloyBlockCollector](AlloyBlockCollector).
+/// This implementation subscribes to `newHeads`.
+#[async_trait]
+impl<P> Collector<NewBlock> for AlloyBlockCollector<P>
+where
+    P: Provider + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, NewBlock>> {
+        let subscription = self.provider.subscribe_blocks().await.retryable()?;
+        let stream = subscription.into_stream().map(|header| NewBlock {
+            hash: H256::from(header.hash.0),
+            number: U64::from(header.number),
+        });
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,70 @@
+use alloy::providers::Provider;
+use alloy::rpc::types::Transaction;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+
+/// A collector that listens for new transactions in the public mempool over an
+/// alloy pubsub provider, e.g. a WebSocket one, and generates a stream of
+/// [events](Transaction) which contain the full transaction.
+///
+/// By default, the node is asked to push full transactions. Nodes that only push
+/// transaction hashes can be used with [with_fetched_bodies](AlloyMempoolCollector::with_fetched_bodies),
+/// in which case the collector fetches every transaction by hash. Transactions
+/// that already left the mempool by then are skipped.
+pub struct AlloyMempoolCollector<P> {
+    provider: Arc<P>,
+    /// The maximum number of transactions fetched concurrently, if bodies are
+    /// fetched by hash.
+    fetch_concurrency: Option<usize>,
+}
+
+impl<P> AlloyMempoolCollector<P> {
+    pub fn new(provider: Arc<P>) -> Self {
+        Self {
+            provider,
+            fetch_concurrency: None,
+        }
+    }
+
+    /// Subscribes to transaction hashes only, fetching up to `concurrency`
+    /// transaction bodies at a time. Transactions may be emitted out of order.
+    pub fn with_fetched_bodies(mut self, concurrency: usize) -> Self {
+        self.fetch_concurrency = Some(concurrency.max(1));
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [AlloyMempoolCollector](AlloyMempoolCollector).
+/// This implementation subscribes to `newPendingTransactions`.
+#[async_trait]
+impl<P> Collector<Transaction> for AlloyMempoolCollector<P>
+where
+    P: Provider + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Transaction>> {
+        let Some(concurrency) = self.fetch_concurrency else {
+            let subscription = self
+                .provider
+                .subscribe_full_pending_transactions()
+                .await
+                .retryable()?;
+            return Ok(Box::pin(subscription.into_stream()));
+        };
+
+        let subscription = self
+            .provider
+            .subscribe_pending_transactions()
+            .await
+            .retryable()?;
+        let stream = subscription
+            .into_stream()
+            .map(|hash| self.provider.get_transaction_by_hash(hash))
+            .buffer_unordered(concurrency)
+            .filter_map(|res| async move { res.ok().flatten() });
+        Ok(Box::pin(stream))
+    }
+}
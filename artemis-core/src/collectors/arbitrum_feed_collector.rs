@@ -0,0 +1,170 @@
+use crate::collectors::ws::{ws_stream, WsOptions};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ethers::{types::Transaction, utils::rlp::Rlp};
+use futures::StreamExt;
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+/// The sequencer feed of Arbitrum One.
+pub const ARBITRUM_ONE_FEED_URL: &str = "wss://arb1.arbitrum.io/feed";
+
+/// The kind of L1 message carrying L2 transactions posted by the sequencer.
+const L1_MESSAGE_L2_MESSAGE: u8 = 3;
+
+/// The kinds of L2 messages.
+const L2_MESSAGE_BATCH: u8 = 3;
+const L2_MESSAGE_SIGNED_TX: u8 = 4;
+
+/// How deep batches of L2 messages may be nested.
+const MAX_BATCH_DEPTH: usize = 16;
+
+/// A transaction sequenced by the Arbitrum sequencer, emitted by the
+/// [ArbitrumFeedCollector](ArbitrumFeedCollector) before it lands in a block.
+#[derive(Debug, Clone)]
+pub struct SequencedTransaction {
+    /// The sequence number of the message carrying the transaction, which is also
+    /// the number of the L2 block it lands in, minus the genesis block number.
+    pub sequence_number: u64,
+    /// The L1 block number the sequencer assigned to the message.
+    pub l1_block_number: u64,
+    /// The timestamp the sequencer assigned to the message.
+    pub timestamp: u64,
+    pub tx: Transaction,
+}
+
+/// A message of the sequencer feed.
+#[derive(Deserialize)]
+struct FeedMessage {
+    #[serde(default)]
+    messages: Vec<BroadcastMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastMessage {
+    sequence_number: u64,
+    message: MessageWithMetadata,
+}
+
+#[derive(Deserialize)]
+struct MessageWithMetadata {
+    message: L1IncomingMessage,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct L1IncomingMessage {
+    header: Header,
+    /// The base64 encoded L2 message.
+    l2_msg: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Header {
+    kind: u8,
+    block_number: u64,
+    timestamp: u64,
+}
+
+/// Decodes the signed transactions of an L2 message, recursing into batches.
+fn decode_l2_message(message: &[u8], depth: usize, txs: &mut Vec<Transaction>) {
+    let Some((kind, mut payload)) = message.split_first() else {
+        return;
+    };
+    match *kind {
+        L2_MESSAGE_SIGNED_TX => match Rlp::new(payload).as_val() {
+            Ok(tx) => txs.push(tx),
+            Err(e) => warn!("error decoding sequenced transaction: {}", e),
+        },
+        L2_MESSAGE_BATCH if depth < MAX_BATCH_DEPTH => {
+            // A batch is a sequence of messages, each prefixed with its length as a
+            // big endian u64.
+            while let Some((length, rest)) = payload.split_first_chunk::<8>() {
+                let length = u64::from_be_bytes(*length) as usize;
+                let Some(nested) = rest.get(..length) else {
+                    warn!("truncated batch in sequencer feed");
+                    return;
+                };
+                decode_l2_message(nested, depth + 1, txs);
+                payload = &rest[length..];
+            }
+        }
+        kind => debug!("ignoring L2 message of kind {}", kind),
+    }
+}
+
+impl FeedMessage {
+    fn into_transactions(self) -> Vec<SequencedTransaction> {
+        let mut sequenced = vec![];
+        for broadcast in self.messages {
+            let message = broadcast.message.message;
+            if message.header.kind != L1_MESSAGE_L2_MESSAGE {
+                continue;
+            }
+            let l2_message = match STANDARD.decode(&message.l2_msg) {
+                Ok(l2_message) => l2_message,
+                Err(e) => {
+                    warn!("error decoding L2 message: {}", e);
+                    continue;
+                }
+            };
+            let mut txs = vec![];
+            decode_l2_message(&l2_message, 0, &mut txs);
+            sequenced.extend(txs.into_iter().map(|tx| SequencedTransaction {
+                sequence_number: broadcast.sequence_number,
+                l1_block_number: message.header.block_number,
+                timestamp: message.header.timestamp,
+                tx,
+            }));
+        }
+        sequenced
+    }
+}
+
+/// A collector that subscribes to the feed of the Arbitrum sequencer, and generates
+/// a stream of the [transactions](SequencedTransaction) it sequences, before they
+/// land in blocks. Since the sequencer orders transactions first-come first-served,
+/// this is the lowest latency view of Arbitrum, as Fiber is on L1.
+///
+/// Only the transactions of the sequencer's messages are emitted, not deposits and
+/// other messages from L1. Messages that fail to decode are skipped.
+pub struct ArbitrumFeedCollector {
+    url: String,
+}
+
+impl ArbitrumFeedCollector {
+    /// Creates a collector subscribing to the feed at `url`, e.g. that of a local
+    /// relay, which is recommended over the public feed.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    /// Creates a collector subscribing to the public feed of Arbitrum One.
+    pub fn arbitrum_one() -> Self {
+        Self::new(ARBITRUM_ONE_FEED_URL)
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [ArbitrumFeedCollector](ArbitrumFeedCollector).
+/// The stream ends when the websocket closes.
+#[async_trait]
+impl Collector<SequencedTransaction> for ArbitrumFeedCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, SequencedTransaction>> {
+        let stream = ws_stream(self.url.as_str(), WsOptions::default()).await?;
+        let stream = stream.flat_map(|message| {
+            let txs = match serde_json::from_str::<FeedMessage>(&message) {
+                Ok(message) => message.into_transactions(),
+                Err(e) => {
+                    warn!("error parsing sequencer feed message: {}", e);
+                    vec![]
+                }
+            };
+            futures::stream::iter(txs)
+        });
+        Ok(Box::pin(stream))
+    }
+}
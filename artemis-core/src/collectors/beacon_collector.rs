@@ -0,0 +1,201 @@
+use crate::collectors::sse::{sse_stream, SseEvent};
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::types::{Address, H256};
+use reqwest::Client;
+use serde::{de, Deserialize, Deserializer};
+use tokio_stream::StreamExt;
+use tracing::warn;
+
+/// A topic of the beacon node event stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BeaconTopic {
+    Head,
+    FinalizedCheckpoint,
+    ChainReorg,
+    PayloadAttributes,
+}
+
+impl BeaconTopic {
+    /// Every topic supported by the [BeaconEventCollector](BeaconEventCollector).
+    pub const ALL: [BeaconTopic; 4] = [
+        BeaconTopic::Head,
+        BeaconTopic::FinalizedCheckpoint,
+        BeaconTopic::ChainReorg,
+        BeaconTopic::PayloadAttributes,
+    ];
+
+    /// Returns the name of the topic in the beacon API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BeaconTopic::Head => "head",
+            BeaconTopic::FinalizedCheckpoint => "finalized_checkpoint",
+            BeaconTopic::ChainReorg => "chain_reorg",
+            BeaconTopic::PayloadAttributes => "payload_attributes",
+        }
+    }
+}
+
+/// A consensus layer event, emitted by the [BeaconEventCollector](BeaconEventCollector).
+#[derive(Debug, Clone)]
+pub enum BeaconEvent {
+    Head(HeadEvent),
+    FinalizedCheckpoint(FinalizedCheckpointEvent),
+    ChainReorg(ChainReorgEvent),
+    PayloadAttributes(PayloadAttributesEvent),
+}
+
+/// A new head of the beacon chain, sent when the node imports a block at the
+/// head of its canonical chain.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeadEvent {
+    #[serde(deserialize_with = "quoted")]
+    pub slot: u64,
+    pub block: H256,
+    pub state: H256,
+    pub epoch_transition: bool,
+    #[serde(default)]
+    pub execution_optimistic: bool,
+}
+
+/// A newly finalized checkpoint.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FinalizedCheckpointEvent {
+    pub block: H256,
+    pub state: H256,
+    #[serde(deserialize_with = "quoted")]
+    pub epoch: u64,
+    #[serde(default)]
+    pub execution_optimistic: bool,
+}
+
+/// A reorg of the beacon chain, replacing the head `old_head_block` with
+/// `new_head_block`, `depth` slots deep.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainReorgEvent {
+    #[serde(deserialize_with = "quoted")]
+    pub slot: u64,
+    #[serde(deserialize_with = "quoted")]
+    pub depth: u64,
+    pub old_head_block: H256,
+    pub new_head_block: H256,
+    pub old_head_state: H256,
+    pub new_head_state: H256,
+    #[serde(deserialize_with = "quoted")]
+    pub epoch: u64,
+    #[serde(default)]
+    pub execution_optimistic: bool,
+}
+
+/// The attributes of the next execution payload, sent once the proposer of the
+/// next slot is known, which is when block builders start building.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayloadAttributesEvent {
+    /// The fork of the payload, e.g. `deneb`.
+    pub version: String,
+    pub data: PayloadAttributesData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayloadAttributesData {
+    #[serde(deserialize_with = "quoted")]
+    pub proposal_slot: u64,
+    pub parent_block_root: H256,
+    #[serde(deserialize_with = "quoted")]
+    pub parent_block_number: u64,
+    pub parent_block_hash: H256,
+    #[serde(deserialize_with = "quoted")]
+    pub proposer_index: u64,
+    pub payload_attributes: PayloadAttributes,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayloadAttributes {
+    #[serde(deserialize_with = "quoted")]
+    pub timestamp: u64,
+    pub prev_randao: H256,
+    pub suggested_fee_recipient: Address,
+}
+
+/// Deserializes an integer encoded as a decimal string, as in the beacon API.
+pub(crate) fn quoted<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<u64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+impl BeaconEvent {
+    fn parse(event: &SseEvent) -> serde_json::Result<Option<Self>> {
+        let event = match event.event.as_str() {
+            "head" => Self::Head(serde_json::from_str(&event.data)?),
+            "finalized_checkpoint" => Self::FinalizedCheckpoint(serde_json::from_str(&event.data)?),
+            "chain_reorg" => Self::ChainReorg(serde_json::from_str(&event.data)?),
+            "payload_attributes" => Self::PayloadAttributes(serde_json::from_str(&event.data)?),
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+}
+
+/// A collector that subscribes to the event stream of a beacon node, and generates
+/// a stream of typed consensus layer [events](BeaconEvent), e.g. so strategies can
+/// time their actions within a slot.
+pub struct BeaconEventCollector {
+    client: Client,
+    beacon_url: String,
+    topics: Vec<BeaconTopic>,
+}
+
+impl BeaconEventCollector {
+    /// Creates a collector subscribing to every topic of the beacon node at
+    /// `beacon_url`, e.g. `http://localhost:5052`.
+    pub fn new(beacon_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            beacon_url: beacon_url.into(),
+            topics: BeaconTopic::ALL.to_vec(),
+        }
+    }
+
+    /// Only subscribes to the given topics.
+    pub fn with_topics(mut self, topics: impl IntoIterator<Item = BeaconTopic>) -> Self {
+        self.topics = topics.into_iter().collect();
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [BeaconEventCollector](BeaconEventCollector).
+/// This implementation streams from the `/eth/v1/events` endpoint. Events that fail
+/// to parse are skipped.
+#[async_trait]
+impl Collector<BeaconEvent> for BeaconEventCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, BeaconEvent>> {
+        let topics: Vec<_> = self.topics.iter().map(BeaconTopic::as_str).collect();
+        let url = format!(
+            "{}/eth/v1/events?topics={}",
+            self.beacon_url.trim_end_matches('/'),
+            topics.join(",")
+        );
+        let response = self
+            .client
+            .get(url)
+            .header("Accept", "text/event-stream")
+            .send()
+            .await
+            .retryable()?
+            .error_for_status()
+            .retryable()?;
+        let stream =
+            sse_stream(response.bytes_stream()).filter_map(|event| {
+                match BeaconEvent::parse(&event) {
+                    Ok(event) => event,
+                    Err(e) => {
+                        warn!("error parsing beacon {} event: {}", event.event, e);
+                        None
+                    }
+                }
+            });
+        Ok(Box::pin(stream))
+    }
+}
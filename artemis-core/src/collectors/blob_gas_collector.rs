@@ -0,0 +1,155 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Block, H256, U256, U64},
+};
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+/// The blob gas used by a blob.
+pub const GAS_PER_BLOB: u64 = 131_072;
+
+/// The minimum blob base fee, in wei.
+const MIN_BLOB_BASE_FEE: u64 = 1;
+
+/// The blob parameters of a fork, which set the target and maximum number of blobs
+/// per block, and how fast the blob base fee moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobSchedule {
+    pub target_blobs: u64,
+    pub max_blobs: u64,
+    pub update_fraction: u64,
+}
+
+impl BlobSchedule {
+    /// The blob parameters introduced by Cancun.
+    pub const CANCUN: Self = Self {
+        target_blobs: 3,
+        max_blobs: 6,
+        update_fraction: 3_338_477,
+    };
+
+    /// The blob parameters introduced by Prague.
+    pub const PRAGUE: Self = Self {
+        target_blobs: 6,
+        max_blobs: 9,
+        update_fraction: 5_007_716,
+    };
+
+    /// Returns the blob base fee of a block with the given excess blob gas.
+    pub fn blob_base_fee(&self, excess_blob_gas: U256) -> U256 {
+        fake_exponential(
+            U256::from(MIN_BLOB_BASE_FEE),
+            excess_blob_gas,
+            U256::from(self.update_fraction),
+        )
+    }
+
+    /// Returns the excess blob gas of the block following one with the given
+    /// excess blob gas and blob gas used.
+    pub fn next_excess_blob_gas(&self, excess_blob_gas: U256, blob_gas_used: U256) -> U256 {
+        (excess_blob_gas + blob_gas_used)
+            .saturating_sub(U256::from(self.target_blobs * GAS_PER_BLOB))
+    }
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using a Taylor expansion,
+/// as defined by EIP-4844.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let mut i = U256::one();
+    let mut output = U256::zero();
+    let mut accum = factor * denominator;
+    while !accum.is_zero() {
+        output = output.saturating_add(accum);
+        accum = accum.saturating_mul(numerator) / (denominator * i);
+        i += U256::one();
+    }
+    output / denominator
+}
+
+/// The blob gas market at a new block, emitted by the [BlobGasCollector](BlobGasCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobGasEvent {
+    pub block_number: U64,
+    pub block_hash: H256,
+    /// The number of blobs included in the block.
+    pub blobs: u64,
+    /// The target number of blobs per block.
+    pub target_blobs: u64,
+    /// The maximum number of blobs per block.
+    pub max_blobs: u64,
+    pub blob_gas_used: U256,
+    pub excess_blob_gas: U256,
+    /// The blob base fee of the block.
+    pub blob_base_fee: U256,
+    /// The blob base fee of the next block, derived from the blobs of this one.
+    pub next_blob_base_fee: U256,
+}
+
+/// A collector that listens for new blocks, and generates a stream of [blob gas
+/// events](BlobGasEvent), so blob submitting strategies, e.g. L2 batchers, can price
+/// their submissions. Blocks without blob fields, i.e. from before Cancun, are
+/// skipped.
+///
+/// Fees are derived from the [blob schedule](BlobSchedule) of the collector, which
+/// must match the fork of the chain.
+pub struct BlobGasCollector<M> {
+    provider: Arc<M>,
+    schedule: BlobSchedule,
+}
+
+impl<M> BlobGasCollector<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self {
+            provider,
+            schedule: BlobSchedule::PRAGUE,
+        }
+    }
+
+    /// Sets the blob parameters of the chain. Defaults to those of Prague.
+    pub fn with_schedule(mut self, schedule: BlobSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    fn market(&self, block: Block<H256>) -> Option<BlobGasEvent> {
+        let (Some(block_hash), Some(block_number)) = (block.hash, block.number) else {
+            return None;
+        };
+        let blob_gas_used = block.blob_gas_used?;
+        let excess_blob_gas = block.excess_blob_gas?;
+        let next_excess_blob_gas = self
+            .schedule
+            .next_excess_blob_gas(excess_blob_gas, blob_gas_used);
+        Some(BlobGasEvent {
+            block_number,
+            block_hash,
+            blobs: (blob_gas_used / GAS_PER_BLOB).low_u64(),
+            target_blobs: self.schedule.target_blobs,
+            max_blobs: self.schedule.max_blobs,
+            blob_gas_used,
+            excess_blob_gas,
+            blob_base_fee: self.schedule.blob_base_fee(excess_blob_gas),
+            next_blob_base_fee: self.schedule.blob_base_fee(next_excess_blob_gas),
+        })
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [BlobGasCollector](BlobGasCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new blocks.
+#[async_trait]
+impl<M> Collector<BlobGasEvent> for BlobGasCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, BlobGasEvent>> {
+        let stream = self.provider.subscribe_blocks().await.retryable()?;
+        let stream = stream.filter_map(move |block| self.market(block));
+        Ok(Box::pin(stream))
+    }
+}
@@ -1,5 +1,5 @@
+use crate::error::{Result, ResultExt};
 use crate::types::{Collector, CollectorStream};
-use anyhow::Result;
 use async_trait::async_trait;
 use ethers::{
     prelude::Middleware,
@@ -38,7 +38,7 @@ where
     M::Error: 'static,
 {
     async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, NewBlock>> {
-        let stream = self.provider.subscribe_blocks().await?;
+        let stream = self.provider.subscribe_blocks().await.retryable()?;
         let stream = stream.filter_map(|block| match block.hash {
             Some(hash) => block.number.map(|number| NewBlock { hash, number }),
             None => None,
@@ -0,0 +1,88 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Block, Transaction, TransactionReceipt, H256, U64},
+};
+use futures::StreamExt;
+use std::sync::Arc;
+use tracing::warn;
+
+/// A new block with its transactions and their receipts, emitted by the
+/// [BlockReceiptsCollector](BlockReceiptsCollector).
+#[derive(Debug, Clone)]
+pub struct BlockWithReceipts {
+    pub block: Block<Transaction>,
+    /// The receipts of the transactions of the block, in the same order.
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+/// A collector that listens for new blocks, fetches each block with its full
+/// transactions and all their receipts, and generates a stream of [enriched
+/// blocks](BlockWithReceipts), so strategies needing receipts don't each fetch them.
+///
+/// Receipts are fetched in one `eth_getBlockReceipts` call. Blocks that fail to be
+/// fetched, e.g. since they were reorged out, are skipped.
+pub struct BlockReceiptsCollector<M> {
+    provider: Arc<M>,
+}
+
+impl<M> BlockReceiptsCollector<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self { provider }
+    }
+}
+
+impl<M> BlockReceiptsCollector<M>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    async fn block_with_receipts(
+        &self,
+        hash: H256,
+        number: U64,
+    ) -> anyhow::Result<BlockWithReceipts> {
+        let (block, receipts) = futures::try_join!(
+            self.provider.get_block_with_txs(hash),
+            self.provider.get_block_receipts(number),
+        )?;
+        let block = block.ok_or_else(|| anyhow::anyhow!("block {:?} not found", hash))?;
+        // The block at `number` may have been replaced since.
+        if receipts
+            .iter()
+            .any(|receipt| receipt.block_hash != Some(hash))
+            || receipts.len() != block.transactions.len()
+        {
+            anyhow::bail!("receipts of block {:?} don't match its transactions", hash);
+        }
+        Ok(BlockWithReceipts { block, receipts })
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [BlockReceiptsCollector](BlockReceiptsCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new blocks.
+#[async_trait]
+impl<M> Collector<BlockWithReceipts> for BlockReceiptsCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, BlockWithReceipts>> {
+        let stream = self.provider.subscribe_blocks().await.retryable()?;
+        let stream = stream.filter_map(move |block| async move {
+            let (hash, number) = (block.hash?, block.number?);
+            match self.block_with_receipts(hash, number).await {
+                Ok(block) => Some(block),
+                Err(e) => {
+                    warn!("error fetching receipts of block {:?}: {}", hash, e);
+                    None
+                }
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,216 @@
+use crate::collectors::ws::{ws_stream, WsOptions};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    types::{Address, U256},
+    utils::parse_ether,
+};
+use futures::StreamExt;
+use serde::{de, Deserialize, Deserializer};
+use tracing::{debug, warn};
+
+/// The Blur feed, a socket.io endpoint.
+pub const BLUR_FEED_URL: &str =
+    "wss://feeds.prod.blur.io/socket.io/?tab=new&EIO=4&transport=websocket";
+
+/// The suffix of the topic streaming the activity of a collection.
+const ACTIVITY_TOPIC: &str = "feeds.activity.eventsCreated";
+
+/// The suffix of the topic streaming the bid levels of a collection.
+const BIDS_TOPIC: &str = "denormalizer.collectionBidPriceUpdates";
+
+/// A new listing on Blur.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlurListing {
+    pub contract: Address,
+    pub token_id: U256,
+    pub maker: Address,
+    /// The asking price, in wei.
+    pub price: U256,
+    /// The marketplace the listing was created on, as aggregated by Blur, e.g. `BLUR`
+    /// or `OPENSEA`.
+    pub marketplace: String,
+}
+
+/// An update of a price level of the collection bids of a collection on Blur. Bids
+/// are pooled by price, so a level with no executable size has been cleared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlurBidLevel {
+    pub contract: Address,
+    /// The price of the level, in wei.
+    pub price: U256,
+    /// The number of items the bids at this price can buy.
+    pub executable_size: u64,
+    pub bidder_count: u64,
+}
+
+/// An order event, emitted by the [BlurOrderCollector](BlurOrderCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlurOrder {
+    Listing(BlurListing),
+    BidLevel(BlurBidLevel),
+}
+
+/// Deserializes an amount of ETH encoded as a decimal string, e.g. `"1.25"`, into wei.
+fn ether<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<U256, D::Error> {
+    parse_ether(String::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Deserializes an integer encoded as a decimal string.
+fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<U256, D::Error> {
+    U256::from_dec_str(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Activity {
+    contract_address: Address,
+    items: Vec<ActivityItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActivityItem {
+    event_type: String,
+    #[serde(deserialize_with = "decimal")]
+    token_id: U256,
+    from_trader: Trader,
+    #[serde(deserialize_with = "ether")]
+    price: U256,
+    marketplace: String,
+}
+
+#[derive(Deserialize)]
+struct Trader {
+    address: Address,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BidUpdates {
+    contract_address: Address,
+    updates: Vec<BidUpdate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BidUpdate {
+    #[serde(deserialize_with = "ether")]
+    price: U256,
+    executable_size: u64,
+    bidder_count: u64,
+}
+
+impl BlurOrder {
+    /// Parses a socket.io event of the Blur feed, i.e. a message of the form
+    /// `42["<contract>.<topic>", <payload>]`, into the orders it contains.
+    fn parse(message: &str) -> serde_json::Result<Vec<Self>> {
+        let Some(event) = message.strip_prefix("42") else {
+            debug!("ignoring Blur message: {}", message);
+            return Ok(vec![]);
+        };
+        let (topic, payload): (String, serde_json::Value) = serde_json::from_str(event)?;
+        if topic.ends_with(ACTIVITY_TOPIC) {
+            let activity: Activity = serde_json::from_value(payload)?;
+            let listings = activity
+                .items
+                .into_iter()
+                .filter(|item| item.event_type == "ORDER_CREATED")
+                .map(|item| {
+                    BlurOrder::Listing(BlurListing {
+                        contract: activity.contract_address,
+                        token_id: item.token_id,
+                        maker: item.from_trader.address,
+                        price: item.price,
+                        marketplace: item.marketplace,
+                    })
+                });
+            Ok(listings.collect())
+        } else if topic.ends_with(BIDS_TOPIC) {
+            let bids: BidUpdates = serde_json::from_value(payload)?;
+            let levels = bids.updates.into_iter().map(|update| {
+                BlurOrder::BidLevel(BlurBidLevel {
+                    contract: bids.contract_address,
+                    price: update.price,
+                    executable_size: update.executable_size,
+                    bidder_count: update.bidder_count,
+                })
+            });
+            Ok(levels.collect())
+        } else {
+            debug!("ignoring Blur {} event", topic);
+            Ok(vec![])
+        }
+    }
+}
+
+/// A collector that subscribes to the Blur feed for a set of collections, and
+/// generates a stream of [order events](BlurOrder): new listings, and updates of
+/// the collection bid levels, e.g. to arbitrage them against Seaport listings from
+/// the [SeaportOrderCollector](crate::collectors::seaport_order_collector::SeaportOrderCollector).
+///
+/// The feed is undocumented and consumed as Blur's own frontend does, over socket.io.
+/// Prices are converted from ETH to wei. Events that fail to parse are skipped.
+pub struct BlurOrderCollector {
+    url: String,
+    collections: Vec<Address>,
+}
+
+impl BlurOrderCollector {
+    /// Creates a collector subscribing to the listings and bids of the given
+    /// collections.
+    pub fn new(collections: Vec<Address>) -> Self {
+        Self {
+            url: BLUR_FEED_URL.to_string(),
+            collections,
+        }
+    }
+
+    /// Streams from the given url, e.g. through a proxy.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [BlurOrderCollector](BlurOrderCollector).
+/// This implementation subscribes to the topics of the collections once the socket.io
+/// namespace is joined, and answers the pings of the server. The stream ends when the
+/// websocket closes.
+#[async_trait]
+impl Collector<BlurOrder> for BlurOrderCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, BlurOrder>> {
+        let topics: Vec<String> = self
+            .collections
+            .iter()
+            .flat_map(|contract| {
+                [
+                    format!("{:?}.{}", contract, ACTIVITY_TOPIC),
+                    format!("{:?}.{}", contract, BIDS_TOPIC),
+                ]
+            })
+            .collect();
+        let subscribe = format!("42{}", serde_json::json!(["subscribe", topics]));
+        let options = WsOptions {
+            subscriptions: vec!["40".to_string()],
+            keepalive: None,
+            reply: Some(Box::new(move |message: &str| match message {
+                "2" => Some("3".to_string()),
+                _ if message.starts_with("40") => Some(subscribe.clone()),
+                _ => None,
+            })),
+            ..Default::default()
+        };
+
+        let stream = ws_stream(self.url.as_str(), options).await?;
+        let stream = stream.flat_map(|message| {
+            let orders = BlurOrder::parse(&message).unwrap_or_else(|e| {
+                warn!("error parsing Blur event: {}", e);
+                vec![]
+            });
+            futures::stream::iter(orders)
+        });
+        Ok(Box::pin(stream))
+    }
+}
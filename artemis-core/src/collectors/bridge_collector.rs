@@ -0,0 +1,344 @@
+use crate::collectors::log_collector::{DecodedLog, LogCollector};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, RawLog},
+    contract::{EthEvent, EthLogDecode},
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Address, Bytes, Filter, Log, H256, U256},
+};
+use futures::stream::select_all;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+/// A deposit on an Across V3 spoke pool, to be filled on the destination chain.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "V3FundsDeposited",
+    abi = "V3FundsDeposited(address,address,uint256,uint256,uint256,uint32,uint32,uint32,uint32,address,address,address,bytes)"
+)]
+pub struct AcrossDeposit {
+    pub input_token: Address,
+    pub output_token: Address,
+    pub input_amount: U256,
+    pub output_amount: U256,
+    #[ethevent(indexed)]
+    pub destination_chain_id: U256,
+    #[ethevent(indexed)]
+    pub deposit_id: u32,
+    pub quote_timestamp: u32,
+    pub fill_deadline: u32,
+    pub exclusivity_deadline: u32,
+    #[ethevent(indexed)]
+    pub depositor: Address,
+    pub recipient: Address,
+    pub exclusive_relayer: Address,
+    pub message: Bytes,
+}
+
+/// A fill of an Across V3 deposit on its destination chain.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "FilledV3Relay",
+    abi = "FilledV3Relay(address,address,uint256,uint256,uint256,uint256,uint32,uint32,uint32,address,address,address,address,bytes,(address,bytes32,uint256,uint8))"
+)]
+pub struct AcrossFill {
+    pub input_token: Address,
+    pub output_token: Address,
+    pub input_amount: U256,
+    pub output_amount: U256,
+    pub repayment_chain_id: U256,
+    #[ethevent(indexed)]
+    pub origin_chain_id: U256,
+    #[ethevent(indexed)]
+    pub deposit_id: u32,
+    pub fill_deadline: u32,
+    pub exclusivity_deadline: u32,
+    pub exclusive_relayer: Address,
+    #[ethevent(indexed)]
+    pub relayer: Address,
+    pub depositor: Address,
+    pub recipient: Address,
+    pub message: Bytes,
+    pub relay_execution_info: (Address, [u8; 32], U256, u8),
+}
+
+/// A transfer sent through a Stargate V2 pool, to be received on the destination
+/// endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "OFTSent",
+    abi = "OFTSent(bytes32,uint32,address,uint256,uint256)"
+)]
+pub struct StargateSent {
+    #[ethevent(indexed)]
+    pub guid: [u8; 32],
+    pub dst_eid: u32,
+    #[ethevent(indexed)]
+    pub from_address: Address,
+    pub amount_sent_ld: U256,
+    pub amount_received_ld: U256,
+}
+
+/// A transfer received through a Stargate V2 pool.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "OFTReceived",
+    abi = "OFTReceived(bytes32,uint32,address,uint256)"
+)]
+pub struct StargateReceived {
+    #[ethevent(indexed)]
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    #[ethevent(indexed)]
+    pub to_address: Address,
+    pub amount_received_ld: U256,
+}
+
+/// A decoded event of a bridge contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BridgeEvent {
+    AcrossDeposit(AcrossDeposit),
+    AcrossFill(AcrossFill),
+    StargateSent(StargateSent),
+    StargateReceived(StargateReceived),
+}
+
+impl EthLogDecode for BridgeEvent {
+    fn decode_log(log: &RawLog) -> std::result::Result<Self, abi::Error> {
+        if let Ok(event) = <AcrossDeposit as EthEvent>::decode_log(log) {
+            return Ok(Self::AcrossDeposit(event));
+        }
+        if let Ok(event) = <AcrossFill as EthEvent>::decode_log(log) {
+            return Ok(Self::AcrossFill(event));
+        }
+        if let Ok(event) = <StargateSent as EthEvent>::decode_log(log) {
+            return Ok(Self::StargateSent(event));
+        }
+        if let Ok(event) = <StargateReceived as EthEvent>::decode_log(log) {
+            return Ok(Self::StargateReceived(event));
+        }
+        Err(abi::Error::InvalidData)
+    }
+}
+
+/// A bridge supported by the [BridgeCollector](BridgeCollector).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BridgeProtocol {
+    /// Across V3, watched through its spoke pools.
+    Across,
+    /// Stargate V2, watched through its pools.
+    Stargate,
+}
+
+impl BridgeProtocol {
+    fn events(&self) -> Vec<H256> {
+        match self {
+            BridgeProtocol::Across => vec![AcrossDeposit::signature(), AcrossFill::signature()],
+            BridgeProtocol::Stargate => {
+                vec![StargateSent::signature(), StargateReceived::signature()]
+            }
+        }
+    }
+}
+
+/// Whether a [BridgeIntent](BridgeIntent) opens a transfer on its origin chain, or
+/// settles one on its destination chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BridgeIntentKind {
+    Deposit,
+    Fill,
+}
+
+/// A bridge transfer, normalized across protocols, emitted by the
+/// [BridgeCollector](BridgeCollector).
+#[derive(Debug, Clone)]
+pub struct BridgeIntent {
+    pub protocol: BridgeProtocol,
+    pub kind: BridgeIntentKind,
+    /// The chain the event was emitted on.
+    pub chain_id: u64,
+    /// The identifier of the transfer on both chains: the deposit id on Across, and
+    /// the guid on Stargate.
+    pub id: H256,
+    /// The chain the transfer comes from, if known.
+    pub origin_chain_id: Option<u64>,
+    /// The chain the transfer goes to, if known.
+    pub destination_chain_id: Option<u64>,
+    /// The sender of the transfer, if known.
+    pub depositor: Option<Address>,
+    /// The receiver of the transfer, if known.
+    pub recipient: Option<Address>,
+    /// The token sent on the origin chain, if known.
+    pub input_token: Option<Address>,
+    /// The token received on the destination chain, if known.
+    pub output_token: Option<Address>,
+    pub input_amount: U256,
+    pub output_amount: U256,
+    /// The deadline to fill the transfer, in seconds since the Unix epoch, if any.
+    pub fill_deadline: Option<u32>,
+    /// The raw log, e.g. to detect logs removed by a reorg.
+    pub log: Log,
+}
+
+/// Returns the chain id of a LayerZero V2 endpoint id, for the chains Stargate is
+/// most used on.
+pub fn chain_id_of_eid(eid: u32) -> Option<u64> {
+    let chain_id = match eid {
+        30101 => 1,
+        30102 => 56,
+        30106 => 43114,
+        30109 => 137,
+        30110 => 42161,
+        30111 => 10,
+        30183 => 59144,
+        30184 => 8453,
+        30214 => 534352,
+        _ => return None,
+    };
+    Some(chain_id)
+}
+
+impl BridgeIntent {
+    fn new(chain_id: u64, event: BridgeEvent, log: Log) -> Self {
+        let (protocol, kind) = match &event {
+            BridgeEvent::AcrossDeposit(_) => (BridgeProtocol::Across, BridgeIntentKind::Deposit),
+            BridgeEvent::AcrossFill(_) => (BridgeProtocol::Across, BridgeIntentKind::Fill),
+            BridgeEvent::StargateSent(_) => (BridgeProtocol::Stargate, BridgeIntentKind::Deposit),
+            BridgeEvent::StargateReceived(_) => (BridgeProtocol::Stargate, BridgeIntentKind::Fill),
+        };
+        let mut intent = Self {
+            protocol,
+            kind,
+            chain_id,
+            id: H256::zero(),
+            origin_chain_id: None,
+            destination_chain_id: None,
+            depositor: None,
+            recipient: None,
+            input_token: None,
+            output_token: None,
+            input_amount: U256::zero(),
+            output_amount: U256::zero(),
+            fill_deadline: None,
+            log,
+        };
+        match event {
+            BridgeEvent::AcrossDeposit(deposit) => {
+                intent.id = H256::from_low_u64_be(deposit.deposit_id.into());
+                intent.origin_chain_id = Some(chain_id);
+                intent.destination_chain_id = Some(deposit.destination_chain_id.low_u64());
+                intent.depositor = Some(deposit.depositor);
+                intent.recipient = Some(deposit.recipient);
+                intent.input_token = Some(deposit.input_token);
+                intent.output_token = Some(deposit.output_token);
+                intent.input_amount = deposit.input_amount;
+                intent.output_amount = deposit.output_amount;
+                intent.fill_deadline = Some(deposit.fill_deadline);
+            }
+            BridgeEvent::AcrossFill(fill) => {
+                intent.id = H256::from_low_u64_be(fill.deposit_id.into());
+                intent.origin_chain_id = Some(fill.origin_chain_id.low_u64());
+                intent.destination_chain_id = Some(chain_id);
+                intent.depositor = Some(fill.depositor);
+                intent.recipient = Some(fill.recipient);
+                intent.input_token = Some(fill.input_token);
+                intent.output_token = Some(fill.output_token);
+                intent.input_amount = fill.input_amount;
+                intent.output_amount = fill.output_amount;
+                intent.fill_deadline = Some(fill.fill_deadline);
+            }
+            BridgeEvent::StargateSent(sent) => {
+                intent.id = H256(sent.guid);
+                intent.origin_chain_id = Some(chain_id);
+                intent.destination_chain_id = chain_id_of_eid(sent.dst_eid);
+                intent.depositor = Some(sent.from_address);
+                intent.input_amount = sent.amount_sent_ld;
+                intent.output_amount = sent.amount_received_ld;
+            }
+            BridgeEvent::StargateReceived(received) => {
+                intent.id = H256(received.guid);
+                intent.origin_chain_id = chain_id_of_eid(received.src_eid);
+                intent.destination_chain_id = Some(chain_id);
+                intent.recipient = Some(received.to_address);
+                intent.input_amount = received.amount_received_ld;
+                intent.output_amount = received.amount_received_ld;
+            }
+        }
+        intent
+    }
+}
+
+/// A set of bridge contracts watched on one chain.
+struct ChainWatch<M> {
+    chain_id: u64,
+    logs: LogCollector<M>,
+}
+
+/// A collector that watches the deposits and fills of bridge contracts on several
+/// chains, and generates a stream of [bridge intents](BridgeIntent) normalized
+/// across protocols and tagged with their chain, e.g. so a filler strategy can match
+/// deposits on one chain against its inventory on another.
+///
+/// The logs of each chain are collected by a [LogCollector](LogCollector), so the
+/// events missed while reconnecting are backfilled.
+pub struct BridgeCollector<M> {
+    chains: Vec<ChainWatch<M>>,
+}
+
+impl<M> BridgeCollector<M> {
+    pub fn new() -> Self {
+        Self { chains: vec![] }
+    }
+
+    /// Watches the given contracts of `protocol`, e.g. the Across spoke pool or the
+    /// Stargate pools, on the chain `chain_id` served by `provider`.
+    pub fn watch(
+        mut self,
+        chain_id: u64,
+        provider: Arc<M>,
+        protocol: BridgeProtocol,
+        contracts: Vec<Address>,
+    ) -> Self {
+        let filter = Filter::new().address(contracts).topic0(protocol.events());
+        self.chains.push(ChainWatch {
+            chain_id,
+            logs: LogCollector::new(provider, filter),
+        });
+        self
+    }
+}
+
+impl<M> Default for BridgeCollector<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [BridgeCollector](BridgeCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new logs
+/// on every chain, and ends once every chain's stream ends.
+#[async_trait]
+impl<M> Collector<BridgeIntent> for BridgeCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, BridgeIntent>> {
+        let mut streams = Vec::with_capacity(self.chains.len());
+        for chain in &self.chains {
+            let stream: CollectorStream<'a, DecodedLog<BridgeEvent>> =
+                chain.logs.get_event_stream().await?;
+            let chain_id = chain.chain_id;
+            let stream: CollectorStream<'a, BridgeIntent> = Box::pin(
+                stream
+                    .map(move |DecodedLog { event, log }| BridgeIntent::new(chain_id, event, log)),
+            );
+            streams.push(stream);
+        }
+        Ok(Box::pin(select_all(streams)))
+    }
+}
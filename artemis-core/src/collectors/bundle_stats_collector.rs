@@ -0,0 +1,315 @@
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    signers::Signer,
+    types::{H256, U64},
+    utils::keccak256,
+};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+/// The Flashbots relay.
+pub const FLASHBOTS_RELAY_URL: &str = "https://relay.flashbots.net";
+
+/// A bundle submitted to the Flashbots relay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmittedBundle {
+    /// The hash of the bundle, as returned by the relay.
+    pub bundle_hash: H256,
+    /// The block the bundle targets.
+    pub block_number: U64,
+    /// The hashes of the transactions of the bundle.
+    pub transactions: Vec<H256>,
+}
+
+/// The bundles submitted by an executor, handed to the
+/// [BundleStatsCollector](BundleStatsCollector) to be tracked. The tracker is a cheap
+/// handle: keep a clone wherever bundles are sent, and track each bundle with the
+/// hash the relay returned for it.
+#[derive(Debug, Clone, Default)]
+pub struct BundleTracker {
+    submitted: Arc<Mutex<Vec<SubmittedBundle>>>,
+}
+
+impl BundleTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tracks a submitted bundle.
+    pub fn track(&self, bundle: SubmittedBundle) {
+        self.submitted.lock().unwrap().push(bundle);
+    }
+
+    /// Returns the bundles submitted since the last call.
+    fn take(&self) -> Vec<SubmittedBundle> {
+        std::mem::take(&mut *self.submitted.lock().unwrap())
+    }
+}
+
+/// The progress of a submitted bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleStatus {
+    /// Builders considered the bundle for their blocks, identified by their public
+    /// keys.
+    Considered { builders: Vec<String> },
+    /// Builders sealed a block including the bundle.
+    Sealed { builders: Vec<String> },
+    /// The bundle landed in its target block.
+    Included,
+    /// The target block was mined without the bundle.
+    NotIncluded,
+}
+
+/// A status update of a submitted bundle, emitted by the
+/// [BundleStatsCollector](BundleStatsCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleStatusEvent {
+    pub bundle_hash: H256,
+    pub block_number: U64,
+    pub status: BundleStatus,
+}
+
+/// The result of `flashbots_getBundleStatsV2`, absent for bundles the relay hasn't
+/// simulated yet.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleStats {
+    #[serde(default)]
+    considered_by_builders_at: Vec<BuilderTimestamp>,
+    #[serde(default)]
+    sealed_by_builders_at: Vec<BuilderTimestamp>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BuilderTimestamp {
+    pubkey: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<BundleStats>,
+    error: Option<serde_json::Value>,
+}
+
+/// A tracked bundle, with the statuses already emitted.
+struct Tracked {
+    bundle: SubmittedBundle,
+    considered: bool,
+    sealed: bool,
+}
+
+/// A collector that polls the Flashbots relay for the stats of the bundles an
+/// executor submitted, and generates a stream of their [statuses](BundleStatusEvent)
+/// as they are considered and sealed by builders, and whether they were included,
+/// closing the loop between submission and outcome.
+///
+/// Stats are fetched with `flashbots_getBundleStatsV2`, signed by the same searcher
+/// key as the bundles. A bundle is dropped once its target block is mined, after
+/// emitting whether its first transaction landed in it. Failed polls are logged and
+/// retried at the next interval.
+pub struct BundleStatsCollector<M, S> {
+    provider: Arc<M>,
+    signer: S,
+    tracker: BundleTracker,
+    client: Client,
+    relay_url: String,
+    poll_interval: Duration,
+}
+
+impl<M, S> BundleStatsCollector<M, S> {
+    /// Creates a collector tracking the bundles of `tracker`, signing requests with
+    /// `signer`, the searcher key the bundles were submitted with.
+    pub fn new(provider: Arc<M>, signer: S, tracker: BundleTracker) -> Self {
+        Self {
+            provider,
+            signer,
+            tracker,
+            client: Client::new(),
+            relay_url: FLASHBOTS_RELAY_URL.to_string(),
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+
+    /// Sets the URL of the relay. Defaults to the Flashbots relay.
+    pub fn with_relay_url(mut self, relay_url: impl Into<String>) -> Self {
+        self.relay_url = relay_url.into();
+        self
+    }
+
+    /// Sets how often the stats are polled. Defaults to 2 seconds.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+}
+
+impl<M, S> BundleStatsCollector<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    async fn bundle_stats(&self, bundle: &SubmittedBundle) -> anyhow::Result<BundleStats> {
+        let body = serde_json::to_string(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "flashbots_getBundleStatsV2",
+            "params": [{
+                "bundleHash": bundle.bundle_hash,
+                "blockNumber": bundle.block_number,
+            }],
+        }))?;
+        let hash = H256::from(keccak256(&body));
+        let signature = self
+            .signer
+            .sign_message(format!("{:?}", hash))
+            .await
+            .map_err(|e| anyhow::anyhow!("error signing request: {}", e))?;
+        let response: RpcResponse = self
+            .client
+            .post(self.relay_url.as_str())
+            .header("Content-Type", "application/json")
+            .header(
+                "X-Flashbots-Signature",
+                format!("{:?}:0x{}", self.signer.address(), signature),
+            )
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(error) = response.error {
+            anyhow::bail!("relay error: {}", error);
+        }
+        Ok(response.result.unwrap_or_default())
+    }
+
+    /// Returns the new statuses of a tracked bundle, and whether it is done.
+    async fn poll(&self, tracked: &mut Tracked, head: U64) -> (Vec<BundleStatus>, bool) {
+        let mut statuses = vec![];
+        match self.bundle_stats(&tracked.bundle).await {
+            Ok(stats) => {
+                if !tracked.considered && !stats.considered_by_builders_at.is_empty() {
+                    tracked.considered = true;
+                    statuses.push(BundleStatus::Considered {
+                        builders: builders(stats.considered_by_builders_at),
+                    });
+                }
+                if !tracked.sealed && !stats.sealed_by_builders_at.is_empty() {
+                    tracked.sealed = true;
+                    statuses.push(BundleStatus::Sealed {
+                        builders: builders(stats.sealed_by_builders_at),
+                    });
+                }
+            }
+            Err(e) => warn!(
+                "error fetching stats of bundle {:?}: {}",
+                tracked.bundle.bundle_hash, e
+            ),
+        }
+        if head < tracked.bundle.block_number {
+            return (statuses, false);
+        }
+        let Some(tx) = tracked.bundle.transactions.first() else {
+            statuses.push(BundleStatus::NotIncluded);
+            return (statuses, true);
+        };
+        match self.provider.get_transaction_receipt(*tx).await {
+            Ok(receipt) => {
+                let included = receipt.is_some_and(|receipt| {
+                    receipt.block_number == Some(tracked.bundle.block_number)
+                });
+                statuses.push(if included {
+                    BundleStatus::Included
+                } else {
+                    BundleStatus::NotIncluded
+                });
+                (statuses, true)
+            }
+            Err(e) => {
+                warn!("error fetching receipt of {:?}: {}", tx, e);
+                (statuses, false)
+            }
+        }
+    }
+}
+
+fn builders(timestamps: Vec<BuilderTimestamp>) -> Vec<String> {
+    timestamps
+        .into_iter()
+        .map(|builder| builder.pubkey)
+        .collect()
+}
+
+/// Implementation of the [Collector](Collector) trait for the [BundleStatsCollector](BundleStatsCollector).
+/// The stream never ends.
+#[async_trait]
+impl<M, S> Collector<BundleStatusEvent> for BundleStatsCollector<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, BundleStatusEvent>> {
+        let mut ticker = interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let state = (ticker, HashMap::new(), VecDeque::new());
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut ticker, mut tracked, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (ticker, tracked, pending)));
+                    }
+                    ticker.tick().await;
+                    for bundle in self.tracker.take() {
+                        tracked.insert(
+                            bundle.bundle_hash,
+                            Tracked {
+                                bundle,
+                                considered: false,
+                                sealed: false,
+                            },
+                        );
+                    }
+                    if tracked.is_empty() {
+                        continue;
+                    }
+                    let head = match self.provider.get_block_number().await {
+                        Ok(head) => head,
+                        Err(e) => {
+                            warn!("error fetching block number: {}", e);
+                            continue;
+                        }
+                    };
+                    let mut done = vec![];
+                    for (bundle_hash, bundle) in tracked.iter_mut() {
+                        let (statuses, finished) = self.poll(bundle, head).await;
+                        pending.extend(statuses.into_iter().map(|status| BundleStatusEvent {
+                            bundle_hash: *bundle_hash,
+                            block_number: bundle.bundle.block_number,
+                            status,
+                        }));
+                        if finished {
+                            done.push(*bundle_hash);
+                        }
+                    }
+                    for bundle_hash in done {
+                        tracked.remove(&bundle_hash);
+                    }
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
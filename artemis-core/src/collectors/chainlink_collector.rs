@@ -0,0 +1,83 @@
+use crate::collectors::log_collector::LogCollector;
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Address, Filter, Log, I256, U256},
+};
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+/// The event emitted by Chainlink aggregators on every new answer.
+const ANSWER_UPDATED: &str = "AnswerUpdated(int256,uint256,uint256)";
+
+/// A new answer of a Chainlink price feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceUpdate {
+    /// The aggregator that emitted the answer.
+    pub feed: Address,
+    /// The new answer, scaled by the decimals of the feed.
+    pub price: I256,
+    /// The round of the answer.
+    pub round: U256,
+    /// When the answer was updated, in seconds since the Unix epoch.
+    pub updated_at: U256,
+}
+
+impl PriceUpdate {
+    /// Decodes an `AnswerUpdated` log, in which the answer and the round are indexed.
+    fn from_log(log: &Log) -> Option<Self> {
+        if log.removed == Some(true) {
+            return None;
+        }
+        let [_, current, round] = log.topics.get(..3)? else {
+            return None;
+        };
+        let updated_at = log.data.get(..32).map(U256::from_big_endian)?;
+        Some(Self {
+            feed: log.address,
+            price: I256::from_raw(U256::from_big_endian(current.as_bytes())),
+            round: U256::from_big_endian(round.as_bytes()),
+            updated_at,
+        })
+    }
+}
+
+/// A collector that watches the `AnswerUpdated` logs of a set of Chainlink
+/// aggregators, and generates a stream of [price updates](PriceUpdate).
+///
+/// Aggregators are the contracts behind the feed proxies usually referenced by
+/// protocols, and change when a feed is upgraded: resolve them with the
+/// `aggregator()` method of the proxies. The logs are collected by a
+/// [LogCollector](LogCollector), so the updates missed while reconnecting are
+/// backfilled.
+pub struct ChainlinkCollector<M> {
+    logs: LogCollector<M>,
+}
+
+impl<M> ChainlinkCollector<M> {
+    pub fn new(provider: Arc<M>, aggregators: Vec<Address>) -> Self {
+        let filter = Filter::new().address(aggregators).event(ANSWER_UPDATED);
+        Self {
+            logs: LogCollector::new(provider, filter),
+        }
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [ChainlinkCollector](ChainlinkCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new logs.
+#[async_trait]
+impl<M> Collector<PriceUpdate> for ChainlinkCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, PriceUpdate>> {
+        let stream: CollectorStream<'a, Log> = self.logs.get_event_stream().await?;
+        let stream = stream.filter_map(|log| PriceUpdate::from_log(&log));
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,198 @@
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::types::{Address, Bytes, H256, U256};
+use reqwest::Client;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+/// The CoW Protocol API on mainnet.
+pub const COW_MAINNET_API_URL: &str = "https://api.cow.fi/mainnet";
+
+/// Whether an order sells an exact amount, or buys one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderKind {
+    Sell,
+    Buy,
+}
+
+/// An order of the CoW Protocol order book that is solvable in the current auction.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CowOrder {
+    pub uid: Bytes,
+    pub owner: Address,
+    pub sell_token: Address,
+    pub buy_token: Address,
+    #[serde(deserialize_with = "decimal")]
+    pub sell_amount: U256,
+    #[serde(deserialize_with = "decimal")]
+    pub buy_amount: U256,
+    /// The expiry of the order, in seconds since the Unix epoch.
+    pub valid_to: u32,
+    pub kind: OrderKind,
+    pub partially_fillable: bool,
+    /// The class of the order, e.g. `market`, `limit` or `liquidity`.
+    pub class: String,
+    /// The receiver of the bought tokens, the owner if unset.
+    #[serde(default)]
+    pub receiver: Option<Address>,
+    pub app_data: H256,
+}
+
+/// A new auction of the CoW Protocol, i.e. the batch solvers compete to settle.
+#[derive(Debug, Clone)]
+pub struct CowAuction {
+    pub id: u64,
+    /// The block the auction was created at.
+    pub block: u64,
+    /// The uids of the orders of the auction.
+    pub orders: Vec<Bytes>,
+    /// The native price of the tokens traded in the auction, i.e. the amount of wei
+    /// one atom of a token is worth, scaled by 1e18.
+    pub prices: HashMap<Address, U256>,
+}
+
+/// An event emitted by the [CowOrderCollector](CowOrderCollector).
+#[derive(Debug, Clone)]
+pub enum CowEvent {
+    Auction(CowAuction),
+    Order(CowOrder),
+}
+
+/// Deserializes an integer encoded as a decimal string.
+fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<U256, D::Error> {
+    U256::from_dec_str(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// The response of the `/api/v1/auction` endpoint.
+#[derive(Deserialize)]
+struct Auction {
+    id: u64,
+    block: u64,
+    orders: Vec<CowOrder>,
+    prices: HashMap<Address, String>,
+}
+
+/// A collector that polls the current auction of the CoW Protocol, and generates a
+/// stream of [events](CowEvent): each new auction, followed by the solvable orders
+/// that were not part of the previous one, so solver-style strategies only price
+/// new orders.
+pub struct CowOrderCollector {
+    client: Client,
+    api_url: String,
+    poll_interval: Duration,
+}
+
+impl CowOrderCollector {
+    /// Creates a collector polling the mainnet order book.
+    pub fn new() -> Self {
+        Self::with_api_url(COW_MAINNET_API_URL)
+    }
+
+    /// Creates a collector polling the API at `api_url`, e.g.
+    /// `https://api.cow.fi/gnosis` for Gnosis Chain.
+    pub fn with_api_url(api_url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_url: api_url.into(),
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+
+    /// Sets how often the current auction is polled. Defaults to 2 seconds.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    async fn auction(&self) -> anyhow::Result<Auction> {
+        let url = format!("{}/api/v1/auction", self.api_url.trim_end_matches('/'));
+        let auction = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(auction)
+    }
+}
+
+impl Default for CowOrderCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the events of a new auction, skipping the orders in `seen`, which is
+/// replaced by the orders of the auction.
+fn auction_events(auction: Auction, seen: &mut HashSet<Bytes>) -> Vec<CowEvent> {
+    let prices = auction
+        .prices
+        .into_iter()
+        .filter_map(|(token, price)| Some((token, U256::from_dec_str(&price).ok()?)))
+        .collect();
+    let uids: Vec<Bytes> = auction
+        .orders
+        .iter()
+        .map(|order| order.uid.clone())
+        .collect();
+    let mut events = vec![CowEvent::Auction(CowAuction {
+        id: auction.id,
+        block: auction.block,
+        orders: uids.clone(),
+        prices,
+    })];
+    events.extend(
+        auction
+            .orders
+            .into_iter()
+            .filter(|order| !seen.contains(&order.uid))
+            .map(CowEvent::Order),
+    );
+    *seen = uids.into_iter().collect();
+    events
+}
+
+/// Implementation of the [Collector](Collector) trait for the [CowOrderCollector](CowOrderCollector).
+/// Auctions are only emitted once, and failed polls are logged and retried at the
+/// next interval.
+#[async_trait]
+impl Collector<CowEvent> for CowOrderCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, CowEvent>> {
+        let mut ticker = interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let state = (ticker, None, HashSet::new(), VecDeque::new());
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut ticker, mut last_id, mut seen, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (ticker, last_id, seen, pending)));
+                    }
+                    ticker.tick().await;
+                    let auction = match self.auction().await {
+                        Ok(auction) => auction,
+                        Err(e) => {
+                            warn!("error fetching CoW auction: {}", e);
+                            continue;
+                        }
+                    };
+                    if last_id == Some(auction.id) {
+                        continue;
+                    }
+                    last_id = Some(auction.id);
+                    pending.extend(auction_events(auction, &mut seen));
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,93 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// A schedule event, emitted by the [CronCollector](CronCollector) when a named
+/// schedule fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleEvent {
+    /// The name of the schedule, e.g. `hourly-rebalance`.
+    pub name: String,
+    /// The time the schedule fired at, which the event is emitted shortly after.
+    pub scheduled_at: SystemTime,
+}
+
+/// A collector driven by cron expressions, which generates a stream of named
+/// [schedule events](ScheduleEvent), so calendar-driven strategies, e.g. rebalancing
+/// every hour or rolling positions before a weekly expiry, don't embed their own
+/// schedulers. Unlike the [IntervalCollector](crate::collectors::interval_collector::IntervalCollector),
+/// schedules are aligned on the wall clock, in UTC.
+///
+/// Expressions have a seconds field, e.g. `0 0 * * * *` fires at the top of every
+/// hour. Firings missed while the stream was not polled are skipped.
+#[derive(Default)]
+pub struct CronCollector {
+    schedules: Vec<(String, Schedule)>,
+}
+
+impl CronCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a schedule named `name` firing according to the cron `expression`.
+    /// Fails if the expression is invalid.
+    pub fn with_schedule(mut self, name: impl Into<String>, expression: &str) -> Result<Self> {
+        let schedule = Schedule::from_str(expression).fatal()?;
+        self.schedules.push((name.into(), schedule));
+        Ok(self)
+    }
+
+    /// Returns the events of the schedules firing next after `after`, all at the
+    /// same time, in the order the schedules were added.
+    fn next_firings(&self, after: &DateTime<Utc>) -> Option<(DateTime<Utc>, Vec<ScheduleEvent>)> {
+        let upcoming: Vec<_> = self
+            .schedules
+            .iter()
+            .filter_map(|(name, schedule)| Some((name, schedule.after(after).next()?)))
+            .collect();
+        let next = upcoming.iter().map(|(_, time)| *time).min()?;
+        let events = upcoming
+            .into_iter()
+            .filter(|(_, time)| *time == next)
+            .map(|(name, time)| ScheduleEvent {
+                name: name.clone(),
+                scheduled_at: time.into(),
+            })
+            .collect();
+        Some((next, events))
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [CronCollector](CronCollector).
+/// The stream ends once no schedule fires anymore, e.g. if every expression names
+/// a past year.
+#[async_trait]
+impl Collector<ScheduleEvent> for CronCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, ScheduleEvent>> {
+        let state = (Utc::now(), VecDeque::new());
+        let stream = futures::stream::unfold(state, move |(mut after, mut pending)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    return Some((event, (after, pending)));
+                }
+                // Skip the firings missed since the last event.
+                let now = Utc::now();
+                if now > after {
+                    after = now;
+                }
+                let (next, events) = self.next_firings(&after)?;
+                let wait = (next - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(wait).await;
+                after = next;
+                pending.extend(events);
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
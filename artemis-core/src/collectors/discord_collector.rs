@@ -0,0 +1,294 @@
+use crate::collectors::telegram_collector::Command;
+use crate::collectors::ws::{ws_stream, Keepalive, WsOptions};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+/// The Discord gateway.
+pub const DISCORD_GATEWAY_URL: &str = "wss://gateway.discord.gg/?v=10&encoding=json";
+
+/// The `GUILD_MESSAGES` and `MESSAGE_CONTENT` intents, needed to read the
+/// commands sent in a channel.
+const INTENTS: u64 = (1 << 9) | (1 << 15);
+
+/// How often heartbeats are sent until the gateway says hello with its own
+/// interval, usually ~41 seconds.
+const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The longest wait between two reconnections.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+
+/// The opcodes of the gateway.
+const DISPATCH: u8 = 0;
+const HEARTBEAT: u8 = 1;
+const IDENTIFY: u8 = 2;
+const RESUME: u8 = 6;
+const INVALID_SESSION: u8 = 9;
+const HELLO: u8 = 10;
+
+/// A payload of the gateway.
+#[derive(Deserialize)]
+struct GatewayPayload {
+    op: u8,
+    #[serde(default)]
+    d: serde_json::Value,
+    s: Option<i64>,
+    t: Option<String>,
+}
+
+/// The data of a `MESSAGE_CREATE` dispatch.
+#[derive(Deserialize)]
+struct MessageCreate {
+    channel_id: String,
+    content: String,
+    author: Author,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    username: String,
+    #[serde(default)]
+    bot: bool,
+}
+
+/// The data of a `HELLO` payload.
+#[derive(Deserialize)]
+struct Hello {
+    /// The interval between heartbeats, in milliseconds.
+    heartbeat_interval: u64,
+}
+
+/// The data of a `READY` dispatch.
+#[derive(Deserialize)]
+struct Ready {
+    session_id: String,
+    resume_gateway_url: String,
+}
+
+/// The gateway session of the bot, resumed after a reconnection so the messages
+/// sent in the meantime are replayed.
+#[derive(Debug, Default)]
+struct Session {
+    /// The id and resume URL of the session, once the gateway is ready.
+    resume: Option<(String, String)>,
+    /// The last sequence number received.
+    sequence: Option<i64>,
+}
+
+/// A collector that connects a bot to the Discord gateway, and generates a stream
+/// of the [commands](Command) sent in a channel, e.g. `!pause`, as an alternative
+/// to the [TelegramCollector](crate::collectors::telegram_collector::TelegramCollector)
+/// for teams operating from Discord.
+///
+/// The bot needs the privileged message content intent. Messages of other channels,
+/// of bots, and that are not commands are skipped.
+pub struct DiscordCollector {
+    url: String,
+    token: String,
+    channel_id: i64,
+    prefix: char,
+}
+
+impl DiscordCollector {
+    /// Creates a collector for the bot with the given token, emitting the commands
+    /// sent in the channel `channel_id`.
+    pub fn new(token: impl Into<String>, channel_id: i64) -> Self {
+        Self {
+            url: DISCORD_GATEWAY_URL.to_string(),
+            token: token.into(),
+            channel_id,
+            prefix: '!',
+        }
+    }
+
+    /// Sets the prefix of the commands. Defaults to `!`, since Discord clients
+    /// intercept messages starting with a slash.
+    pub fn with_prefix(mut self, prefix: char) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Connects to the gateway at `url`.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Returns the payload identifying the bot, or resuming its session if any.
+    fn handshake(&self, session: &Session) -> String {
+        match (&session.resume, session.sequence) {
+            (Some((session_id, _)), Some(sequence)) => json!({
+                "op": RESUME,
+                "d": {
+                    "token": self.token,
+                    "session_id": session_id,
+                    "seq": sequence,
+                },
+            }),
+            _ => json!({
+                "op": IDENTIFY,
+                "d": {
+                    "token": self.token,
+                    "intents": INTENTS,
+                    "properties": {
+                        "os": std::env::consts::OS,
+                        "browser": "artemis",
+                        "device": "artemis",
+                    },
+                },
+            }),
+        }
+        .to_string()
+    }
+
+    /// Connects to the gateway, resuming the session if any, and returns the stream
+    /// of its payloads. Heartbeats and handshakes are answered in the background.
+    async fn connect(
+        &self,
+        session: Arc<Mutex<Session>>,
+    ) -> Result<impl Stream<Item = String> + Send + 'static> {
+        let (url, handshake) = {
+            let session = session.lock().unwrap();
+            let url = match &session.resume {
+                Some((_, resume_url)) => format!("{}/?v=10&encoding=json", resume_url),
+                None => self.url.clone(),
+            };
+            (url, self.handshake(&session))
+        };
+        let (interval_sender, interval) = watch::channel(DEFAULT_HEARTBEAT_INTERVAL);
+        let heartbeat = {
+            let session = session.clone();
+            move || json!({ "op": HEARTBEAT, "d": session.lock().unwrap().sequence }).to_string()
+        };
+        let identify = self.handshake(&Session::default());
+
+        let options = WsOptions {
+            keepalive: Some(Keepalive {
+                interval: DEFAULT_HEARTBEAT_INTERVAL,
+                message: Box::new(heartbeat.clone()),
+            }),
+            keepalive_interval: Some(interval),
+            reply: Some(Box::new(move |message: &str| {
+                let payload: GatewayPayload = serde_json::from_str(message).ok()?;
+                let mut session = session.lock().unwrap();
+                if payload.s.is_some() {
+                    session.sequence = payload.s;
+                }
+                match payload.op {
+                    HELLO => {
+                        match serde_json::from_value::<Hello>(payload.d) {
+                            Ok(hello) => {
+                                let interval = Duration::from_millis(hello.heartbeat_interval);
+                                let _ = interval_sender.send(interval);
+                            }
+                            Err(e) => warn!("error parsing Discord hello: {}", e),
+                        }
+                        Some(handshake.clone())
+                    }
+                    HEARTBEAT => {
+                        drop(session);
+                        Some(heartbeat())
+                    }
+                    // The session can't be resumed, so the bot identifies again.
+                    INVALID_SESSION => {
+                        *session = Session::default();
+                        Some(identify.clone())
+                    }
+                    DISPATCH if payload.t.as_deref() == Some("READY") => {
+                        match serde_json::from_value::<Ready>(payload.d.clone()) {
+                            Ok(ready) => {
+                                session.resume = Some((ready.session_id, ready.resume_gateway_url))
+                            }
+                            Err(e) => warn!("error parsing Discord ready: {}", e),
+                        }
+                        None
+                    }
+                    _ => None,
+                }
+            })),
+            ..Default::default()
+        };
+        ws_stream(url.as_str(), options).await
+    }
+
+    /// Parses a payload of the gateway into a command, if it is one sent in the
+    /// channel.
+    fn command(&self, payload: GatewayPayload) -> serde_json::Result<Option<Command>> {
+        if payload.op != DISPATCH || payload.t.as_deref() != Some("MESSAGE_CREATE") {
+            return Ok(None);
+        }
+        let message: MessageCreate = serde_json::from_value(payload.d)?;
+        let channel_id = message.channel_id.parse::<i64>().ok();
+        if message.author.bot || channel_id != Some(self.channel_id) {
+            debug!("ignoring Discord message in channel {}", message.channel_id);
+            return Ok(None);
+        }
+        Ok(Command::parse_with_prefix(
+            &message.content,
+            self.prefix,
+            self.channel_id,
+            Some(message.author.username),
+        ))
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [DiscordCollector](DiscordCollector).
+/// This implementation identifies once the gateway says hello, and keeps the session
+/// alive with heartbeats carrying the last sequence number, at the interval the
+/// gateway asked for. When the gateway closes the connection, e.g. to ask for a
+/// reconnection, the collector reconnects with an exponential backoff and resumes
+/// the session, or identifies again if it can't be resumed.
+#[async_trait]
+impl Collector<Command> for DiscordCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Command>> {
+        let session = Arc::new(Mutex::new(Session::default()));
+        let first = self.connect(session.clone()).await?;
+
+        // Each connection is a stream of payloads, chained into a single stream.
+        let reconnections = stream::unfold(Duration::from_secs(1), move |delay| {
+            let session = session.clone();
+            async move {
+                warn!("Discord gateway disconnected, reconnecting in {:?}", delay);
+                tokio::time::sleep(delay).await;
+                match self.connect(session).await {
+                    Ok(connection) => {
+                        info!("reconnected to the Discord gateway");
+                        let connection: stream::BoxStream<'a, String> = Box::pin(connection);
+                        Some((connection, Duration::from_secs(1)))
+                    }
+                    Err(e) => {
+                        warn!("error reconnecting to the Discord gateway: {}", e);
+                        let connection: stream::BoxStream<'a, String> = Box::pin(stream::empty());
+                        Some((connection, (delay * 2).min(MAX_RECONNECT_DELAY)))
+                    }
+                }
+            }
+        });
+        let payloads = stream::once(async move {
+            let first: stream::BoxStream<'a, String> = Box::pin(first);
+            first
+        })
+        .chain(reconnections)
+        .flatten();
+
+        let stream = payloads.filter_map(move |message| {
+            let command = serde_json::from_str(&message).and_then(|payload| self.command(payload));
+            let command = match command {
+                Ok(command) => command,
+                Err(e) => {
+                    warn!("error parsing Discord payload: {}", e);
+                    None
+                }
+            };
+            futures::future::ready(command)
+        });
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,54 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::{Authorization, Provider, Ws},
+    types::Transaction,
+};
+
+/// The Eden Network transaction stream, in the EU west region.
+pub const EDEN_STREAM_URL: &str = "wss://speed-eu-west.edennetwork.io";
+
+/// A collector that listens to the Eden Network transaction stream, and generates
+/// a stream of [events](Transaction) which contain the transaction.
+///
+/// The stream carries the private orderflow relayed through Eden, along with the
+/// public mempool as seen by its nodes, and is authenticated with an Eden API key.
+/// Transactions are emitted with the same type as the
+/// [MempoolCollector](crate::collectors::mempool_collector::MempoolCollector), so
+/// both collectors can feed the same engine.
+pub struct EdenCollector {
+    provider: Provider<Ws>,
+}
+
+impl EdenCollector {
+    /// Connects to the Eden transaction stream with the given API key.
+    pub async fn connect(api_key: impl Into<String>) -> Result<Self> {
+        Self::connect_to(EDEN_STREAM_URL, api_key).await
+    }
+
+    /// Connects to the Eden transaction stream at `url`, e.g. that of another region.
+    pub async fn connect_to(url: &str, api_key: impl Into<String>) -> Result<Self> {
+        let ws = Ws::connect_with_auth(url, Authorization::raw(api_key.into()))
+            .await
+            .retryable()?;
+        Ok(Self {
+            provider: Provider::new(ws),
+        })
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [EdenCollector](EdenCollector).
+/// This implementation subscribes to full pending transactions.
+#[async_trait]
+impl Collector<Transaction> for EdenCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Transaction>> {
+        let stream = self
+            .provider
+            .subscribe_full_pending_txs()
+            .await
+            .retryable()?;
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,317 @@
+use crate::collectors::log_collector::{DecodedLog, LogCollector};
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, RawLog, Token},
+    contract::{EthEvent, EthLogDecode},
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Address, Filter, TransactionRequest, H256, U256},
+    utils::{id, keccak256},
+};
+use futures::StreamExt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::sleep;
+
+/// The ENS base registrar of `.eth` names on mainnet.
+pub const ENS_BASE_REGISTRAR: &str = "0x57f1887a8BF19b14fC0dF6Fd9B2acc9Af147eA85";
+
+/// How long an expired name can still be renewed by its owner before it is
+/// released, in seconds.
+pub const GRACE_PERIOD: u64 = 90 * 24 * 60 * 60;
+
+/// A registration of a name.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "NameRegistered",
+    abi = "NameRegistered(uint256,address,uint256)"
+)]
+pub struct NameRegistered {
+    #[ethevent(indexed)]
+    pub id: U256,
+    #[ethevent(indexed)]
+    pub owner: Address,
+    pub expires: U256,
+}
+
+/// A renewal of a name.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "NameRenewed", abi = "NameRenewed(uint256,uint256)")]
+pub struct NameRenewed {
+    #[ethevent(indexed)]
+    pub id: U256,
+    pub expires: U256,
+}
+
+/// A decoded event of the ENS base registrar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistrarEvent {
+    NameRegistered(NameRegistered),
+    NameRenewed(NameRenewed),
+}
+
+impl EthLogDecode for RegistrarEvent {
+    fn decode_log(log: &RawLog) -> std::result::Result<Self, abi::Error> {
+        if let Ok(event) = <NameRegistered as EthEvent>::decode_log(log) {
+            return Ok(Self::NameRegistered(event));
+        }
+        if let Ok(event) = <NameRenewed as EthEvent>::decode_log(log) {
+            return Ok(Self::NameRenewed(event));
+        }
+        Err(abi::Error::InvalidData)
+    }
+}
+
+/// A change of the registration of a watched name, emitted by the
+/// [EnsExpiryCollector](EnsExpiryCollector). Names are labels, without the `.eth`
+/// suffix, and times are in seconds since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnsEvent {
+    /// The name expires within the warning period of the collector.
+    ExpiringSoon { name: String, expires: u64 },
+    /// The grace period of the name ended, so anyone can register it, at a premium
+    /// decaying over the following 21 days.
+    Released { name: String, expires: u64 },
+    /// The name was registered, e.g. after its release.
+    Registered {
+        name: String,
+        owner: Address,
+        expires: u64,
+    },
+    /// The name was renewed until `expires`.
+    Renewed { name: String, expires: u64 },
+}
+
+/// The registration of a watched name.
+struct NameState {
+    name: String,
+    expires: u64,
+    warned: bool,
+    released: bool,
+}
+
+impl NameState {
+    fn new(name: String, expires: u64) -> Self {
+        Self {
+            name,
+            expires,
+            warned: false,
+            released: false,
+        }
+    }
+
+    /// Returns the next time an event is due for the name, if any.
+    fn deadline(&self, warning: u64) -> Option<u64> {
+        if self.expires == 0 || self.released {
+            None
+        } else if !self.warned {
+            Some(self.expires.saturating_sub(warning))
+        } else {
+            Some(self.expires + GRACE_PERIOD)
+        }
+    }
+
+    /// Returns the event due for the name at `now`, if any.
+    fn due(&mut self, now: u64, warning: u64) -> Option<EnsEvent> {
+        if self.expires == 0 || self.released {
+            return None;
+        }
+        if now >= self.expires + GRACE_PERIOD {
+            self.warned = true;
+            self.released = true;
+            return Some(EnsEvent::Released {
+                name: self.name.clone(),
+                expires: self.expires,
+            });
+        }
+        if !self.warned && now >= self.expires.saturating_sub(warning) {
+            self.warned = true;
+            return Some(EnsEvent::ExpiringSoon {
+                name: self.name.clone(),
+                expires: self.expires,
+            });
+        }
+        None
+    }
+}
+
+/// Updates the state of the name of a registrar event, if watched, and returns the
+/// event to emit.
+fn apply(states: &mut HashMap<U256, NameState>, event: RegistrarEvent) -> Option<EnsEvent> {
+    match event {
+        RegistrarEvent::NameRegistered(registered) => {
+            let state = states.get_mut(&registered.id)?;
+            *state = NameState::new(state.name.clone(), registered.expires.low_u64());
+            Some(EnsEvent::Registered {
+                name: state.name.clone(),
+                owner: registered.owner,
+                expires: state.expires,
+            })
+        }
+        RegistrarEvent::NameRenewed(renewed) => {
+            let state = states.get_mut(&renewed.id)?;
+            *state = NameState::new(state.name.clone(), renewed.expires.low_u64());
+            Some(EnsEvent::Renewed {
+                name: state.name.clone(),
+                expires: state.expires,
+            })
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// A collector that tracks the expiry of a set of `.eth` names, and generates a
+/// stream of [events](EnsEvent) when they are about to expire, are released, or
+/// are registered or renewed, e.g. to snipe names as they are released.
+///
+/// It is stateful: the expiry of every name is fetched from the registrar when the
+/// stream starts, then updated from the registrar's logs, and the events due are
+/// emitted as time passes. Names already released when the stream starts are
+/// emitted as released right away, and names never registered are only reported
+/// once registered.
+pub struct EnsExpiryCollector<M> {
+    provider: Arc<M>,
+    registrar: Address,
+    logs: LogCollector<M>,
+    /// The watched names, by token id.
+    names: HashMap<U256, String>,
+    warning: Duration,
+}
+
+/// Returns the token id of a `.eth` name in the base registrar, the hash of its
+/// label.
+pub fn token_id(name: &str) -> U256 {
+    U256::from_big_endian(&keccak256(name.trim_end_matches(".eth")))
+}
+
+impl<M> EnsExpiryCollector<M> {
+    /// Creates a collector watching `names`, e.g. `vitalik` or `vitalik.eth`, on the
+    /// mainnet base registrar.
+    pub fn new(provider: Arc<M>, names: Vec<String>) -> Self {
+        let registrar = ENS_BASE_REGISTRAR.parse().expect("valid address");
+        Self::with_registrar(provider, registrar, names)
+    }
+
+    /// Creates a collector watching `names` on the base registrar at `registrar`.
+    pub fn with_registrar(provider: Arc<M>, registrar: Address, names: Vec<String>) -> Self {
+        let names: HashMap<_, _> = names
+            .into_iter()
+            .map(|name| {
+                let name = name.trim_end_matches(".eth").to_string();
+                (token_id(&name), name)
+            })
+            .collect();
+        let ids: Vec<H256> = names
+            .keys()
+            .map(|id| {
+                let mut topic = H256::zero();
+                id.to_big_endian(topic.as_bytes_mut());
+                topic
+            })
+            .collect();
+        let filter = Filter::new()
+            .address(registrar)
+            .topic0(vec![NameRegistered::signature(), NameRenewed::signature()])
+            .topic1(ids);
+        Self {
+            logs: LogCollector::new(provider.clone(), filter),
+            provider,
+            registrar,
+            names,
+            warning: Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+
+    /// Sets how long before its expiry a name is reported as expiring soon.
+    /// Defaults to 7 days.
+    pub fn with_warning(mut self, warning: Duration) -> Self {
+        self.warning = warning;
+        self
+    }
+}
+
+impl<M> EnsExpiryCollector<M>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    /// Returns the expiry of the token `id`, 0 if it was never registered.
+    async fn name_expires(&self, token: U256) -> Result<u64> {
+        let mut data = id("nameExpires(uint256)").to_vec();
+        data.extend(abi::encode(&[Token::Uint(token)]));
+        let tx = TransactionRequest::new().to(self.registrar).data(data);
+        let output = self.provider.call(&tx.into(), None).await.retryable()?;
+        let expires = output
+            .get(..32)
+            .map(U256::from_big_endian)
+            .unwrap_or_default();
+        Ok(expires.low_u64())
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [EnsExpiryCollector](EnsExpiryCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to the
+/// logs of the registrar, and ends along with them.
+#[async_trait]
+impl<M> Collector<EnsEvent> for EnsExpiryCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, EnsEvent>> {
+        let logs: CollectorStream<'a, DecodedLog<RegistrarEvent>> =
+            self.logs.get_event_stream().await?;
+        let mut states = HashMap::new();
+        for (id, name) in &self.names {
+            let expires = self.name_expires(*id).await?;
+            states.insert(*id, NameState::new(name.clone(), expires));
+        }
+        let warning = self.warning.as_secs();
+
+        let stream = futures::stream::unfold(
+            (logs, states, VecDeque::new()),
+            move |(mut logs, mut states, mut pending)| async move {
+                loop {
+                    let now = unix_now();
+                    pending.extend(
+                        states
+                            .values_mut()
+                            .filter_map(|state| state.due(now, warning)),
+                    );
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (logs, states, pending)));
+                    }
+
+                    let deadline = states
+                        .values()
+                        .filter_map(|state| state.deadline(warning))
+                        .min();
+                    let timer = async move {
+                        match deadline {
+                            Some(deadline) => {
+                                sleep(Duration::from_secs(deadline.saturating_sub(now))).await
+                            }
+                            // Nothing is due until a watched name is registered.
+                            None => futures::future::pending().await,
+                        }
+                    };
+                    tokio::select! {
+                        log = logs.next() => pending.extend(apply(&mut states, log?.event)),
+                        _ = timer => {}
+                    }
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
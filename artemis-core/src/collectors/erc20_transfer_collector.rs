@@ -0,0 +1,138 @@
+use crate::collectors::log_collector::{DecodedLog, LogCollector};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, RawLog},
+    contract::{EthEvent, EthLogDecode},
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Address, Filter, Log, U256},
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+/// A transfer of ERC-20 tokens.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Transfer", abi = "Transfer(address,address,uint256)")]
+pub struct Transfer {
+    #[ethevent(indexed)]
+    pub from: Address,
+    #[ethevent(indexed)]
+    pub to: Address,
+    pub value: U256,
+}
+
+/// An approval of a spender of ERC-20 tokens.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Approval", abi = "Approval(address,address,uint256)")]
+pub struct Approval {
+    #[ethevent(indexed)]
+    pub owner: Address,
+    #[ethevent(indexed)]
+    pub spender: Address,
+    pub value: U256,
+}
+
+/// A decoded event of an ERC-20 token. ERC-721 transfers and approvals, which
+/// share the signatures but index the token id, fail to decode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Erc20Event {
+    Transfer(Transfer),
+    Approval(Approval),
+}
+
+impl Erc20Event {
+    /// Returns the two accounts of the event, the sender and the recipient of a
+    /// transfer, or the owner and the spender of an approval.
+    pub fn accounts(&self) -> [Address; 2] {
+        match self {
+            Self::Transfer(transfer) => [transfer.from, transfer.to],
+            Self::Approval(approval) => [approval.owner, approval.spender],
+        }
+    }
+}
+
+impl EthLogDecode for Erc20Event {
+    fn decode_log(log: &RawLog) -> std::result::Result<Self, abi::Error> {
+        if let Ok(event) = <Transfer as EthEvent>::decode_log(log) {
+            return Ok(Self::Transfer(event));
+        }
+        if let Ok(event) = <Approval as EthEvent>::decode_log(log) {
+            return Ok(Self::Approval(event));
+        }
+        Err(abi::Error::InvalidData)
+    }
+}
+
+/// An event of a token, emitted by the [Erc20TransferCollector](Erc20TransferCollector).
+#[derive(Debug, Clone)]
+pub struct TokenEvent {
+    /// The token that emitted the event.
+    pub token: Address,
+    pub event: Erc20Event,
+    /// The raw log, e.g. to order events or to detect logs removed by a reorg.
+    pub log: Log,
+}
+
+/// A collector that watches the transfers and approvals of ERC-20 tokens involving
+/// a set of accounts, and generates a stream of [decoded events](TokenEvent), e.g.
+/// to monitor treasury wallets, flows into exchanges, or whale movements.
+///
+/// Tokens are filtered by the node, while accounts are filtered by the collector,
+/// since a filter can't match the sender or the recipient. The logs are collected
+/// by a [LogCollector](LogCollector), so the events missed while reconnecting are
+/// backfilled.
+pub struct Erc20TransferCollector<M> {
+    logs: LogCollector<M>,
+    accounts: HashSet<Address>,
+}
+
+impl<M> Erc20TransferCollector<M> {
+    /// Creates a collector watching the events of `tokens` involving `accounts`.
+    /// Empty tokens watch every token, and empty accounts every account, which
+    /// only one of should be, given the volume of transfers.
+    pub fn new(provider: Arc<M>, tokens: Vec<Address>, accounts: Vec<Address>) -> Self {
+        let mut filter = Filter::new().topic0(vec![Transfer::signature(), Approval::signature()]);
+        if !tokens.is_empty() {
+            filter = filter.address(tokens);
+        }
+        Self {
+            logs: LogCollector::new(provider, filter),
+            accounts: accounts.into_iter().collect(),
+        }
+    }
+
+    /// Whether the event involves one of the watched accounts.
+    fn is_watched(&self, event: &Erc20Event) -> bool {
+        self.accounts.is_empty()
+            || event
+                .accounts()
+                .iter()
+                .any(|account| self.accounts.contains(account))
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [Erc20TransferCollector](Erc20TransferCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new logs.
+#[async_trait]
+impl<M> Collector<TokenEvent> for Erc20TransferCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, TokenEvent>> {
+        let stream: CollectorStream<'a, DecodedLog<Erc20Event>> =
+            self.logs.get_event_stream().await?;
+        let stream = stream.filter_map(|DecodedLog { event, log }| {
+            self.is_watched(&event).then(|| TokenEvent {
+                token: log.address,
+                event,
+                log,
+            })
+        });
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,187 @@
+use crate::engine::{RecordedEvent, ReplaySpeed};
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+use tracing::error;
+
+/// How many events are read ahead of the stream.
+const READ_AHEAD: usize = 1024;
+
+/// The format of a file of recorded events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayFormat {
+    /// One JSON encoded [RecordedEvent](RecordedEvent) per line, as written by an
+    /// [EventRecorder](crate::engine::EventRecorder).
+    JsonLines,
+    /// A Parquet file with a `recorded_at` integer column, in milliseconds since
+    /// the Unix epoch, and an `event` string column holding the JSON encoded event.
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl ReplayFormat {
+    /// Returns the format of the file at `path` according to its extension, JSON
+    /// lines unless it is `.parquet`.
+    #[cfg_attr(not(feature = "parquet"), allow(unused_variables))]
+    pub fn from_path(path: &Path) -> Self {
+        #[cfg(feature = "parquet")]
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "parquet")
+        {
+            return ReplayFormat::Parquet;
+        }
+        ReplayFormat::JsonLines
+    }
+}
+
+/// A collector that reads previously recorded events from a file, and generates a
+/// stream of the events in the order they were recorded, optionally with their
+/// original spacing, forming the input half of a backtest.
+///
+/// Unlike a [Replay](crate::engine::Replay), which replaces every collector of the
+/// engine and loads its events in memory, the file is streamed from disk, and the
+/// collector can run alongside live collectors. The stream ends at the end of the
+/// file, or at the first event that fails to be read.
+pub struct FileReplayCollector<E> {
+    path: PathBuf,
+    format: ReplayFormat,
+    speed: ReplaySpeed,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E> FileReplayCollector<E> {
+    /// Creates a collector replaying the file at `path`, as fast as the strategies
+    /// consume the events. The format is guessed from the extension of the file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            format: ReplayFormat::from_path(&path),
+            path,
+            speed: ReplaySpeed::default(),
+            _event: PhantomData,
+        }
+    }
+
+    /// Sets the format of the file.
+    pub fn with_format(mut self, format: ReplayFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the pace at which events are emitted. Defaults to
+    /// [AsFastAsPossible](ReplaySpeed::AsFastAsPossible).
+    pub fn with_speed(mut self, speed: ReplaySpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+}
+
+/// Reads a JSON lines file into `events`. Blank lines are skipped.
+fn read_json_lines<E: DeserializeOwned>(
+    path: &Path,
+    events: &mpsc::Sender<RecordedEvent<E>>,
+) -> anyhow::Result<()> {
+    let reader = BufReader::new(File::open(path)?);
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line)
+            .map_err(|e| anyhow::anyhow!("line {}: {}", index + 1, e))?;
+        if events.blocking_send(event).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Reads a Parquet file into `events`.
+#[cfg(feature = "parquet")]
+fn read_parquet<E: DeserializeOwned>(
+    path: &Path,
+    events: &mpsc::Sender<RecordedEvent<E>>,
+) -> anyhow::Result<()> {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::Field;
+
+    let reader = SerializedFileReader::new(File::open(path)?)?;
+    for (index, row) in reader.get_row_iter(None)?.enumerate() {
+        let row = row?;
+        let mut recorded_at = None;
+        let mut event = None;
+        for (name, field) in row.get_column_iter() {
+            match (name.as_str(), field) {
+                ("recorded_at", Field::Long(millis)) => recorded_at = Some(*millis as u64),
+                ("recorded_at", Field::ULong(millis)) => recorded_at = Some(*millis),
+                ("event", Field::Str(json)) => event = Some(serde_json::from_str(json)?),
+                _ => {}
+            }
+        }
+        let (Some(recorded_at), Some(event)) = (recorded_at, event) else {
+            anyhow::bail!("row {}: missing recorded_at or event column", index);
+        };
+        if events
+            .blocking_send(RecordedEvent { recorded_at, event })
+            .is_err()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Implementation of the [Collector](Collector) trait for the [FileReplayCollector](FileReplayCollector).
+/// The file is read on a blocking thread, ahead of the stream. Paced replays start
+/// timing from the moment the stream is created.
+#[async_trait]
+impl<E> Collector<E> for FileReplayCollector<E>
+where
+    E: DeserializeOwned + Send + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, E>> {
+        std::fs::metadata(&self.path).fatal()?;
+        let (sender, receiver) = mpsc::channel(READ_AHEAD);
+        let path = self.path.clone();
+        let format = self.format;
+        tokio::task::spawn_blocking(move || {
+            let read = match format {
+                ReplayFormat::JsonLines => read_json_lines(&path, &sender),
+                #[cfg(feature = "parquet")]
+                ReplayFormat::Parquet => read_parquet(&path, &sender),
+            };
+            if let Err(e) = read {
+                error!(
+                    "error reading recorded events from {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        });
+
+        let start = Instant::now();
+        let speed = self.speed;
+        let stream = futures::stream::unfold(
+            (receiver, None),
+            move |(mut receiver, mut first)| async move {
+                let recorded = receiver.recv().await?;
+                let first = *first.get_or_insert(recorded.recorded_at);
+                let elapsed = Duration::from_millis(recorded.recorded_at.saturating_sub(first));
+                if let Some(delay) = speed.delay(elapsed) {
+                    sleep_until(start + delay).await;
+                }
+                Some((recorded.event, (receiver, Some(first))))
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
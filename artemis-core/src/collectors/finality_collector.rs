@@ -0,0 +1,195 @@
+use crate::collectors::beacon_collector::{quoted, BeaconEvent, BeaconEventCollector, BeaconTopic};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::types::H256;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::collections::VecDeque;
+use tracing::warn;
+
+/// A checkpoint of the beacon chain, i.e. the block at the start of an epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Checkpoint {
+    #[serde(deserialize_with = "quoted")]
+    pub epoch: u64,
+    pub root: H256,
+    /// The number of the execution block of the checkpoint, if it has one.
+    #[serde(skip)]
+    pub execution_block_number: Option<u64>,
+    /// The hash of the execution block of the checkpoint, if it has one.
+    #[serde(skip)]
+    pub execution_block_hash: Option<H256>,
+}
+
+/// A change of the finality of the beacon chain, emitted by the
+/// [FinalityCollector](FinalityCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FinalityEvent {
+    /// A new checkpoint was justified, which is then unlikely to be reverted.
+    Justified(Checkpoint),
+    /// A new checkpoint was finalized, which can't be reverted without slashing a
+    /// third of the validators.
+    Finalized(Checkpoint),
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct FinalityCheckpoints {
+    current_justified: Checkpoint,
+    finalized: Checkpoint,
+}
+
+#[derive(Deserialize)]
+struct BlindedBlock {
+    message: BlindedBlockMessage,
+}
+
+#[derive(Deserialize)]
+struct BlindedBlockMessage {
+    body: BlindedBlockBody,
+}
+
+#[derive(Deserialize)]
+struct BlindedBlockBody {
+    execution_payload_header: Option<ExecutionPayloadHeader>,
+}
+
+#[derive(Deserialize)]
+struct ExecutionPayloadHeader {
+    #[serde(deserialize_with = "quoted")]
+    block_number: u64,
+    block_hash: H256,
+}
+
+/// A collector that tracks the finality checkpoints of a beacon node, and generates a
+/// stream of [justified and finalized](FinalityEvent) checkpoints, with their
+/// execution blocks, so strategies that settle or reconcile state can key off
+/// finality rather than head blocks.
+///
+/// Checkpoints are fetched when the collector starts, which emits the current ones,
+/// and at every epoch transition, when justification and finalization are processed.
+/// Failed fetches are logged and retried at the next epoch transition.
+pub struct FinalityCollector {
+    client: Client,
+    beacon_url: String,
+    heads: BeaconEventCollector,
+}
+
+impl FinalityCollector {
+    /// Creates a collector tracking the beacon node at `beacon_url`, e.g.
+    /// `http://localhost:5052`.
+    pub fn new(beacon_url: impl Into<String>) -> Self {
+        let beacon_url = beacon_url.into();
+        Self {
+            client: Client::new(),
+            heads: BeaconEventCollector::new(beacon_url.clone()).with_topics([BeaconTopic::Head]),
+            beacon_url,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        let url = format!("{}{}", self.beacon_url.trim_end_matches('/'), path);
+        let response: Response<T> = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.data)
+    }
+
+    /// Fills in the execution block of a checkpoint. The genesis checkpoint, with a
+    /// zero root, and checkpoints from before the merge have none.
+    async fn with_execution_block(&self, mut checkpoint: Checkpoint) -> anyhow::Result<Checkpoint> {
+        if checkpoint.root.is_zero() {
+            return Ok(checkpoint);
+        }
+        let block: BlindedBlock = self
+            .get(&format!(
+                "/eth/v1/beacon/blinded_blocks/{:?}",
+                checkpoint.root
+            ))
+            .await?;
+        if let Some(header) = block.message.body.execution_payload_header {
+            checkpoint.execution_block_number = Some(header.block_number);
+            checkpoint.execution_block_hash = Some(header.block_hash);
+        }
+        Ok(checkpoint)
+    }
+
+    /// Returns the current justified and finalized checkpoints of the head state.
+    async fn checkpoints(&self) -> anyhow::Result<(Checkpoint, Checkpoint)> {
+        let checkpoints: FinalityCheckpoints = self
+            .get("/eth/v1/beacon/states/head/finality_checkpoints")
+            .await?;
+        Ok((checkpoints.current_justified, checkpoints.finalized))
+    }
+
+    /// Returns the events of the checkpoints that changed since `last`, which is
+    /// updated.
+    async fn changes(&self, last: &mut (Option<H256>, Option<H256>)) -> Vec<FinalityEvent> {
+        let (justified, finalized) = match self.checkpoints().await {
+            Ok(checkpoints) => checkpoints,
+            Err(e) => {
+                warn!("error fetching finality checkpoints: {}", e);
+                return vec![];
+            }
+        };
+        let mut events = vec![];
+        if last.0 != Some(justified.root) {
+            match self.with_execution_block(justified).await {
+                Ok(justified) => {
+                    last.0 = Some(justified.root);
+                    events.push(FinalityEvent::Justified(justified));
+                }
+                Err(e) => warn!("error fetching justified block: {}", e),
+            }
+        }
+        if last.1 != Some(finalized.root) {
+            match self.with_execution_block(finalized).await {
+                Ok(finalized) => {
+                    last.1 = Some(finalized.root);
+                    events.push(FinalityEvent::Finalized(finalized));
+                }
+                Err(e) => warn!("error fetching finalized block: {}", e),
+            }
+        }
+        events
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [FinalityCollector](FinalityCollector).
+/// This implementation subscribes to the head events of the beacon node, and ends
+/// along with them.
+#[async_trait]
+impl Collector<FinalityEvent> for FinalityCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, FinalityEvent>> {
+        let heads = self.heads.get_event_stream().await?;
+        let mut last = (None, None);
+        let pending: VecDeque<_> = self.changes(&mut last).await.into();
+
+        let stream = futures::stream::unfold(
+            (heads, last, pending),
+            move |(mut heads, mut last, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (heads, last, pending)));
+                    }
+                    let head = heads.next().await?;
+                    if matches!(head, BeaconEvent::Head(head) if head.epoch_transition) {
+                        pending.extend(self.changes(&mut last).await);
+                    }
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,184 @@
+use crate::collectors::ws::{ws_stream, Keepalive, WsOptions};
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::types::{Address, Bytes, H256, U256};
+use serde::{de, Deserialize, Deserializer};
+use std::time::Duration;
+use tokio_stream::StreamExt;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, http::HeaderValue};
+use tracing::{debug, warn};
+
+/// The 1inch Fusion websocket API.
+pub const FUSION_WS_URL: &str = "wss://api.1inch.dev/fusion/ws/v2.0";
+
+/// How often the connection is pinged.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A new Fusion order, auctioned to resolvers. The rate offered to the taker
+/// decreases from the start to the end of the auction.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FusionOrder {
+    pub order_hash: H256,
+    pub order: LimitOrder,
+    pub signature: Bytes,
+    /// The start of the auction, in seconds since the Unix epoch.
+    pub auction_start_date: u64,
+    /// The end of the auction, in seconds since the Unix epoch.
+    pub auction_end_date: u64,
+    /// The amount of the maker asset left to fill.
+    #[serde(deserialize_with = "decimal")]
+    pub remaining_maker_amount: U256,
+    /// The extension of the order, carrying the auction details.
+    #[serde(default)]
+    pub extension: Bytes,
+}
+
+/// The limit order backing a [FusionOrder](FusionOrder).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitOrder {
+    #[serde(deserialize_with = "decimal")]
+    pub salt: U256,
+    pub maker: Address,
+    pub receiver: Address,
+    pub maker_asset: Address,
+    pub taker_asset: Address,
+    #[serde(deserialize_with = "decimal")]
+    pub making_amount: U256,
+    #[serde(deserialize_with = "decimal")]
+    pub taking_amount: U256,
+    #[serde(deserialize_with = "decimal")]
+    pub maker_traits: U256,
+}
+
+/// An event of the Fusion order feed, emitted by the [FusionOrderCollector](FusionOrderCollector).
+#[derive(Debug, Clone)]
+pub enum FusionEvent {
+    OrderCreated(FusionOrder),
+    OrderFilled(H256),
+    OrderFilledPartially {
+        order_hash: H256,
+        remaining_maker_amount: U256,
+    },
+    /// The order can no longer be filled, e.g. because it expired or the maker
+    /// revoked its allowance.
+    OrderInvalid(H256),
+    OrderCancelled(H256),
+}
+
+/// Deserializes an integer encoded as a decimal string.
+fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<U256, D::Error> {
+    U256::from_dec_str(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// A message of the Fusion websocket API. Replies to pings have no event.
+#[derive(Deserialize)]
+struct FeedMessage {
+    event: Option<String>,
+    #[serde(default)]
+    result: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderUpdate {
+    order_hash: H256,
+    #[serde(default, deserialize_with = "decimal_opt")]
+    remaining_maker_amount: Option<U256>,
+}
+
+/// Deserializes an optional integer encoded as a decimal string.
+fn decimal_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<U256>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|amount| U256::from_dec_str(&amount).map_err(de::Error::custom))
+        .transpose()
+}
+
+impl FusionEvent {
+    fn parse(message: &str) -> serde_json::Result<Option<Self>> {
+        let message: FeedMessage = serde_json::from_str(message)?;
+        let Some(event) = message.event else {
+            return Ok(None);
+        };
+        let event = match event.as_str() {
+            "order_created" => Self::OrderCreated(serde_json::from_value(message.result)?),
+            "order_filled" | "order_filled_partially" | "order_invalid" | "order_cancelled" => {
+                let update: OrderUpdate = serde_json::from_value(message.result)?;
+                match event.as_str() {
+                    "order_filled" => Self::OrderFilled(update.order_hash),
+                    "order_filled_partially" => Self::OrderFilledPartially {
+                        order_hash: update.order_hash,
+                        remaining_maker_amount: update.remaining_maker_amount.unwrap_or_default(),
+                    },
+                    "order_invalid" => Self::OrderInvalid(update.order_hash),
+                    _ => Self::OrderCancelled(update.order_hash),
+                }
+            }
+            _ => {
+                debug!("ignoring Fusion {} event", event);
+                return Ok(None);
+            }
+        };
+        Ok(Some(event))
+    }
+}
+
+/// A collector that subscribes to the 1inch Fusion order feed of a chain, and
+/// generates a stream of [events](FusionEvent): new orders auctioned to resolvers,
+/// and updates of the orders already known, so resolver strategies can keep an
+/// order book and reuse the engine's executors to fill them.
+///
+/// The feed is authenticated with a 1inch Developer Portal API key. Events that fail
+/// to parse are skipped.
+pub struct FusionOrderCollector {
+    url: String,
+    api_key: String,
+}
+
+impl FusionOrderCollector {
+    /// Creates a collector subscribing to the orders of the chain `chain_id`.
+    pub fn new(chain_id: u64, api_key: impl Into<String>) -> Self {
+        Self {
+            url: format!("{}/{}", FUSION_WS_URL, chain_id),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Streams from the given url, which includes the chain id.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [FusionOrderCollector](FusionOrderCollector).
+/// The stream ends when the websocket closes.
+#[async_trait]
+impl Collector<FusionEvent> for FusionOrderCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, FusionEvent>> {
+        let mut request = self.url.as_str().into_client_request().fatal()?;
+        let authorization = HeaderValue::from_str(&format!("Bearer {}", self.api_key)).fatal()?;
+        request.headers_mut().insert("Authorization", authorization);
+        let options = WsOptions {
+            keepalive: Some(Keepalive {
+                interval: PING_INTERVAL,
+                message: Box::new(|| r#"{"method":"ping"}"#.to_string()),
+            }),
+            ..Default::default()
+        };
+
+        let stream = ws_stream(request, options).await?;
+        let stream = stream.filter_map(|message| match FusionEvent::parse(&message) {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("error parsing Fusion event: {}", e);
+                None
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
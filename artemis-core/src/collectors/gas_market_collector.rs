@@ -0,0 +1,134 @@
+use crate::collectors::blob_gas_collector::BlobSchedule;
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Block, BlockNumber, H256, U256, U64},
+};
+use futures::StreamExt;
+use std::sync::Arc;
+use tracing::warn;
+
+/// The market for gas at a new block, emitted by the [GasMarketCollector](GasMarketCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasMarketEvent {
+    pub block_number: U64,
+    pub block_hash: H256,
+    /// The base fee of the block.
+    pub base_fee: U256,
+    /// The base fee of the next block, derived from the gas used by this one.
+    pub next_base_fee: U256,
+    /// The priority fees paid in the block at the percentiles of the collector,
+    /// in the same order. Empty if `eth_feeHistory` failed.
+    pub priority_fees: Vec<U256>,
+    /// The blob base fee of the block, if blobs are enabled.
+    pub blob_base_fee: Option<U256>,
+}
+
+/// A collector that listens for new blocks, and generates a stream of [gas market
+/// events](GasMarketEvent), so strategies and executors can share a single fee
+/// signal. Priority fee percentiles are fetched with `eth_feeHistory`. Blocks
+/// without a base fee, i.e. from before London, are skipped. The blob base fee is
+/// derived from the [blob schedule](BlobSchedule) of the collector, which must
+/// match the fork of the chain.
+pub struct GasMarketCollector<M> {
+    provider: Arc<M>,
+    /// The percentiles of the priority fees paid in each block, from 0 to 100.
+    percentiles: Vec<f64>,
+    schedule: BlobSchedule,
+}
+
+impl<M> GasMarketCollector<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self {
+            provider,
+            percentiles: vec![10.0, 50.0, 90.0],
+            schedule: BlobSchedule::PRAGUE,
+        }
+    }
+
+    /// Sets the percentiles of the priority fees reported, in increasing order from
+    /// 0 to 100. Defaults to the 10th, 50th and 90th percentiles.
+    pub fn with_percentiles(mut self, percentiles: Vec<f64>) -> Self {
+        self.percentiles = percentiles;
+        self
+    }
+
+    /// Sets the blob parameters of the chain. Defaults to those of Prague.
+    pub fn with_schedule(mut self, schedule: BlobSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+impl<M> GasMarketCollector<M>
+where
+    M: Middleware,
+{
+    async fn market(&self, block: Block<H256>) -> Option<GasMarketEvent> {
+        let (Some(block_hash), Some(block_number)) = (block.hash, block.number) else {
+            return None;
+        };
+        let base_fee = block.base_fee_per_gas?;
+
+        let priority_fees = match self
+            .provider
+            .fee_history(1u64, BlockNumber::Number(block_number), &self.percentiles)
+            .await
+        {
+            Ok(history) => history.reward.into_iter().next().unwrap_or_default(),
+            Err(e) => {
+                warn!(
+                    "error fetching fee history of block {}: {}",
+                    block_number, e
+                );
+                vec![]
+            }
+        };
+
+        Some(GasMarketEvent {
+            block_number,
+            block_hash,
+            base_fee,
+            next_base_fee: next_base_fee(base_fee, block.gas_used, block.gas_limit),
+            priority_fees,
+            blob_base_fee: block
+                .excess_blob_gas
+                .map(|excess_blob_gas| self.schedule.blob_base_fee(excess_blob_gas)),
+        })
+    }
+}
+
+/// Returns the base fee of the block following one with the given base fee, gas
+/// used and gas limit, as defined by EIP-1559.
+pub fn next_base_fee(base_fee: U256, gas_used: U256, gas_limit: U256) -> U256 {
+    let target = gas_limit / 2;
+    if target.is_zero() || gas_used == target {
+        return base_fee;
+    }
+    if gas_used > target {
+        let delta = base_fee * (gas_used - target) / target / 8;
+        base_fee + delta.max(U256::one())
+    } else {
+        let delta = base_fee * (target - gas_used) / target / 8;
+        base_fee.saturating_sub(delta)
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [GasMarketCollector](GasMarketCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new blocks.
+#[async_trait]
+impl<M> Collector<GasMarketEvent> for GasMarketCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, GasMarketEvent>> {
+        let stream = self.provider.subscribe_blocks().await.retryable()?;
+        let stream = stream.filter_map(move |block| self.market(block));
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,322 @@
+use crate::collectors::log_collector::{DecodedLog, LogCollector};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, RawLog, Token},
+    contract::{EthEvent, EthLogDecode},
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Address, Bytes, Filter, TransactionRequest, U256},
+    utils::id,
+};
+use futures::StreamExt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+/// How many positions are refreshed concurrently.
+const CONCURRENT_REFRESHES: usize = 16;
+
+/// A borrow from an Aave V3 pool.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "Borrow",
+    abi = "Borrow(address,address,address,uint256,uint8,uint256,uint16)"
+)]
+pub struct AaveBorrow {
+    #[ethevent(indexed)]
+    pub reserve: Address,
+    pub user: Address,
+    #[ethevent(indexed)]
+    pub on_behalf_of: Address,
+    pub amount: U256,
+    pub interest_rate_mode: u8,
+    pub borrow_rate: U256,
+    #[ethevent(indexed)]
+    pub referral_code: u16,
+}
+
+/// A withdrawal from a Compound V3 market, which borrows the base asset once the
+/// supplied balance is exhausted.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Withdraw", abi = "Withdraw(address,address,uint256)")]
+pub struct CometWithdraw {
+    #[ethevent(indexed)]
+    pub src: Address,
+    #[ethevent(indexed)]
+    pub to: Address,
+    pub amount: U256,
+}
+
+/// A decoded event opening or growing a debt position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebtEvent {
+    AaveBorrow(AaveBorrow),
+    CometWithdraw(CometWithdraw),
+}
+
+impl DebtEvent {
+    /// Returns the account whose debt the event changes.
+    pub fn account(&self) -> Address {
+        match self {
+            Self::AaveBorrow(borrow) => borrow.on_behalf_of,
+            Self::CometWithdraw(withdraw) => withdraw.src,
+        }
+    }
+}
+
+impl EthLogDecode for DebtEvent {
+    fn decode_log(log: &RawLog) -> std::result::Result<Self, abi::Error> {
+        if let Ok(event) = <AaveBorrow as EthEvent>::decode_log(log) {
+            return Ok(Self::AaveBorrow(event));
+        }
+        if let Ok(event) = <CometWithdraw as EthEvent>::decode_log(log) {
+            return Ok(Self::CometWithdraw(event));
+        }
+        Err(abi::Error::InvalidData)
+    }
+}
+
+/// A lending protocol whose positions are tracked by the
+/// [HealthFactorCollector](HealthFactorCollector).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LendingProtocol {
+    /// An Aave V3 pool, or one of its forks.
+    AaveV3,
+    /// A Compound V3 market, i.e. a Comet.
+    CompoundV3,
+}
+
+/// A position that crossed a risk threshold, emitted by the
+/// [HealthFactorCollector](HealthFactorCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionAtRisk {
+    pub protocol: LendingProtocol,
+    /// The Aave pool or Compound market of the position.
+    pub market: Address,
+    pub account: Address,
+    /// The health factor of the position, scaled by 1e18, if the protocol reports
+    /// one. Compound V3 doesn't.
+    pub health_factor: Option<U256>,
+    /// Whether the position can be liquidated.
+    pub liquidatable: bool,
+}
+
+/// How risky a position is, in increasing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum RiskLevel {
+    Healthy,
+    AtRisk,
+    Liquidatable,
+}
+
+/// A tracked position.
+type Position = (Address, Address);
+
+/// A collector that indexes the debt positions of Aave V3 pools and Compound V3
+/// markets from their borrow logs, refreshes them periodically, and generates a
+/// stream of [positions at risk](PositionAtRisk), so liquidation strategies only
+/// receive pre-screened candidates.
+///
+/// A position is emitted when its risk increases: when an Aave health factor falls
+/// below the threshold of the collector, and when a position becomes liquidatable.
+/// Compound V3 positions are only emitted once liquidatable. Positions opened before
+/// the stream starts are only indexed once they borrow again, unless seeded with
+/// [with_positions](HealthFactorCollector::with_positions), e.g. from a subgraph.
+pub struct HealthFactorCollector<M> {
+    provider: Arc<M>,
+    markets: HashMap<Address, LendingProtocol>,
+    logs: LogCollector<M>,
+    positions: Vec<Position>,
+    threshold: U256,
+    refresh_interval: Duration,
+}
+
+impl<M> HealthFactorCollector<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self {
+            logs: LogCollector::new(provider.clone(), Self::filter(&HashMap::new())),
+            provider,
+            markets: HashMap::new(),
+            positions: vec![],
+            // A health factor of 1.05.
+            threshold: U256::exp10(18) * 105 / 100,
+            refresh_interval: Duration::from_secs(12),
+        }
+    }
+
+    fn filter(markets: &HashMap<Address, LendingProtocol>) -> Filter {
+        Filter::new()
+            .address(markets.keys().copied().collect::<Vec<_>>())
+            .topic0(vec![AaveBorrow::signature(), CometWithdraw::signature()])
+    }
+
+    /// Tracks the positions of the Aave V3 pool or Compound V3 market at `market`.
+    pub fn with_market(mut self, protocol: LendingProtocol, market: Address) -> Self {
+        self.markets.insert(market, protocol);
+        self.logs = LogCollector::new(self.provider.clone(), Self::filter(&self.markets));
+        self
+    }
+
+    /// Tracks the positions of `accounts` in `market` from the start, e.g. known
+    /// borrowers fetched from a subgraph. The market must be tracked.
+    pub fn with_positions(
+        mut self,
+        market: Address,
+        accounts: impl IntoIterator<Item = Address>,
+    ) -> Self {
+        self.positions
+            .extend(accounts.into_iter().map(|account| (market, account)));
+        self
+    }
+
+    /// Sets the health factor, scaled by 1e18, below which an Aave position is at
+    /// risk. Defaults to 1.05.
+    pub fn with_threshold(mut self, threshold: U256) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the interval at which every position is refreshed. Defaults to 12s.
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+}
+
+impl<M> HealthFactorCollector<M>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    async fn call(&self, to: Address, signature: &str, account: Address) -> anyhow::Result<Bytes> {
+        let mut data = id(signature).to_vec();
+        data.extend(abi::encode(&[Token::Address(account)]));
+        let tx = TransactionRequest::new().to(to).data(data);
+        Ok(self.provider.call(&tx.into(), None).await?)
+    }
+
+    /// Returns the current state of a position.
+    async fn position(&self, (market, account): Position) -> anyhow::Result<PositionAtRisk> {
+        let protocol = *self
+            .markets
+            .get(&market)
+            .ok_or_else(|| anyhow::anyhow!("market {:?} is not tracked", market))?;
+        let (health_factor, liquidatable) = match protocol {
+            LendingProtocol::AaveV3 => {
+                // The health factor is the last of the six words of the account data.
+                let output = self
+                    .call(market, "getUserAccountData(address)", account)
+                    .await?;
+                let health_factor = output
+                    .get(160..192)
+                    .map(U256::from_big_endian)
+                    .ok_or_else(|| anyhow::anyhow!("invalid account data"))?;
+                (Some(health_factor), health_factor < U256::exp10(18))
+            }
+            LendingProtocol::CompoundV3 => {
+                let output = self
+                    .call(market, "isLiquidatable(address)", account)
+                    .await?;
+                let liquidatable = output.get(..32).map(U256::from_big_endian);
+                (None, liquidatable.is_some_and(|flag| !flag.is_zero()))
+            }
+        };
+        Ok(PositionAtRisk {
+            protocol,
+            market,
+            account,
+            health_factor,
+            liquidatable,
+        })
+    }
+
+    fn risk_level(&self, position: &PositionAtRisk) -> RiskLevel {
+        if position.liquidatable {
+            RiskLevel::Liquidatable
+        } else if position
+            .health_factor
+            .is_some_and(|health_factor| health_factor < self.threshold)
+        {
+            RiskLevel::AtRisk
+        } else {
+            RiskLevel::Healthy
+        }
+    }
+
+    /// Refreshes `positions`, and returns those whose risk increased since the last
+    /// refresh. Positions that fail to be refreshed keep their last risk level.
+    async fn refresh(
+        &self,
+        positions: Vec<Position>,
+        levels: &mut HashMap<Position, RiskLevel>,
+    ) -> Vec<PositionAtRisk> {
+        let refreshed: Vec<_> = futures::stream::iter(positions)
+            .map(|position| async move { (position, self.position(position).await) })
+            .buffer_unordered(CONCURRENT_REFRESHES)
+            .collect()
+            .await;
+        let mut at_risk = vec![];
+        for (position, state) in refreshed {
+            let state = match state {
+                Ok(state) => state,
+                Err(e) => {
+                    warn!("error refreshing position {:?}: {}", position, e);
+                    continue;
+                }
+            };
+            let level = self.risk_level(&state);
+            let previous = levels.insert(position, level).unwrap_or(RiskLevel::Healthy);
+            if level > previous {
+                at_risk.push(state);
+            }
+        }
+        at_risk
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [HealthFactorCollector](HealthFactorCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to the
+/// borrow logs of the markets, and ends along with them.
+#[async_trait]
+impl<M> Collector<PositionAtRisk> for HealthFactorCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, PositionAtRisk>> {
+        let logs: CollectorStream<'a, DecodedLog<DebtEvent>> = self.logs.get_event_stream().await?;
+        let mut ticker = interval(self.refresh_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let levels: HashMap<_, _> = self
+            .positions
+            .iter()
+            .map(|position| (*position, RiskLevel::Healthy))
+            .collect();
+
+        let state = (logs, ticker, levels, VecDeque::new());
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut logs, mut ticker, mut levels, mut pending)| async move {
+                loop {
+                    if let Some(position) = pending.pop_front() {
+                        return Some((position, (logs, ticker, levels, pending)));
+                    }
+                    let positions = tokio::select! {
+                        log = logs.next() => {
+                            let DecodedLog { event, log } = log?;
+                            vec![(log.address, event.account())]
+                        }
+                        _ = ticker.tick() => levels.keys().copied().collect(),
+                    };
+                    pending.extend(self.refresh(positions, &mut levels).await);
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
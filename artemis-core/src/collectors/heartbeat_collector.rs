@@ -0,0 +1,99 @@
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use std::time::{Duration, SystemTime};
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+use tokio_stream::StreamExt;
+
+/// A wrapper around a [Collector](Collector) that injects periodic [heartbeats](Heartbeat)
+/// into its event stream, so strategies and monitoring can detect a silently-stalled
+/// upstream (e.g. a hung subscription that never errors).
+pub struct HeartbeatCollector<E> {
+    collector: Box<dyn Collector<E>>,
+    name: String,
+    interval: Duration,
+}
+
+/// A heartbeat event, emitted at a fixed interval by the [HeartbeatCollector](HeartbeatCollector).
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    /// The name of the wrapped collector.
+    pub collector: String,
+    /// The time at which the heartbeat was emitted.
+    pub timestamp: SystemTime,
+    /// The time at which the wrapped collector last emitted an event, if any.
+    pub last_event_at: Option<SystemTime>,
+}
+
+impl Heartbeat {
+    /// Returns how long the wrapped collector has been silent, or `None` if it
+    /// has not emitted any event yet.
+    pub fn silence(&self) -> Option<Duration> {
+        let last_event_at = self.last_event_at?;
+        Some(
+            self.timestamp
+                .duration_since(last_event_at)
+                .unwrap_or_default(),
+        )
+    }
+}
+
+/// Events emitted by the [HeartbeatCollector](HeartbeatCollector): either an event
+/// from the wrapped collector, or a heartbeat.
+#[derive(Debug, Clone)]
+pub enum HeartbeatEvent<E> {
+    Event(E),
+    Heartbeat(Heartbeat),
+}
+
+impl<E> HeartbeatCollector<E> {
+    pub fn new(
+        collector: Box<dyn Collector<E>>,
+        name: impl Into<String>,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            collector,
+            name: name.into(),
+            interval,
+        }
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [HeartbeatCollector](HeartbeatCollector).
+/// The heartbeat stream ends when the wrapped collector's stream ends.
+#[async_trait]
+impl<E> Collector<HeartbeatEvent<E>> for HeartbeatCollector<E>
+where
+    E: Send + Sync + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, HeartbeatEvent<E>>> {
+        let stream = self.collector.get_event_stream().await?;
+
+        // Heartbeats start one interval after the stream is created.
+        let mut ticker = interval_at(Instant::now() + self.interval, self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let state = (stream, ticker, None::<SystemTime>);
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut stream, mut ticker, mut last)| async move {
+                let event = tokio::select! {
+                    event = stream.next() => {
+                        let event = event?;
+                        last = Some(SystemTime::now());
+                        HeartbeatEvent::Event(event)
+                    }
+                    _ = ticker.tick() => HeartbeatEvent::Heartbeat(Heartbeat {
+                        collector: self.name.clone(),
+                        timestamp: SystemTime::now(),
+                        last_event_at: last,
+                    }),
+                };
+                Some((event, (stream, ticker, last)))
+            },
+        );
+
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,200 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{
+        Address, BlockNumber, Bytes, CallFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
+        GethDebugTracingOptions, GethTrace, GethTraceFrame, NameOrAddress, H256, U256, U64,
+    },
+};
+use futures::StreamExt;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::warn;
+
+/// A call made during the execution of a transaction, emitted by the
+/// [InternalCallCollector](InternalCallCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InternalCall {
+    pub block_number: U64,
+    /// The hash of the transaction making the call.
+    pub tx_hash: H256,
+    /// How deep the call is nested, 0 being the transaction itself.
+    pub depth: usize,
+    /// The type of the call, e.g. `CALL`, `DELEGATECALL` or `CREATE2`.
+    pub call_type: String,
+    pub from: Address,
+    /// The callee, or the created contract.
+    pub to: Option<Address>,
+    pub value: U256,
+    pub input: Bytes,
+    /// The error the call reverted with, if any.
+    pub error: Option<String>,
+}
+
+impl InternalCall {
+    /// Returns the function selector of the call, if its input has one.
+    pub fn selector(&self) -> Option<[u8; 4]> {
+        self.input.get(..4)?.try_into().ok()
+    }
+}
+
+/// The calls emitted by the [InternalCallCollector](InternalCallCollector). Each
+/// set criterion must match, and empty sets match any call. By default, only
+/// nested calls are matched, not the transactions themselves.
+#[derive(Debug, Clone, Default)]
+pub struct CallFilter {
+    from: HashSet<Address>,
+    to: HashSet<Address>,
+    selectors: HashSet<[u8; 4]>,
+    min_value: U256,
+    include_transactions: bool,
+}
+
+impl CallFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches the calls made by one of `addresses`.
+    pub fn from(mut self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        self.from.extend(addresses);
+        self
+    }
+
+    /// Matches the calls to one of `addresses`.
+    pub fn to(mut self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        self.to.extend(addresses);
+        self
+    }
+
+    /// Matches the calls to one of the functions `selectors`.
+    pub fn selectors(mut self, selectors: impl IntoIterator<Item = [u8; 4]>) -> Self {
+        self.selectors.extend(selectors);
+        self
+    }
+
+    /// Matches the calls transferring at least `value` wei, e.g. raw ETH transfers
+    /// into contracts, which emit no log.
+    pub fn min_value(mut self, value: U256) -> Self {
+        self.min_value = value;
+        self
+    }
+
+    /// Also matches the top-level calls of transactions.
+    pub fn include_transactions(mut self) -> Self {
+        self.include_transactions = true;
+        self
+    }
+
+    fn matches(&self, call: &InternalCall) -> bool {
+        (self.include_transactions || call.depth > 0)
+            && (self.from.is_empty() || self.from.contains(&call.from))
+            && (self.to.is_empty() || call.to.is_some_and(|to| self.to.contains(&to)))
+            && (self.selectors.is_empty()
+                || call
+                    .selector()
+                    .is_some_and(|selector| self.selectors.contains(&selector)))
+            && call.value >= self.min_value
+    }
+}
+
+/// A collector that traces every new block with the `callTracer` of
+/// `debug_traceBlockByNumber`, and generates a stream of the [calls](InternalCall)
+/// matching a [filter](CallFilter), surfacing activity invisible in logs, e.g. raw
+/// ETH transfers into contracts.
+///
+/// The node must expose the `debug` namespace, and tracing every block is expensive,
+/// so the collector is best pointed at a local node. Blocks that fail to be traced
+/// are skipped.
+pub struct InternalCallCollector<M> {
+    provider: Arc<M>,
+    filter: CallFilter,
+}
+
+impl<M> InternalCallCollector<M> {
+    pub fn new(provider: Arc<M>, filter: CallFilter) -> Self {
+        Self { provider, filter }
+    }
+}
+
+impl<M> InternalCallCollector<M>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    /// Returns the calls of the block `block_number` matching the filter.
+    async fn calls(&self, block_number: U64) -> anyhow::Result<Vec<InternalCall>> {
+        let options = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::CallTracer,
+            )),
+            ..Default::default()
+        };
+        let (block, traces) = futures::try_join!(
+            self.provider.get_block(block_number),
+            self.provider
+                .debug_trace_block_by_number(Some(BlockNumber::Number(block_number)), options),
+        )?;
+        let block = block.ok_or_else(|| anyhow::anyhow!("block {} not found", block_number))?;
+
+        let mut calls = vec![];
+        for (tx_hash, trace) in block.transactions.into_iter().zip(traces) {
+            let GethTrace::Known(GethTraceFrame::CallTracer(frame)) = trace else {
+                continue;
+            };
+            let mut frames = vec![(0, frame)];
+            while let Some((depth, frame)) = frames.pop() {
+                let call = InternalCall {
+                    block_number,
+                    tx_hash,
+                    depth,
+                    call_type: frame.typ,
+                    from: frame.from,
+                    to: match frame.to {
+                        Some(NameOrAddress::Address(to)) => Some(to),
+                        _ => None,
+                    },
+                    value: frame.value.unwrap_or_default(),
+                    input: frame.input,
+                    error: frame.error,
+                };
+                if self.filter.matches(&call) {
+                    calls.push(call);
+                }
+                // Push the nested calls in reverse, so they pop in execution order.
+                let nested: Vec<CallFrame> = frame.calls.unwrap_or_default();
+                frames.extend(nested.into_iter().rev().map(|frame| (depth + 1, frame)));
+            }
+        }
+        Ok(calls)
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [InternalCallCollector](InternalCallCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new blocks.
+#[async_trait]
+impl<M> Collector<InternalCall> for InternalCallCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, InternalCall>> {
+        let stream = self.provider.subscribe_blocks().await.retryable()?;
+        let stream = stream
+            .then(move |block| async move {
+                let Some(number) = block.number else {
+                    return vec![];
+                };
+                self.calls(number).await.unwrap_or_else(|e| {
+                    warn!("error tracing block {}: {}", number, e);
+                    vec![]
+                })
+            })
+            .flat_map(futures::stream::iter);
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,51 @@
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+use tokio::time::{interval_at, MissedTickBehavior};
+
+/// A collector that emits a [Tick](Tick) at a fixed interval, for time-driven
+/// strategies such as funding arbitrage, TWAP checks or expiry sweeps. Run one
+/// collector per cadence.
+pub struct IntervalCollector {
+    interval: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+}
+
+/// A tick event, emitted by the [IntervalCollector](IntervalCollector) at the
+/// given instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tick(pub Instant);
+
+impl IntervalCollector {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            missed_tick_behavior: MissedTickBehavior::Delay,
+        }
+    }
+
+    /// Sets how ticks missed while the engine was busy are made up for. Defaults
+    /// to [Delay](MissedTickBehavior::Delay), which never emits ticks in a burst.
+    pub fn with_missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.missed_tick_behavior = behavior;
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [IntervalCollector](IntervalCollector).
+/// The first tick is emitted one interval after the stream is created.
+#[async_trait]
+impl Collector<Tick> for IntervalCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Tick>> {
+        let start = tokio::time::Instant::now() + self.interval;
+        let mut ticker = interval_at(start, self.interval);
+        ticker.set_missed_tick_behavior(self.missed_tick_behavior);
+
+        let stream = futures::stream::unfold(ticker, |mut ticker| async move {
+            let instant = ticker.tick().await;
+            Some((Tick(instant.into_std()), ticker))
+        });
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use futures::StreamExt;
+use rdkafka::{
+    config::ClientConfig,
+    consumer::{Consumer, StreamConsumer},
+    message::Message,
+};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use tracing::warn;
+
+/// A record consumed from Kafka, emitted by the [KafkaCollector](KafkaCollector).
+#[derive(Debug, Clone)]
+pub struct KafkaRecord<E> {
+    pub event: E,
+    pub topic: String,
+    pub partition: i32,
+    /// The offset of the record in its partition.
+    pub offset: i64,
+    pub key: Option<Vec<u8>>,
+    /// The timestamp of the record, in milliseconds since the Unix epoch, if any.
+    pub timestamp: Option<i64>,
+}
+
+/// A collector that consumes Kafka topics as part of a consumer group, and generates
+/// a stream of [records](KafkaRecord) whose JSON payloads are deserialized into `E`,
+/// so an engine can sit downstream of an existing data pipeline.
+///
+/// The offset of a record is stored once the record is emitted, and committed in the
+/// background, so a restarted collector resumes after the last record handed to the
+/// engine. Records that fail to deserialize are skipped, and their offset stored.
+pub struct KafkaCollector<E> {
+    consumer: StreamConsumer,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E> KafkaCollector<E> {
+    /// Creates a collector consuming `topics` from the brokers at `brokers`, e.g.
+    /// `localhost:9092`, as a member of the consumer group `group_id`.
+    pub fn new(brokers: &str, group_id: &str, topics: &[&str]) -> Result<Self> {
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", brokers)
+            .set("group.id", group_id);
+        Self::with_config(config, topics)
+    }
+
+    /// Creates a collector from a consumer configuration, e.g. one setting up
+    /// authentication. Offsets are always stored by the collector, and committed
+    /// automatically.
+    pub fn with_config(mut config: ClientConfig, topics: &[&str]) -> Result<Self> {
+        let consumer: StreamConsumer = config
+            .set("enable.auto.commit", "true")
+            .set("enable.auto.offset.store", "false")
+            .create()
+            .fatal()?;
+        consumer.subscribe(topics).fatal()?;
+        Ok(Self {
+            consumer,
+            _event: PhantomData,
+        })
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [KafkaCollector](KafkaCollector).
+/// Errors of the consumer are logged, and the consumer keeps reconnecting to the
+/// brokers in the background.
+#[async_trait]
+impl<E> Collector<KafkaRecord<E>> for KafkaCollector<E>
+where
+    E: DeserializeOwned + Send + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, KafkaRecord<E>>> {
+        let stream = self.consumer.stream().filter_map(move |message| {
+            let record = match message {
+                Ok(message) => {
+                    let payload = message.payload().unwrap_or_default();
+                    let record = match serde_json::from_slice(payload) {
+                        Ok(event) => Some(KafkaRecord {
+                            event,
+                            topic: message.topic().to_string(),
+                            partition: message.partition(),
+                            offset: message.offset(),
+                            key: message.key().map(<[u8]>::to_vec),
+                            timestamp: message.timestamp().to_millis(),
+                        }),
+                        Err(e) => {
+                            warn!(
+                                "error deserializing record {} of Kafka topic {}: {}",
+                                message.offset(),
+                                message.topic(),
+                                e
+                            );
+                            None
+                        }
+                    };
+                    if let Err(e) = self.consumer.store_offset_from_message(&message) {
+                        warn!("error storing Kafka offset: {}", e);
+                    }
+                    record
+                }
+                Err(e) => {
+                    warn!("Kafka consumer error: {}", e);
+                    None
+                }
+            };
+            futures::future::ready(record)
+        });
+        Ok(Box::pin(stream))
+    }
+}
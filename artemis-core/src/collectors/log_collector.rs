@@ -1,24 +1,113 @@
+use crate::error::{Result, ResultExt};
 use crate::types::{Collector, CollectorStream};
-use anyhow::Result;
 use async_trait::async_trait;
 use ethers::{
+    abi::RawLog,
+    contract::EthLogDecode,
     prelude::Middleware,
     providers::PubsubClient,
     types::{Filter, Log},
 };
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio_stream::StreamExt;
+use tracing::{debug, warn};
 
 /// A collector that listens for new blockchain event logs based on a [Filter](Filter),
-/// and generates a stream of [events](Log).
+/// and generates a stream of [events](Log), or of [decoded events](DecodedLog).
+///
+/// The collector remembers the last log it emitted. When its stream is
+/// re-created, e.g. by the engine after a disconnect when the collector has a
+/// [RestartPolicy](crate::engine::RestartPolicy), the logs emitted by the blocks
+/// missed in the meantime are backfilled before new ones, without duplicates.
 pub struct LogCollector<M> {
     provider: Arc<M>,
     filter: Filter,
+    /// The maximum number of blocks backfilled after a reconnect.
+    max_backfill: u64,
+    /// The block number and log index of the last log emitted.
+    position: Mutex<Option<(u64, u64)>>,
 }
 
 impl<M> LogCollector<M> {
     pub fn new(provider: Arc<M>, filter: Filter) -> Self {
-        Self { provider, filter }
+        Self {
+            provider,
+            filter,
+            max_backfill: 1_000,
+            position: Mutex::new(None),
+        }
+    }
+
+    /// Sets the maximum number of blocks backfilled after a reconnect, since
+    /// nodes limit the range of `eth_getLogs`. Older logs are skipped. Defaults
+    /// to 1000 blocks, and 0 disables backfilling.
+    pub fn with_max_backfill(mut self, blocks: u64) -> Self {
+        self.max_backfill = blocks;
+        self
+    }
+
+    /// Returns true if the log was not emitted yet, and records it as the last
+    /// log emitted. Logs removed by a reorg are always emitted, and rewind the
+    /// position so the logs replacing them are emitted too.
+    fn advance(&self, log: &Log) -> bool {
+        let (Some(block), Some(index)) = (log.block_number, log.log_index) else {
+            return true;
+        };
+        let position = (block.as_u64(), index.low_u64());
+        let mut last = self.position.lock().unwrap();
+        if log.removed == Some(true) {
+            if last.is_some_and(|last| last >= position) {
+                *last = Some((position.0.saturating_sub(1), u64::MAX));
+            }
+            return true;
+        }
+        if last.is_some_and(|last| last >= position) {
+            return false;
+        }
+        *last = Some(position);
+        true
+    }
+}
+
+impl<M> LogCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    /// Subscribes to new logs, then fetches the logs missed since the last one
+    /// emitted, if any.
+    async fn stream_logs(&self) -> Result<CollectorStream<'_, Log>> {
+        let subscription = self
+            .provider
+            .subscribe_logs(&self.filter)
+            .await
+            .retryable()?;
+        let missed = self.backfill().await?;
+        let stream = tokio_stream::iter(missed)
+            .chain(subscription)
+            .filter(move |log| self.advance(log));
+        Ok(Box::pin(stream))
+    }
+
+    async fn backfill(&self) -> Result<Vec<Log>> {
+        let Some((last, _)) = *self.position.lock().unwrap() else {
+            return Ok(vec![]);
+        };
+        if self.max_backfill == 0 {
+            return Ok(vec![]);
+        }
+        let latest = self.provider.get_block_number().await.retryable()?.as_u64();
+        let from = last.max(latest.saturating_sub(self.max_backfill - 1));
+        if from > last {
+            warn!(
+                "skipping logs of blocks {} to {}, beyond the backfill range",
+                last,
+                from - 1
+            );
+        }
+        let filter = self.filter.clone().from_block(from).to_block(latest);
+        self.provider.get_logs(&filter).await.retryable()
     }
 }
 
@@ -32,8 +121,44 @@ where
     M::Error: 'static,
 {
     async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Log>> {
-        let stream = self.provider.subscribe_logs(&self.filter).await?;
-        let stream = stream.filter_map(Some);
+        self.stream_logs().await
+    }
+}
+
+/// A log decoded into an event of a contract ABI, e.g. one generated by the
+/// `abigen!` macro, along with the raw log.
+#[derive(Debug, Clone)]
+pub struct DecodedLog<D> {
+    pub event: D,
+    pub log: Log,
+}
+
+/// Implementation of the [Collector](Collector) trait for the [LogCollector](LogCollector),
+/// decoding logs into `D`. Logs that fail to decode, e.g. since the filter matches
+/// other events, are dropped.
+#[async_trait]
+impl<M, D> Collector<DecodedLog<D>> for LogCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+    D: EthLogDecode + Send + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, DecodedLog<D>>> {
+        let stream = self.stream_logs().await?;
+        let stream = stream.filter_map(|log| {
+            let raw = RawLog {
+                topics: log.topics.clone(),
+                data: log.data.to_vec(),
+            };
+            match D::decode_log(&raw) {
+                Ok(event) => Some(DecodedLog { event, log }),
+                Err(e) => {
+                    debug!("dropping log that failed to decode: {}", e);
+                    None
+                }
+            }
+        });
         Ok(Box::pin(stream))
     }
 }
@@ -4,8 +4,8 @@ use ethers::{prelude::Middleware, providers::PubsubClient, types::Transaction};
 use futures::StreamExt;
 use std::sync::Arc;
 
+use crate::error::{Result, ResultExt};
 use crate::types::{Collector, CollectorStream};
-use anyhow::Result;
 
 /// A collector that listens for new transactions in the mempool, and generates a stream of
 /// [events](Transaction) which contain the transaction.
@@ -29,7 +29,7 @@ where
     M::Error: 'static,
 {
     async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Transaction>> {
-        let stream = self.provider.subscribe_pending_txs().await?;
+        let stream = self.provider.subscribe_pending_txs().await.retryable()?;
         let stream = stream.transactions_unordered(256);
         let stream = stream.filter_map(|res| async move { res.ok() });
         Ok(Box::pin(stream))
@@ -0,0 +1,64 @@
+use crate::collectors::ws::{ws_stream, WsOptions};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{types::Transaction, utils::hex, utils::rlp::Rlp};
+use futures::StreamExt;
+use tracing::warn;
+
+/// The Merkle private transaction stream.
+pub const MERKLE_STREAM_URL: &str = "wss://txs.merkle.io/ws";
+
+/// A collector that listens to the Merkle searcher websocket API, and generates a
+/// stream of [events](Transaction) which contain the private transactions sent
+/// through Merkle.
+///
+/// Merkle streams signed, RLP-encoded transactions, which are decoded into the same
+/// type as the [MempoolCollector](crate::collectors::mempool_collector::MempoolCollector)
+/// emits, with their hash and sender recovered, so both collectors can feed the
+/// same engine. Messages that fail to decode are skipped.
+pub struct MerkleCollector {
+    url: String,
+}
+
+impl MerkleCollector {
+    /// Creates a collector authenticated with the given Merkle API key.
+    pub fn new(api_key: &str) -> Self {
+        Self::with_url(format!("{}/{}", MERKLE_STREAM_URL, api_key))
+    }
+
+    /// Creates a collector streaming from the given url, which includes the API key.
+    pub fn with_url(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+/// Decodes a hex encoded, signed transaction.
+fn decode_transaction(raw: &str) -> Option<Transaction> {
+    let bytes = match hex::decode(raw.trim()) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("error decoding Merkle transaction: {}", e);
+            return None;
+        }
+    };
+    match Rlp::new(&bytes).as_val() {
+        Ok(tx) => Some(tx),
+        Err(e) => {
+            warn!("error decoding Merkle transaction: {}", e);
+            None
+        }
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [MerkleCollector](MerkleCollector).
+/// The stream ends when the websocket closes.
+#[async_trait]
+impl Collector<Transaction> for MerkleCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Transaction>> {
+        let stream = ws_stream(self.url.as_str(), WsOptions::default()).await?;
+        let stream =
+            stream.filter_map(|message| futures::future::ready(decode_transaction(&message)));
+        Ok(Box::pin(stream))
+    }
+}
@@ -1,11 +1,21 @@
+use crate::error::{Result, ResultExt};
 use crate::types::{Collector, CollectorStream};
-use anyhow::Result;
 use async_trait::async_trait;
 use mev_share::sse::{Event, EventClient};
 use tokio_stream::StreamExt;
+use tracing::warn;
+
+/// The Flashbots MEV-Share event stream on mainnet.
+pub const MEV_SHARE_SSE_URL: &str = "https://mev-share.flashbots.net";
 
 /// A collector that streams from MEV-Share SSE endpoint
 /// and generates [events](Event), which return tx hash, logs, and bundled txs.
+///
+/// Every event carries the hints the sender chose to share: the hash of the
+/// transaction or bundle, its logs, and for each transaction its `to` address,
+/// function selector and calldata, when shared. Backrunning strategies can match
+/// on these hints, and reference the hash from a `mev_sendBundle` request.
+/// Events that fail to parse are skipped.
 pub struct MevShareCollector {
     mevshare_sse_url: String,
 }
@@ -14,6 +24,11 @@ impl MevShareCollector {
     pub fn new(mevshare_sse_url: String) -> Self {
         Self { mevshare_sse_url }
     }
+
+    /// Creates a collector streaming from the Flashbots MEV-Share endpoint on mainnet.
+    pub fn mainnet() -> Self {
+        Self::new(MEV_SHARE_SSE_URL.to_string())
+    }
 }
 
 /// Implementation of the [Collector](Collector) trait for the
@@ -22,8 +37,14 @@ impl MevShareCollector {
 impl Collector<Event> for MevShareCollector {
     async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Event>> {
         let client = EventClient::default();
-        let stream = client.events(&self.mevshare_sse_url).await.unwrap();
-        let stream = stream.filter_map(|event| event.ok());
+        let stream = client.events(&self.mevshare_sse_url).await.retryable()?;
+        let stream = stream.filter_map(|event| match event {
+            Ok(event) => Some(event),
+            Err(e) => {
+                warn!("error parsing MEV-Share event: {}", e);
+                None
+            }
+        });
         Ok(Box::pin(stream))
     }
 }
@@ -5,13 +5,145 @@
 /// This collector listens to a stream of new blocks.
 pub mod block_collector;
 
+/// This collector emits new blocks with their transactions and receipts.
+pub mod block_receipts_collector;
+
+/// This collector listens to a stream of new blocks over an alloy provider, e.g. an IPC one.
+#[cfg(feature = "alloy")]
+pub mod alloy_block_collector;
+
+/// This collector listens to Chainlink price feed updates.
+pub mod chainlink_collector;
+
+/// This collector listens to the events of Uniswap V2 and V3 pools.
+pub mod uniswap_pool_collector;
+
+/// This collector listens to the transfers and approvals of ERC-20 tokens.
+pub mod erc20_transfer_collector;
+
+/// This collector listens to the deposits and fills of bridges across chains.
+pub mod bridge_collector;
+
+/// This collector reports the gas market at every new block.
+pub mod gas_market_collector;
+
+/// This collector reports the blob gas market at every new block.
+pub mod blob_gas_collector;
+
+/// This collector detects reorgs of the canonical chain.
+pub mod reorg_collector;
+
+/// This collector watches the balances and nonces of accounts.
+pub mod account_collector;
+
+/// This collector tracks the expiry of ENS names.
+pub mod ens_expiry_collector;
+
+/// This collector screens lending positions by health factor.
+pub mod health_factor_collector;
+
 /// This collector listens to a stream of new event logs.
 pub mod log_collector;
 
+/// This collector traces new blocks for internal calls.
+pub mod internal_call_collector;
+
 /// This collector listens to a stream of new pending transactions.
 pub mod mempool_collector;
 
+/// This collector listens to a stream of new pending transactions over an alloy provider.
+#[cfg(feature = "alloy")]
+pub mod alloy_mempool_collector;
+
+/// This collector polls the transaction pool of a node for new transactions.
+pub mod txpool_collector;
+
+/// This collector listens to the Eden Network transaction stream.
+pub mod eden_collector;
+
+/// This collector listens to the Merkle private transaction stream.
+pub mod merkle_collector;
+
+/// This collector listens to the transactions sequenced by the Arbitrum sequencer.
+pub mod arbitrum_feed_collector;
+
+/// This collector listens to the unsafe blocks of an OP stack chain.
+pub mod op_unsafe_block_collector;
+
 /// This collector listens to a stream of new Opensea orders.
 pub mod opensea_order_collector;
 
+/// This collector listens to a stream of new Seaport orders listed on OpenSea.
+pub mod seaport_order_collector;
+
+/// This collector listens to the listings and bids of the Blur feed.
+pub mod blur_order_collector;
+
+/// This collector polls the auctions of the CoW Protocol order book.
+pub mod cow_order_collector;
+
+/// This collector polls the open UniswapX orders.
+pub mod uniswapx_order_collector;
+
+/// This collector listens to the 1inch Fusion order feed.
+pub mod fusion_order_collector;
+
+/// This collector listens to the MEV-Share event stream.
 pub mod mevshare_collector;
+
+/// This collector wraps another collector and injects periodic heartbeats.
+pub mod heartbeat_collector;
+
+/// This collector merges the collectors of several chains, tagging their events.
+pub mod multi_chain_collector;
+
+/// This collector emits ticks at a fixed interval.
+pub mod interval_collector;
+
+/// This collector emits named events on cron schedules.
+pub mod cron_collector;
+
+/// This collector listens to the commands sent to a Telegram bot.
+pub mod telegram_collector;
+
+/// This collector listens to the commands sent in a Discord channel.
+pub mod discord_collector;
+
+/// This collector listens to the messages published on Redis channels.
+#[cfg(feature = "redis")]
+pub mod redis_collector;
+
+/// This collector consumes Kafka topics.
+#[cfg(feature = "kafka")]
+pub mod kafka_collector;
+
+/// This collector consumes NATS JetStream streams.
+#[cfg(feature = "nats")]
+pub mod nats_collector;
+
+/// This collector replays events recorded in a file.
+pub mod file_replay_collector;
+
+/// This collector listens to the event stream of a beacon node.
+pub mod beacon_collector;
+
+/// This collector tracks the justified and finalized checkpoints of a beacon node.
+pub mod finality_collector;
+
+/// This collector follows the slot clock and proposer duties of a beacon node.
+pub mod proposer_collector;
+
+/// This collector listens to beacon deposits and tracks the lifecycle of validators.
+pub mod validator_collector;
+
+/// This collector polls the data API of MEV-Boost relays.
+pub mod relay_data_collector;
+
+/// This collector tracks the status of bundles submitted to the Flashbots relay.
+pub mod bundle_stats_collector;
+
+/// Parsing of server-sent event streams, shared by the collectors consuming them.
+pub(crate) mod sse;
+
+/// Websocket connections, shared by the collectors consuming JSON feeds.
+pub(crate) mod ws;
@@ -0,0 +1,83 @@
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream, EventTopic};
+use async_trait::async_trait;
+use ethers::types::{Address, Filter};
+use futures::stream::select_all;
+use tokio_stream::StreamExt;
+
+/// An event of one chain, emitted by the [MultiChainCollector](MultiChainCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainEvent<E> {
+    /// The id of the chain the event comes from.
+    pub chain_id: u64,
+    pub event: E,
+}
+
+/// Chain events are routed by the topic of the event they carry.
+impl<E: EventTopic> EventTopic for ChainEvent<E> {
+    fn topic(&self) -> &'static str {
+        self.event.topic()
+    }
+}
+
+/// A collector that wraps one collector per chain, and generates a single stream of
+/// their events tagged with their [chain id](ChainEvent), so one engine can drive a
+/// cross-chain strategy without an enum variant per chain.
+///
+/// Several collectors may be added for the same chain. The stream ends once every
+/// wrapped stream ends, and fails if any collector fails to start.
+pub struct MultiChainCollector<E> {
+    chains: Vec<(u64, Box<dyn Collector<E>>)>,
+}
+
+impl<E> MultiChainCollector<E> {
+    pub fn new() -> Self {
+        Self { chains: vec![] }
+    }
+
+    /// Adds the collector of the chain `chain_id`.
+    pub fn with_chain(mut self, chain_id: u64, collector: Box<dyn Collector<E>>) -> Self {
+        self.chains.push((chain_id, collector));
+        self
+    }
+}
+
+impl<E> Default for MultiChainCollector<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns one log filter per chain, matching the events of `template` emitted by
+/// the contracts deployed on that chain, e.g. to build a
+/// [LogCollector](crate::collectors::log_collector::LogCollector) per chain for a
+/// protocol deployed at different addresses.
+pub fn chain_filters(
+    template: &Filter,
+    contracts: impl IntoIterator<Item = (u64, Vec<Address>)>,
+) -> Vec<(u64, Filter)> {
+    contracts
+        .into_iter()
+        .map(|(chain_id, addresses)| (chain_id, template.clone().address(addresses)))
+        .collect()
+}
+
+/// Implementation of the [Collector](Collector) trait for the [MultiChainCollector](MultiChainCollector).
+/// Events of different chains are interleaved in the order they arrive.
+#[async_trait]
+impl<E> Collector<ChainEvent<E>> for MultiChainCollector<E>
+where
+    E: Send + Sync + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, ChainEvent<E>>> {
+        let mut streams = Vec::with_capacity(self.chains.len());
+        for (chain_id, collector) in &self.chains {
+            let chain_id = *chain_id;
+            let stream = collector.get_event_stream().await?;
+            let stream: CollectorStream<'a, ChainEvent<E>> =
+                Box::pin(stream.map(move |event| ChainEvent { chain_id, event }));
+            streams.push(stream);
+        }
+        Ok(Box::pin(select_all(streams)))
+    }
+}
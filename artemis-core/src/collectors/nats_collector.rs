@@ -0,0 +1,112 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_nats::jetstream::{self, consumer::pull};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use tracing::warn;
+
+/// A message consumed from JetStream, emitted by the [NatsCollector](NatsCollector).
+#[derive(Debug, Clone)]
+pub struct NatsRecord<E> {
+    pub event: E,
+    pub subject: String,
+    /// The sequence number of the message in its stream, if known.
+    pub sequence: Option<u64>,
+}
+
+/// A collector that consumes a NATS JetStream stream through a durable pull
+/// consumer, and generates a stream of [records](NatsRecord) whose JSON payloads
+/// are deserialized into `E`, for infrastructure standardized on NATS.
+///
+/// Messages are acknowledged once emitted, so a restarted collector resumes from
+/// the first message not handed to the engine, and the durable consumer is created
+/// on first use. Messages that fail to deserialize are skipped, and acknowledged.
+pub struct NatsCollector<E> {
+    url: String,
+    stream: String,
+    durable: String,
+    filter_subject: Option<String>,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E> NatsCollector<E> {
+    /// Creates a collector consuming the JetStream stream `stream` of the server at
+    /// `url`, e.g. `nats://localhost:4222`, through the durable consumer `durable`.
+    pub fn new(
+        url: impl Into<String>,
+        stream: impl Into<String>,
+        durable: impl Into<String>,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            stream: stream.into(),
+            durable: durable.into(),
+            filter_subject: None,
+            _event: PhantomData,
+        }
+    }
+
+    /// Only consumes the messages of the subjects matching `subject`, e.g.
+    /// `prices.>`, when creating the durable consumer.
+    pub fn with_filter_subject(mut self, subject: impl Into<String>) -> Self {
+        self.filter_subject = Some(subject.into());
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [NatsCollector](NatsCollector).
+/// The stream ends when the connection to the server is lost.
+#[async_trait]
+impl<E> Collector<NatsRecord<E>> for NatsCollector<E>
+where
+    E: DeserializeOwned + Send + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, NatsRecord<E>>> {
+        let client = async_nats::connect(self.url.as_str()).await.retryable()?;
+        let context = jetstream::new(client);
+        let stream = context.get_stream(self.stream.as_str()).await.fatal()?;
+        let config = pull::Config {
+            durable_name: Some(self.durable.clone()),
+            filter_subject: self.filter_subject.clone().unwrap_or_default(),
+            ..Default::default()
+        };
+        let consumer = stream
+            .get_or_create_consumer(self.durable.as_str(), config)
+            .await
+            .retryable()?;
+        let messages = consumer.messages().await.retryable()?;
+
+        let stream = messages
+            .take_while(|message| {
+                if let Err(e) = message {
+                    warn!("NATS consumer error: {}", e);
+                }
+                futures::future::ready(message.is_ok())
+            })
+            .filter_map(|message| async move {
+                let message = message.ok()?;
+                let subject = message.subject.to_string();
+                let record = match serde_json::from_slice(&message.payload) {
+                    Ok(event) => Some(NatsRecord {
+                        event,
+                        sequence: message.info().ok().map(|info| info.stream_sequence),
+                        subject,
+                    }),
+                    Err(e) => {
+                        warn!(
+                            "error deserializing message of NATS subject {}: {}",
+                            subject, e
+                        );
+                        None
+                    }
+                };
+                if let Err(e) = message.ack().await {
+                    warn!("error acknowledging NATS message: {}", e);
+                }
+                record
+            });
+        Ok(Box::pin(stream))
+    }
+}
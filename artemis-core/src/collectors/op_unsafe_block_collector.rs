@@ -0,0 +1,177 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::{Http, Provider},
+    types::{Block, Transaction, H256},
+};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+/// How many unsafe blocks missed between two polls are fetched at most.
+const MAX_CATCH_UP: u64 = 64;
+
+/// A reference to an L1 block, as reported by op-node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct L1BlockRef {
+    pub hash: H256,
+    pub number: u64,
+}
+
+/// A reference to an L2 block, as reported by op-node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2BlockRef {
+    pub hash: H256,
+    pub number: u64,
+    pub parent_hash: H256,
+    pub timestamp: u64,
+    /// The L1 block the L2 block was derived from.
+    #[serde(rename = "l1origin")]
+    pub l1_origin: L1BlockRef,
+    /// The position of the L2 block in the epoch of its L1 origin.
+    pub sequence_number: u64,
+}
+
+/// The heads of the L2 chain, as returned by `optimism_syncStatus`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SyncStatus {
+    /// The latest block received from the sequencer, not yet derived from L1.
+    pub unsafe_l2: L2BlockRef,
+    /// The latest block derived from batches posted to L1.
+    pub safe_l2: L2BlockRef,
+    /// The latest block derived from finalized L1 blocks.
+    pub finalized_l2: L2BlockRef,
+}
+
+/// A new unsafe block of an OP stack chain, emitted by the
+/// [OpUnsafeBlockCollector](OpUnsafeBlockCollector).
+#[derive(Debug, Clone)]
+pub struct UnsafeBlock {
+    pub block: Block<Transaction>,
+    /// The latest safe block when the unsafe block was seen.
+    pub safe_l2: L2BlockRef,
+    /// The latest finalized block when the unsafe block was seen.
+    pub finalized_l2: L2BlockRef,
+}
+
+/// A collector that polls the sync status of an op-node, and generates a stream of
+/// the [unsafe blocks](UnsafeBlock) it receives from the sequencer, with their
+/// transactions, so strategies on OP stack chains, e.g. Optimism or Base, see blocks
+/// before L1 derivation confirms them.
+///
+/// Blocks are fetched from the execution client `provider` paired with the op-node.
+/// Blocks missed between two polls are fetched in order, up to 64 of them, and a
+/// reorg of the unsafe head emits the new head.
+pub struct OpUnsafeBlockCollector<M> {
+    op_node: Provider<Http>,
+    provider: Arc<M>,
+    poll_interval: Duration,
+}
+
+impl<M> OpUnsafeBlockCollector<M> {
+    /// Creates a collector polling the RPC of the op-node at `op_node_url`, and
+    /// fetching blocks from `provider`.
+    pub fn new(op_node_url: &str, provider: Arc<M>) -> Result<Self> {
+        Ok(Self {
+            op_node: Provider::<Http>::try_from(op_node_url).fatal()?,
+            provider,
+            poll_interval: Duration::from_millis(250),
+        })
+    }
+
+    /// Sets the interval at which the sync status is polled. Defaults to 250ms.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+}
+
+impl<M> OpUnsafeBlockCollector<M>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    /// Returns the current sync status of the op-node.
+    pub async fn sync_status(&self) -> anyhow::Result<SyncStatus> {
+        Ok(self.op_node.request("optimism_syncStatus", ()).await?)
+    }
+
+    /// Returns the unsafe blocks since `last`, the last unsafe head emitted.
+    async fn new_blocks(
+        &self,
+        status: &SyncStatus,
+        last: Option<L2BlockRef>,
+    ) -> anyhow::Result<Vec<Block<Transaction>>> {
+        let head = status.unsafe_l2;
+        let first = match last {
+            Some(last) if last.hash == head.hash => return Ok(vec![]),
+            Some(last) if last.number < head.number => {
+                (last.number + 1).max(head.number.saturating_sub(MAX_CATCH_UP - 1))
+            }
+            _ => head.number,
+        };
+        let mut blocks = vec![];
+        for number in first..head.number {
+            if let Some(block) = self.provider.get_block_with_txs(number).await? {
+                blocks.push(block);
+            }
+        }
+        match self.provider.get_block_with_txs(head.hash).await? {
+            Some(block) => blocks.push(block),
+            None => anyhow::bail!("unsafe head {} not found", head.hash),
+        }
+        Ok(blocks)
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [OpUnsafeBlockCollector](OpUnsafeBlockCollector).
+/// Failed polls are logged and retried at the next interval.
+#[async_trait]
+impl<M> Collector<UnsafeBlock> for OpUnsafeBlockCollector<M>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, UnsafeBlock>> {
+        let mut ticker = interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let state = (ticker, None, VecDeque::new());
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut ticker, mut last, mut pending)| async move {
+                loop {
+                    if let Some(block) = pending.pop_front() {
+                        return Some((block, (ticker, last, pending)));
+                    }
+                    ticker.tick().await;
+                    let status = match self.sync_status().await {
+                        Ok(status) => status,
+                        Err(e) => {
+                            warn!("error fetching op-node sync status: {}", e);
+                            continue;
+                        }
+                    };
+                    match self.new_blocks(&status, last).await {
+                        Ok(blocks) => {
+                            last = Some(status.unsafe_l2);
+                            pending.extend(blocks.into_iter().map(|block| UnsafeBlock {
+                                block,
+                                safe_l2: status.safe_l2,
+                                finalized_l2: status.finalized_l2,
+                            }));
+                        }
+                        Err(e) => warn!("error fetching unsafe blocks: {}", e),
+                    }
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
@@ -1,5 +1,5 @@
+use crate::error::{Result, ResultExt};
 use crate::types::{Collector, CollectorStream};
-use anyhow::Result;
 use async_trait::async_trait;
 use opensea_stream::{
     client,
@@ -36,7 +36,7 @@ impl Collector<OpenseaOrder> for OpenseaOrderCollector {
 
         let collection = Collection::All;
 
-        let (_, subscription) = subscribe_to(&mut client, collection).await?;
+        let (_, subscription) = subscribe_to(&mut client, collection).await.retryable()?;
 
         let stream = BroadcastStream::new(subscription);
 
@@ -0,0 +1,247 @@
+use crate::collectors::beacon_collector::{quoted, BeaconEvent, BeaconEventCollector, BeaconTopic};
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::types::Bytes;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep_until, Instant};
+use tracing::warn;
+
+/// The duty of a validator to propose the block of a slot.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ProposerDuty {
+    pub pubkey: Bytes,
+    #[serde(deserialize_with = "quoted")]
+    pub validator_index: u64,
+    #[serde(deserialize_with = "quoted")]
+    pub slot: u64,
+}
+
+/// An event of the slot clock of the beacon chain, emitted by the
+/// [ProposerCollector](ProposerCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlotEvent {
+    /// The proposer of an upcoming slot became known, up to an epoch ahead.
+    ProposerKnown(ProposerDuty),
+    /// A slot started, with its proposer if known.
+    SlotStart {
+        slot: u64,
+        proposer: Option<ProposerDuty>,
+    },
+    /// No block was seen for a slot by the start of the next one, with the proposer
+    /// that missed it if known.
+    MissedSlot {
+        slot: u64,
+        proposer: Option<ProposerDuty>,
+    },
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct Genesis {
+    #[serde(deserialize_with = "quoted")]
+    genesis_time: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct Spec {
+    #[serde(deserialize_with = "quoted")]
+    seconds_per_slot: u64,
+    #[serde(deserialize_with = "quoted")]
+    slots_per_epoch: u64,
+}
+
+/// The slot clock of the chain.
+#[derive(Clone, Copy)]
+struct SlotClock {
+    genesis_time: u64,
+    seconds_per_slot: u64,
+    slots_per_epoch: u64,
+}
+
+impl SlotClock {
+    /// Returns the slot in progress.
+    fn current_slot(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now.saturating_sub(self.genesis_time) / self.seconds_per_slot
+    }
+
+    /// Returns the instant at which `slot` starts.
+    fn slot_start(&self, slot: u64) -> Instant {
+        let start =
+            UNIX_EPOCH + Duration::from_secs(self.genesis_time + slot * self.seconds_per_slot);
+        let until = start.duration_since(SystemTime::now()).unwrap_or_default();
+        Instant::now() + until
+    }
+
+    fn epoch(&self, slot: u64) -> u64 {
+        slot / self.slots_per_epoch
+    }
+}
+
+/// The state of the stream of the [ProposerCollector](ProposerCollector).
+struct SlotState {
+    /// The next slot to start.
+    next_slot: u64,
+    /// The latest slot a block was seen for.
+    last_block_slot: u64,
+    duties: HashMap<u64, ProposerDuty>,
+    fetched_epochs: HashSet<u64>,
+    pending: VecDeque<SlotEvent>,
+}
+
+/// A collector that follows the slot clock of a beacon node, and generates a stream
+/// of [slot events](SlotEvent): the proposers of upcoming slots as soon as their
+/// duties are known, the start of every slot, and the slots missed by their
+/// proposer, e.g. for timing games and builder-aware submission strategies.
+///
+/// Duties are fetched for the current and next epoch. A slot is missed if no head
+/// event was seen for it by the start of the next slot, so a block arriving later
+/// than that is reported missed.
+pub struct ProposerCollector {
+    client: Client,
+    beacon_url: String,
+    heads: BeaconEventCollector,
+}
+
+impl ProposerCollector {
+    /// Creates a collector following the beacon node at `beacon_url`, e.g.
+    /// `http://localhost:5052`.
+    pub fn new(beacon_url: impl Into<String>) -> Self {
+        let beacon_url = beacon_url.into();
+        Self {
+            client: Client::new(),
+            heads: BeaconEventCollector::new(beacon_url.clone()).with_topics([BeaconTopic::Head]),
+            beacon_url,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        let url = format!("{}{}", self.beacon_url.trim_end_matches('/'), path);
+        let response: Response<T> = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.data)
+    }
+
+    async fn clock(&self) -> anyhow::Result<SlotClock> {
+        let genesis: Genesis = self.get("/eth/v1/beacon/genesis").await?;
+        let spec: Spec = self.get("/eth/v1/config/spec").await?;
+        Ok(SlotClock {
+            genesis_time: genesis.genesis_time,
+            seconds_per_slot: spec.seconds_per_slot,
+            slots_per_epoch: spec.slots_per_epoch,
+        })
+    }
+
+    /// Fetches the duties of the epochs of `slot` and the next one, if not fetched
+    /// yet, and queues the duties of the slots from `slot` on.
+    async fn fetch_duties(&self, clock: &SlotClock, slot: u64, state: &mut SlotState) {
+        let epoch = clock.epoch(slot);
+        for epoch in [epoch, epoch + 1] {
+            if state.fetched_epochs.contains(&epoch) {
+                continue;
+            }
+            let duties: Vec<ProposerDuty> = match self
+                .get(&format!("/eth/v1/validator/duties/proposer/{}", epoch))
+                .await
+            {
+                Ok(duties) => duties,
+                Err(e) => {
+                    warn!("error fetching proposer duties of epoch {}: {}", epoch, e);
+                    continue;
+                }
+            };
+            state.fetched_epochs.insert(epoch);
+            for duty in duties {
+                if duty.slot >= slot {
+                    state
+                        .pending
+                        .push_back(SlotEvent::ProposerKnown(duty.clone()));
+                }
+                state.duties.insert(duty.slot, duty);
+            }
+        }
+    }
+
+    /// Queues the events of the start of the next slot, after checking whether the
+    /// previous one was missed.
+    async fn start_slot(&self, clock: &SlotClock, state: &mut SlotState) {
+        let slot = state.next_slot;
+        state.next_slot += 1;
+        if let Some(previous) = slot.checked_sub(1) {
+            if state.last_block_slot < previous {
+                state.pending.push_back(SlotEvent::MissedSlot {
+                    slot: previous,
+                    proposer: state.duties.get(&previous).cloned(),
+                });
+            }
+        }
+        self.fetch_duties(clock, slot, state).await;
+        state.pending.push_back(SlotEvent::SlotStart {
+            slot,
+            proposer: state.duties.get(&slot).cloned(),
+        });
+
+        // Forget the duties and epochs of past slots.
+        state.duties.retain(|duty_slot, _| *duty_slot >= slot);
+        let epoch = clock.epoch(slot);
+        state.fetched_epochs.retain(|fetched| *fetched >= epoch);
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [ProposerCollector](ProposerCollector).
+/// This implementation subscribes to the head events of the beacon node, and ends
+/// along with them. The stream starts at the next slot.
+#[async_trait]
+impl Collector<SlotEvent> for ProposerCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, SlotEvent>> {
+        let clock = self.clock().await.retryable()?;
+        let heads = self.heads.get_event_stream().await?;
+        let current_slot = clock.current_slot();
+        let state = SlotState {
+            next_slot: current_slot + 1,
+            last_block_slot: current_slot.saturating_sub(1),
+            duties: HashMap::new(),
+            fetched_epochs: HashSet::new(),
+            pending: VecDeque::new(),
+        };
+
+        let stream =
+            futures::stream::unfold((heads, state), move |(mut heads, mut state)| async move {
+                loop {
+                    if let Some(event) = state.pending.pop_front() {
+                        return Some((event, (heads, state)));
+                    }
+                    tokio::select! {
+                        head = heads.next() => {
+                            if let BeaconEvent::Head(head) = head? {
+                                state.last_block_slot = state.last_block_slot.max(head.slot);
+                            }
+                        }
+                        _ = sleep_until(clock.slot_start(state.next_slot)) => {
+                            self.start_slot(&clock, &mut state).await;
+                        }
+                    }
+                }
+            });
+        Ok(Box::pin(stream))
+    }
+}
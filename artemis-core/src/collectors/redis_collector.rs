@@ -0,0 +1,75 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use tracing::warn;
+
+/// A collector that subscribes to Redis pub/sub channels, and generates a stream
+/// of the JSON messages published on them, deserialized into `E`, so external
+/// services such as pricers or risk systems can inject events into a running
+/// engine.
+///
+/// Channels are subscribed to by name, and patterns, e.g. `prices:*`, with
+/// [psubscribe](Self::with_pattern). Messages that fail to deserialize are skipped.
+pub struct RedisCollector<E> {
+    client: redis::Client,
+    channels: Vec<String>,
+    patterns: Vec<String>,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E> RedisCollector<E> {
+    /// Creates a collector for the Redis server at `url`, e.g. `redis://127.0.0.1/`,
+    /// subscribing to the given channels.
+    pub fn new(url: &str, channels: Vec<String>) -> Result<Self> {
+        let client = redis::Client::open(url).fatal()?;
+        Ok(Self {
+            client,
+            channels,
+            patterns: vec![],
+            _event: PhantomData,
+        })
+    }
+
+    /// Also subscribes to the channels matching `pattern`.
+    pub fn with_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [RedisCollector](RedisCollector).
+/// The stream ends when the connection to the server is lost.
+#[async_trait]
+impl<E> Collector<E> for RedisCollector<E>
+where
+    E: DeserializeOwned + Send + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, E>> {
+        let mut pubsub = self.client.get_async_pubsub().await.retryable()?;
+        for channel in &self.channels {
+            pubsub.subscribe(channel).await.retryable()?;
+        }
+        for pattern in &self.patterns {
+            pubsub.psubscribe(pattern).await.retryable()?;
+        }
+
+        let stream = pubsub.into_on_message().filter_map(|message| {
+            let event = match serde_json::from_slice(message.get_payload_bytes()) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    warn!(
+                        "error deserializing message of Redis channel {}: {}",
+                        message.get_channel_name(),
+                        e
+                    );
+                    None
+                }
+            };
+            futures::future::ready(event)
+        });
+        Ok(Box::pin(stream))
+    }
+}
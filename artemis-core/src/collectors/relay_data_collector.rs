@@ -0,0 +1,184 @@
+use crate::collectors::beacon_collector::quoted;
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::types::{Address, Bytes, H256, U256};
+use reqwest::Client;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+/// The Flashbots relay on mainnet.
+pub const FLASHBOTS_RELAY_URL: &str = "https://boost-relay.flashbots.net";
+
+/// The trace of a bid of a builder for a slot, as returned by the relay data API.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BidTrace {
+    #[serde(deserialize_with = "quoted")]
+    pub slot: u64,
+    pub parent_hash: H256,
+    pub block_hash: H256,
+    pub builder_pubkey: Bytes,
+    pub proposer_pubkey: Bytes,
+    pub proposer_fee_recipient: Address,
+    #[serde(deserialize_with = "quoted")]
+    pub gas_limit: u64,
+    #[serde(deserialize_with = "quoted")]
+    pub gas_used: u64,
+    /// The value of the bid paid to the proposer, in wei.
+    #[serde(deserialize_with = "decimal")]
+    pub value: U256,
+    #[serde(deserialize_with = "quoted")]
+    pub block_number: u64,
+    #[serde(deserialize_with = "quoted")]
+    pub num_tx: u64,
+}
+
+/// Deserializes an integer encoded as a decimal string.
+fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<U256, D::Error> {
+    U256::from_dec_str(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// An event of a MEV-Boost relay, emitted by the [RelayDataCollector](RelayDataCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayEvent {
+    /// The relay delivered the payload of the winning bid of a slot to its proposer.
+    PayloadDelivered { relay: String, trace: BidTrace },
+    /// The bids the relay received for a slot whose payload it delivered, including
+    /// the winning one, if the collector fetches them.
+    BuilderBids {
+        relay: String,
+        slot: u64,
+        bids: Vec<BidTrace>,
+    },
+}
+
+/// A collector that polls the data API of MEV-Boost relays, and generates a stream
+/// of [events](RelayEvent) about which builder won each slot and at what bid, and
+/// optionally the bids it competed against, as feedback on the competitive
+/// landscape.
+///
+/// The first poll of a relay only emits the latest slot it delivered, and failed
+/// polls are logged and retried at the next interval.
+pub struct RelayDataCollector {
+    client: Client,
+    relays: Vec<String>,
+    poll_interval: Duration,
+    builder_bids: bool,
+}
+
+impl RelayDataCollector {
+    /// Creates a collector polling the relays at `relays`, e.g.
+    /// [FLASHBOTS_RELAY_URL](FLASHBOTS_RELAY_URL).
+    pub fn new(relays: Vec<String>) -> Self {
+        Self {
+            client: Client::new(),
+            relays,
+            poll_interval: Duration::from_secs(12),
+            builder_bids: false,
+        }
+    }
+
+    /// Sets how often the relays are polled. Defaults to 12 seconds, one slot.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Also fetches and emits the bids received for every delivered slot, which is
+    /// one more request per slot and relay.
+    pub fn with_builder_bids(mut self) -> Self {
+        self.builder_bids = true;
+        self
+    }
+
+    async fn get(&self, relay: &str, path: &str) -> anyhow::Result<Vec<BidTrace>> {
+        let url = format!(
+            "{}/relay/v1/data/bidtraces/{}",
+            relay.trim_end_matches('/'),
+            path
+        );
+        let traces = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(traces)
+    }
+
+    /// Returns the events of the slots `relay` delivered since `last_slot`, which is
+    /// updated, in increasing slot order.
+    async fn poll(&self, relay: &str, last_slot: &mut Option<u64>) -> Vec<RelayEvent> {
+        let mut traces = match self.get(relay, "proposer_payload_delivered").await {
+            Ok(traces) => traces,
+            Err(e) => {
+                warn!("error polling relay {}: {}", relay, e);
+                return vec![];
+            }
+        };
+        traces.sort_by_key(|trace| trace.slot);
+        let new: Vec<_> = match *last_slot {
+            Some(last_slot) => traces
+                .into_iter()
+                .filter(|trace| trace.slot > last_slot)
+                .collect(),
+            None => traces.pop().into_iter().collect(),
+        };
+        let mut events = vec![];
+        for trace in new {
+            *last_slot = Some(trace.slot);
+            let slot = trace.slot;
+            events.push(RelayEvent::PayloadDelivered {
+                relay: relay.to_string(),
+                trace,
+            });
+            if self.builder_bids {
+                match self
+                    .get(relay, &format!("builder_blocks_received?slot={}", slot))
+                    .await
+                {
+                    Ok(bids) => events.push(RelayEvent::BuilderBids {
+                        relay: relay.to_string(),
+                        slot,
+                        bids,
+                    }),
+                    Err(e) => warn!("error fetching bids of slot {} from {}: {}", slot, relay, e),
+                }
+            }
+        }
+        events
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [RelayDataCollector](RelayDataCollector).
+/// Relays are polled one after the other.
+#[async_trait]
+impl Collector<RelayEvent> for RelayDataCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, RelayEvent>> {
+        let mut ticker = interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let state = (ticker, HashMap::new(), VecDeque::new());
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut ticker, mut last_slots, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (ticker, last_slots, pending)));
+                    }
+                    ticker.tick().await;
+                    for relay in &self.relays {
+                        let last_slot = last_slots.entry(relay.as_str()).or_insert(None);
+                        pending.extend(self.poll(relay, last_slot).await);
+                    }
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
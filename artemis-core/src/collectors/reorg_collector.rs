@@ -0,0 +1,220 @@
+use crate::error::{Result, ResultExt};
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Block, H256},
+};
+use futures::StreamExt;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// A change of the canonical chain, emitted by the [ReorgCollector](ReorgCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorgEvent {
+    /// The number of blocks orphaned by the reorg.
+    pub depth: u64,
+    /// The tip of the chain before the reorg.
+    pub old_tip: H256,
+    /// The tip of the chain after the reorg.
+    pub new_tip: H256,
+    /// The hashes of the orphaned blocks, oldest first.
+    pub orphaned_blocks: Vec<H256>,
+}
+
+/// A collector that tracks the hashes of recent blocks, and generates a stream of
+/// [events](ReorgEvent) whenever the canonical chain changes, so strategies can
+/// invalidate the state they built on orphaned blocks.
+///
+/// Reorgs deeper than the tracked window are reported as orphaning the whole
+/// window. Blocks seen before the stream was created are forgotten when it is
+/// re-created, so reorgs across a reconnect may go unnoticed.
+pub struct ReorgCollector<M> {
+    provider: Arc<M>,
+    /// The number of recent blocks tracked.
+    max_depth: usize,
+    /// The hashes of the recent canonical blocks, by number.
+    chain: Mutex<BTreeMap<u64, H256>>,
+}
+
+impl<M> ReorgCollector<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self {
+            provider,
+            max_depth: 64,
+            chain: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Sets the number of recent blocks tracked, which bounds the depth of the
+    /// reorgs detected. Defaults to 64 blocks.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth.max(1);
+        self
+    }
+
+    /// Returns the hash of the tracked block with the given number, if any.
+    fn canonical(&self, number: u64) -> Option<H256> {
+        self.chain.lock().unwrap().get(&number).copied()
+    }
+
+    /// Makes `branch`, ordered from the new tip down, the canonical chain from its
+    /// lowest block on, and returns the reorg if any tracked block was orphaned.
+    fn extend(&self, branch: Vec<(u64, H256)>) -> Option<ReorgEvent> {
+        let (lowest, _) = *branch.last()?;
+        let (_, new_tip) = *branch.first()?;
+        let mut chain = self.chain.lock().unwrap();
+        let old_tip = chain.values().next_back().copied();
+
+        let replaced = chain.split_off(&lowest);
+        let orphaned_blocks: Vec<H256> = replaced
+            .into_iter()
+            .filter(|block| !branch.contains(block))
+            .map(|(_, hash)| hash)
+            .collect();
+        chain.extend(branch);
+        while chain.len() > self.max_depth {
+            chain.pop_first();
+        }
+
+        if orphaned_blocks.is_empty() {
+            return None;
+        }
+        Some(ReorgEvent {
+            depth: orphaned_blocks.len() as u64,
+            old_tip: old_tip.unwrap_or_default(),
+            new_tip,
+            orphaned_blocks,
+        })
+    }
+}
+
+impl<M> ReorgCollector<M>
+where
+    M: Middleware,
+{
+    /// Records a new block, walking back its ancestors until they join the tracked
+    /// chain, and returns the reorg it caused, if any.
+    async fn observe(&self, block: Block<H256>) -> Option<ReorgEvent> {
+        let (Some(hash), Some(number)) = (block.hash, block.number) else {
+            return None;
+        };
+        let number = number.as_u64();
+        if self.canonical(number) == Some(hash) {
+            return None;
+        }
+
+        let mut branch = vec![(number, hash)];
+        let mut parent = block.parent_hash;
+        let mut height = number;
+        while height > 0 && branch.len() < self.max_depth {
+            match self.canonical(height - 1) {
+                // The branch joins the tracked chain, or extends beyond it.
+                Some(known) if known == parent => break,
+                None => break,
+                Some(_) => {}
+            }
+            let ancestor = match self.provider.get_block(parent).await {
+                Ok(Some(ancestor)) => ancestor,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("error fetching block {:?}: {}", parent, e);
+                    break;
+                }
+            };
+            height -= 1;
+            branch.push((height, parent));
+            parent = ancestor.parent_hash;
+        }
+        self.extend(branch)
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [ReorgCollector](ReorgCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new blocks.
+#[async_trait]
+impl<M> Collector<ReorgEvent> for ReorgCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, ReorgEvent>> {
+        self.chain.lock().unwrap().clear();
+        let stream = self.provider.subscribe_blocks().await.retryable()?;
+        let stream = stream.filter_map(move |block| self.observe(block));
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(n: u64) -> H256 {
+        H256::from_low_u64_be(n)
+    }
+
+    /// Returns a collector tracking blocks 1 to `tip`, with hashes equal to their number.
+    fn collector(tip: u64) -> ReorgCollector<()> {
+        let collector = ReorgCollector::new(Arc::new(()));
+        for number in 1..=tip {
+            assert_eq!(collector.extend(vec![(number, hash(number))]), None);
+        }
+        collector
+    }
+
+    #[test]
+    fn test_linear_extension() {
+        let collector = collector(3);
+        assert_eq!(collector.extend(vec![(4, hash(4))]), None);
+        assert_eq!(collector.canonical(4), Some(hash(4)));
+        assert_eq!(collector.canonical(1), Some(hash(1)));
+    }
+
+    #[test]
+    fn test_one_block_reorg() {
+        let collector = collector(3);
+        let event = collector.extend(vec![(3, hash(103))]);
+        assert_eq!(
+            event,
+            Some(ReorgEvent {
+                depth: 1,
+                old_tip: hash(3),
+                new_tip: hash(103),
+                orphaned_blocks: vec![hash(3)],
+            })
+        );
+        assert_eq!(collector.canonical(3), Some(hash(103)));
+    }
+
+    #[test]
+    fn test_deep_reorg() {
+        let collector = collector(5);
+        let branch = vec![(5, hash(105)), (4, hash(104)), (3, hash(103))];
+        let event = collector.extend(branch);
+        assert_eq!(
+            event,
+            Some(ReorgEvent {
+                depth: 3,
+                old_tip: hash(5),
+                new_tip: hash(105),
+                orphaned_blocks: vec![hash(3), hash(4), hash(5)],
+            })
+        );
+        assert_eq!(collector.canonical(2), Some(hash(2)));
+        assert_eq!(collector.canonical(3), Some(hash(103)));
+    }
+
+    #[test]
+    fn test_tracked_window_is_bounded() {
+        let collector = ReorgCollector::new(Arc::new(())).with_max_depth(2);
+        for number in 1..=3 {
+            collector.extend(vec![(number, hash(number))]);
+        }
+        assert_eq!(collector.canonical(1), None);
+        assert_eq!(collector.canonical(3), Some(hash(3)));
+    }
+}
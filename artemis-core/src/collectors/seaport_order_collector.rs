@@ -0,0 +1,342 @@
+use crate::collectors::ws::{ws_stream, Keepalive, WsOptions};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::types::{Address, Bytes, H256, U256};
+use serde::{de, Deserialize, Deserializer};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio_stream::StreamExt;
+use tracing::{debug, warn};
+
+/// The OpenSea Stream API on mainnet.
+pub const OPENSEA_STREAM_URL: &str = "wss://stream.openseabeta.com/socket/websocket";
+
+/// Seaport 1.5, the protocol address of most OpenSea listings.
+pub const SEAPORT_1_5: &str = "0x00000000000000ADc04C56Bf30aC9d3c0aAF14dC";
+
+/// Seaport 1.6.
+pub const SEAPORT_1_6: &str = "0x0000000000000068F116a894984e2DB1123eB395";
+
+/// How often the Phoenix channel expects a heartbeat.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The type of an item of a Seaport order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemType {
+    Native,
+    Erc20,
+    Erc721,
+    Erc1155,
+    Erc721WithCriteria,
+    Erc1155WithCriteria,
+}
+
+impl<'de> Deserialize<'de> for ItemType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match u8::try_from(numeric(deserializer)?).map_err(de::Error::custom)? {
+            0 => Ok(ItemType::Native),
+            1 => Ok(ItemType::Erc20),
+            2 => Ok(ItemType::Erc721),
+            3 => Ok(ItemType::Erc1155),
+            4 => Ok(ItemType::Erc721WithCriteria),
+            5 => Ok(ItemType::Erc1155WithCriteria),
+            n => Err(de::Error::custom(format!("invalid item type {}", n))),
+        }
+    }
+}
+
+/// An item offered by the offerer of a Seaport order.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferItem {
+    pub item_type: ItemType,
+    pub token: Address,
+    #[serde(deserialize_with = "numeric")]
+    pub identifier_or_criteria: U256,
+    #[serde(deserialize_with = "numeric")]
+    pub start_amount: U256,
+    #[serde(deserialize_with = "numeric")]
+    pub end_amount: U256,
+}
+
+/// An item that must be received by `recipient` to fulfill a Seaport order, e.g.
+/// the payment of the seller or the marketplace and creator fees.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsiderationItem {
+    pub item_type: ItemType,
+    pub token: Address,
+    #[serde(deserialize_with = "numeric")]
+    pub identifier_or_criteria: U256,
+    #[serde(deserialize_with = "numeric")]
+    pub start_amount: U256,
+    #[serde(deserialize_with = "numeric")]
+    pub end_amount: U256,
+    pub recipient: Address,
+}
+
+/// The parameters of a Seaport order, as signed by the offerer.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderParameters {
+    pub offerer: Address,
+    pub zone: Address,
+    pub offer: Vec<OfferItem>,
+    pub consideration: Vec<ConsiderationItem>,
+    #[serde(deserialize_with = "numeric")]
+    pub order_type: U256,
+    #[serde(deserialize_with = "numeric")]
+    pub start_time: U256,
+    #[serde(deserialize_with = "numeric")]
+    pub end_time: U256,
+    pub zone_hash: H256,
+    #[serde(deserialize_with = "numeric")]
+    pub salt: U256,
+    pub conduit_key: H256,
+    #[serde(deserialize_with = "numeric")]
+    pub total_original_consideration_items: U256,
+    #[serde(default, deserialize_with = "numeric")]
+    pub counter: U256,
+}
+
+/// A validated Seaport listing, emitted by the [SeaportOrderCollector](SeaportOrderCollector).
+/// It carries the signed order, so it can be fulfilled directly against the Seaport
+/// contract without fetching it from the OpenSea API first.
+#[derive(Debug, Clone)]
+pub struct SeaportOrder {
+    pub order_hash: H256,
+    /// The slug of the collection of the listed item.
+    pub collection: String,
+    /// The Seaport contract the order was signed for.
+    pub protocol_address: Address,
+    pub parameters: OrderParameters,
+    pub signature: Bytes,
+}
+
+impl SeaportOrder {
+    /// Returns the total amount of native tokens the fulfiller must pay, at the start
+    /// price of the order.
+    pub fn native_price(&self) -> U256 {
+        self.parameters
+            .consideration
+            .iter()
+            .filter(|item| item.item_type == ItemType::Native)
+            .fold(U256::zero(), |total, item| {
+                total.saturating_add(item.start_amount)
+            })
+    }
+
+    /// Checks that the order can be fulfilled at `now`, in seconds since the Unix
+    /// epoch: it was signed for a known Seaport contract by a non-zero offerer, it
+    /// is active, and it offers and asks for at least one item.
+    pub fn validate(&self, seaport: &[Address], now: u64) -> std::result::Result<(), &'static str> {
+        let parameters = &self.parameters;
+        if !seaport.contains(&self.protocol_address) {
+            return Err("unknown protocol address");
+        }
+        if parameters.offerer.is_zero() {
+            return Err("zero offerer");
+        }
+        if parameters.offer.is_empty() || parameters.consideration.is_empty() {
+            return Err("empty offer or consideration");
+        }
+        if parameters.total_original_consideration_items > parameters.consideration.len().into() {
+            return Err("missing consideration items");
+        }
+        if parameters.start_time > now.into() || parameters.end_time <= now.into() {
+            return Err("inactive order");
+        }
+        if self.signature.is_empty() {
+            return Err("missing signature");
+        }
+        Ok(())
+    }
+}
+
+/// An integer encoded as a JSON number, a decimal string or a hex string.
+fn numeric<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<U256, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Numeric {
+        Number(u64),
+        String(String),
+    }
+    match Numeric::deserialize(deserializer)? {
+        Numeric::Number(n) => Ok(n.into()),
+        Numeric::String(s) => match s.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16).map_err(de::Error::custom),
+            None => U256::from_dec_str(&s).map_err(de::Error::custom),
+        },
+    }
+}
+
+/// A message of the Phoenix channel of the OpenSea Stream API.
+#[derive(Deserialize)]
+struct ChannelMessage {
+    event: String,
+    #[serde(default)]
+    payload: serde_json::Value,
+}
+
+/// The payload of an `item_listed` event.
+#[derive(Deserialize)]
+struct ItemListed {
+    payload: ItemListedPayload,
+}
+
+#[derive(Deserialize)]
+struct ItemListedPayload {
+    order_hash: H256,
+    collection: CollectionSlug,
+    protocol_address: Address,
+    protocol_data: ProtocolData,
+}
+
+#[derive(Deserialize)]
+struct CollectionSlug {
+    slug: String,
+}
+
+#[derive(Deserialize)]
+struct ProtocolData {
+    parameters: OrderParameters,
+    signature: Option<Bytes>,
+}
+
+impl SeaportOrder {
+    /// Parses a message of the OpenSea Stream API, returning `None` for any message
+    /// other than a listing.
+    fn parse(message: &str) -> serde_json::Result<Option<Self>> {
+        let message: ChannelMessage = serde_json::from_str(message)?;
+        if message.event != "item_listed" {
+            debug!("ignoring OpenSea {} message", message.event);
+            return Ok(None);
+        }
+        let listing: ItemListed = serde_json::from_value(message.payload)?;
+        let listing = listing.payload;
+        Ok(Some(Self {
+            order_hash: listing.order_hash,
+            collection: listing.collection.slug,
+            protocol_address: listing.protocol_address,
+            parameters: listing.protocol_data.parameters,
+            signature: listing.protocol_data.signature.unwrap_or_default(),
+        }))
+    }
+}
+
+/// A collector that subscribes to the listings of the OpenSea Stream API, and
+/// generates a stream of validated [Seaport orders](SeaportOrder).
+///
+/// Unlike the [OpenseaOrderCollector](crate::collectors::opensea_order_collector::OpenseaOrderCollector),
+/// which only emits the listing metadata, each event carries the signed Seaport
+/// order. Orders that fail to parse, or that fail [validation](SeaportOrder::validate),
+/// e.g. because they are already expired, are skipped.
+pub struct SeaportOrderCollector {
+    url: String,
+    api_key: String,
+    /// The slugs of the collections subscribed to, `*` for every collection.
+    collections: Vec<String>,
+    /// The Seaport contracts orders may be signed for.
+    seaport: Vec<Address>,
+}
+
+impl SeaportOrderCollector {
+    /// Creates a collector subscribing to the listings of every collection on
+    /// mainnet, authenticated with the given OpenSea API key.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            url: OPENSEA_STREAM_URL.to_string(),
+            api_key: api_key.into(),
+            collections: vec!["*".to_string()],
+            seaport: [SEAPORT_1_5, SEAPORT_1_6]
+                .iter()
+                .map(|address| address.parse().expect("valid address"))
+                .collect(),
+        }
+    }
+
+    /// Streams from the given url, e.g. that of the testnets API.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Only subscribes to the listings of the collections with the given slugs.
+    pub fn with_collections(mut self, collections: impl IntoIterator<Item = String>) -> Self {
+        self.collections = collections.into_iter().collect();
+        self
+    }
+
+    /// Sets the Seaport contracts orders may be signed for. Defaults to Seaport 1.5
+    /// and 1.6.
+    pub fn with_seaport(mut self, seaport: Vec<Address>) -> Self {
+        self.seaport = seaport;
+        self
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [SeaportOrderCollector](SeaportOrderCollector).
+/// This implementation joins a Phoenix channel per collection, and keeps them alive
+/// with heartbeats. The stream ends when the websocket closes.
+#[async_trait]
+impl Collector<SeaportOrder> for SeaportOrderCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, SeaportOrder>> {
+        let url = format!("{}?token={}", self.url, self.api_key);
+        let joins = self
+            .collections
+            .iter()
+            .enumerate()
+            .map(|(i, slug)| {
+                serde_json::json!({
+                    "topic": format!("collection:{}", slug),
+                    "event": "phx_join",
+                    "payload": {},
+                    "ref": i,
+                })
+                .to_string()
+            })
+            .collect();
+        let mut heartbeat_ref = self.collections.len();
+        let keepalive = Keepalive {
+            interval: HEARTBEAT_INTERVAL,
+            message: Box::new(move || {
+                heartbeat_ref += 1;
+                serde_json::json!({
+                    "topic": "phoenix",
+                    "event": "heartbeat",
+                    "payload": {},
+                    "ref": heartbeat_ref,
+                })
+                .to_string()
+            }),
+        };
+
+        let options = WsOptions {
+            subscriptions: joins,
+            keepalive: Some(keepalive),
+            ..Default::default()
+        };
+        let stream = ws_stream(url, options).await?;
+        let stream = stream.filter_map(move |message| {
+            let order = match SeaportOrder::parse(&message) {
+                Ok(order) => order?,
+                Err(e) => {
+                    warn!("error parsing OpenSea listing: {}", e);
+                    return None;
+                }
+            };
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            match order.validate(&self.seaport, now) {
+                Ok(()) => Some(order),
+                Err(reason) => {
+                    debug!("skipping Seaport order {:?}: {}", order.order_hash, reason);
+                    None
+                }
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+
+use futures::{stream, Stream, StreamExt};
+use tracing::warn;
+
+/// An event received from a server-sent event stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SseEvent {
+    /// The name of the event, `message` if unnamed.
+    pub(crate) event: String,
+    /// The data of the event, with multiple data lines joined by newlines.
+    pub(crate) data: String,
+}
+
+/// Parses a stream of bytes, e.g. the body of a `text/event-stream` response, into
+/// server-sent events. Comments and events without data are skipped. The stream
+/// ends along with the body, or at the first error reading it.
+pub(crate) fn sse_stream<'a, S, B, E>(body: S) -> impl Stream<Item = SseEvent> + Send + 'a
+where
+    S: Stream<Item = Result<B, E>> + Send + 'a,
+    B: AsRef<[u8]>,
+    E: Display,
+{
+    let state = (Box::pin(body), Vec::new(), VecDeque::new());
+    stream::unfold(state, |(mut body, mut buffer, mut pending)| async move {
+        loop {
+            if let Some(event) = pending.pop_front() {
+                return Some((event, (body, buffer, pending)));
+            }
+            match body.next().await? {
+                Ok(chunk) => {
+                    buffer.extend(chunk.as_ref().iter().filter(|byte| **byte != b'\r'));
+                    while let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+                        let block: Vec<u8> = buffer.drain(..end + 2).collect();
+                        pending.extend(parse_event(&String::from_utf8_lossy(&block)));
+                    }
+                }
+                Err(e) => {
+                    warn!("error reading event stream: {}", e);
+                    return None;
+                }
+            }
+        }
+    })
+}
+
+/// Parses the lines of a single event.
+fn parse_event(block: &str) -> Option<SseEvent> {
+    let mut event = None;
+    let mut data: Vec<&str> = vec![];
+    for line in block.lines() {
+        if line.is_empty() || line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event = Some(value.to_string()),
+            "data" => data.push(value),
+            _ => {}
+        }
+    }
+    if data.is_empty() {
+        return None;
+    }
+    Some(SseEvent {
+        event: event.unwrap_or_else(|| "message".to_string()),
+        data: data.join("\n"),
+    })
+}
@@ -0,0 +1,213 @@
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// The Telegram Bot API.
+pub const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// How long to wait before polling again after a failed poll.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// An operator command, e.g. `/setparam min_profit 0.1`, emitted by the
+/// [TelegramCollector](TelegramCollector) and the
+/// [DiscordCollector](crate::collectors::discord_collector::DiscordCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    /// The name of the command, without the leading prefix nor the bot mention,
+    /// e.g. `setparam`.
+    pub name: String,
+    /// The whitespace-separated arguments of the command.
+    pub args: Vec<String>,
+    /// The chat the command was sent in, e.g. to reply to it: the chat id on
+    /// Telegram, and the channel id on Discord.
+    pub chat_id: i64,
+    /// The username of the sender, if any.
+    pub from: Option<String>,
+}
+
+impl Command {
+    /// Parses the text of a message into a command, returning `None` if it is not
+    /// one, i.e. if it does not start with a slash.
+    pub fn parse(text: &str, chat_id: i64, from: Option<String>) -> Option<Self> {
+        Self::parse_with_prefix(text, '/', chat_id, from)
+    }
+
+    /// Parses the text of a message into a command, returning `None` if it does not
+    /// start with `prefix`.
+    pub fn parse_with_prefix(
+        text: &str,
+        prefix: char,
+        chat_id: i64,
+        from: Option<String>,
+    ) -> Option<Self> {
+        let mut words = text.split_whitespace();
+        let name = words.next()?.strip_prefix(prefix)?;
+        let name = name.split('@').next().unwrap_or(name);
+        if name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            args: words.map(str::to_string).collect(),
+            chat_id,
+            from,
+        })
+    }
+}
+
+/// The response of the `getUpdates` method.
+#[derive(Deserialize)]
+struct Updates {
+    ok: bool,
+    #[serde(default)]
+    result: Vec<Update>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    chat: Chat,
+    from: Option<User>,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+#[derive(Deserialize)]
+struct User {
+    username: Option<String>,
+}
+
+/// A collector that long-polls the updates of a Telegram bot, and generates a
+/// stream of the [commands](Command) sent to it, so operators can pause, inspect
+/// or tune a running strategy from Telegram.
+///
+/// Only the commands sent in the allowed chats are emitted, since anyone can message
+/// a bot. Other messages are skipped.
+pub struct TelegramCollector {
+    client: Client,
+    api_url: String,
+    token: String,
+    allowed_chats: Vec<i64>,
+    poll_timeout: Duration,
+}
+
+impl TelegramCollector {
+    /// Creates a collector for the bot with the given token, emitting the commands
+    /// sent in the `allowed_chats`.
+    pub fn new(token: impl Into<String>, allowed_chats: Vec<i64>) -> Self {
+        Self {
+            client: Client::new(),
+            api_url: TELEGRAM_API_URL.to_string(),
+            token: token.into(),
+            allowed_chats,
+            poll_timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Polls the API at `api_url`, e.g. a local Bot API server.
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
+    /// Sets how long each poll waits for new updates. Defaults to 30 seconds.
+    pub fn with_poll_timeout(mut self, poll_timeout: Duration) -> Self {
+        self.poll_timeout = poll_timeout;
+        self
+    }
+
+    /// Fetches the updates from `offset`, returning the commands and the offset of
+    /// the next poll.
+    async fn poll(&self, offset: i64) -> anyhow::Result<(Vec<Command>, i64)> {
+        let url = format!(
+            "{}/bot{}/getUpdates",
+            self.api_url.trim_end_matches('/'),
+            self.token
+        );
+        let timeout = self.poll_timeout.as_secs();
+        let updates: Updates = self
+            .client
+            .get(url)
+            .query(&[
+                ("offset", offset.to_string()),
+                ("timeout", timeout.to_string()),
+                ("allowed_updates", r#"["message"]"#.to_string()),
+            ])
+            .timeout(self.poll_timeout + Duration::from_secs(10))
+            .send()
+            .await?
+            .json()
+            .await?;
+        if !updates.ok {
+            anyhow::bail!(
+                "getUpdates failed: {}",
+                updates.description.unwrap_or_default()
+            );
+        }
+
+        let next_offset = updates
+            .result
+            .iter()
+            .map(|update| update.update_id + 1)
+            .max()
+            .unwrap_or(offset);
+        let commands = updates
+            .result
+            .into_iter()
+            .filter_map(|update| {
+                let message = update.message?;
+                if !self.allowed_chats.contains(&message.chat.id) {
+                    debug!("ignoring Telegram message from chat {}", message.chat.id);
+                    return None;
+                }
+                let from = message.from.and_then(|user| user.username);
+                Command::parse(&message.text?, message.chat.id, from)
+            })
+            .collect();
+        Ok((commands, next_offset))
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [TelegramCollector](TelegramCollector).
+/// Updates are acknowledged as they are fetched, so commands sent while the engine
+/// is down are emitted once it restarts, but not twice. Failed polls are logged
+/// and retried.
+#[async_trait]
+impl Collector<Command> for TelegramCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Command>> {
+        let state = (0, VecDeque::new());
+        let stream = futures::stream::unfold(state, move |(mut offset, mut pending)| async move {
+            loop {
+                if let Some(command) = pending.pop_front() {
+                    return Some((command, (offset, pending)));
+                }
+                match self.poll(offset).await {
+                    Ok((commands, next_offset)) => {
+                        pending.extend(commands);
+                        offset = next_offset;
+                    }
+                    Err(e) => {
+                        warn!("error polling Telegram updates: {}", e);
+                        tokio::time::sleep(RETRY_DELAY).await;
+                    }
+                }
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
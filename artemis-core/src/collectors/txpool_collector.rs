@@ -0,0 +1,112 @@
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    prelude::Middleware,
+    types::{Transaction, TxpoolContent, H256},
+};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+/// A collector that periodically polls `txpool_content` from a Geth or Erigon node,
+/// and generates a stream of the [transactions](Transaction) that entered the pool
+/// since the last poll, as the same events as the
+/// [MempoolCollector](crate::collectors::mempool_collector::MempoolCollector).
+///
+/// It is a fallback for providers without pending transaction subscriptions, or
+/// rate limiting them. Transactions that enter and leave the pool between two polls
+/// are missed. The first poll emits the whole pool.
+pub struct TxpoolCollector<M> {
+    provider: Arc<M>,
+    poll_interval: Duration,
+    include_queued: bool,
+}
+
+impl<M> TxpoolCollector<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self {
+            provider,
+            poll_interval: Duration::from_secs(1),
+            include_queued: false,
+        }
+    }
+
+    /// Sets the interval at which the pool is polled. Defaults to 1s.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Also emits the queued transactions, which have a nonce gap and can't be
+    /// included yet, and not only the pending ones.
+    pub fn with_queued(mut self) -> Self {
+        self.include_queued = true;
+        self
+    }
+}
+
+/// Returns the transactions of `content` not in `seen`, and replaces `seen` with the
+/// hashes of the pool, so it doesn't grow beyond the size of the pool.
+fn new_transactions(
+    content: TxpoolContent,
+    include_queued: bool,
+    seen: &mut HashSet<H256>,
+) -> Vec<Transaction> {
+    let queued = if include_queued {
+        content.queued
+    } else {
+        Default::default()
+    };
+    let txs: Vec<_> = content
+        .pending
+        .into_values()
+        .chain(queued.into_values())
+        .flat_map(|txs| txs.into_values())
+        .collect();
+    let hashes = txs.iter().map(|tx| tx.hash).collect();
+    let new = txs
+        .into_iter()
+        .filter(|tx| !seen.contains(&tx.hash))
+        .collect();
+    *seen = hashes;
+    new
+}
+
+/// Implementation of the [Collector](Collector) trait for the [TxpoolCollector](TxpoolCollector).
+/// Failed polls are logged and retried at the next interval.
+#[async_trait]
+impl<M> Collector<Transaction> for TxpoolCollector<M>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Transaction>> {
+        let mut ticker = interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let state = (ticker, HashSet::new(), VecDeque::new());
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut ticker, mut seen, mut pending)| async move {
+                loop {
+                    if let Some(tx) = pending.pop_front() {
+                        return Some((tx, (ticker, seen, pending)));
+                    }
+                    ticker.tick().await;
+                    match self.provider.txpool_content().await {
+                        Ok(content) => pending.extend(new_transactions(
+                            content,
+                            self.include_queued,
+                            &mut seen,
+                        )),
+                        Err(e) => warn!("error fetching txpool content: {}", e),
+                    }
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
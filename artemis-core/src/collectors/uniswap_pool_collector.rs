@@ -0,0 +1,213 @@
+use crate::collectors::log_collector::{DecodedLog, LogCollector};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, RawLog},
+    contract::{EthEvent, EthLogDecode},
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Address, Filter, Log, I256, U256},
+};
+use std::sync::Arc;
+use tokio_stream::StreamExt;
+
+/// A swap on a Uniswap V2 pair.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "Swap",
+    abi = "Swap(address,uint256,uint256,uint256,uint256,address)"
+)]
+pub struct V2Swap {
+    #[ethevent(indexed)]
+    pub sender: Address,
+    pub amount_0_in: U256,
+    pub amount_1_in: U256,
+    pub amount_0_out: U256,
+    pub amount_1_out: U256,
+    #[ethevent(indexed)]
+    pub to: Address,
+}
+
+/// A deposit of liquidity in a Uniswap V2 pair.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Mint", abi = "Mint(address,uint256,uint256)")]
+pub struct V2Mint {
+    #[ethevent(indexed)]
+    pub sender: Address,
+    pub amount_0: U256,
+    pub amount_1: U256,
+}
+
+/// A withdrawal of liquidity from a Uniswap V2 pair.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Burn", abi = "Burn(address,uint256,uint256,address)")]
+pub struct V2Burn {
+    #[ethevent(indexed)]
+    pub sender: Address,
+    pub amount_0: U256,
+    pub amount_1: U256,
+    #[ethevent(indexed)]
+    pub to: Address,
+}
+
+/// The reserves of a Uniswap V2 pair, emitted after every swap, mint and burn.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(name = "Sync", abi = "Sync(uint112,uint112)")]
+pub struct V2Sync {
+    pub reserve_0: u128,
+    pub reserve_1: u128,
+}
+
+/// A swap on a Uniswap V3 pool. The amounts are the deltas of the balances of
+/// the pool, and the price, liquidity and tick are the state of the pool after
+/// the swap.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "Swap",
+    abi = "Swap(address,address,int256,int256,uint160,uint128,int24)"
+)]
+pub struct V3Swap {
+    #[ethevent(indexed)]
+    pub sender: Address,
+    #[ethevent(indexed)]
+    pub recipient: Address,
+    pub amount_0: I256,
+    pub amount_1: I256,
+    pub sqrt_price_x96: U256,
+    pub liquidity: u128,
+    pub tick: i32,
+}
+
+/// A deposit of liquidity in a tick range of a Uniswap V3 pool.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "Mint",
+    abi = "Mint(address,address,int24,int24,uint128,uint256,uint256)"
+)]
+pub struct V3Mint {
+    pub sender: Address,
+    #[ethevent(indexed)]
+    pub owner: Address,
+    #[ethevent(indexed)]
+    pub tick_lower: i32,
+    #[ethevent(indexed)]
+    pub tick_upper: i32,
+    pub amount: u128,
+    pub amount_0: U256,
+    pub amount_1: U256,
+}
+
+/// A withdrawal of liquidity from a tick range of a Uniswap V3 pool.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "Burn",
+    abi = "Burn(address,int24,int24,uint128,uint256,uint256)"
+)]
+pub struct V3Burn {
+    #[ethevent(indexed)]
+    pub owner: Address,
+    #[ethevent(indexed)]
+    pub tick_lower: i32,
+    #[ethevent(indexed)]
+    pub tick_upper: i32,
+    pub amount: u128,
+    pub amount_0: U256,
+    pub amount_1: U256,
+}
+
+/// A decoded event of a Uniswap V2 pair or V3 pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UniswapEvent {
+    V2Swap(V2Swap),
+    V2Mint(V2Mint),
+    V2Burn(V2Burn),
+    V2Sync(V2Sync),
+    V3Swap(V3Swap),
+    V3Mint(V3Mint),
+    V3Burn(V3Burn),
+}
+
+impl EthLogDecode for UniswapEvent {
+    fn decode_log(log: &RawLog) -> std::result::Result<Self, abi::Error> {
+        if let Ok(event) = <V2Swap as EthEvent>::decode_log(log) {
+            return Ok(Self::V2Swap(event));
+        }
+        if let Ok(event) = <V2Mint as EthEvent>::decode_log(log) {
+            return Ok(Self::V2Mint(event));
+        }
+        if let Ok(event) = <V2Burn as EthEvent>::decode_log(log) {
+            return Ok(Self::V2Burn(event));
+        }
+        if let Ok(event) = <V2Sync as EthEvent>::decode_log(log) {
+            return Ok(Self::V2Sync(event));
+        }
+        if let Ok(event) = <V3Swap as EthEvent>::decode_log(log) {
+            return Ok(Self::V3Swap(event));
+        }
+        if let Ok(event) = <V3Mint as EthEvent>::decode_log(log) {
+            return Ok(Self::V3Mint(event));
+        }
+        if let Ok(event) = <V3Burn as EthEvent>::decode_log(log) {
+            return Ok(Self::V3Burn(event));
+        }
+        Err(abi::Error::InvalidData)
+    }
+}
+
+/// An event of a pool, emitted by the [UniswapPoolCollector](UniswapPoolCollector).
+#[derive(Debug, Clone)]
+pub struct PoolEvent {
+    /// The pool that emitted the event.
+    pub pool: Address,
+    pub event: UniswapEvent,
+    /// The raw log, e.g. to order events or to detect logs removed by a reorg.
+    pub log: Log,
+}
+
+/// A collector that subscribes to the swaps, mints, burns and syncs of a set of
+/// Uniswap V2 pairs and V3 pools, and generates a stream of [decoded
+/// events](PoolEvent). V2 and V3 pools can be mixed, and so can their forks
+/// sharing the same events. The logs are collected by a [LogCollector](LogCollector),
+/// so the events missed while reconnecting are backfilled.
+pub struct UniswapPoolCollector<M> {
+    logs: LogCollector<M>,
+}
+
+impl<M> UniswapPoolCollector<M> {
+    pub fn new(provider: Arc<M>, pools: Vec<Address>) -> Self {
+        let filter = Filter::new().address(pools).topic0(vec![
+            V2Swap::signature(),
+            V2Mint::signature(),
+            V2Burn::signature(),
+            V2Sync::signature(),
+            V3Swap::signature(),
+            V3Mint::signature(),
+            V3Burn::signature(),
+        ]);
+        Self {
+            logs: LogCollector::new(provider, filter),
+        }
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [UniswapPoolCollector](UniswapPoolCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to new logs.
+#[async_trait]
+impl<M> Collector<PoolEvent> for UniswapPoolCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, PoolEvent>> {
+        let stream: CollectorStream<'a, DecodedLog<UniswapEvent>> =
+            self.logs.get_event_stream().await?;
+        let stream = stream.map(|DecodedLog { event, log }| PoolEvent {
+            pool: log.address,
+            event,
+            log,
+        });
+        Ok(Box::pin(stream))
+    }
+}
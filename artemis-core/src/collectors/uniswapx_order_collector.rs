@@ -0,0 +1,221 @@
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::types::{Address, Bytes, H256, U256};
+use reqwest::Client;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+
+/// The UniswapX order API.
+pub const UNISWAPX_API_URL: &str = "https://api.uniswap.org/v2";
+
+/// The input of a Dutch order, sold by the swapper. The amount decays from
+/// `start_amount` to `end_amount` over the decay period of the order.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DutchInput {
+    pub token: Address,
+    #[serde(deserialize_with = "decimal")]
+    pub start_amount: U256,
+    #[serde(deserialize_with = "decimal")]
+    pub end_amount: U256,
+}
+
+/// An output of a Dutch order, which the filler must send to `recipient`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DutchOutput {
+    pub token: Address,
+    #[serde(deserialize_with = "decimal")]
+    pub start_amount: U256,
+    #[serde(deserialize_with = "decimal")]
+    pub end_amount: U256,
+    pub recipient: Address,
+}
+
+/// An open UniswapX order, i.e. a signed intent filled through a Dutch auction,
+/// emitted by the [UniswapXOrderCollector](UniswapXOrderCollector).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UniswapXOrder {
+    pub order_hash: H256,
+    pub chain_id: u64,
+    /// The type of the order, e.g. `Dutch` or `Dutch_V2`.
+    pub order_type: String,
+    pub swapper: Address,
+    /// The reactor contract the order must be executed against.
+    pub reactor: Address,
+    /// The ABI encoded order, to pass to the reactor along with the signature.
+    pub encoded_order: Bytes,
+    pub signature: Bytes,
+    /// The start of the decay, in seconds since the Unix epoch.
+    pub decay_start_time: u64,
+    /// The end of the decay, in seconds since the Unix epoch.
+    pub decay_end_time: u64,
+    /// The expiry of the order, in seconds since the Unix epoch.
+    pub deadline: u64,
+    pub input: DutchInput,
+    pub outputs: Vec<DutchOutput>,
+}
+
+impl UniswapXOrder {
+    /// Returns the input amount of the order at `now`, in seconds since the Unix epoch.
+    pub fn input_amount(&self, now: u64) -> U256 {
+        self.decay(self.input.start_amount, self.input.end_amount, now)
+    }
+
+    /// Returns the amount of each output of the order at `now`, in seconds since the
+    /// Unix epoch.
+    pub fn output_amounts(&self, now: u64) -> Vec<U256> {
+        self.outputs
+            .iter()
+            .map(|output| self.decay(output.start_amount, output.end_amount, now))
+            .collect()
+    }
+
+    /// Decays an amount linearly over the decay period, as the Dutch reactors do.
+    fn decay(&self, start: U256, end: U256, now: u64) -> U256 {
+        if now <= self.decay_start_time || start == end {
+            return start;
+        }
+        if now >= self.decay_end_time {
+            return end;
+        }
+        let elapsed = U256::from(now - self.decay_start_time);
+        let duration = U256::from(self.decay_end_time - self.decay_start_time);
+        if end < start {
+            start - (start - end) * elapsed / duration
+        } else {
+            start + (end - start) * elapsed / duration
+        }
+    }
+}
+
+/// Deserializes an integer encoded as a decimal string.
+fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<U256, D::Error> {
+    U256::from_dec_str(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+/// The response of the `/orders` endpoint.
+#[derive(Deserialize)]
+struct Orders {
+    orders: Vec<serde_json::Value>,
+}
+
+/// A collector that polls the open UniswapX orders of a chain, and generates a
+/// stream of the [orders](UniswapXOrder) not seen in the previous poll, with their
+/// decay parameters, so filler strategies can price them as they decay.
+///
+/// Orders that fail to parse, e.g. of an order type without a Dutch decay, are
+/// skipped.
+pub struct UniswapXOrderCollector {
+    client: Client,
+    api_url: String,
+    chain_id: u64,
+    order_type: Option<String>,
+    poll_interval: Duration,
+}
+
+impl UniswapXOrderCollector {
+    /// Creates a collector polling the open orders of the chain `chain_id`.
+    pub fn new(chain_id: u64) -> Self {
+        Self {
+            client: Client::new(),
+            api_url: UNISWAPX_API_URL.to_string(),
+            chain_id,
+            order_type: None,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Polls the API at `api_url`, e.g. through a proxy adding an API key.
+    pub fn with_api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into();
+        self
+    }
+
+    /// Only polls the orders of the given type, e.g. `Dutch_V2`.
+    pub fn with_order_type(mut self, order_type: impl Into<String>) -> Self {
+        self.order_type = Some(order_type.into());
+        self
+    }
+
+    /// Sets how often the open orders are polled. Defaults to 1 second.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    async fn open_orders(&self) -> anyhow::Result<Vec<UniswapXOrder>> {
+        let url = format!("{}/orders", self.api_url.trim_end_matches('/'));
+        let mut query = vec![
+            ("orderStatus", "open".to_string()),
+            ("chainId", self.chain_id.to_string()),
+        ];
+        if let Some(order_type) = &self.order_type {
+            query.push(("orderType", order_type.clone()));
+        }
+        let response: Orders = self
+            .client
+            .get(url)
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let orders = response
+            .orders
+            .into_iter()
+            .filter_map(|order| match serde_json::from_value(order) {
+                Ok(order) => Some(order),
+                Err(e) => {
+                    warn!("error parsing UniswapX order: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Ok(orders)
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [UniswapXOrderCollector](UniswapXOrderCollector).
+/// Failed polls are logged and retried at the next interval.
+#[async_trait]
+impl Collector<UniswapXOrder> for UniswapXOrderCollector {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, UniswapXOrder>> {
+        let mut ticker = interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let state = (ticker, HashSet::new(), VecDeque::new());
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut ticker, mut seen, mut pending)| async move {
+                loop {
+                    if let Some(order) = pending.pop_front() {
+                        return Some((order, (ticker, seen, pending)));
+                    }
+                    ticker.tick().await;
+                    let orders = match self.open_orders().await {
+                        Ok(orders) => orders,
+                        Err(e) => {
+                            warn!("error fetching UniswapX orders: {}", e);
+                            continue;
+                        }
+                    };
+                    let open: HashSet<H256> = orders.iter().map(|order| order.order_hash).collect();
+                    pending.extend(
+                        orders
+                            .into_iter()
+                            .filter(|order| !seen.contains(&order.order_hash)),
+                    );
+                    seen = open;
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
@@ -0,0 +1,317 @@
+use crate::collectors::beacon_collector::{quoted, BeaconEvent, BeaconEventCollector, BeaconTopic};
+use crate::collectors::log_collector::{DecodedLog, LogCollector};
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream};
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, RawLog},
+    contract::{EthEvent, EthLogDecode},
+    prelude::Middleware,
+    providers::PubsubClient,
+    types::{Address, Bytes, Filter, Log, H256},
+};
+use futures::StreamExt;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tracing::warn;
+
+/// The beacon deposit contract on mainnet.
+pub const DEPOSIT_CONTRACT: &str = "0x00000000219ab540356cBB839Cbe05303d7705Fa";
+
+/// How many validators are fetched per request.
+const VALIDATORS_PER_REQUEST: usize = 64;
+
+/// A deposit to the beacon deposit contract, whose integers are little-endian
+/// encoded bytes.
+#[derive(Debug, Clone, PartialEq, Eq, EthEvent)]
+#[ethevent(
+    name = "DepositEvent",
+    abi = "DepositEvent(bytes,bytes,bytes,bytes,bytes)"
+)]
+pub struct DepositEvent {
+    pub pubkey: Bytes,
+    pub withdrawal_credentials: Bytes,
+    pub amount: Bytes,
+    pub signature: Bytes,
+    pub index: Bytes,
+}
+
+/// A decoded deposit to the beacon deposit contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deposit {
+    /// The public key of the validator, which may not exist yet.
+    pub pubkey: Bytes,
+    pub withdrawal_credentials: H256,
+    /// The deposited amount, in gwei.
+    pub amount: u64,
+    /// The index of the deposit in the contract.
+    pub index: u64,
+}
+
+impl EthLogDecode for Deposit {
+    fn decode_log(log: &RawLog) -> std::result::Result<Self, abi::Error> {
+        let event = <DepositEvent as EthEvent>::decode_log(log)?;
+        Ok(Self {
+            pubkey: event.pubkey,
+            withdrawal_credentials: Some(&event.withdrawal_credentials[..])
+                .filter(|credentials| credentials.len() == 32)
+                .map(H256::from_slice)
+                .ok_or(abi::Error::InvalidData)?,
+            amount: little_endian(&event.amount)?,
+            index: little_endian(&event.index)?,
+        })
+    }
+}
+
+fn little_endian(bytes: &[u8]) -> std::result::Result<u64, abi::Error> {
+    let bytes = bytes.try_into().map_err(|_| abi::Error::InvalidData)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// The status of a validator, as reported by the beacon node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    PendingInitialized,
+    PendingQueued,
+    ActiveOngoing,
+    ActiveExiting,
+    ActiveSlashed,
+    ExitedUnslashed,
+    ExitedSlashed,
+    WithdrawalPossible,
+    WithdrawalDone,
+}
+
+impl ValidatorStatus {
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::PendingInitialized | Self::PendingQueued)
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self,
+            Self::ActiveOngoing | Self::ActiveExiting | Self::ActiveSlashed
+        )
+    }
+
+    pub fn is_exited(&self) -> bool {
+        !self.is_pending() && !self.is_active()
+    }
+}
+
+/// The state of a validator, as returned by the beacon API.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ValidatorState {
+    #[serde(deserialize_with = "quoted")]
+    pub index: u64,
+    /// The balance of the validator, in gwei.
+    #[serde(deserialize_with = "quoted")]
+    pub balance: u64,
+    pub status: ValidatorStatus,
+    pub validator: Validator,
+}
+
+/// The registry entry of a validator. Epochs that aren't reached yet are
+/// `u64::MAX`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Validator {
+    pub pubkey: Bytes,
+    pub withdrawal_credentials: H256,
+    /// The effective balance of the validator, in gwei.
+    #[serde(deserialize_with = "quoted")]
+    pub effective_balance: u64,
+    pub slashed: bool,
+    #[serde(deserialize_with = "quoted")]
+    pub activation_epoch: u64,
+    #[serde(deserialize_with = "quoted")]
+    pub exit_epoch: u64,
+    #[serde(deserialize_with = "quoted")]
+    pub withdrawable_epoch: u64,
+}
+
+/// A deposit or a change of the lifecycle of a watched validator, emitted by the
+/// [ValidatorCollector](ValidatorCollector).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidatorEvent {
+    /// A deposit was made to the deposit contract.
+    Deposit { deposit: Deposit, log: Log },
+    /// The validator became active.
+    Activated(ValidatorState),
+    /// The validator initiated its exit, effective at its exit epoch.
+    ExitInitiated(ValidatorState),
+    /// The validator was slashed, which also exits it.
+    Slashed(ValidatorState),
+    /// The validator exited.
+    Exited(ValidatorState),
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+/// Returns the events of the change of a validator from `previous` to `current`.
+fn transitions(previous: &ValidatorState, current: &ValidatorState) -> Vec<ValidatorEvent> {
+    let (from, to) = (previous.status, current.status);
+    let mut events = vec![];
+    if from.is_pending() && to.is_active() {
+        events.push(ValidatorEvent::Activated(current.clone()));
+    }
+    if !previous.validator.slashed && current.validator.slashed {
+        events.push(ValidatorEvent::Slashed(current.clone()));
+    } else if from != ValidatorStatus::ActiveExiting && to == ValidatorStatus::ActiveExiting {
+        events.push(ValidatorEvent::ExitInitiated(current.clone()));
+    }
+    if !from.is_exited() && to.is_exited() {
+        events.push(ValidatorEvent::Exited(current.clone()));
+    }
+    events
+}
+
+/// A collector that listens for deposits to the beacon deposit contract, tracks the
+/// lifecycle of a set of validators, and generates a stream of [typed
+/// events](ValidatorEvent) with their indices and amounts, for staking-adjacent
+/// strategies and monitoring.
+///
+/// Deposits are emitted for every validator, from the logs of the deposit contract.
+/// The watched validators are fetched from the beacon node when the stream starts,
+/// and at every epoch transition, when their status can change; their activations,
+/// exits and slashings are emitted as of the next fetch. Failed fetches are logged
+/// and retried at the next epoch transition.
+pub struct ValidatorCollector<M> {
+    provider: Arc<M>,
+    client: Client,
+    beacon_url: String,
+    heads: BeaconEventCollector,
+    deposits: LogCollector<M>,
+    /// The watched validators, by index or public key.
+    validators: Vec<String>,
+}
+
+impl<M> ValidatorCollector<M> {
+    /// Creates a collector listening to the mainnet deposit contract, and tracking
+    /// validators on the beacon node at `beacon_url`, e.g. `http://localhost:5052`.
+    pub fn new(provider: Arc<M>, beacon_url: impl Into<String>) -> Self {
+        let beacon_url = beacon_url.into();
+        let deposit_contract = DEPOSIT_CONTRACT.parse().expect("valid address");
+        Self {
+            client: Client::new(),
+            heads: BeaconEventCollector::new(beacon_url.clone()).with_topics([BeaconTopic::Head]),
+            deposits: LogCollector::new(provider.clone(), Self::filter(deposit_contract)),
+            provider,
+            beacon_url,
+            validators: vec![],
+        }
+    }
+
+    fn filter(deposit_contract: Address) -> Filter {
+        Filter::new()
+            .address(deposit_contract)
+            .topic0(DepositEvent::signature())
+    }
+
+    /// Listens to the deposit contract at `deposit_contract`, e.g. on a testnet.
+    pub fn with_deposit_contract(mut self, deposit_contract: Address) -> Self {
+        self.deposits = LogCollector::new(self.provider.clone(), Self::filter(deposit_contract));
+        self
+    }
+
+    /// Tracks the lifecycle of `validators`, by index or `0x`-prefixed public key.
+    pub fn with_validators(mut self, validators: impl IntoIterator<Item = String>) -> Self {
+        self.validators.extend(validators);
+        self
+    }
+
+    /// Returns the current states of the watched validators, by index.
+    async fn states(&self) -> anyhow::Result<HashMap<u64, ValidatorState>> {
+        let mut states = HashMap::new();
+        for ids in self.validators.chunks(VALIDATORS_PER_REQUEST) {
+            let url = format!(
+                "{}/eth/v1/beacon/states/head/validators?id={}",
+                self.beacon_url.trim_end_matches('/'),
+                ids.join(",")
+            );
+            let response: Response<Vec<ValidatorState>> = self
+                .client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            states.extend(response.data.into_iter().map(|state| (state.index, state)));
+        }
+        Ok(states)
+    }
+
+    /// Returns the events of the validators whose status changed since `last`, which
+    /// is updated. Validators seen for the first time only set their baseline.
+    async fn changes(&self, last: &mut HashMap<u64, ValidatorState>) -> Vec<ValidatorEvent> {
+        let states = match self.states().await {
+            Ok(states) => states,
+            Err(e) => {
+                warn!("error fetching validators: {}", e);
+                return vec![];
+            }
+        };
+        let mut events = vec![];
+        for (index, state) in states {
+            if let Some(previous) = last.get(&index) {
+                events.extend(transitions(previous, &state));
+            }
+            last.insert(index, state);
+        }
+        events
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [ValidatorCollector](ValidatorCollector).
+/// This implementation uses the [PubsubClient](PubsubClient) to subscribe to the logs
+/// of the deposit contract, subscribes to the head events of the beacon node, and
+/// ends along with either.
+#[async_trait]
+impl<M> Collector<ValidatorEvent> for ValidatorCollector<M>
+where
+    M: Middleware,
+    M::Provider: PubsubClient,
+    M::Error: 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, ValidatorEvent>> {
+        let deposits: CollectorStream<'a, DecodedLog<Deposit>> =
+            self.deposits.get_event_stream().await?;
+        let heads = self.heads.get_event_stream().await?;
+        let mut last = HashMap::new();
+        if !self.validators.is_empty() {
+            self.changes(&mut last).await;
+        }
+
+        let state = (deposits, heads, last, VecDeque::new());
+        let stream = futures::stream::unfold(
+            state,
+            move |(mut deposits, mut heads, mut last, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (deposits, heads, last, pending)));
+                    }
+                    tokio::select! {
+                        deposit = deposits.next() => {
+                            let DecodedLog { event, log } = deposit?;
+                            pending.push_back(ValidatorEvent::Deposit { deposit: event, log });
+                        }
+                        head = heads.next() => {
+                            let epoch_transition =
+                                matches!(head?, BeaconEvent::Head(head) if head.epoch_transition);
+                            if epoch_transition && !self.validators.is_empty() {
+                                pending.extend(self.changes(&mut last).await);
+                            }
+                        }
+                    }
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
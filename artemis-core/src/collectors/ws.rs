@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use futures::{SinkExt, Stream, StreamExt};
+use tokio::{
+    sync::{mpsc, watch},
+    task::JoinHandle,
+};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, Message},
+};
+use tracing::{debug, warn};
+
+use crate::error::{Result, ResultExt};
+
+/// A message sent periodically to keep a websocket subscription alive, e.g. the
+/// heartbeat of a Phoenix channel.
+pub(crate) struct Keepalive {
+    pub(crate) interval: Duration,
+    pub(crate) message: Box<dyn FnMut() -> String + Send>,
+}
+
+/// Answers the text messages of the server, e.g. application level pings. Messages
+/// it returns a reply for are not emitted.
+pub(crate) type Reply = Box<dyn FnMut(&str) -> Option<String> + Send>;
+
+/// How to subscribe to a websocket feed, and keep the subscription alive.
+#[derive(Default)]
+pub(crate) struct WsOptions {
+    /// The messages sent once connected.
+    pub(crate) subscriptions: Vec<String>,
+    pub(crate) keepalive: Option<Keepalive>,
+    /// Changes the interval of the keepalive, e.g. to the one the server asked for
+    /// once connected.
+    pub(crate) keepalive_interval: Option<watch::Receiver<Duration>>,
+    pub(crate) reply: Option<Reply>,
+}
+
+/// Aborts the writer task once the stream is dropped.
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Waits until the keepalive interval changes, and returns the new interval. Never
+/// resolves if the interval is fixed.
+async fn interval_changed(interval: &mut Option<watch::Receiver<Duration>>) -> Option<Duration> {
+    let Some(receiver) = interval else {
+        return std::future::pending().await;
+    };
+    if receiver.changed().await.is_err() {
+        *interval = None;
+        return None;
+    }
+    let period = *receiver.borrow_and_update();
+    Some(period)
+}
+
+/// Connects to a websocket, sends the subscription messages, and returns a stream
+/// of the text messages received, e.g. for collectors consuming JSON feeds. Other
+/// messages are skipped. The stream ends when the websocket closes, or at the first
+/// error reading it.
+pub(crate) async fn ws_stream<R>(
+    request: R,
+    options: WsOptions,
+) -> Result<impl Stream<Item = String> + Send + 'static>
+where
+    R: IntoClientRequest + Unpin,
+{
+    let WsOptions {
+        subscriptions,
+        keepalive,
+        mut keepalive_interval,
+        mut reply,
+    } = options;
+    let (socket, _) = connect_async(request).await.retryable()?;
+    let (mut sink, stream) = socket.split();
+    for message in subscriptions {
+        sink.send(Message::Text(message)).await.retryable()?;
+    }
+
+    // A single task owns the sink, and sends both the keepalives and the replies.
+    let (replies, mut outgoing) = mpsc::unbounded_channel::<String>();
+    let writer = AbortOnDrop(tokio::spawn(async move {
+        let (period, mut keepalive) = match keepalive {
+            Some(keepalive) => (keepalive.interval, Some(keepalive.message)),
+            // The interval is never polled without a keepalive.
+            None => (Duration::from_secs(3600), None),
+        };
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            let message = tokio::select! {
+                message = outgoing.recv() => match message {
+                    Some(message) => message,
+                    None => return,
+                },
+                _ = interval.tick(), if keepalive.is_some() => {
+                    (keepalive.as_mut().expect("keepalive is set"))()
+                }
+                Some(period) = interval_changed(&mut keepalive_interval) => {
+                    interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                    continue;
+                }
+            };
+            if let Err(e) = sink.send(Message::Text(message)).await {
+                warn!("error writing to websocket: {}", e);
+                return;
+            }
+        }
+    }));
+
+    let stream = stream
+        .take_while(|message| {
+            let open = match message {
+                Ok(Message::Close(_)) => false,
+                Ok(_) => true,
+                Err(e) => {
+                    warn!("websocket error: {}", e);
+                    false
+                }
+            };
+            futures::future::ready(open)
+        })
+        .filter_map(move |message| {
+            let _writer = &writer;
+            let text = match message {
+                Ok(Message::Text(text)) => match reply.as_mut().and_then(|reply| reply(&text)) {
+                    Some(answer) => {
+                        let _ = replies.send(answer);
+                        None
+                    }
+                    None => Some(text),
+                },
+                Ok(message) => {
+                    debug!("ignoring websocket message: {:?}", message);
+                    None
+                }
+                Err(_) => None,
+            };
+            futures::future::ready(text)
+        });
+    Ok(stream)
+}
@@ -0,0 +1,431 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::state::{PersistentStrategyAdapter, StateStore};
+use crate::types::{
+    ActionPriority, BatchStrategy, BatchStrategyAdapter, Collector, EventMiddleware, EventTopic,
+    Executor, PersistentStrategy, Strategy,
+};
+
+use super::{
+    ActionRoute, BackpressurePolicy, BatchConfig, CollectorConfig, CollectorEntry, DeadLetterQueue,
+    DryRun, Engine, EngineEvent, ExecutionFeedback, ExecutorConfig, ExecutorEntry, PauseMode,
+    Replay, Shard, SpendGuard, StrategyConfig, StrategyEntry,
+};
+
+/// Errors returned when building a misconfigured [Engine](Engine).
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BuildError {
+    #[error("no collectors were added to the engine")]
+    NoCollectors,
+    #[error("no strategies were added to the engine")]
+    NoStrategies,
+    #[error("no executors were added to the engine")]
+    NoExecutors,
+    #[error("the {0} channel capacity must be greater than zero")]
+    ZeroCapacity(&'static str),
+    #[error("component names must not be empty")]
+    EmptyName,
+    #[error("duplicate component name: {0}")]
+    DuplicateName(String),
+    #[error("executor {0} must have a concurrency greater than zero")]
+    ZeroConcurrency(String),
+    #[error("route targets unknown executor: {0}")]
+    UnknownExecutor(String),
+    #[error("strategy {0} must have at least one shard")]
+    ZeroShards(String),
+    #[error("strategy {0} must have a batch size greater than zero")]
+    ZeroBatchSize(String),
+    #[error("strict ordering can't be combined with the Block event channel policy")]
+    BlockingStrictOrdering,
+}
+
+/// A builder for the [Engine](Engine), which registers named components with
+/// their own configuration, and validates the resulting pipeline in
+/// [build](EngineBuilder::build).
+pub struct EngineBuilder<E, A> {
+    engine: Engine<E, A>,
+    routes: Vec<(String, ActionRoute<A>)>,
+    /// The first sharded strategy added without any shard, if any.
+    unsharded: Option<String>,
+}
+
+impl<E, A> Default for EngineBuilder<E, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, A: ActionPriority> EngineBuilder<E, A> {
+    /// Executes queued actions by [priority](ActionPriority). See
+    /// [Engine::with_action_priorities](Engine::with_action_priorities).
+    pub fn with_action_priorities(mut self) -> Self {
+        self.engine = self.engine.with_action_priorities();
+        self
+    }
+}
+
+impl<E: EventTopic, A> EngineBuilder<E, A> {
+    /// Routes events by [topic](EventTopic). See
+    /// [Engine::with_event_topics](Engine::with_event_topics).
+    pub fn with_event_topics(mut self) -> Self {
+        self.engine = self.engine.with_event_topics();
+        self
+    }
+}
+
+impl<E, A> EngineBuilder<E, A> {
+    pub fn new() -> Self {
+        Self {
+            engine: Engine::new(),
+            routes: vec![],
+            unsharded: None,
+        }
+    }
+
+    pub fn with_event_channel_capacity(mut self, capacity: usize) -> Self {
+        self.engine.event_channel_capacity = capacity;
+        self
+    }
+
+    pub fn with_action_channel_capacity(mut self, capacity: usize) -> Self {
+        self.engine.action_channel_capacity = capacity;
+        self
+    }
+
+    /// Sets the policy applied when the event queue of a strategy is full.
+    pub fn with_event_channel_policy(mut self, policy: BackpressurePolicy) -> Self {
+        self.engine.event_channel_policy = policy;
+        self
+    }
+
+    /// Sets the policy applied when strategies send into a full action channel.
+    pub fn with_action_channel_policy(mut self, policy: BackpressurePolicy) -> Self {
+        self.engine.action_channel_policy = policy;
+        self
+    }
+
+    /// Delivers [engine events](EngineEvent) to the strategies, mapped into the
+    /// pipeline's event type with `f`. See [Engine::with_engine_events](Engine::with_engine_events).
+    pub fn with_engine_events<F>(mut self, f: F) -> Self
+    where
+        F: Fn(EngineEvent) -> Option<E> + Send + Sync + 'static,
+    {
+        self.engine = self.engine.with_engine_events(f);
+        self
+    }
+
+    /// Feeds the outcome of every executed action back to the strategies, mapped
+    /// into the pipeline's event type with `f`. See
+    /// [Engine::with_execution_feedback](Engine::with_execution_feedback).
+    pub fn with_execution_feedback<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ExecutionFeedback<A>) -> Option<E> + Send + Sync + 'static,
+    {
+        self.engine = self.engine.with_execution_feedback(f);
+        self
+    }
+
+    /// Adds a named collector with the default configuration.
+    pub fn with_collector(self, name: impl Into<String>, collector: Box<dyn Collector<E>>) -> Self {
+        self.with_collector_config(name, collector, CollectorConfig::default())
+    }
+
+    /// Adds a named collector with the given configuration.
+    pub fn with_collector_config(
+        mut self,
+        name: impl Into<String>,
+        collector: Box<dyn Collector<E>>,
+        config: CollectorConfig,
+    ) -> Self {
+        self.engine.collectors.push(CollectorEntry {
+            name: name.into(),
+            collector,
+            config,
+        });
+        self
+    }
+
+    /// Sets what the engine does with collected events while it is paused.
+    pub fn with_pause_mode(mut self, mode: PauseMode) -> Self {
+        self.engine.pause_mode = mode;
+        self
+    }
+
+    /// Lets strategies publish events to the other strategies. See
+    /// [Engine::with_event_bus](Engine::with_event_bus).
+    pub fn with_event_bus<F>(mut self, f: F) -> Self
+    where
+        F: Fn(A) -> Result<E, A> + Send + Sync + 'static,
+    {
+        self.engine = self.engine.with_event_bus(f);
+        self
+    }
+
+    /// Deduplicates identical actions emitted within `window`. See
+    /// [Engine::with_action_dedup](Engine::with_action_dedup).
+    pub fn with_action_dedup<F>(mut self, window: Duration, key: F) -> Self
+    where
+        F: Fn(&A) -> u64 + Send + Sync + 'static,
+    {
+        self.engine = self.engine.with_action_dedup(window, key);
+        self
+    }
+
+    /// Records failed actions in `queue`. See
+    /// [Engine::with_dead_letter_queue](Engine::with_dead_letter_queue).
+    pub fn with_dead_letter_queue(mut self, queue: DeadLetterQueue<A>) -> Self {
+        self.engine = self.engine.with_dead_letter_queue(queue);
+        self
+    }
+
+    /// Captures actions in `dry_run` instead of executing them. See
+    /// [Engine::with_dry_run](Engine::with_dry_run).
+    pub fn with_dry_run(mut self, dry_run: DryRun<A>) -> Self {
+        self.engine = self.engine.with_dry_run(dry_run);
+        self
+    }
+
+    /// Halts the execution of actions once `guard` trips. See
+    /// [Engine::with_spend_guard](Engine::with_spend_guard).
+    pub fn with_spend_guard(mut self, guard: SpendGuard) -> Self {
+        self.engine = self.engine.with_spend_guard(guard);
+        self
+    }
+
+    /// Spawns the tasks of the engine on `runtime`. See
+    /// [Engine::with_runtime](Engine::with_runtime).
+    pub fn with_runtime(mut self, runtime: tokio::runtime::Handle) -> Self {
+        self.engine = self.engine.with_runtime(runtime);
+        self
+    }
+
+    /// Replays the events recorded in `replay` instead of running the collectors.
+    /// See [Engine::with_replay](Engine::with_replay).
+    pub fn with_replay(mut self, replay: Replay<E>) -> Self
+    where
+        E: Clone + Send + Sync + 'static,
+    {
+        self.engine = self.engine.with_replay(replay);
+        self
+    }
+
+    /// Delivers events in a single global sequence. See
+    /// [Engine::with_strict_ordering](Engine::with_strict_ordering).
+    pub fn with_strict_ordering(mut self) -> Self {
+        self.engine = self.engine.with_strict_ordering();
+        self
+    }
+
+    /// Adds a middleware to the stack applied to collected events, before they
+    /// reach the strategies. Middleware is applied in the order it was added.
+    pub fn with_middleware(mut self, middleware: Box<dyn EventMiddleware<E>>) -> Self {
+        self.engine.middleware.push(middleware);
+        self
+    }
+
+    /// Adds a named strategy with the default configuration.
+    pub fn with_strategy(self, name: impl Into<String>, strategy: Box<dyn Strategy<E, A>>) -> Self {
+        self.with_strategy_config(name, strategy, StrategyConfig::default())
+    }
+
+    /// Adds a named strategy with the given configuration.
+    pub fn with_strategy_config(
+        mut self,
+        name: impl Into<String>,
+        strategy: Box<dyn Strategy<E, A>>,
+        config: StrategyConfig,
+    ) -> Self {
+        self.engine.strategies.push(StrategyEntry {
+            name: name.into(),
+            strategy,
+            config,
+            shard: None,
+        });
+        self
+    }
+
+    /// Adds a named [BatchStrategy](BatchStrategy) with the given configuration.
+    /// Events are delivered to it in batches, bounded by the configuration's
+    /// [batch](StrategyConfig::batch) settings, or by the default [BatchConfig](BatchConfig).
+    pub fn with_batch_strategy(
+        self,
+        name: impl Into<String>,
+        strategy: Box<dyn BatchStrategy<E, A>>,
+        mut config: StrategyConfig,
+    ) -> Self
+    where
+        E: Send + 'static,
+        A: Send + 'static,
+    {
+        config.batch.get_or_insert_with(BatchConfig::default);
+        let strategy = Box::new(BatchStrategyAdapter::new(strategy));
+        self.with_strategy_config(name, strategy, config)
+    }
+
+    /// Adds a named [PersistentStrategy](PersistentStrategy) with the given
+    /// configuration, checkpointing its state into `store` under its name. See
+    /// [PersistentStrategyAdapter](PersistentStrategyAdapter) for details.
+    pub fn with_persistent_strategy<S>(
+        self,
+        name: impl Into<String>,
+        strategy: S,
+        store: Arc<dyn StateStore>,
+        config: StrategyConfig,
+    ) -> Self
+    where
+        E: Send + 'static,
+        A: Send + 'static,
+        S: PersistentStrategy<E, A> + 'static,
+    {
+        let name = name.into();
+        let strategy = Box::new(PersistentStrategyAdapter::new(
+            strategy,
+            name.clone(),
+            store,
+        ));
+        self.with_strategy_config(name, strategy, config)
+    }
+
+    /// Adds a strategy sharded into `shards` copies, each created with `factory`
+    /// from its shard index, and each processing a disjoint partition of the event
+    /// stream, e.g. by pool address, as declared by [Strategy::shard_key]. The
+    /// copies are named `{name}-{index}`, and share the given configuration.
+    pub fn with_sharded_strategy<F>(
+        mut self,
+        name: impl Into<String>,
+        shards: usize,
+        mut factory: F,
+        config: StrategyConfig,
+    ) -> Self
+    where
+        F: FnMut(usize) -> Box<dyn Strategy<E, A>>,
+    {
+        let name = name.into();
+        if shards == 0 {
+            self.unsharded.get_or_insert(name);
+            return self;
+        }
+        for index in 0..shards {
+            self.engine.strategies.push(StrategyEntry {
+                name: format!("{}-{}", name, index),
+                strategy: factory(index),
+                config: config.clone(),
+                shard: Some(Shard {
+                    index: index as u64,
+                    count: shards as u64,
+                }),
+            });
+        }
+        self
+    }
+
+    /// Adds a named executor with the default configuration.
+    pub fn with_executor(self, name: impl Into<String>, executor: Box<dyn Executor<A>>) -> Self {
+        self.with_executor_config(name, executor, ExecutorConfig::default())
+    }
+
+    /// Adds a named executor with the given configuration.
+    pub fn with_executor_config(
+        mut self,
+        name: impl Into<String>,
+        executor: Box<dyn Executor<A>>,
+        config: ExecutorConfig,
+    ) -> Self {
+        self.engine.executors.push(ExecutorEntry {
+            name: name.into(),
+            executor,
+            config,
+            route: None,
+        });
+        self
+    }
+
+    /// Routes to the named executor only the actions matching `route`, e.g.
+    /// `|action| matches!(action, Actions::FlashbotsBundle(_))`. Executors
+    /// without a route receive every action. Routing the same executor twice
+    /// replaces its previous route.
+    pub fn with_route<F>(mut self, executor: impl Into<String>, route: F) -> Self
+    where
+        F: Fn(&A) -> bool + Send + Sync + 'static,
+    {
+        self.routes.push((executor.into(), Arc::new(route)));
+        self
+    }
+
+    /// Validates the pipeline and builds the [Engine](Engine).
+    ///
+    /// The pipeline is rejected if any stage is empty, if a channel has no
+    /// capacity, if component names are empty or not unique, if an executor
+    /// has a concurrency of zero, if a route targets an unknown executor, or if
+    /// strict ordering is combined with the [Block](BackpressurePolicy::Block)
+    /// event channel policy, since the sequencer stays locked while an event is
+    /// queued, and a full queue could then deadlock the engine.
+    pub fn build(self) -> Result<Engine<E, A>, BuildError> {
+        let mut engine = self.engine;
+
+        if let Some(name) = self.unsharded {
+            return Err(BuildError::ZeroShards(name));
+        }
+        if engine.collectors.is_empty() && engine.replay.is_none() {
+            return Err(BuildError::NoCollectors);
+        }
+        if engine.strategies.is_empty() {
+            return Err(BuildError::NoStrategies);
+        }
+        if engine.executors.is_empty() {
+            return Err(BuildError::NoExecutors);
+        }
+        if engine.event_channel_capacity == 0 {
+            return Err(BuildError::ZeroCapacity("event"));
+        }
+        if engine.action_channel_capacity == 0 {
+            return Err(BuildError::ZeroCapacity("action"));
+        }
+        if engine.strict_ordering && engine.event_channel_policy == BackpressurePolicy::Block {
+            return Err(BuildError::BlockingStrictOrdering);
+        }
+
+        let names = engine
+            .collectors
+            .iter()
+            .map(|c| &c.name)
+            .chain(engine.strategies.iter().map(|s| &s.name))
+            .chain(engine.executors.iter().map(|e| &e.name));
+        let mut seen = HashSet::new();
+        for name in names {
+            if name.is_empty() {
+                return Err(BuildError::EmptyName);
+            }
+            if !seen.insert(name) {
+                return Err(BuildError::DuplicateName(name.clone()));
+            }
+        }
+
+        if let Some(strategy) = engine
+            .strategies
+            .iter()
+            .find(|s| s.config.batch.as_ref().is_some_and(|b| b.max_size == 0))
+        {
+            return Err(BuildError::ZeroBatchSize(strategy.name.clone()));
+        }
+
+        if let Some(executor) = engine.executors.iter().find(|e| e.config.concurrency == 0) {
+            return Err(BuildError::ZeroConcurrency(executor.name.clone()));
+        }
+
+        for (name, route) in self.routes {
+            let executor = engine
+                .executors
+                .iter_mut()
+                .find(|e| e.name == name)
+                .ok_or(BuildError::UnknownExecutor(name))?;
+            executor.route = Some(route);
+        }
+
+        Ok(engine)
+    }
+}
@@ -0,0 +1,566 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+
+use metrics::Counter;
+use thiserror::Error;
+use tokio::sync::broadcast::{
+    self,
+    error::{RecvError, TryRecvError},
+    Receiver, Sender,
+};
+use tokio::sync::{Mutex, Notify};
+
+use super::metrics::channel_rejected;
+
+/// The policy applied when sending into a full engine channel.
+///
+/// Every strategy has its own event queue, so the policy of the event channel
+/// applies to each strategy separately: a slow strategy only drops events for
+/// itself. With [Block](BackpressurePolicy::Block), however, a full queue holds
+/// back the events of every strategy, and eventually the collectors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    /// Send the new message, and let receivers that fall behind skip the oldest
+    /// messages, which they are told about. On a strategy's event queue, the
+    /// oldest queued event is evicted to make room for the new one.
+    #[default]
+    DropOldest,
+    /// Discard the new message if the channel is full.
+    DropNewest,
+    /// Wait until there is room in the channel before sending the new message.
+    Block,
+    /// Discard the new message and report an error to the sender if the channel is full.
+    Error,
+}
+
+/// Counters for messages lost on an engine channel.
+#[derive(Debug, Default)]
+pub struct ChannelStats {
+    /// Messages skipped by lagging receivers ([DropOldest](BackpressurePolicy::DropOldest)).
+    /// For the event channel, this sums the events skipped by every strategy.
+    lagged: AtomicU64,
+    /// Messages discarded on send because the channel was full
+    /// ([DropNewest](BackpressurePolicy::DropNewest) and [Error](BackpressurePolicy::Error)).
+    rejected: AtomicU64,
+}
+
+impl ChannelStats {
+    /// Returns the number of messages skipped by lagging receivers. A message
+    /// skipped by several receivers is counted once per receiver.
+    pub fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of messages discarded on send because the channel was full.
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total number of dropped messages.
+    pub fn dropped(&self) -> u64 {
+        self.lagged() + self.rejected()
+    }
+}
+
+/// Errors returned when sending into an engine channel.
+#[derive(Debug, Error)]
+pub enum ChannelError {
+    /// The channel was full, and the [Error](BackpressurePolicy::Error) policy is in use.
+    #[error("channel is full")]
+    Full,
+    /// There are no receivers left on the channel.
+    #[error("channel is closed")]
+    Closed,
+}
+
+/// Assigns sequence numbers to the messages sent into one or more channels. The
+/// lock is held for the whole send, so a message is only visible to receivers
+/// once every message with a lower sequence number is.
+pub(crate) type Sequencer = Arc<Mutex<u64>>;
+
+/// A broadcast channel that applies a [BackpressurePolicy] on send, and keeps
+/// track of dropped messages. Messages are stamped with a sequence number if the
+/// channel has a [Sequencer], and with zero otherwise.
+#[derive(Debug)]
+pub(crate) struct Channel<T> {
+    sender: Sender<(u64, T)>,
+    capacity: usize,
+    policy: BackpressurePolicy,
+    stats: Arc<ChannelStats>,
+    rejected: Counter,
+    sequencer: Option<Sequencer>,
+    /// Notified by receivers whenever they receive a message or drop, so senders
+    /// using the [Block](BackpressurePolicy::Block) policy can check for room again.
+    room: Arc<Notify>,
+}
+
+impl<T> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            capacity: self.capacity,
+            policy: self.policy,
+            stats: self.stats.clone(),
+            rejected: self.rejected.clone(),
+            sequencer: self.sequencer.clone(),
+            room: self.room.clone(),
+        }
+    }
+}
+
+impl<T: Clone> Channel<T> {
+    /// Creates a new channel. The `label` is used to tell the engine's channels
+    /// apart in metrics.
+    pub(crate) fn new(
+        label: &'static str,
+        capacity: usize,
+        policy: BackpressurePolicy,
+        stats: Arc<ChannelStats>,
+    ) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            capacity,
+            policy,
+            stats,
+            rejected: channel_rejected(label),
+            sequencer: None,
+            room: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Stamps the messages sent into the channel with sequence numbers from
+    /// `sequencer`, which may be shared with other channels.
+    pub(crate) fn with_sequencer(mut self, sequencer: Sequencer) -> Self {
+        self.sequencer = Some(sequencer);
+        self
+    }
+
+    /// Creates a new receiver for the channel.
+    pub(crate) fn subscribe(&self) -> ChannelReceiver<T> {
+        ChannelReceiver {
+            receiver: self.sender.subscribe(),
+            stats: self.stats.clone(),
+            room: Room(self.room.clone()),
+        }
+    }
+
+    /// Sends a message, applying the channel's backpressure policy.
+    pub(crate) async fn send(&self, value: T) -> Result<(), ChannelError> {
+        let Some(sequencer) = &self.sequencer else {
+            return self.send_with_seq(0, value).await.map(|_| ());
+        };
+        let mut next = sequencer.lock().await;
+        if self.send_with_seq(*next, value).await? {
+            *next += 1;
+        }
+        Ok(())
+    }
+
+    /// Sends a message with the given sequence number. Returns false if the
+    /// message was discarded by the [DropNewest](BackpressurePolicy::DropNewest) policy.
+    async fn send_with_seq(&self, seq: u64, value: T) -> Result<bool, ChannelError> {
+        let full = self.sender.len() >= self.capacity;
+        match self.policy {
+            BackpressurePolicy::DropOldest => {}
+            BackpressurePolicy::DropNewest if full => {
+                self.stats.rejected.fetch_add(1, Ordering::Relaxed);
+                self.rejected.increment(1);
+                return Ok(false);
+            }
+            BackpressurePolicy::Error if full => {
+                self.stats.rejected.fetch_add(1, Ordering::Relaxed);
+                self.rejected.increment(1);
+                return Err(ChannelError::Full);
+            }
+            BackpressurePolicy::Block => loop {
+                // Register for the notification before checking for room, so a
+                // message received in between still wakes the sender up.
+                let notified = self.room.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.sender.len() < self.capacity || self.sender.receiver_count() == 0 {
+                    break;
+                }
+                notified.await;
+            },
+            BackpressurePolicy::DropNewest | BackpressurePolicy::Error => {}
+        }
+
+        self.sender
+            .send((seq, value))
+            .map(|_| true)
+            .map_err(|_| ChannelError::Closed)
+    }
+}
+
+/// The receiving half of a [Channel], which accounts for lagged messages.
+#[derive(Debug)]
+pub(crate) struct ChannelReceiver<T> {
+    receiver: Receiver<(u64, T)>,
+    stats: Arc<ChannelStats>,
+    /// Declared after the receiver, so the receiver is dropped before blocked
+    /// senders are woken up.
+    room: Room,
+}
+
+/// Wakes up the senders blocked on a [Channel] when a receiver frees up room, or
+/// drops, since the dropped receiver may have been the slowest one.
+#[derive(Debug)]
+struct Room(Arc<Notify>);
+
+impl Drop for Room {
+    fn drop(&mut self) {
+        self.0.notify_waiters();
+    }
+}
+
+impl<T: Clone> ChannelReceiver<T> {
+    /// Receives the next message, skipping over lagged messages.
+    ///
+    /// Returns `Err(n)` if `n` messages were skipped because the receiver fell
+    /// behind, and `Ok(None)` once all senders have been dropped.
+    pub(crate) async fn recv(&mut self) -> Result<Option<T>, u64> {
+        self.recv_sequenced()
+            .await
+            .map(|value| value.map(|(_, value)| value))
+    }
+
+    /// Receives the next message along with its sequence number. See
+    /// [recv](ChannelReceiver::recv).
+    pub(crate) async fn recv_sequenced(&mut self) -> Result<Option<(u64, T)>, u64> {
+        match self.receiver.recv().await {
+            Ok(value) => {
+                self.room.0.notify_waiters();
+                Ok(Some(value))
+            }
+            Err(RecvError::Closed) => Ok(None),
+            Err(RecvError::Lagged(skipped)) => Err(self.lagged(skipped)),
+        }
+    }
+
+    /// Receives the next message along with its sequence number, if one is
+    /// available right away. Returns `Ok(None)` if the channel is empty or closed.
+    pub(crate) fn try_recv_sequenced(&mut self) -> Result<Option<(u64, T)>, u64> {
+        match self.receiver.try_recv() {
+            Ok(value) => {
+                self.room.0.notify_waiters();
+                Ok(Some(value))
+            }
+            Err(TryRecvError::Empty | TryRecvError::Closed) => Ok(None),
+            Err(TryRecvError::Lagged(skipped)) => Err(self.lagged(skipped)),
+        }
+    }
+
+    fn lagged(&self, skipped: u64) -> u64 {
+        self.stats.lagged.fetch_add(skipped, Ordering::Relaxed);
+        skipped
+    }
+}
+
+/// Creates a bounded queue delivering events to a single strategy. The `label` is
+/// used to tell the engine's channels apart in metrics.
+pub(crate) fn queue<T>(
+    label: &'static str,
+    capacity: usize,
+    policy: BackpressurePolicy,
+    stats: Arc<ChannelStats>,
+) -> (QueueSender<T>, QueueReceiver<T>) {
+    let queue = Arc::new(Queue {
+        state: StdMutex::new(QueueState {
+            slots: VecDeque::with_capacity(capacity),
+            skipped: 0,
+            sender_dropped: false,
+            receiver_dropped: false,
+        }),
+        readable: Notify::new(),
+        writable: Notify::new(),
+    });
+    let sender = QueueSender {
+        queue: queue.clone(),
+        capacity: capacity.max(1),
+        policy,
+        stats,
+        rejected: channel_rejected(label),
+    };
+    (sender, QueueReceiver { queue })
+}
+
+/// The state shared by the two halves of a strategy's event queue.
+#[derive(Debug)]
+struct Queue<T> {
+    state: StdMutex<QueueState<T>>,
+    /// Notifies the receiver that a message was queued, or the sender dropped.
+    readable: Notify,
+    /// Notifies a blocked sender that a message was received, or the receiver
+    /// dropped.
+    writable: Notify,
+}
+
+#[derive(Debug)]
+struct QueueState<T> {
+    /// The queued messages, with their sequence numbers, oldest first.
+    slots: VecDeque<(u64, T)>,
+    /// The number of messages evicted since the receiver was last told about it.
+    skipped: u64,
+    sender_dropped: bool,
+    receiver_dropped: bool,
+}
+
+/// The sending half of a strategy's event queue, which applies a [BackpressurePolicy]
+/// on send. With [DropOldest](BackpressurePolicy::DropOldest), the oldest queued
+/// message is evicted to make room for the new one, so a slow strategy always
+/// works on the most recent events, and the evicted messages are reported to the
+/// receiver before the remaining ones.
+#[derive(Debug)]
+pub(crate) struct QueueSender<T> {
+    queue: Arc<Queue<T>>,
+    capacity: usize,
+    policy: BackpressurePolicy,
+    stats: Arc<ChannelStats>,
+    rejected: Counter,
+}
+
+impl<T> QueueSender<T> {
+    /// Sends a message with the given sequence number, applying the queue's
+    /// backpressure policy.
+    pub(crate) async fn send(&mut self, seq: u64, value: T) -> Result<(), ChannelError> {
+        loop {
+            {
+                let mut state = self.queue.state.lock().unwrap();
+                if state.receiver_dropped {
+                    return Err(ChannelError::Closed);
+                }
+                if state.slots.len() < self.capacity {
+                    state.slots.push_back((seq, value));
+                    drop(state);
+                    self.queue.readable.notify_one();
+                    return Ok(());
+                }
+                match self.policy {
+                    BackpressurePolicy::DropOldest => {
+                        state.slots.pop_front();
+                        state.slots.push_back((seq, value));
+                        state.skipped += 1;
+                        drop(state);
+                        self.stats.lagged.fetch_add(1, Ordering::Relaxed);
+                        self.queue.readable.notify_one();
+                        return Ok(());
+                    }
+                    BackpressurePolicy::DropNewest => {
+                        self.reject();
+                        return Ok(());
+                    }
+                    BackpressurePolicy::Error => {
+                        self.reject();
+                        return Err(ChannelError::Full);
+                    }
+                    // The receiver stores a permit when it frees up room, so the
+                    // wakeup can't be missed after the lock is released.
+                    BackpressurePolicy::Block => {}
+                }
+            }
+            self.queue.writable.notified().await;
+        }
+    }
+
+    fn reject(&self) {
+        self.stats.rejected.fetch_add(1, Ordering::Relaxed);
+        self.rejected.increment(1);
+    }
+
+    /// Returns true if the receiver was dropped.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.queue.state.lock().unwrap().receiver_dropped
+    }
+}
+
+impl<T> Drop for QueueSender<T> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().sender_dropped = true;
+        self.queue.readable.notify_one();
+    }
+}
+
+/// The receiving half of a strategy's event queue.
+#[derive(Debug)]
+pub(crate) struct QueueReceiver<T> {
+    queue: Arc<Queue<T>>,
+}
+
+impl<T> QueueReceiver<T> {
+    /// Receives the next message along with its sequence number.
+    ///
+    /// Returns `Err(n)` if `n` messages were evicted because the receiver fell
+    /// behind, and `Ok(None)` once the sender has been dropped and the queue is
+    /// empty.
+    pub(crate) async fn recv_sequenced(&mut self) -> Result<Option<(u64, T)>, u64> {
+        loop {
+            if let Some(received) = self.poll()? {
+                return Ok(received);
+            }
+            self.queue.readable.notified().await;
+        }
+    }
+
+    /// Receives the next message along with its sequence number, if one is
+    /// available right away. Returns `Ok(None)` if the queue is empty or closed.
+    pub(crate) fn try_recv_sequenced(&mut self) -> Result<Option<(u64, T)>, u64> {
+        self.poll().map(Option::flatten)
+    }
+
+    /// Returns the next message, `Some(None)` if the queue is closed, and `None`
+    /// if it is empty.
+    #[allow(clippy::type_complexity)]
+    fn poll(&mut self) -> Result<Option<Option<(u64, T)>>, u64> {
+        let mut state = self.queue.state.lock().unwrap();
+        if state.skipped > 0 {
+            return Err(std::mem::take(&mut state.skipped));
+        }
+        if let Some(slot) = state.slots.pop_front() {
+            drop(state);
+            self.queue.writable.notify_one();
+            return Ok(Some(Some(slot)));
+        }
+        Ok(state.sender_dropped.then_some(None))
+    }
+}
+
+impl<T> Drop for QueueReceiver<T> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().receiver_dropped = true;
+        self.queue.writable.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn test_queue(policy: BackpressurePolicy) -> (QueueSender<u64>, QueueReceiver<u64>) {
+        queue("test", 2, policy, Arc::new(ChannelStats::default()))
+    }
+
+    fn test_channel(policy: BackpressurePolicy) -> (Channel<u64>, ChannelReceiver<u64>) {
+        let channel = Channel::new("test", 2, policy, Arc::new(ChannelStats::default()));
+        let receiver = channel.subscribe();
+        (channel, receiver)
+    }
+
+    #[tokio::test]
+    async fn test_channel_drop_oldest_counts_lagged() {
+        let (channel, mut receiver) = test_channel(BackpressurePolicy::DropOldest);
+        for value in 0..4 {
+            channel.send(value).await.unwrap();
+        }
+        assert_eq!(receiver.recv().await, Err(2));
+        assert_eq!(receiver.recv().await, Ok(Some(2)));
+        assert_eq!(channel.stats.lagged(), 2);
+        assert_eq!(channel.stats.rejected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_channel_drop_newest_counts_rejected() {
+        let (channel, mut receiver) = test_channel(BackpressurePolicy::DropNewest);
+        for value in 0..4 {
+            channel.send(value).await.unwrap();
+        }
+        assert_eq!(receiver.recv().await, Ok(Some(0)));
+        assert_eq!(receiver.recv().await, Ok(Some(1)));
+        assert_eq!(receiver.try_recv_sequenced(), Ok(None));
+        assert_eq!(channel.stats.rejected(), 2);
+        assert_eq!(channel.stats.dropped(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_channel_error_reports_full() {
+        let (channel, receiver) = test_channel(BackpressurePolicy::Error);
+        channel.send(0).await.unwrap();
+        channel.send(1).await.unwrap();
+        assert!(matches!(channel.send(2).await, Err(ChannelError::Full)));
+        assert_eq!(channel.stats.rejected(), 1);
+
+        drop(receiver);
+        assert!(matches!(channel.send(3).await, Err(ChannelError::Closed)));
+    }
+
+    #[tokio::test]
+    async fn test_channel_block_waits_for_receivers() {
+        let (channel, mut receiver) = test_channel(BackpressurePolicy::Block);
+        channel.send(0).await.unwrap();
+        channel.send(1).await.unwrap();
+        let sender = channel.clone();
+        let blocked = tokio::spawn(async move { sender.send(2).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(receiver.recv().await, Ok(Some(0)));
+        blocked.await.unwrap().unwrap();
+        assert_eq!(receiver.recv().await, Ok(Some(1)));
+        assert_eq!(receiver.recv().await, Ok(Some(2)));
+        assert_eq!(channel.stats.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_drop_oldest_evicts_head() {
+        let (mut sender, mut receiver) = test_queue(BackpressurePolicy::DropOldest);
+        for seq in 0..4 {
+            sender.send(seq, seq).await.unwrap();
+        }
+        assert_eq!(receiver.recv_sequenced().await, Err(2));
+        assert_eq!(receiver.recv_sequenced().await, Ok(Some((2, 2))));
+        assert_eq!(receiver.recv_sequenced().await, Ok(Some((3, 3))));
+        assert_eq!(sender.stats.lagged(), 2);
+        assert_eq!(sender.stats.rejected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_drop_newest_discards_incoming() {
+        let (mut sender, mut receiver) = test_queue(BackpressurePolicy::DropNewest);
+        for seq in 0..4 {
+            sender.send(seq, seq).await.unwrap();
+        }
+        assert_eq!(receiver.recv_sequenced().await, Ok(Some((0, 0))));
+        assert_eq!(receiver.recv_sequenced().await, Ok(Some((1, 1))));
+        assert_eq!(receiver.try_recv_sequenced(), Ok(None));
+        assert_eq!(sender.stats.rejected(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_queue_error_reports_full() {
+        let (mut sender, mut receiver) = test_queue(BackpressurePolicy::Error);
+        sender.send(0, 0).await.unwrap();
+        sender.send(1, 1).await.unwrap();
+        assert!(matches!(sender.send(2, 2).await, Err(ChannelError::Full)));
+        assert_eq!(receiver.recv_sequenced().await, Ok(Some((0, 0))));
+        sender.send(3, 3).await.unwrap();
+        assert_eq!(sender.stats.rejected(), 1);
+
+        drop(receiver);
+        assert!(sender.is_closed());
+        assert!(matches!(sender.send(4, 4).await, Err(ChannelError::Closed)));
+    }
+
+    #[tokio::test]
+    async fn test_queue_block_waits_for_room() {
+        let (mut sender, mut receiver) = test_queue(BackpressurePolicy::Block);
+        sender.send(0, 0).await.unwrap();
+        sender.send(1, 1).await.unwrap();
+        let blocked = tokio::spawn(async move {
+            sender.send(2, 2).await.unwrap();
+            sender
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(receiver.recv_sequenced().await, Ok(Some((0, 0))));
+        let sender = blocked.await.unwrap();
+        assert_eq!(receiver.recv_sequenced().await, Ok(Some((1, 1))));
+        assert_eq!(receiver.recv_sequenced().await, Ok(Some((2, 2))));
+        drop(sender);
+        assert_eq!(receiver.recv_sequenced().await, Ok(None));
+    }
+}
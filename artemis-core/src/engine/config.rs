@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+/// The policy applied when a component of the [Engine](super::Engine) fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Log the error and carry on.
+    Ignore,
+    /// Log the error and stop the failing component, leaving the rest of the
+    /// engine running.
+    Stop,
+    /// Log the error and gracefully shut down the whole engine.
+    Shutdown,
+}
+
+/// The policy used by the [Engine](super::Engine) to restart a collector whose
+/// event stream ended or could not be created, or a strategy that panicked.
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    /// Delay before the first restart.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between two restarts.
+    pub max_backoff: Duration,
+    /// Maximum number of consecutive restarts without any successfully processed
+    /// event in between, before the engine gives up on the component. `None`
+    /// restarts forever.
+    pub max_restarts: Option<u32>,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            max_restarts: None,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Returns the delay to wait before the given (zero-indexed) restart.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Configuration of a single collector in the [Engine](super::Engine).
+#[derive(Debug, Clone)]
+pub struct CollectorConfig {
+    /// The policy applied when the collector fails to create its event stream,
+    /// or when the engine gives up restarting it. [Ignore](ErrorPolicy::Ignore)
+    /// and [Stop](ErrorPolicy::Stop) both stop the collector, since it has no
+    /// stream to read from.
+    pub error_policy: ErrorPolicy,
+    /// The policy used to restart the collector when its stream ends or fails.
+    /// Collectors failing with a [fatal](crate::error::ArtemisError::Fatal) error
+    /// are never restarted. `None` stops the collector instead.
+    pub restart_policy: Option<RestartPolicy>,
+}
+
+impl Default for CollectorConfig {
+    fn default() -> Self {
+        Self {
+            error_policy: ErrorPolicy::Stop,
+            restart_policy: None,
+        }
+    }
+}
+
+impl CollectorConfig {
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policy = Some(policy);
+        self
+    }
+}
+
+/// Configuration of a single strategy in the [Engine](super::Engine).
+#[derive(Debug, Clone)]
+pub struct StrategyConfig {
+    /// The policy applied when the strategy fails to sync its state on startup,
+    /// after any retries.
+    /// [Ignore](ErrorPolicy::Ignore) starts the strategy anyway, [Stop](ErrorPolicy::Stop)
+    /// leaves it out of the pipeline, and [Shutdown](ErrorPolicy::Shutdown) makes
+    /// [Engine::run](super::Engine::run) return the error.
+    ///
+    /// The policy is also applied when the strategy panics while processing an
+    /// event, and either has no restart policy or the engine gives up restarting it.
+    pub error_policy: ErrorPolicy,
+    /// The policy used to restart the strategy after it panicked while processing
+    /// an event. Restarting resyncs the strategy's state before it resumes
+    /// consuming events. `None` applies the error policy instead.
+    pub restart_policy: Option<RestartPolicy>,
+    /// The policy used to retry a failed sync of the strategy's state, with
+    /// backoff. `max_restarts` bounds the number of retries, and
+    /// [fatal](crate::error::ArtemisError::Fatal) errors are never retried. `None`
+    /// does not retry.
+    pub sync_retry: Option<RestartPolicy>,
+    /// The maximum time a single sync may take before it is treated as failed.
+    /// `None` waits forever.
+    pub sync_timeout: Option<Duration>,
+    /// How often the strategy's state is resynced while the engine runs. `None`
+    /// only resyncs when the strategy asks for it through
+    /// [needs_resync](crate::types::Strategy::needs_resync).
+    pub resync_interval: Option<Duration>,
+    /// Delivers events to the strategy in batches, through
+    /// [process_batch](crate::types::Strategy::process_batch). `None` delivers
+    /// events one at a time.
+    pub batch: Option<BatchConfig>,
+    /// Whether the strategy is sent an [EventsDropped](super::EngineEvent::EventsDropped)
+    /// event whenever it falls behind and skips events, at the position of the
+    /// gap. Requires [engine events](super::Engine::with_engine_events).
+    pub drop_notifications: bool,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        Self {
+            error_policy: ErrorPolicy::Shutdown,
+            restart_policy: None,
+            sync_retry: None,
+            sync_timeout: None,
+            resync_interval: None,
+            batch: None,
+            drop_notifications: false,
+        }
+    }
+}
+
+impl StrategyConfig {
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policy = Some(policy);
+        self
+    }
+
+    pub fn with_sync_retry(mut self, policy: RestartPolicy) -> Self {
+        self.sync_retry = Some(policy);
+        self
+    }
+
+    pub fn with_sync_timeout(mut self, timeout: Duration) -> Self {
+        self.sync_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_resync_interval(mut self, interval: Duration) -> Self {
+        self.resync_interval = Some(interval);
+        self
+    }
+
+    pub fn with_batching(mut self, batch: BatchConfig) -> Self {
+        self.batch = Some(batch);
+        self
+    }
+
+    pub fn with_drop_notifications(mut self) -> Self {
+        self.drop_notifications = true;
+        self
+    }
+}
+
+/// The bounds of the event batches delivered to a strategy. A batch is delivered
+/// as soon as it is full, or once the delay since its first event has elapsed.
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    /// The maximum number of events in a batch.
+    pub max_size: usize,
+    /// The maximum time to wait for more events after the first event of a batch.
+    pub max_delay: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 64,
+            max_delay: Duration::from_millis(5),
+        }
+    }
+}
+
+/// Configuration of a single executor in the [Engine](super::Engine).
+#[derive(Debug, Clone)]
+pub struct ExecutorConfig {
+    /// The maximum number of actions the executor may execute concurrently.
+    /// With a concurrency of 1, actions are executed in the order they are received.
+    pub concurrency: usize,
+    /// The policy applied when the executor fails to execute an action.
+    pub error_policy: ErrorPolicy,
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 1,
+            error_policy: ErrorPolicy::Ignore,
+        }
+    }
+}
+
+impl ExecutorConfig {
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use thiserror::Error;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinSet;
+use tracing::info;
+
+use super::pause::PauseSwitch;
+use super::strategy::StrategyLauncher;
+use super::supervisor::{spawn_collector, CollectorContext};
+use super::{CollectorConfig, CollectorEntry, ShutdownHandle, StrategyConfig, StrategyEntry};
+use crate::types::{Collector, Strategy};
+
+/// Errors returned by the [ControlHandle](ControlHandle).
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ControlError {
+    #[error("a collector named {0} is already running")]
+    DuplicateName(String),
+    #[error("no collector named {0} is running")]
+    UnknownCollector(String),
+    #[error("a strategy named {0} is already running")]
+    DuplicateStrategy(String),
+    #[error("no strategy named {0} is running")]
+    UnknownStrategy(String),
+    #[error("the engine has stopped")]
+    EngineStopped,
+}
+
+/// A handle to control a running [Engine](super::Engine), obtained with
+/// [Engine::control_handle](super::Engine::control_handle).
+///
+/// Collectors and strategies can be added and removed at runtime without
+/// restarting the engine, e.g. to hot-swap a strategy without losing the
+/// collectors' subscriptions, and the engine can be paused and resumed.
+/// Components added before the engine runs are started along with it. While any
+/// control handle is alive, the engine keeps running even if all of its
+/// collectors have stopped, since new ones may still be added.
+pub struct ControlHandle<E, A> {
+    commands: UnboundedSender<Command<E, A>>,
+    registry: Arc<ComponentRegistry>,
+    strategies: Arc<ComponentRegistry>,
+    shutdown: ShutdownHandle,
+    pause: PauseSwitch,
+}
+
+impl<E, A> Clone for ControlHandle<E, A> {
+    fn clone(&self) -> Self {
+        Self {
+            commands: self.commands.clone(),
+            registry: self.registry.clone(),
+            strategies: self.strategies.clone(),
+            shutdown: self.shutdown.clone(),
+            pause: self.pause.clone(),
+        }
+    }
+}
+
+impl<E, A> ControlHandle<E, A> {
+    pub(crate) fn new(
+        commands: UnboundedSender<Command<E, A>>,
+        registry: Arc<ComponentRegistry>,
+        strategies: Arc<ComponentRegistry>,
+        shutdown: ShutdownHandle,
+        pause: PauseSwitch,
+    ) -> Self {
+        Self {
+            commands,
+            registry,
+            strategies,
+            shutdown,
+            pause,
+        }
+    }
+
+    /// Starts a new named collector. Its events flow through the same middleware
+    /// and reach the same strategies as those of the engine's other collectors.
+    pub fn add_collector(
+        &self,
+        name: impl Into<String>,
+        collector: Box<dyn Collector<E>>,
+        config: CollectorConfig,
+    ) -> Result<(), ControlError> {
+        let name = name.into();
+        let registration = self
+            .registry
+            .register(&name, &self.shutdown)
+            .ok_or_else(|| ControlError::DuplicateName(name.clone()))?;
+        let id = registration.id;
+        let command = Command::Collector(SpawnCollector {
+            entry: CollectorEntry {
+                name: name.clone(),
+                collector,
+                config,
+            },
+            registration,
+        });
+        self.commands.send(command).map_err(|_| {
+            self.registry.deregister(&name, id);
+            ControlError::EngineStopped
+        })
+    }
+
+    /// Stops the named collector. Events it already emitted are still processed.
+    pub fn remove_collector(&self, name: &str) -> Result<(), ControlError> {
+        let stop = self
+            .registry
+            .remove(name)
+            .ok_or_else(|| ControlError::UnknownCollector(name.to_string()))?;
+        stop.shutdown();
+        Ok(())
+    }
+
+    /// Starts a new named strategy, which receives the events collected from then
+    /// on. Its state is synced first, according to its configuration, and it is
+    /// left out of the pipeline if the sync fails, unless its
+    /// [ErrorPolicy](super::ErrorPolicy) is [Ignore](super::ErrorPolicy::Ignore).
+    pub fn add_strategy(
+        &self,
+        name: impl Into<String>,
+        strategy: Box<dyn Strategy<E, A>>,
+        config: StrategyConfig,
+    ) -> Result<(), ControlError> {
+        let name = name.into();
+        let registration = self
+            .strategies
+            .register(&name, &ShutdownHandle::new())
+            .ok_or_else(|| ControlError::DuplicateStrategy(name.clone()))?;
+        let id = registration.id;
+        let command = Command::Strategy(SpawnStrategy {
+            entry: StrategyEntry {
+                name: name.clone(),
+                strategy,
+                config,
+                shard: None,
+            },
+            registration,
+        });
+        self.commands.send(command).map_err(|_| {
+            self.strategies.deregister(&name, id);
+            ControlError::EngineStopped
+        })
+    }
+
+    /// Stops the named strategy once it has processed its current event. Events
+    /// still queued for it are dropped, and it is shut down through
+    /// [on_shutdown](Strategy::on_shutdown).
+    pub fn remove_strategy(&self, name: &str) -> Result<(), ControlError> {
+        let stop = self
+            .strategies
+            .remove(name)
+            .ok_or_else(|| ControlError::UnknownStrategy(name.to_string()))?;
+        stop.shutdown();
+        Ok(())
+    }
+
+    /// Stops forwarding collected events to the strategies, e.g. to halt trading
+    /// during an incident. Collectors keep running, and their events are dropped
+    /// or buffered depending on the engine's [PauseMode](super::PauseMode).
+    pub fn pause(&self) {
+        self.pause.set(true);
+    }
+
+    /// Resumes forwarding collected events to the strategies, starting with any
+    /// events buffered while paused.
+    pub fn resume(&self) {
+        self.pause.set(false);
+    }
+
+    /// Returns true if the engine is paused.
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// Returns the names of the running collectors.
+    pub fn collectors(&self) -> Vec<String> {
+        self.registry.names()
+    }
+
+    /// Returns the names of the running strategies.
+    pub fn strategies(&self) -> Vec<String> {
+        self.strategies.names()
+    }
+}
+
+/// A request sent from a [ControlHandle] to the engine.
+pub(crate) enum Command<E, A> {
+    Collector(SpawnCollector<E>),
+    Strategy(SpawnStrategy<E, A>),
+}
+
+/// A request to start a collector.
+pub(crate) struct SpawnCollector<E> {
+    entry: CollectorEntry<E>,
+    registration: Registration,
+}
+
+/// A request to start a strategy.
+pub(crate) struct SpawnStrategy<E, A> {
+    entry: StrategyEntry<E, A>,
+    registration: Registration,
+}
+
+/// The registration of a running collector or strategy.
+pub(crate) struct Registration {
+    /// Tells apart components registered under the same name over time.
+    pub(crate) id: u64,
+    /// Stops the component.
+    pub(crate) stop: ShutdownHandle,
+}
+
+/// The set of running collectors, or strategies, by name.
+#[derive(Default)]
+pub(crate) struct ComponentRegistry {
+    next_id: AtomicU64,
+    components: Mutex<HashMap<String, (u64, ShutdownHandle)>>,
+}
+
+impl ComponentRegistry {
+    /// Registers a component, unless one with the same name is already running.
+    /// The component is stopped along with `parent`.
+    pub(crate) fn register(&self, name: &str, parent: &ShutdownHandle) -> Option<Registration> {
+        let mut components = self.components.lock().unwrap();
+        if components.contains_key(name) {
+            return None;
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let stop = parent.child();
+        components.insert(name.to_string(), (id, stop.clone()));
+        Some(Registration { id, stop })
+    }
+
+    /// Removes a component from the registry, if it has not been replaced by
+    /// another component with the same name.
+    pub(crate) fn deregister(&self, name: &str, id: u64) {
+        let mut components = self.components.lock().unwrap();
+        if components
+            .get(name)
+            .is_some_and(|(current, _)| *current == id)
+        {
+            components.remove(name);
+        }
+    }
+
+    /// Removes a component from the registry, and returns the handle to stop it.
+    pub(crate) fn remove(&self, name: &str) -> Option<ShutdownHandle> {
+        let mut components = self.components.lock().unwrap();
+        components.remove(name).map(|(_, stop)| stop)
+    }
+
+    pub(crate) fn names(&self) -> Vec<String> {
+        let components = self.components.lock().unwrap();
+        components.keys().cloned().collect()
+    }
+}
+
+/// Starts the collectors and strategies requested through [ControlHandle]s.
+/// Exits once all handles have been dropped and the components it started have
+/// stopped, or once the engine shuts down.
+pub(crate) async fn run_control<E, A>(
+    mut commands: UnboundedReceiver<Command<E, A>>,
+    ctx: CollectorContext<E>,
+    launcher: StrategyLauncher<E, A>,
+) where
+    E: Clone + Send + 'static,
+    A: Clone + Send + 'static,
+{
+    let shutdown = ctx.shutdown.clone();
+    let mut components = JoinSet::new();
+    // The contexts keep the event channel open, so they are dropped as soon as
+    // no more components can be added, to let the strategies drain it.
+    let mut contexts = Some((ctx, launcher));
+
+    while contexts.is_some() || !components.is_empty() {
+        tokio::select! {
+            command = commands.recv(), if contexts.is_some() => {
+                let (Some(command), Some((ctx, launcher))) = (command, &contexts) else {
+                    contexts = None;
+                    continue;
+                };
+                match command {
+                    Command::Collector(SpawnCollector { entry, registration }) => {
+                        info!("adding collector {}", entry.name);
+                        spawn_collector(&mut components, entry, registration, ctx.clone());
+                    }
+                    Command::Strategy(SpawnStrategy { entry, registration }) => {
+                        info!("adding strategy {}", entry.name);
+                        launcher.add(&mut components, entry, registration);
+                    }
+                }
+            },
+            Some(_) = components.join_next(), if !components.is_empty() => {}
+            _ = shutdown.wait() => break,
+        }
+    }
+
+    // Collectors stop along with the engine, and strategies once they drained
+    // their events, so this only waits for them to wind down.
+    drop(contexts);
+    while components.join_next().await.is_some() {}
+}
@@ -0,0 +1,236 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use thiserror::Error;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tracing::error;
+
+/// An action that an executor failed to execute.
+#[derive(Debug, Clone)]
+pub struct DeadLetter<A> {
+    /// Identifies the dead letter within its [DeadLetterQueue](DeadLetterQueue).
+    pub id: u64,
+    /// The name of the executor that failed to execute the action.
+    pub executor: String,
+    /// The failed action.
+    pub action: A,
+    /// The error returned by the executor.
+    pub error: String,
+    /// When the action failed.
+    pub failed_at: SystemTime,
+}
+
+/// Errors returned when re-driving a [DeadLetter](DeadLetter).
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DeadLetterError {
+    #[error("no dead letter with id {0}")]
+    UnknownLetter(u64),
+    #[error("executor {0} is not running")]
+    ExecutorStopped(String),
+}
+
+/// Persistent storage for dead letters, e.g. a database table or a file, so that
+/// failed actions survive a restart of the process.
+#[async_trait]
+pub trait DeadLetterBackend<A>: Send + Sync {
+    /// Persists a dead letter.
+    async fn store(&self, letter: DeadLetter<A>) -> Result<()>;
+
+    /// Removes a dead letter once it has been re-driven or discarded.
+    async fn remove(&self, id: u64) -> Result<()>;
+}
+
+/// Records the actions that executors failed to execute, along with the error,
+/// so they can be inspected and re-driven instead of being lost.
+///
+/// The most recent dead letters are kept in memory, up to the queue's capacity,
+/// and every dead letter is also handed to the [DeadLetterBackend](DeadLetterBackend),
+/// if set. The queue is a cheap handle: keep a clone to inspect it while the
+/// [Engine](super::Engine) is running.
+pub struct DeadLetterQueue<A> {
+    inner: Arc<Inner<A>>,
+}
+
+struct Inner<A> {
+    capacity: usize,
+    next_id: AtomicU64,
+    letters: Mutex<VecDeque<DeadLetter<A>>>,
+    backend: Option<Box<dyn DeadLetterBackend<A>>>,
+    /// Re-driven actions are sent back to the executor that failed them.
+    executors: Mutex<HashMap<String, UnboundedSender<A>>>,
+}
+
+impl<A> Clone for DeadLetterQueue<A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<A> DeadLetterQueue<A> {
+    /// Creates a queue keeping up to `capacity` dead letters in memory. Once full,
+    /// the oldest dead letter is evicted from memory.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_inner(capacity, None)
+    }
+
+    /// Creates a queue that also persists every dead letter to `backend`.
+    pub fn with_backend(capacity: usize, backend: Box<dyn DeadLetterBackend<A>>) -> Self {
+        Self::with_inner(capacity, Some(backend))
+    }
+
+    fn with_inner(capacity: usize, backend: Option<Box<dyn DeadLetterBackend<A>>>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                capacity,
+                next_id: AtomicU64::new(0),
+                letters: Mutex::default(),
+                backend,
+                executors: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Returns the number of dead letters kept in memory.
+    pub fn len(&self) -> usize {
+        self.inner.letters.lock().unwrap().len()
+    }
+
+    /// Returns true if no dead letters are kept in memory.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Re-drives a dead letter: its action is sent back to the executor that failed
+    /// it, and the dead letter is removed from the queue.
+    pub async fn redrive(&self, id: u64) -> Result<(), DeadLetterError> {
+        let letter = self.take(id).ok_or(DeadLetterError::UnknownLetter(id))?;
+        let sender = self
+            .inner
+            .executors
+            .lock()
+            .unwrap()
+            .get(&letter.executor)
+            .cloned();
+        let Some(sender) = sender else {
+            let executor = letter.executor.clone();
+            self.restore(letter);
+            return Err(DeadLetterError::ExecutorStopped(executor));
+        };
+        let DeadLetter {
+            id,
+            executor,
+            action,
+            error,
+            failed_at,
+        } = letter;
+        if let Err(rejected) = sender.send(action) {
+            self.restore(DeadLetter {
+                id,
+                executor: executor.clone(),
+                action: rejected.0,
+                error,
+                failed_at,
+            });
+            return Err(DeadLetterError::ExecutorStopped(executor));
+        }
+        self.remove_persisted(id).await;
+        Ok(())
+    }
+
+    /// Re-drives every dead letter kept in memory, oldest first. Returns the number
+    /// of re-driven dead letters.
+    pub async fn redrive_all(&self) -> usize {
+        let ids: Vec<u64> = {
+            let letters = self.inner.letters.lock().unwrap();
+            letters.iter().map(|letter| letter.id).collect()
+        };
+        let mut redriven = 0;
+        for id in ids {
+            if self.redrive(id).await.is_ok() {
+                redriven += 1;
+            }
+        }
+        redriven
+    }
+
+    /// Discards a dead letter without re-driving it.
+    pub async fn discard(&self, id: u64) -> Option<DeadLetter<A>> {
+        let letter = self.take(id)?;
+        self.remove_persisted(id).await;
+        Some(letter)
+    }
+
+    /// Records an action that an executor failed to execute.
+    pub(crate) async fn record(&self, executor: String, action: A, error: String)
+    where
+        A: Clone,
+    {
+        let letter = DeadLetter {
+            id: self.inner.next_id.fetch_add(1, Ordering::Relaxed),
+            executor,
+            action,
+            error,
+            failed_at: SystemTime::now(),
+        };
+        if let Some(backend) = &self.inner.backend {
+            if let Err(e) = backend.store(letter.clone()).await {
+                error!("error persisting dead letter {}: {}", letter.id, e);
+            }
+        }
+        let mut letters = self.inner.letters.lock().unwrap();
+        letters.push_back(letter);
+        if letters.len() > self.inner.capacity {
+            letters.pop_front();
+        }
+    }
+
+    /// Registers a running executor, and returns the receiver of the actions
+    /// re-driven to it.
+    pub(crate) fn attach(&self, executor: &str) -> UnboundedReceiver<A> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut executors = self.inner.executors.lock().unwrap();
+        executors.insert(executor.to_string(), sender);
+        receiver
+    }
+
+    fn take(&self, id: u64) -> Option<DeadLetter<A>> {
+        let mut letters = self.inner.letters.lock().unwrap();
+        let index = letters.iter().position(|letter| letter.id == id)?;
+        letters.remove(index)
+    }
+
+    /// Puts back a dead letter that could not be re-driven, keeping the queue
+    /// ordered by id.
+    fn restore(&self, letter: DeadLetter<A>) {
+        let mut letters = self.inner.letters.lock().unwrap();
+        let index = letters.partition_point(|other| other.id < letter.id);
+        letters.insert(index, letter);
+    }
+
+    async fn remove_persisted(&self, id: u64) {
+        if let Some(backend) = &self.inner.backend {
+            if let Err(e) = backend.remove(id).await {
+                error!("error removing persisted dead letter {}: {}", id, e);
+            }
+        }
+    }
+}
+
+impl<A: Clone> DeadLetterQueue<A> {
+    /// Returns the dead letters kept in memory, oldest first.
+    pub fn letters(&self) -> Vec<DeadLetter<A>> {
+        self.inner.letters.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Returns the dead letter with the given id, if it is kept in memory.
+    pub fn get(&self, id: u64) -> Option<DeadLetter<A>> {
+        let letters = self.inner.letters.lock().unwrap();
+        letters.iter().find(|letter| letter.id == id).cloned()
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Deduplicates identical actions emitted within a time window, across all
+/// strategies of the engine. Actions are identified by a user-provided key.
+pub(crate) struct ActionDedup<A> {
+    key: Arc<dyn Fn(&A) -> u64 + Send + Sync>,
+    window: Duration,
+    state: Mutex<DedupState>,
+}
+
+struct DedupState {
+    /// When each key was last let through.
+    seen: HashMap<u64, Instant>,
+    /// When expired keys were last pruned.
+    pruned_at: Instant,
+}
+
+impl<A> ActionDedup<A> {
+    pub(crate) fn new(window: Duration, key: Arc<dyn Fn(&A) -> u64 + Send + Sync>) -> Self {
+        Self {
+            key,
+            window,
+            state: Mutex::new(DedupState {
+                seen: HashMap::new(),
+                pruned_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Returns true if the action should be sent, i.e. no action with the same
+    /// key was sent within the window.
+    pub(crate) fn check(&self, action: &A) -> bool {
+        let key = (self.key)(action);
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+
+        // Prune expired keys at most once per window, so memory stays bounded by
+        // the number of distinct actions emitted within two windows.
+        if now.duration_since(state.pruned_at) >= self.window {
+            let window = self.window;
+            state
+                .seen
+                .retain(|_, sent_at| now.duration_since(*sent_at) < window);
+            state.pruned_at = now;
+        }
+
+        match state.seen.get(&key) {
+            Some(sent_at) if now.duration_since(*sent_at) < self.window => false,
+            _ => {
+                state.seen.insert(key, now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dedup(window: Duration) -> ActionDedup<u64> {
+        ActionDedup::new(window, Arc::new(|action: &u64| *action))
+    }
+
+    #[test]
+    fn test_dedup_drops_duplicates_within_window() {
+        let dedup = dedup(Duration::from_secs(60));
+        assert!(dedup.check(&1));
+        assert!(!dedup.check(&1));
+        assert!(dedup.check(&2));
+        assert!(!dedup.check(&2));
+    }
+
+    #[test]
+    fn test_dedup_sends_again_after_window() {
+        let dedup = dedup(Duration::from_millis(20));
+        assert!(dedup.check(&1));
+        assert!(!dedup.check(&1));
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(dedup.check(&1));
+        assert!(!dedup.check(&1));
+        // Expired keys are pruned once a window has passed.
+        assert_eq!(dedup.state.lock().unwrap().seen.len(), 1);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::error::Result;
+use crate::types::{ExecutionReceipt, Executor};
+
+/// An action captured by the [Engine](super::Engine) in dry-run mode, instead of
+/// being executed.
+#[derive(Debug, Clone)]
+pub struct CapturedAction<A> {
+    /// The name of the executor that would have executed the action.
+    pub executor: String,
+    /// The captured action.
+    pub action: A,
+    /// When the action was captured.
+    pub captured_at: SystemTime,
+}
+
+/// Records the actions that would have been executed while the [Engine](super::Engine)
+/// runs in dry-run mode, so a new strategy can be tested against live collectors
+/// without risking funds.
+///
+/// In dry-run mode, every executor is replaced with one that captures its actions.
+/// Routes, priorities and concurrency still apply, so each captured action names
+/// the executor that would have received it. The most recent actions are kept, up
+/// to the recorder's capacity. The recorder is a cheap handle: keep a clone to
+/// inspect it while the engine is running.
+pub struct DryRun<A> {
+    capacity: usize,
+    captured: Arc<Mutex<VecDeque<CapturedAction<A>>>>,
+}
+
+impl<A> Clone for DryRun<A> {
+    fn clone(&self) -> Self {
+        Self {
+            capacity: self.capacity,
+            captured: self.captured.clone(),
+        }
+    }
+}
+
+impl<A> DryRun<A> {
+    /// Creates a recorder keeping up to `capacity` captured actions. Once full,
+    /// the oldest action is evicted.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            captured: Arc::default(),
+        }
+    }
+
+    /// Returns the number of captured actions.
+    pub fn len(&self) -> usize {
+        self.captured.lock().unwrap().len()
+    }
+
+    /// Returns true if no action has been captured.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes and returns the captured actions, oldest first.
+    pub fn drain(&self) -> Vec<CapturedAction<A>> {
+        self.captured.lock().unwrap().drain(..).collect()
+    }
+
+    /// Returns an executor that captures the actions of the named executor.
+    pub(crate) fn executor(&self, name: &str) -> CapturingExecutor<A> {
+        CapturingExecutor {
+            name: name.to_string(),
+            dry_run: self.clone(),
+        }
+    }
+
+    fn capture(&self, executor: String, action: A) {
+        let mut captured = self.captured.lock().unwrap();
+        captured.push_back(CapturedAction {
+            executor,
+            action,
+            captured_at: SystemTime::now(),
+        });
+        if captured.len() > self.capacity {
+            captured.pop_front();
+        }
+    }
+}
+
+impl<A: Clone> DryRun<A> {
+    /// Returns the captured actions, oldest first.
+    pub fn captured(&self) -> Vec<CapturedAction<A>> {
+        self.captured.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// An executor that records actions in a [DryRun](DryRun) instead of executing them.
+pub(crate) struct CapturingExecutor<A> {
+    name: String,
+    dry_run: DryRun<A>,
+}
+
+#[async_trait]
+impl<A> Executor<A> for CapturingExecutor<A>
+where
+    A: Debug + Send + 'static,
+{
+    async fn execute(&self, action: A) -> Result<()> {
+        info!(
+            "dry run: executor {} would have executed {:?}",
+            self.name, action
+        );
+        self.dry_run.capture(self.name.clone(), action);
+        Ok(())
+    }
+
+    async fn execute_with_receipt(&self, action: A) -> Result<ExecutionReceipt> {
+        self.execute(action).await?;
+        Ok(ExecutionReceipt::DryRun)
+    }
+}
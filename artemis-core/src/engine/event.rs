@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::types::ExecutionReceipt;
+
+/// Lifecycle events emitted by the [Engine](super::Engine) itself.
+///
+/// Engine events are delivered to strategies alongside regular events once they
+/// are mapped into the pipeline's event type with
+/// [with_engine_events](super::Engine::with_engine_events).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EngineEvent {
+    /// A collector was restarted after its stream ended or failed.
+    CollectorRestarted {
+        /// The name of the collector.
+        collector: String,
+        /// The number of consecutive restarts, starting at 1.
+        attempt: u32,
+        /// Why the collector was restarted.
+        reason: String,
+    },
+    /// The engine gave up restarting a collector.
+    CollectorGaveUp {
+        /// The name of the collector.
+        collector: String,
+        /// The number of consecutive restarts that were attempted.
+        attempts: u32,
+    },
+    /// A strategy panicked while processing an event.
+    StrategyPanicked {
+        /// The name of the strategy.
+        strategy: String,
+        /// The panic message.
+        message: String,
+    },
+    /// A strategy was restarted after it panicked.
+    StrategyRestarted {
+        /// The name of the strategy.
+        strategy: String,
+        /// The number of consecutive restarts, starting at 1.
+        attempt: u32,
+    },
+    /// The engine gave up restarting a strategy.
+    StrategyGaveUp {
+        /// The name of the strategy.
+        strategy: String,
+        /// The number of consecutive restarts that were attempted.
+        attempts: u32,
+    },
+    /// A strategy fell behind, and skipped events. Only sent to the strategy that
+    /// skipped them, if it enabled [drop notifications](super::StrategyConfig::drop_notifications).
+    EventsDropped {
+        /// The name of the strategy.
+        strategy: String,
+        /// The number of skipped events.
+        count: u64,
+    },
+}
+
+/// Maps [engine events](EngineEvent) into the pipeline's event type. Events
+/// mapped to `None` are not delivered.
+pub(crate) type EngineEventMap<E> = Arc<dyn Fn(EngineEvent) -> Option<E> + Send + Sync>;
+
+/// The outcome of an action executed by the [Engine](super::Engine), fed back to
+/// the strategies once mapped into the pipeline's event type with
+/// [with_execution_feedback](super::Engine::with_execution_feedback).
+#[derive(Debug, Clone)]
+pub struct ExecutionFeedback<A> {
+    /// The name of the executor that executed the action.
+    pub executor: String,
+    /// The executed action.
+    pub action: A,
+    /// The receipt returned by the executor, or the error it failed with.
+    pub result: Result<ExecutionReceipt, String>,
+}
+
+/// Maps [execution feedback](ExecutionFeedback) into the pipeline's event type.
+/// Feedback mapped to `None` is not delivered.
+pub(crate) type FeedbackMap<A, E> = Arc<dyn Fn(ExecutionFeedback<A>) -> Option<E> + Send + Sync>;
+
+/// Splits the actions emitted by strategies into events published to the other
+/// strategies, and actions sent to the executors.
+pub(crate) type PublishMap<A, E> = Arc<dyn Fn(A) -> Result<E, A> + Send + Sync>;
+
+/// Maps an [EngineEvent](EngineEvent) into the pipeline's event type, if engine
+/// events are enabled.
+pub(crate) fn map_engine_event<E>(
+    engine_events: &Option<EngineEventMap<E>>,
+    event: EngineEvent,
+) -> Option<E> {
+    engine_events.as_ref().and_then(|map| map(event))
+}
@@ -0,0 +1,339 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::Semaphore;
+use tokio::task::{JoinError, JoinSet};
+use tracing::{error, info, warn};
+
+use super::channel::{Channel, ChannelReceiver};
+use super::dead_letter::DeadLetterQueue;
+use super::event::{ExecutionFeedback, FeedbackMap};
+use super::health::{ComponentStatus, HealthRegistry, HealthTracker};
+use super::metrics::ExecutorMetrics;
+use super::runtime::Spawner;
+use super::spend::SpendGuard;
+use super::{ErrorPolicy, ExecutorEntry, ShutdownHandle};
+use crate::error::{ArtemisError, Result};
+use crate::types::{ExecutionReceipt, Executor};
+
+/// The engine state shared by all executor tasks.
+pub(crate) struct ExecutorContext<E, A> {
+    pub(crate) shutdown: ShutdownHandle,
+    pub(crate) feedback: Option<(Channel<E>, FeedbackMap<A, E>)>,
+    pub(crate) priority: Option<fn(&A) -> u8>,
+    /// The maximum number of actions queued by each executor.
+    pub(crate) queue_capacity: usize,
+    /// Records failed actions, if set.
+    pub(crate) dead_letters: Option<DeadLetterQueue<A>>,
+    pub(crate) health: Arc<HealthRegistry>,
+    pub(crate) spawner: Spawner,
+    /// Halts execution once the spend budget is exceeded, if set.
+    pub(crate) spend_guard: Option<SpendGuard>,
+}
+
+impl<E, A> Clone for ExecutorContext<E, A> {
+    fn clone(&self) -> Self {
+        Self {
+            shutdown: self.shutdown.clone(),
+            feedback: self.feedback.clone(),
+            priority: self.priority,
+            queue_capacity: self.queue_capacity,
+            dead_letters: self.dead_letters.clone(),
+            health: self.health.clone(),
+            spawner: self.spawner.clone(),
+            spend_guard: self.spend_guard.clone(),
+        }
+    }
+}
+
+/// Receives actions from the action channel and executes them, running up to
+/// the executor's concurrency at once. Queued actions are executed by priority,
+/// and in the order they were received within the same priority. Actions that
+/// fail are recorded in the dead-letter queue, if enabled, which can re-drive
+/// them to this executor.
+pub(crate) async fn run_executor<E, A>(
+    entry: ExecutorEntry<A>,
+    mut receiver: ChannelReceiver<A>,
+    ctx: ExecutorContext<E, A>,
+) where
+    E: Clone + Send + 'static,
+    A: Clone + Send + 'static,
+{
+    let ExecutorEntry {
+        name,
+        executor,
+        config,
+        route,
+    } = entry;
+    let executor: Arc<dyn Executor<A>> = Arc::from(executor);
+    let permits = Arc::new(Semaphore::new(config.concurrency));
+    let metrics = ExecutorMetrics::new(&name);
+    let health = ctx.health.executor(&name);
+    let mut queue = ActionQueue::new(ctx.priority);
+    let mut pending = JoinSet::new();
+    let mut open = true;
+    let mut redrives = ctx
+        .dead_letters
+        .as_ref()
+        .map(|dead_letters| dead_letters.attach(&name));
+
+    info!("starting executor {}... ", name);
+    health.set_status(ComponentStatus::Running);
+    while open || !queue.is_empty() {
+        tokio::select! {
+            Some(result) = pending.join_next(), if !pending.is_empty() => {
+                if handle_execution(result, &name, config.error_policy, &ctx.shutdown, &metrics, &health) {
+                    break;
+                }
+            }
+            action = receiver.recv(), if open && queue.len() < ctx.queue_capacity => match action {
+                Ok(Some(action)) => {
+                    if route.as_ref().is_none_or(|route| route(&action)) {
+                        queue.push(action);
+                    }
+                }
+                Ok(None) => open = false,
+                Err(skipped) => {
+                    metrics.lagged.increment(skipped);
+                    health.lagged(skipped);
+                    warn!("executor {} lagged, skipped {} actions", name, skipped)
+                }
+            },
+            action = recv_redrive(&mut redrives), if open => match action {
+                Some(action) => queue.push(action),
+                None => redrives = None,
+            },
+            permit = permits.clone().acquire_owned(), if !queue.is_empty() => {
+                let permit = permit.expect("executor semaphore is never closed");
+                let Some(action) = queue.pop() else {
+                    continue;
+                };
+                if ctx.spend_guard.as_ref().is_some_and(SpendGuard::is_tripped) {
+                    metrics.halted.increment(1);
+                    warn!("executor {} dropped an action, execution is halted", name);
+                    if let Some(dead_letters) = &ctx.dead_letters {
+                        let reason = "execution halted by the spend guard".to_string();
+                        dead_letters.record(name.clone(), action, reason).await;
+                    }
+                    continue;
+                }
+                let executor = executor.clone();
+                let latency = metrics.latency.clone();
+                let feedback = ctx.feedback.clone();
+                let dead_letters = ctx.dead_letters.clone();
+                let spend_guard = ctx.spend_guard.clone();
+                let task = {
+                    let name = name.clone();
+                    async move {
+                        let _permit = permit;
+                        let started = Instant::now();
+                        let failed = dead_letters.as_ref().map(|_| action.clone());
+                        let spent = spend_guard
+                            .as_ref()
+                            .and_then(|_| executor.spent(&action));
+                        let result = match feedback {
+                            Some((sender, map)) => {
+                                let executed = action.clone();
+                                let result = executor.execute_with_receipt(action).await;
+                                send_feedback(&sender, &map, name.clone(), executed, &result).await;
+                                result.map(|_| ())
+                            }
+                            None => executor.execute(action).await,
+                        };
+                        latency.record(started.elapsed().as_secs_f64());
+                        // Spend is recorded before the permit is released, so with a
+                        // concurrency of 1 the next action sees a tripped guard.
+                        if let (Ok(()), Some(spend_guard), Some(spent)) =
+                            (&result, spend_guard, spent)
+                        {
+                            spend_guard.record(spent);
+                        }
+                        if let (Err(e), Some(dead_letters), Some(action)) =
+                            (&result, dead_letters, failed)
+                        {
+                            dead_letters.record(name, action, e.to_string()).await;
+                        }
+                        result
+                    }
+                };
+                ctx.spawner.spawn(&mut pending, "action", &name, task);
+            }
+        }
+    }
+
+    // Let pending submissions finish before exiting.
+    while let Some(result) = pending.join_next().await {
+        handle_execution(
+            result,
+            &name,
+            config.error_policy,
+            &ctx.shutdown,
+            &metrics,
+            &health,
+        );
+    }
+    health.set_status(ComponentStatus::Stopped);
+}
+
+/// An action waiting to be executed.
+struct Queued<A> {
+    priority: u8,
+    /// Breaks ties between actions of the same priority, oldest first.
+    seq: Reverse<u64>,
+    action: A,
+}
+
+impl<A> PartialEq for Queued<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<A> Eq for Queued<A> {}
+
+impl<A> PartialOrd for Queued<A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<A> Ord for Queued<A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.priority, self.seq).cmp(&(other.priority, other.seq))
+    }
+}
+
+/// The queue of actions waiting for an executor. Without a priority function,
+/// every action has the same priority, and the queue is first-in, first-out.
+struct ActionQueue<A> {
+    heap: BinaryHeap<Queued<A>>,
+    priority: Option<fn(&A) -> u8>,
+    next_seq: u64,
+}
+
+impl<A> ActionQueue<A> {
+    fn new(priority: Option<fn(&A) -> u8>) -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            priority,
+            next_seq: 0,
+        }
+    }
+
+    fn push(&mut self, action: A) {
+        let priority = self.priority.map_or(0, |priority| priority(&action));
+        let seq = Reverse(self.next_seq);
+        self.next_seq += 1;
+        self.heap.push(Queued {
+            priority,
+            seq,
+            action,
+        });
+    }
+
+    fn pop(&mut self) -> Option<A> {
+        self.heap.pop().map(|queued| queued.action)
+    }
+
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+/// Receives the next action re-driven from the dead-letter queue, if enabled.
+/// Never resolves once the dead-letter queue is disabled.
+async fn recv_redrive<A>(receiver: &mut Option<UnboundedReceiver<A>>) -> Option<A> {
+    match receiver {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Maps the outcome of an executed action into an event, and delivers it to the
+/// strategies.
+async fn send_feedback<A, E: Clone>(
+    sender: &Channel<E>,
+    map: &FeedbackMap<A, E>,
+    executor: String,
+    action: A,
+    result: &Result<ExecutionReceipt>,
+) {
+    let feedback = ExecutionFeedback {
+        executor,
+        action,
+        result: result.as_ref().map(Clone::clone).map_err(|e| e.to_string()),
+    };
+    if let Some(event) = map(feedback) {
+        if let Err(e) = sender.send(event).await {
+            error!("error sending execution feedback: {}", e);
+        }
+    }
+}
+
+/// Logs the outcome of an executed action and applies the executor's error
+/// policy. Returns true if the executor should stop accepting new actions.
+fn handle_execution(
+    result: Result<Result<()>, JoinError>,
+    name: &str,
+    policy: ErrorPolicy,
+    shutdown: &ShutdownHandle,
+    metrics: &ExecutorMetrics,
+    health: &HealthTracker,
+) -> bool {
+    metrics.actions.increment(1);
+    health.processed(1);
+    let e = match result {
+        Ok(Ok(())) => return false,
+        Ok(Err(e)) => e,
+        Err(e) => ArtemisError::other(e),
+    };
+    metrics.errors.increment(1);
+    health.failed(&e);
+    error!("error executing action in executor {}: {}", name, e);
+    match policy {
+        ErrorPolicy::Ignore => false,
+        ErrorPolicy::Stop => true,
+        ErrorPolicy::Shutdown => {
+            shutdown.shutdown();
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_queue_is_fifo_without_priority() {
+        let mut queue = ActionQueue::new(None);
+        for action in [3u8, 1, 2] {
+            queue.push(action);
+        }
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.pop(), Some(2));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_action_queue_orders_by_priority_then_fifo() {
+        // Actions are (priority, id) pairs.
+        let mut queue = ActionQueue::new(Some(|action: &(u8, u8)| action.0));
+        for action in [(0, 0), (2, 1), (1, 2), (2, 3), (0, 4), (1, 5)] {
+            queue.push(action);
+        }
+        let order: Vec<_> = std::iter::from_fn(|| queue.pop())
+            .map(|(_, id)| id)
+            .collect();
+        assert_eq!(order, vec![1, 3, 2, 5, 0, 4]);
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use tokio::sync::mpsc::{self, Receiver, Sender, UnboundedReceiver};
+use tracing::error;
+
+use super::channel::{ChannelError, QueueSender, Sequencer};
+
+/// The event queue of a strategy, along with the topics it subscribed to.
+pub(crate) struct Route<E> {
+    pub(crate) strategy: String,
+    /// The topics of the events delivered to the strategy. `None` delivers every
+    /// event.
+    pub(crate) topics: Option<HashSet<&'static str>>,
+    pub(crate) queue: QueueSender<E>,
+}
+
+impl<E> Route<E> {
+    fn accepts(&self, topic: Option<&'static str>) -> bool {
+        match (&self.topics, topic) {
+            (Some(topics), Some(topic)) => topics.contains(topic),
+            _ => true,
+        }
+    }
+}
+
+/// Sends collected events to the [FanOut](FanOut) task, which delivers them to
+/// the strategies.
+pub(crate) struct EventRouter<E> {
+    sender: Sender<E>,
+}
+
+impl<E> Clone for EventRouter<E> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<E> EventRouter<E> {
+    /// Sends an event to the strategies. Waits while the fan-out task is busy
+    /// delivering earlier events.
+    pub(crate) async fn send(&self, event: E) -> Result<(), ChannelError> {
+        self.sender
+            .send(event)
+            .await
+            .map_err(|_| ChannelError::Closed)
+    }
+}
+
+/// Delivers collected events to the strategies. Every strategy has its own queue,
+/// so a strategy that falls behind only drops events for itself, while all
+/// strategies receive events in the same order. Strategies subscribed to topics
+/// only receive the events of their topics. Strategies added at runtime receive
+/// the events collected once their route was handed to the task.
+pub(crate) struct FanOut<E> {
+    receiver: Receiver<E>,
+    topic: Option<fn(&E) -> &'static str>,
+    routes: Vec<Route<E>>,
+    new_routes: UnboundedReceiver<Route<E>>,
+    sequencer: Option<Sequencer>,
+}
+
+/// Creates the fan-out task of the engine, along with the sender collectors use to
+/// feed it. If `sequencer` is set, events are stamped with sequence numbers from it.
+pub(crate) fn fan_out<E>(
+    capacity: usize,
+    topic: Option<fn(&E) -> &'static str>,
+    routes: Vec<Route<E>>,
+    new_routes: UnboundedReceiver<Route<E>>,
+    sequencer: Option<Sequencer>,
+) -> (EventRouter<E>, FanOut<E>) {
+    let (sender, receiver) = mpsc::channel(capacity);
+    let fan_out = FanOut {
+        receiver,
+        topic,
+        routes,
+        new_routes,
+        sequencer,
+    };
+    (EventRouter { sender }, fan_out)
+}
+
+impl<E: Clone> FanOut<E> {
+    /// Delivers events until every collector has stopped, or every strategy has
+    /// and no more strategies can be added.
+    pub(crate) async fn run(mut self) {
+        let sequencer = self.sequencer.take();
+        let mut adding = true;
+        while adding || !self.routes.is_empty() {
+            let event = tokio::select! {
+                // New routes are taken first, so a strategy receives every event
+                // collected after it was added.
+                biased;
+                route = self.new_routes.recv(), if adding => {
+                    match route {
+                        Some(route) => self.routes.push(route),
+                        None => adding = false,
+                    }
+                    continue;
+                }
+                event = self.receiver.recv() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+            };
+
+            // The sequencer stays locked until the event was queued for every
+            // strategy, so sequence numbers are consistent with the internal channel.
+            match &sequencer {
+                Some(sequencer) => {
+                    let mut next = sequencer.lock().await;
+                    self.deliver(*next, event).await;
+                    *next += 1;
+                }
+                None => self.deliver(0, event).await,
+            }
+
+            // Strategies that stopped, e.g. after a panic or once removed, no
+            // longer need events.
+            self.routes.retain(|route| !route.queue.is_closed());
+        }
+    }
+
+    async fn deliver(&mut self, seq: u64, event: E) {
+        let topic = self.topic.map(|topic| topic(&event));
+        for route in self.routes.iter_mut().filter(|route| route.accepts(topic)) {
+            match route.queue.send(seq, event.clone()).await {
+                Ok(()) | Err(ChannelError::Closed) => {}
+                Err(e) => error!("error sending event to strategy {}: {}", route.strategy, e),
+            }
+        }
+    }
+}
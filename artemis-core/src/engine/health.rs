@@ -0,0 +1,260 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use super::pause::PauseSwitch;
+use super::{ChannelStats, ShutdownHandle};
+
+/// A point-in-time view of the health of a running [Engine](super::Engine),
+/// obtained with [Engine::health](super::Engine::health) or a [HealthHandle](HealthHandle).
+///
+/// The snapshot is serializable, so it can be exposed as is by a readiness probe
+/// or fed to a dashboard. Timestamps are in milliseconds since the Unix epoch.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineHealth {
+    /// The collectors started by the engine, by name.
+    pub collectors: Vec<ComponentHealth>,
+    /// The strategies started by the engine, by name.
+    pub strategies: Vec<ComponentHealth>,
+    /// The executors started by the engine, by name.
+    pub executors: Vec<ComponentHealth>,
+    /// Dropped message counters of the event channels.
+    pub event_channel: ChannelHealth,
+    /// Dropped message counters of the action channel.
+    pub action_channel: ChannelHealth,
+    /// Whether the engine is paused.
+    pub paused: bool,
+    /// Whether a shutdown of the engine was triggered.
+    pub shutting_down: bool,
+}
+
+impl EngineHealth {
+    /// Returns true if every component started by the engine is running.
+    pub fn is_healthy(&self) -> bool {
+        !self.shutting_down
+            && self
+                .collectors
+                .iter()
+                .chain(&self.strategies)
+                .chain(&self.executors)
+                .all(|component| component.status == ComponentStatus::Running)
+    }
+}
+
+/// The health of a single collector, strategy or executor.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub name: String,
+    pub status: ComponentStatus,
+    /// When the component last received an event, or completed an action for
+    /// executors.
+    pub last_event_at: Option<u64>,
+    /// The number of events processed by the component, or actions executed for
+    /// executors.
+    pub processed: u64,
+    /// The number of times the component was restarted.
+    pub restarts: u32,
+    /// The number of messages the component skipped because it fell behind.
+    pub lagged: u64,
+    /// The number of failures: errors starting a collector's stream, strategy
+    /// panics, and failed actions.
+    pub errors: u64,
+    /// The last failure of the component.
+    pub last_error: Option<String>,
+    /// When the last failure occurred.
+    pub last_error_at: Option<u64>,
+}
+
+/// The lifecycle status of a component of the engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    /// The component is starting, e.g. a collector creating its event stream.
+    Starting,
+    /// The component is running.
+    Running,
+    /// The component failed, and waits to be restarted.
+    Restarting,
+    /// The component has stopped.
+    Stopped,
+}
+
+/// Dropped message counters of an engine channel. See [ChannelStats](ChannelStats).
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ChannelHealth {
+    pub lagged: u64,
+    pub rejected: u64,
+}
+
+impl From<&ChannelStats> for ChannelHealth {
+    fn from(stats: &ChannelStats) -> Self {
+        Self {
+            lagged: stats.lagged(),
+            rejected: stats.rejected(),
+        }
+    }
+}
+
+/// A handle to read the health of a running [Engine](super::Engine), obtained
+/// with [Engine::health_handle](super::Engine::health_handle). The handle is
+/// cheap to clone, and outlives the engine.
+#[derive(Clone)]
+pub struct HealthHandle {
+    registry: Arc<HealthRegistry>,
+    event_channel: Arc<ChannelStats>,
+    action_channel: Arc<ChannelStats>,
+    pause: PauseSwitch,
+    shutdown: ShutdownHandle,
+}
+
+impl HealthHandle {
+    pub(crate) fn new(
+        registry: Arc<HealthRegistry>,
+        event_channel: Arc<ChannelStats>,
+        action_channel: Arc<ChannelStats>,
+        pause: PauseSwitch,
+        shutdown: ShutdownHandle,
+    ) -> Self {
+        Self {
+            registry,
+            event_channel,
+            action_channel,
+            pause,
+            shutdown,
+        }
+    }
+
+    /// Returns a snapshot of the engine's health.
+    pub fn health(&self) -> EngineHealth {
+        EngineHealth {
+            collectors: snapshot(&self.registry.collectors),
+            strategies: snapshot(&self.registry.strategies),
+            executors: snapshot(&self.registry.executors),
+            event_channel: self.event_channel.as_ref().into(),
+            action_channel: self.action_channel.as_ref().into(),
+            paused: self.pause.is_paused(),
+            shutting_down: self.shutdown.is_shutdown(),
+        }
+    }
+}
+
+/// The health trackers of the components started by the engine. A component
+/// started again under the same name, e.g. a collector re-added through a
+/// [ControlHandle](super::ControlHandle), replaces the previous tracker.
+#[derive(Default)]
+pub(crate) struct HealthRegistry {
+    collectors: Mutex<Vec<Arc<HealthTracker>>>,
+    strategies: Mutex<Vec<Arc<HealthTracker>>>,
+    executors: Mutex<Vec<Arc<HealthTracker>>>,
+}
+
+impl HealthRegistry {
+    pub(crate) fn collector(&self, name: &str) -> Arc<HealthTracker> {
+        register(&self.collectors, name)
+    }
+
+    pub(crate) fn strategy(&self, name: &str) -> Arc<HealthTracker> {
+        register(&self.strategies, name)
+    }
+
+    pub(crate) fn executor(&self, name: &str) -> Arc<HealthTracker> {
+        register(&self.executors, name)
+    }
+}
+
+fn register(trackers: &Mutex<Vec<Arc<HealthTracker>>>, name: &str) -> Arc<HealthTracker> {
+    let tracker = Arc::new(HealthTracker::new(name));
+    let mut trackers = trackers.lock().unwrap();
+    match trackers.iter_mut().find(|other| other.name == name) {
+        Some(other) => *other = tracker.clone(),
+        None => trackers.push(tracker.clone()),
+    }
+    tracker
+}
+
+fn snapshot(trackers: &Mutex<Vec<Arc<HealthTracker>>>) -> Vec<ComponentHealth> {
+    let trackers = trackers.lock().unwrap();
+    trackers.iter().map(|tracker| tracker.snapshot()).collect()
+}
+
+/// Tracks the health of a single component. Updates on the hot path are atomic,
+/// so components never contend with each other or with readers.
+pub(crate) struct HealthTracker {
+    name: String,
+    status: AtomicU8,
+    last_event_at: AtomicU64,
+    processed: AtomicU64,
+    restarts: AtomicU32,
+    lagged: AtomicU64,
+    errors: AtomicU64,
+    last_error: Mutex<Option<(String, u64)>>,
+}
+
+impl HealthTracker {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: AtomicU8::new(ComponentStatus::Starting as u8),
+            last_event_at: AtomicU64::new(0),
+            processed: AtomicU64::new(0),
+            restarts: AtomicU32::new(0),
+            lagged: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            last_error: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn set_status(&self, status: ComponentStatus) {
+        self.status.store(status as u8, Ordering::Relaxed);
+    }
+
+    /// Records `count` processed events or executed actions.
+    pub(crate) fn processed(&self, count: u64) {
+        self.processed.fetch_add(count, Ordering::Relaxed);
+        self.last_event_at.store(now(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn restarted(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn lagged(&self, skipped: u64) {
+        self.lagged.fetch_add(skipped, Ordering::Relaxed);
+    }
+
+    pub(crate) fn failed(&self, error: impl ToString) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock().unwrap() = Some((error.to_string(), now()));
+    }
+
+    fn snapshot(&self) -> ComponentHealth {
+        let status = match self.status.load(Ordering::Relaxed) {
+            s if s == ComponentStatus::Starting as u8 => ComponentStatus::Starting,
+            s if s == ComponentStatus::Running as u8 => ComponentStatus::Running,
+            s if s == ComponentStatus::Restarting as u8 => ComponentStatus::Restarting,
+            _ => ComponentStatus::Stopped,
+        };
+        let last_event_at = self.last_event_at.load(Ordering::Relaxed);
+        let (last_error, last_error_at) = self.last_error.lock().unwrap().clone().unzip();
+        ComponentHealth {
+            name: self.name.clone(),
+            status,
+            last_event_at: (last_event_at > 0).then_some(last_event_at),
+            processed: self.processed.load(Ordering::Relaxed),
+            restarts: self.restarts.load(Ordering::Relaxed),
+            lagged: self.lagged.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            last_error,
+            last_error_at,
+        }
+    }
+}
+
+/// Returns the current time in milliseconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
@@ -0,0 +1,105 @@
+use metrics::{counter, histogram, Counter, Histogram};
+
+/// Events emitted by a collector.
+pub const COLLECTOR_EVENTS: &str = "artemis_collector_events_total";
+/// Collector events dropped by the middleware stack, or while the engine is paused.
+pub const COLLECTOR_DROPPED: &str = "artemis_collector_events_dropped_total";
+/// Collector restarts performed by the engine.
+pub const COLLECTOR_RESTARTS: &str = "artemis_collector_restarts_total";
+/// Events processed by a strategy.
+pub const STRATEGY_EVENTS: &str = "artemis_strategy_events_processed_total";
+/// Time spent by a strategy processing a single event, in seconds.
+pub const STRATEGY_LATENCY: &str = "artemis_strategy_processing_seconds";
+/// Actions emitted by a strategy.
+pub const STRATEGY_ACTIONS: &str = "artemis_strategy_actions_emitted_total";
+/// Panics raised by a strategy while processing an event.
+pub const STRATEGY_PANICS: &str = "artemis_strategy_panics_total";
+/// Actions emitted by a strategy and discarded as duplicates.
+pub const STRATEGY_DEDUPLICATED: &str = "artemis_strategy_actions_deduplicated_total";
+/// Actions executed by an executor, successfully or not.
+pub const EXECUTOR_ACTIONS: &str = "artemis_executor_actions_total";
+/// Actions that an executor failed to execute.
+pub const EXECUTOR_ERRORS: &str = "artemis_executor_errors_total";
+/// Time spent by an executor executing a single action, in seconds.
+pub const EXECUTOR_LATENCY: &str = "artemis_executor_latency_seconds";
+/// Actions dropped by an executor because execution was halted by the spend guard.
+pub const EXECUTOR_HALTED: &str = "artemis_executor_actions_halted_total";
+/// Bundles submitted to a builder by the builder executor, by outcome.
+pub const BUILDER_SUBMISSIONS: &str = "artemis_builder_submissions_total";
+/// Time spent submitting a single bundle to a builder, in seconds.
+pub const BUILDER_LATENCY: &str = "artemis_builder_latency_seconds";
+/// Bundles simulated by the simulation gate, by outcome.
+pub const BUNDLE_SIMULATIONS: &str = "artemis_bundle_simulations_total";
+/// Messages skipped by a strategy or executor that fell behind its channel.
+pub const CHANNEL_LAGGED: &str = "artemis_channel_lagged_total";
+/// Messages discarded on send because a channel was full.
+pub const CHANNEL_REJECTED: &str = "artemis_channel_rejected_total";
+
+/// Metric handles for a single collector.
+#[derive(Clone)]
+pub(crate) struct CollectorMetrics {
+    pub(crate) events: Counter,
+    pub(crate) dropped: Counter,
+    pub(crate) restarts: Counter,
+}
+
+impl CollectorMetrics {
+    pub(crate) fn new(name: &str) -> Self {
+        Self {
+            events: counter!(COLLECTOR_EVENTS, "collector" => name.to_string()),
+            dropped: counter!(COLLECTOR_DROPPED, "collector" => name.to_string()),
+            restarts: counter!(COLLECTOR_RESTARTS, "collector" => name.to_string()),
+        }
+    }
+}
+
+/// Metric handles for a single strategy.
+#[derive(Clone)]
+pub(crate) struct StrategyMetrics {
+    pub(crate) events: Counter,
+    pub(crate) latency: Histogram,
+    pub(crate) actions: Counter,
+    pub(crate) deduplicated: Counter,
+    pub(crate) panics: Counter,
+    pub(crate) lagged: Counter,
+}
+
+impl StrategyMetrics {
+    pub(crate) fn new(name: &str) -> Self {
+        Self {
+            events: counter!(STRATEGY_EVENTS, "strategy" => name.to_string()),
+            latency: histogram!(STRATEGY_LATENCY, "strategy" => name.to_string()),
+            actions: counter!(STRATEGY_ACTIONS, "strategy" => name.to_string()),
+            deduplicated: counter!(STRATEGY_DEDUPLICATED, "strategy" => name.to_string()),
+            panics: counter!(STRATEGY_PANICS, "strategy" => name.to_string()),
+            lagged: counter!(CHANNEL_LAGGED, "channel" => "event", "receiver" => name.to_string()),
+        }
+    }
+}
+
+/// Metric handles for a single executor.
+#[derive(Clone)]
+pub(crate) struct ExecutorMetrics {
+    pub(crate) actions: Counter,
+    pub(crate) errors: Counter,
+    pub(crate) latency: Histogram,
+    pub(crate) halted: Counter,
+    pub(crate) lagged: Counter,
+}
+
+impl ExecutorMetrics {
+    pub(crate) fn new(name: &str) -> Self {
+        Self {
+            actions: counter!(EXECUTOR_ACTIONS, "executor" => name.to_string()),
+            errors: counter!(EXECUTOR_ERRORS, "executor" => name.to_string()),
+            latency: histogram!(EXECUTOR_LATENCY, "executor" => name.to_string()),
+            halted: counter!(EXECUTOR_HALTED, "executor" => name.to_string()),
+            lagged: counter!(CHANNEL_LAGGED, "channel" => "action", "receiver" => name.to_string()),
+        }
+    }
+}
+
+/// Returns the counter of messages rejected by the given channel.
+pub(crate) fn channel_rejected(channel: &'static str) -> Counter {
+    counter!(CHANNEL_REJECTED, "channel" => channel)
+}
@@ -0,0 +1,771 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinSet;
+use tracing::error;
+
+use crate::state::{PersistentStrategyAdapter, StateStore};
+use crate::types::{
+    ActionPriority, BatchStrategy, BatchStrategyAdapter, Collector, EventMiddleware, EventTopic,
+    Executor, PersistentStrategy, Strategy,
+};
+
+pub use builder::{BuildError, EngineBuilder};
+pub use channel::{BackpressurePolicy, ChannelError, ChannelStats};
+use channel::{Channel, Sequencer};
+pub use config::{
+    BatchConfig, CollectorConfig, ErrorPolicy, ExecutorConfig, RestartPolicy, StrategyConfig,
+};
+use control::{Command, ComponentRegistry};
+pub use control::{ControlError, ControlHandle};
+pub use dead_letter::{DeadLetter, DeadLetterBackend, DeadLetterError, DeadLetterQueue};
+use dedup::ActionDedup;
+pub use dry_run::{CapturedAction, DryRun};
+pub use event::{EngineEvent, ExecutionFeedback};
+use event::{EngineEventMap, FeedbackMap, PublishMap};
+use executor::ExecutorContext;
+use health::HealthRegistry;
+pub use health::{ChannelHealth, ComponentHealth, ComponentStatus, EngineHealth, HealthHandle};
+pub use pause::PauseMode;
+use pause::PauseSwitch;
+pub use replay::{EventRecorder, RecordedEvent, Replay, ReplaySpeed};
+use runtime::Spawner;
+pub use shutdown::ShutdownHandle;
+pub use spend::{SpendBudget, SpendGuard};
+use strategy::{StrategyContext, StrategyLauncher};
+use supervisor::CollectorContext;
+
+mod builder;
+mod channel;
+mod config;
+mod control;
+mod dead_letter;
+mod dedup;
+mod dry_run;
+mod event;
+mod executor;
+mod fanout;
+mod health;
+/// This module contains the names of the metrics recorded by the [Engine](Engine)
+/// through the [metrics](::metrics) facade. Nothing is exported unless the
+/// application installs a recorder, e.g. for Prometheus or statsd.
+pub mod metrics;
+mod pause;
+mod replay;
+mod runtime;
+mod shutdown;
+mod spend;
+mod strategy;
+mod supervisor;
+
+/// The main engine of Artemis. This struct is responsible for orchestrating the
+/// data flow between collectors, strategies, and executors.
+pub struct Engine<E, A> {
+    /// The set of collectors that the engine will use to collect events.
+    collectors: Vec<CollectorEntry<E>>,
+
+    /// The middleware stack applied to collected events, in order.
+    middleware: Vec<Box<dyn EventMiddleware<E>>>,
+
+    /// The set of strategies that the engine will use to process events.
+    strategies: Vec<StrategyEntry<E, A>>,
+
+    /// The set of executors that the engine will use to execute actions.
+    executors: Vec<ExecutorEntry<A>>,
+
+    /// The capacity of the event channel.
+    event_channel_capacity: usize,
+
+    /// The capacity of the action channel.
+    action_channel_capacity: usize,
+
+    /// The backpressure policy of the event channel.
+    event_channel_policy: BackpressurePolicy,
+
+    /// The backpressure policy of the action channel.
+    action_channel_policy: BackpressurePolicy,
+
+    /// Dropped message counters for the event channel.
+    event_channel_stats: Arc<ChannelStats>,
+
+    /// Dropped message counters for the action channel.
+    action_channel_stats: Arc<ChannelStats>,
+
+    /// The handle used to gracefully shut down the engine.
+    shutdown: ShutdownHandle,
+
+    /// Maps engine lifecycle events into the event channel, if set.
+    engine_events: Option<EngineEventMap<E>>,
+
+    /// Maps execution feedback into events for the strategies, if set.
+    execution_feedback: Option<FeedbackMap<A, E>>,
+
+    /// Turns actions into events published to the strategies, if set.
+    event_bus: Option<PublishMap<A, E>>,
+
+    /// The collectors running in the engine, by name.
+    registry: Arc<ComponentRegistry>,
+
+    /// The strategies running in the engine, by name.
+    strategy_registry: Arc<ComponentRegistry>,
+
+    /// The sending half of the control channel, cloned into every [ControlHandle](ControlHandle).
+    control_sender: UnboundedSender<Command<E, A>>,
+
+    /// The receiving half of the control channel, used once the engine runs.
+    control_receiver: UnboundedReceiver<Command<E, A>>,
+
+    /// Whether the engine is paused.
+    pause: PauseSwitch,
+
+    /// What to do with collected events while the engine is paused.
+    pause_mode: PauseMode,
+
+    /// Returns the priority of an action, if actions are prioritized.
+    action_priority: Option<fn(&A) -> u8>,
+
+    /// Deduplicates actions emitted by the strategies, if set.
+    action_dedup: Option<ActionDedup<A>>,
+
+    /// Records the actions that executors failed to execute, if set.
+    dead_letters: Option<DeadLetterQueue<A>>,
+
+    /// Captures actions instead of executing them, if set.
+    dry_run: Option<DryRun<A>>,
+
+    /// Halts execution once the spend budget is exceeded, if set.
+    spend_guard: Option<SpendGuard>,
+
+    /// The recorded events consumed instead of the collectors, if set.
+    replay: Option<Box<dyn Collector<E>>>,
+
+    /// Whether events are delivered in a single global sequence.
+    strict_ordering: bool,
+
+    /// Returns the topic of an event, if events are routed by topic.
+    event_topic: Option<fn(&E) -> &'static str>,
+
+    /// The health of the components started by the engine.
+    health: Arc<HealthRegistry>,
+
+    /// Spawns the tasks of the engine.
+    spawner: Spawner,
+}
+
+/// A named collector along with its configuration.
+struct CollectorEntry<E> {
+    name: String,
+    collector: Box<dyn Collector<E>>,
+    config: CollectorConfig,
+}
+
+/// A named strategy along with its configuration.
+struct StrategyEntry<E, A> {
+    name: String,
+    strategy: Box<dyn Strategy<E, A>>,
+    config: StrategyConfig,
+    /// The partition of the event stream processed by the strategy, if sharded.
+    shard: Option<Shard>,
+}
+
+/// One of the copies of a sharded strategy.
+#[derive(Debug, Clone, Copy)]
+struct Shard {
+    index: u64,
+    count: u64,
+}
+
+impl Shard {
+    /// Returns true if the event with the given [shard key](Strategy::shard_key)
+    /// belongs to this shard. Events without a key belong to every shard.
+    fn owns(&self, key: Option<u64>) -> bool {
+        key.is_none_or(|key| key % self.count == self.index)
+    }
+}
+
+/// A named executor along with its configuration.
+struct ExecutorEntry<A> {
+    name: String,
+    executor: Box<dyn Executor<A>>,
+    config: ExecutorConfig,
+    route: Option<ActionRoute<A>>,
+}
+
+/// A predicate deciding which actions are routed to an executor.
+type ActionRoute<A> = Arc<dyn Fn(&A) -> bool + Send + Sync>;
+
+impl<E, A> Engine<E, A> {
+    pub fn new() -> Self {
+        let (control_sender, control_receiver) = mpsc::unbounded_channel();
+        Self {
+            collectors: vec![],
+            middleware: vec![],
+            strategies: vec![],
+            executors: vec![],
+            event_channel_capacity: 512,
+            action_channel_capacity: 512,
+            event_channel_policy: BackpressurePolicy::default(),
+            action_channel_policy: BackpressurePolicy::default(),
+            event_channel_stats: Arc::default(),
+            action_channel_stats: Arc::default(),
+            shutdown: ShutdownHandle::new(),
+            engine_events: None,
+            execution_feedback: None,
+            event_bus: None,
+            registry: Arc::default(),
+            strategy_registry: Arc::default(),
+            control_sender,
+            control_receiver,
+            pause: PauseSwitch::default(),
+            pause_mode: PauseMode::default(),
+            action_priority: None,
+            action_dedup: None,
+            dead_letters: None,
+            dry_run: None,
+            spend_guard: None,
+            replay: None,
+            strict_ordering: false,
+            event_topic: None,
+            health: Arc::default(),
+            spawner: Spawner::default(),
+        }
+    }
+
+    /// Returns an [EngineBuilder](EngineBuilder), which registers named components
+    /// with their own configuration and validates the pipeline.
+    pub fn builder() -> EngineBuilder<E, A> {
+        EngineBuilder::new()
+    }
+
+    /// Sets the capacity of the event channel, and of the event queue of each
+    /// strategy.
+    pub fn with_event_channel_capacity(mut self, capacity: usize) -> Self {
+        self.event_channel_capacity = capacity;
+        self
+    }
+
+    pub fn with_action_channel_capacity(mut self, capacity: usize) -> Self {
+        self.action_channel_capacity = capacity;
+        self
+    }
+
+    /// Sets the policy applied when the event queue of a strategy is full. See
+    /// [BackpressurePolicy](BackpressurePolicy).
+    pub fn with_event_channel_policy(mut self, policy: BackpressurePolicy) -> Self {
+        self.event_channel_policy = policy;
+        self
+    }
+
+    /// Sets the policy applied when strategies send into a full action channel.
+    pub fn with_action_channel_policy(mut self, policy: BackpressurePolicy) -> Self {
+        self.action_channel_policy = policy;
+        self
+    }
+
+    /// Delivers [engine events](EngineEvent), such as collector restarts, to the
+    /// strategies. Each engine event is mapped into the pipeline's event type with
+    /// `f`, and skipped if `f` returns `None`.
+    pub fn with_engine_events<F>(mut self, f: F) -> Self
+    where
+        F: Fn(EngineEvent) -> Option<E> + Send + Sync + 'static,
+    {
+        self.engine_events = Some(Arc::new(f));
+        self
+    }
+
+    /// Feeds the outcome of every executed action back to the strategies. Executors
+    /// are run through [execute_with_receipt](Executor::execute_with_receipt), and
+    /// each [ExecutionFeedback](ExecutionFeedback) is mapped into the pipeline's
+    /// event type with `f`, and skipped if `f` returns `None`.
+    ///
+    /// Feedback for actions that complete after the collectors have stopped may
+    /// not be delivered, since strategies exit once the event channel is drained.
+    pub fn with_execution_feedback<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ExecutionFeedback<A>) -> Option<E> + Send + Sync + 'static,
+    {
+        self.execution_feedback = Some(Arc::new(f));
+        self
+    }
+
+    /// Lets strategies publish events to the other strategies, e.g. a tracker
+    /// strategy feeding derived pool states to several trading strategies. Every
+    /// action emitted by a strategy is passed to `f`: actions mapped to `Ok(event)`
+    /// are published into the event stream instead of being executed, while
+    /// actions returned as `Err(action)` are sent to the executors as usual.
+    ///
+    /// Published events are delivered to every strategy, including the one that
+    /// published them, but do not go through the middleware stack or topic
+    /// routing. Events published after the collectors have stopped may not be
+    /// delivered, since strategies exit once the event channel is drained.
+    pub fn with_event_bus<F>(mut self, f: F) -> Self
+    where
+        F: Fn(A) -> Result<E, A> + Send + Sync + 'static,
+    {
+        self.event_bus = Some(Arc::new(f));
+        self
+    }
+
+    /// Deduplicates identical actions emitted within `window`, across all
+    /// strategies, to avoid e.g. submitting the same bundle twice when several
+    /// strategies trigger on the same opportunity. Two actions are identical if
+    /// `key` returns the same hash for both.
+    pub fn with_action_dedup<F>(mut self, window: Duration, key: F) -> Self
+    where
+        F: Fn(&A) -> u64 + Send + Sync + 'static,
+    {
+        self.action_dedup = Some(ActionDedup::new(window, Arc::new(key)));
+        self
+    }
+
+    /// Records the actions that executors fail to execute in `queue`, instead of
+    /// dropping them. Keep a clone of the queue to inspect and re-drive the failed
+    /// actions while the engine is running.
+    pub fn with_dead_letter_queue(mut self, queue: DeadLetterQueue<A>) -> Self {
+        self.dead_letters = Some(queue);
+        self
+    }
+
+    /// Runs the engine in dry-run mode: actions are captured in `dry_run` instead
+    /// of being executed, and no executor is ever called. See [DryRun](DryRun)
+    /// for details.
+    pub fn with_dry_run(mut self, dry_run: DryRun<A>) -> Self {
+        self.dry_run = Some(dry_run);
+        self
+    }
+
+    /// Halts the execution of actions once `guard` trips, e.g. because the gas
+    /// spent over the last hour exceeded its budget. See [SpendGuard](SpendGuard)
+    /// for details.
+    pub fn with_spend_guard(mut self, guard: SpendGuard) -> Self {
+        self.spend_guard = Some(guard);
+        self
+    }
+
+    /// Spawns the tasks of the engine on `runtime` instead of the runtime calling
+    /// [run](Engine::run), e.g. to keep the engine on a dedicated runtime while
+    /// embedding it into an application. On a current-thread runtime, all tasks
+    /// run on a single thread, which makes the interleaving of strategies and
+    /// executors easier to reason about.
+    pub fn with_runtime(mut self, runtime: tokio::runtime::Handle) -> Self {
+        self.spawner = Spawner::new(Some(runtime));
+        self
+    }
+
+    /// Runs the engine in replay mode: the events recorded in `replay` are
+    /// delivered to the strategies instead of those of the collectors, which are
+    /// never started. See [Replay](Replay) for details.
+    pub fn with_replay(mut self, replay: Replay<E>) -> Self
+    where
+        E: Clone + Send + Sync + 'static,
+    {
+        self.replay = Some(Box::new(replay));
+        self
+    }
+
+    /// Delivers events to the strategies in a single global sequence, with
+    /// monotonically increasing sequence numbers, for strategies that require
+    /// causal ordering. Collected events, engine events and execution feedback are
+    /// sequenced together, and strategies receive each event's sequence number
+    /// through [process_sequenced](Strategy::process_sequenced).
+    ///
+    /// Sends into the event channels are serialized, so this lowers throughput
+    /// when many collectors are busy at once. Strict ordering can't be combined
+    /// with the [Block](BackpressurePolicy::Block) event channel policy, and
+    /// [run](Engine::run) fails if both are set.
+    pub fn with_strict_ordering(mut self) -> Self {
+        self.strict_ordering = true;
+        self
+    }
+
+    /// Returns the dropped message counters of the event channel. The counters
+    /// are shared with the engine, so they can be read while it is running.
+    pub fn event_channel_stats(&self) -> Arc<ChannelStats> {
+        self.event_channel_stats.clone()
+    }
+
+    /// Returns the dropped message counters of the action channel. The counters
+    /// are shared with the engine, so they can be read while it is running.
+    pub fn action_channel_stats(&self) -> Arc<ChannelStats> {
+        self.action_channel_stats.clone()
+    }
+
+    /// Sets what the engine does with collected events while it is paused through
+    /// a [ControlHandle](ControlHandle).
+    pub fn with_pause_mode(mut self, mode: PauseMode) -> Self {
+        self.pause_mode = mode;
+        self
+    }
+
+    /// Returns a handle that can be used to add and remove collectors and
+    /// strategies, and to pause and resume the engine, once it is running. See
+    /// [ControlHandle](ControlHandle) for details.
+    pub fn control_handle(&self) -> ControlHandle<E, A> {
+        ControlHandle::new(
+            self.control_sender.clone(),
+            self.registry.clone(),
+            self.strategy_registry.clone(),
+            self.shutdown.clone(),
+            self.pause.clone(),
+        )
+    }
+
+    /// Returns a handle that can be used to gracefully shut down the engine once
+    /// it is running. See [ShutdownHandle](ShutdownHandle) for details.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Returns a snapshot of the engine's health. Components only appear once
+    /// the engine runs, so keep a [HealthHandle](HealthHandle) to read the health
+    /// of a running engine.
+    pub fn health(&self) -> EngineHealth {
+        self.health_handle().health()
+    }
+
+    /// Returns a handle that can be used to read the health of the engine once it
+    /// is running, e.g. from a readiness probe. See [EngineHealth](EngineHealth)
+    /// for details.
+    pub fn health_handle(&self) -> HealthHandle {
+        HealthHandle::new(
+            self.health.clone(),
+            self.event_channel_stats.clone(),
+            self.action_channel_stats.clone(),
+            self.pause.clone(),
+            self.shutdown.clone(),
+        )
+    }
+}
+
+impl<E, A: ActionPriority> Engine<E, A> {
+    /// Executes queued actions by [priority](ActionPriority), so latency-critical
+    /// actions jump ahead of low-priority ones when an executor is busy. Actions
+    /// of the same priority are executed in the order they were emitted.
+    pub fn with_action_priorities(mut self) -> Self {
+        self.action_priority = Some(A::priority);
+        self
+    }
+}
+
+impl<E: EventTopic, A> Engine<E, A> {
+    /// Routes events by [topic](EventTopic), so strategies that declare
+    /// [topics](Strategy::topics) only receive, and only clone, the events of
+    /// those topics. Strategies without topics still receive every event.
+    pub fn with_event_topics(mut self) -> Self {
+        self.event_topic = Some(E::topic);
+        self
+    }
+}
+
+impl<E, A> Default for Engine<E, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, A> Engine<E, A>
+where
+    E: Send + Clone + 'static + std::fmt::Debug,
+    A: Send + Clone + 'static + std::fmt::Debug,
+{
+    /// Adds a collector to be used by the engine.
+    pub fn add_collector(&mut self, collector: Box<dyn Collector<E>>) {
+        self.collectors.push(CollectorEntry {
+            name: format!("collector-{}", self.collectors.len()),
+            collector,
+            config: CollectorConfig::default(),
+        });
+    }
+
+    /// Adds a middleware to the stack applied to collected events, before they
+    /// reach the strategies. Middleware is applied in the order it was added.
+    pub fn add_middleware(&mut self, middleware: Box<dyn EventMiddleware<E>>) {
+        self.middleware.push(middleware);
+    }
+
+    /// Adds a strategy to be used by the engine.
+    pub fn add_strategy(&mut self, strategy: Box<dyn Strategy<E, A>>) {
+        self.strategies.push(StrategyEntry {
+            name: format!("strategy-{}", self.strategies.len()),
+            strategy,
+            config: StrategyConfig::default(),
+            shard: None,
+        });
+    }
+
+    /// Adds a [BatchStrategy](BatchStrategy) to be used by the engine, delivering
+    /// events to it in batches with the default [BatchConfig](BatchConfig).
+    pub fn add_batch_strategy(&mut self, strategy: Box<dyn BatchStrategy<E, A>>) {
+        self.strategies.push(StrategyEntry {
+            name: format!("strategy-{}", self.strategies.len()),
+            strategy: Box::new(BatchStrategyAdapter::new(strategy)),
+            config: StrategyConfig::default().with_batching(BatchConfig::default()),
+            shard: None,
+        });
+    }
+
+    /// Adds a [PersistentStrategy](PersistentStrategy) to be used by the engine,
+    /// checkpointing its state into `store`. See [PersistentStrategyAdapter](PersistentStrategyAdapter)
+    /// for details.
+    pub fn add_persistent_strategy<S>(&mut self, strategy: S, store: Arc<dyn StateStore>)
+    where
+        S: PersistentStrategy<E, A> + 'static,
+    {
+        let name = format!("strategy-{}", self.strategies.len());
+        self.strategies.push(StrategyEntry {
+            strategy: Box::new(PersistentStrategyAdapter::new(
+                strategy,
+                name.clone(),
+                store,
+            )),
+            name,
+            config: StrategyConfig::default(),
+            shard: None,
+        });
+    }
+
+    /// Adds an executor to be used by the engine.
+    pub fn add_executor(&mut self, executor: Box<dyn Executor<A>>) {
+        self.executors.push(ExecutorEntry {
+            name: format!("executor-{}", self.executors.len()),
+            executor,
+            config: ExecutorConfig::default(),
+            route: None,
+        });
+    }
+
+    /// Adds an executor that only receives the actions matching `route`, e.g.
+    /// `|action| matches!(action, Actions::FlashbotsBundle(_))`. Executors
+    /// added without a route receive every action.
+    pub fn add_routed_executor<F>(&mut self, executor: Box<dyn Executor<A>>, route: F)
+    where
+        F: Fn(&A) -> bool + Send + Sync + 'static,
+    {
+        self.executors.push(ExecutorEntry {
+            name: format!("executor-{}", self.executors.len()),
+            executor,
+            config: ExecutorConfig::default(),
+            route: Some(Arc::new(route)),
+        });
+    }
+
+    /// The core run loop of the engine. This function will spawn a thread for
+    /// each collector, strategy, and executor. It will then orchestrate the
+    /// data flow between them.
+    ///
+    /// Once all collectors have stopped, either because their streams ended or
+    /// because a shutdown was triggered, the strategies drain the remaining events
+    /// and the executors the remaining actions, after which all tasks exit.
+    pub async fn run(self) -> Result<JoinSet<()>, Box<dyn std::error::Error>> {
+        let mut set = JoinSet::new();
+        self.run_in(&mut set).await?;
+        Ok(set)
+    }
+
+    /// Runs the engine like [run](Engine::run), spawning its tasks into `set`, so
+    /// they can be joined along with the application's own tasks. If an error is
+    /// returned, the tasks spawned so far are left in `set`.
+    pub async fn run_in(self, set: &mut JoinSet<()>) -> Result<(), Box<dyn std::error::Error>> {
+        // The sequencer stays locked while an event is queued for every strategy,
+        // so a full queue would block the strategies waiting on it for good.
+        if self.strict_ordering && self.event_channel_policy == BackpressurePolicy::Block {
+            return Err(BuildError::BlockingStrictOrdering.into());
+        }
+        let spawner = self.spawner;
+        // With strict ordering, the event and internal channels share a sequencer,
+        // so strategies receive all events in a single global sequence.
+        let sequencer = self.strict_ordering.then(Sequencer::default);
+        let sequenced = |channel: Channel<E>| match &sequencer {
+            Some(sequencer) => channel.with_sequencer(sequencer.clone()),
+            None => channel,
+        };
+
+        let action_sender: Channel<A> = Channel::new(
+            "action",
+            self.action_channel_capacity,
+            self.action_channel_policy,
+            self.action_channel_stats,
+        );
+
+        // Events raised inside the engine, i.e. execution feedback, strategy alerts
+        // and published events, are delivered on their own channel: executors and
+        // strategies must not keep the event channel open, or strategies would
+        // never drain it.
+        let internal = self.execution_feedback.is_some()
+            || self.engine_events.is_some()
+            || self.event_bus.is_some();
+        let internal_sender: Option<Channel<E>> = internal.then(|| {
+            sequenced(Channel::new(
+                "internal",
+                self.event_channel_capacity,
+                self.event_channel_policy,
+                self.event_channel_stats.clone(),
+            ))
+        });
+        let feedback = self
+            .execution_feedback
+            .zip(internal_sender.clone())
+            .map(|(map, sender)| (sender, map));
+
+        // Spawn executors in separate threads.
+        let ctx = ExecutorContext {
+            shutdown: self.shutdown.clone(),
+            feedback: feedback.clone(),
+            priority: self.action_priority,
+            queue_capacity: self.action_channel_capacity,
+            dead_letters: self.dead_letters,
+            health: self.health.clone(),
+            spawner: spawner.clone(),
+            spend_guard: self.spend_guard,
+        };
+        for mut entry in self.executors {
+            if let Some(dry_run) = &self.dry_run {
+                entry.executor = Box::new(dry_run.executor(&entry.name));
+            }
+            let receiver = action_sender.subscribe();
+            let name = entry.name.clone();
+            let task = executor::run_executor(entry, receiver, ctx.clone());
+            spawner.spawn(set, "executor", &name, task);
+        }
+
+        // Spawn strategies in separate threads.
+        let strategy_ctx = StrategyContext {
+            action_sender: action_sender.clone(),
+            internal_sender: internal_sender.clone(),
+            engine_events: self.engine_events.clone(),
+            event_bus: self.event_bus,
+            dedup: self.action_dedup.map(Arc::new),
+            shutdown: self.shutdown.clone(),
+            strict_ordering: self.strict_ordering,
+            health: self.health.clone(),
+        };
+        let (route_sender, route_receiver) = mpsc::unbounded_channel();
+        let launcher = StrategyLauncher {
+            ctx: strategy_ctx,
+            queue_capacity: self.event_channel_capacity,
+            queue_policy: self.event_channel_policy,
+            queue_stats: self.event_channel_stats.clone(),
+            event_topic: self.event_topic,
+            registry: self.strategy_registry.clone(),
+            routes: route_sender,
+            spawner: spawner.clone(),
+        };
+        let mut routes = vec![];
+        for mut entry in self.strategies {
+            let synced =
+                strategy::sync_strategy(&entry.name, entry.strategy.as_mut(), &entry.config).await;
+            if let Err(e) = synced {
+                match entry.config.error_policy {
+                    ErrorPolicy::Ignore => {
+                        error!(
+                            "error syncing state of strategy {}, starting anyway: {}",
+                            entry.name, e
+                        )
+                    }
+                    ErrorPolicy::Stop => {
+                        error!(
+                            "error syncing state of strategy {}, skipping it: {}",
+                            entry.name, e
+                        );
+                        continue;
+                    }
+                    ErrorPolicy::Shutdown => return Err(e.into()),
+                }
+            }
+
+            // Every strategy gets its own queue, so a strategy that falls behind
+            // only drops events for itself. Strategies are not stopped along with
+            // the engine, so they drain their queues first.
+            let Some(registration) = self
+                .strategy_registry
+                .register(&entry.name, &ShutdownHandle::new())
+            else {
+                return Err(format!("duplicate strategy name: {}", entry.name).into());
+            };
+            routes.push(launcher.start(set, entry, registration));
+        }
+
+        // Spawn the fan-out task, which delivers collected events to the strategies.
+        let (event_sender, fan_out) = fanout::fan_out(
+            self.event_channel_capacity,
+            self.event_topic,
+            routes,
+            route_receiver,
+            sequencer,
+        );
+        spawner.spawn(set, "fanout", "events", fan_out.run());
+
+        // Spawn collectors in separate threads.
+        let ctx = CollectorContext {
+            middleware: self.middleware.into(),
+            event_sender: event_sender.clone(),
+            engine_events: self.engine_events.clone(),
+            shutdown: self.shutdown.clone(),
+            registry: self.registry.clone(),
+            pause: self.pause.clone(),
+            pause_mode: self.pause_mode,
+            health: self.health.clone(),
+            spawner: spawner.clone(),
+        };
+        // In replay mode, the recorded events are the only source of events.
+        let collectors = match self.replay {
+            Some(replay) => vec![CollectorEntry {
+                name: "replay".to_string(),
+                collector: replay,
+                config: CollectorConfig::default(),
+            }],
+            None => self.collectors,
+        };
+        for entry in collectors {
+            let Some(registration) = self.registry.register(&entry.name, &self.shutdown) else {
+                return Err(format!("duplicate collector name: {}", entry.name).into());
+            };
+            supervisor::spawn_collector(set, entry, registration, ctx.clone());
+        }
+
+        // Collectors and strategies added through control handles are started by
+        // a dedicated task, which exits once all handles have been dropped.
+        drop(self.control_sender);
+        let task = control::run_control(self.control_receiver, ctx, launcher);
+        spawner.spawn(set, "control", "components", task);
+
+        // Drop the engine's own senders, so the channels close once the last
+        // collector and strategy have stopped.
+        drop(event_sender);
+        drop(action_sender);
+        drop(internal_sender);
+        drop(feedback);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_owns_keys_by_modulo() {
+        let shards = [
+            Shard { index: 0, count: 3 },
+            Shard { index: 1, count: 3 },
+            Shard { index: 2, count: 3 },
+        ];
+        for key in 0..9 {
+            let owners: Vec<_> = shards
+                .iter()
+                .filter(|shard| shard.owns(Some(key)))
+                .collect();
+            assert_eq!(owners.len(), 1);
+            assert_eq!(owners[0].index, key % 3);
+        }
+    }
+
+    #[test]
+    fn test_shard_owns_events_without_key() {
+        for index in 0..3 {
+            assert!(Shard { index, count: 3 }.owns(None));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// What the [Engine](super::Engine) does with collected events while it is paused.
+/// In both modes collectors keep reading their streams, so no subscription is lost.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PauseMode {
+    /// Discard events while paused.
+    #[default]
+    Drop,
+    /// Buffer up to the given number of events per collector while paused, and
+    /// deliver them on resume. The oldest events are discarded once the buffer
+    /// is full.
+    Buffer(usize),
+}
+
+/// The shared pause state of the engine.
+#[derive(Debug, Clone)]
+pub(crate) struct PauseSwitch {
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl Default for PauseSwitch {
+    fn default() -> Self {
+        Self {
+            paused: Arc::new(watch::Sender::new(false)),
+        }
+    }
+}
+
+impl PauseSwitch {
+    pub(crate) fn set(&self, paused: bool) {
+        self.paused.send_replace(paused);
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<bool> {
+        self.paused.subscribe()
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use futures::stream;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep_until, Instant};
+use tracing::error;
+
+use crate::error::Result;
+use crate::types::{Collector, CollectorStream, EventMiddleware};
+
+/// An event recorded by an [EventRecorder](EventRecorder), along with the time it
+/// was recorded at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedEvent<E> {
+    /// When the event was recorded, in milliseconds since the Unix epoch.
+    pub recorded_at: u64,
+    /// The recorded event.
+    pub event: E,
+}
+
+/// The pace at which a [Replay](Replay) delivers its events.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ReplaySpeed {
+    /// Delivers events as fast as the strategies consume them.
+    #[default]
+    AsFastAsPossible,
+    /// Delivers events with the same spacing as when they were recorded.
+    RealTime,
+    /// Delivers events with their original spacing divided by the given factor,
+    /// e.g. `Accelerated(10.0)` replays an hour of events in six minutes.
+    Accelerated(f64),
+}
+
+impl ReplaySpeed {
+    /// Returns the delay between the start of the replay and the delivery of an
+    /// event recorded `elapsed` after the first one, or `None` if events are not
+    /// paced.
+    pub(crate) fn delay(&self, elapsed: Duration) -> Option<Duration> {
+        match self {
+            Self::AsFastAsPossible => None,
+            Self::RealTime => Some(elapsed),
+            Self::Accelerated(factor) if *factor > 0.0 => Some(elapsed.div_f64(*factor)),
+            Self::Accelerated(_) => None,
+        }
+    }
+}
+
+/// A recorded event log, which the [Engine](super::Engine) consumes instead of its
+/// live collectors in replay mode, so strategy behavior can be reproduced
+/// deterministically when debugging or in regression tests.
+///
+/// In replay mode, the collectors added to the engine are never started. The
+/// recorded events go through the middleware stack and are delivered to the
+/// strategies in the order they were recorded, after which the engine drains and
+/// stops. Executors still run: combine replay with [dry-run](super::DryRun) mode
+/// to capture the actions instead of executing them.
+pub struct Replay<E> {
+    events: Arc<Vec<RecordedEvent<E>>>,
+    speed: ReplaySpeed,
+}
+
+impl<E> Clone for Replay<E> {
+    fn clone(&self) -> Self {
+        Self {
+            events: self.events.clone(),
+            speed: self.speed,
+        }
+    }
+}
+
+impl<E> Replay<E> {
+    /// Creates a replay of the given events, which are delivered in order.
+    pub fn new(events: Vec<RecordedEvent<E>>) -> Self {
+        Self {
+            events: Arc::new(events),
+            speed: ReplaySpeed::default(),
+        }
+    }
+
+    /// Sets the pace at which events are delivered. Defaults to
+    /// [AsFastAsPossible](ReplaySpeed::AsFastAsPossible).
+    pub fn with_speed(mut self, speed: ReplaySpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Returns the number of recorded events.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if there is no recorded event.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl<E: DeserializeOwned> Replay<E> {
+    /// Loads the event log written by an [EventRecorder](EventRecorder), i.e. one
+    /// JSON encoded [RecordedEvent](RecordedEvent) per line. Blank lines are
+    /// skipped.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut events = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                events.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self::new(events))
+    }
+}
+
+/// Implementation of the [Collector](Collector) trait for the [Replay](Replay).
+/// Paced replays start timing from the moment the stream is created.
+#[async_trait]
+impl<E> Collector<E> for Replay<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, E>> {
+        let start = Instant::now();
+        let first = self.events.first().map_or(0, |e| e.recorded_at);
+        let stream = stream::unfold(0, move |index| async move {
+            let recorded = self.events.get(index)?;
+            let elapsed = Duration::from_millis(recorded.recorded_at.saturating_sub(first));
+            if let Some(delay) = self.speed.delay(elapsed) {
+                sleep_until(start + delay).await;
+            }
+            Some((recorded.event.clone(), index + 1))
+        });
+        Ok(Box::pin(stream))
+    }
+}
+
+/// A middleware that records every event passing through it into an event log,
+/// which can later be replayed with a [Replay](Replay). Events are passed on
+/// unchanged.
+///
+/// Events are written as one JSON encoded [RecordedEvent](RecordedEvent) per line.
+/// Since replayed events go through the middleware stack again, add the recorder
+/// first to record events as the collectors emitted them. Events that fail to be
+/// recorded are logged and still passed on.
+pub struct EventRecorder {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EventRecorder {
+    /// Creates a recorder writing to the file at `path`, which is truncated if it
+    /// exists.
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Creates a recorder writing to `writer`. Each event is flushed once written.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    fn record<E: Serialize>(&self, event: &E) -> anyhow::Result<()> {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let line = serde_json::to_string(&RecordedEvent { recorded_at, event })?;
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{}", line)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<E> EventMiddleware<E> for EventRecorder
+where
+    E: Serialize + Send + 'static,
+{
+    async fn process(&self, event: E) -> Option<E> {
+        if let Err(e) = self.record(&event) {
+            error!("error recording event: {}", e);
+        }
+        Some(event)
+    }
+}
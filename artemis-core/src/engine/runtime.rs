@@ -0,0 +1,51 @@
+use std::future::Future;
+
+use tokio::runtime::Handle;
+use tokio::task::JoinSet;
+use tracing::{info_span, Instrument};
+
+/// Spawns the tasks of the [Engine](super::Engine) on its configured runtime.
+/// Every task is instrumented with an `artemis` span naming the component it runs,
+/// and, when built with `--cfg tokio_unstable`, named after it for tokio-console.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Spawner {
+    /// The runtime tasks are spawned on. `None` spawns them on the current runtime.
+    runtime: Option<Handle>,
+}
+
+impl Spawner {
+    pub(crate) fn new(runtime: Option<Handle>) -> Self {
+        Self { runtime }
+    }
+
+    /// Spawns a task into `set`. `kind` is the kind of component the task runs,
+    /// e.g. `strategy`, and `name` the name of the component.
+    pub(crate) fn spawn<T, F>(&self, set: &mut JoinSet<T>, kind: &'static str, name: &str, task: F)
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        let task = task.instrument(info_span!("artemis", task = kind, name = %name));
+
+        #[cfg(tokio_unstable)]
+        {
+            let task_name = format!("{}:{}", kind, name);
+            let builder = set.build_task().name(&task_name);
+            let spawned = match &self.runtime {
+                Some(runtime) => builder.spawn_on(task, runtime),
+                None => builder.spawn(task),
+            };
+            spawned.expect("failed to spawn engine task");
+        }
+
+        #[cfg(not(tokio_unstable))]
+        match &self.runtime {
+            Some(runtime) => {
+                set.spawn_on(task, runtime);
+            }
+            None => {
+                set.spawn(task);
+            }
+        }
+    }
+}
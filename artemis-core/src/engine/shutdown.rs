@@ -0,0 +1,42 @@
+use tokio_util::sync::CancellationToken;
+
+/// A handle used to gracefully shut down a running [Engine](super::Engine).
+///
+/// Triggering a shutdown stops the collectors from producing new events. Events
+/// already in flight are still processed by the strategies, and the resulting
+/// actions are still executed, before the engine's tasks exit and its
+/// [JoinSet](tokio::task::JoinSet) resolves.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownHandle {
+    token: CancellationToken,
+}
+
+impl ShutdownHandle {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a handle that is shut down along with this one, but can also be
+    /// shut down on its own, e.g. to stop a single component.
+    pub(crate) fn child(&self) -> Self {
+        Self {
+            token: self.token.child_token(),
+        }
+    }
+
+    /// Triggers a graceful shutdown of the engine. Calling this more than once
+    /// has no further effect.
+    pub fn shutdown(&self) {
+        self.token.cancel();
+    }
+
+    /// Returns true if a shutdown has been triggered.
+    pub fn is_shutdown(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Waits until a shutdown is triggered.
+    pub async fn wait(&self) {
+        self.token.cancelled().await
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ethers::types::U256;
+use tracing::{error, info};
+
+/// An engine-level kill switch, which stops the [Engine](super::Engine) from
+/// executing actions once the gas or ETH spent over a rolling window exceeds a
+/// budget, e.g. to cap the damage of a misbehaving strategy.
+///
+/// The engine records what each executed action [spent](crate::types::Executor::spent),
+/// and the application may report other spend with [record](SpendGuard::record).
+/// Once any budget is exceeded the guard trips, and the engine drops every action
+/// it would have executed, recording it in the dead-letter queue if enabled. The
+/// guard stays tripped until it is manually [resumed](SpendGuard::resume), and can
+/// also be [tripped](SpendGuard::trip) by hand. The guard is a cheap handle: keep
+/// a clone to report spend and resume execution while the engine is running.
+#[derive(Debug, Clone, Default)]
+pub struct SpendGuard {
+    state: Arc<Mutex<SpendState>>,
+}
+
+#[derive(Debug, Default)]
+struct SpendState {
+    budgets: Vec<SpendBudget>,
+    /// The spend recorded within the longest budget window, oldest first.
+    spent: VecDeque<(Instant, U256)>,
+    tripped: bool,
+}
+
+/// The maximum amount that may be spent within a rolling window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpendBudget {
+    pub limit: U256,
+    pub window: Duration,
+}
+
+impl SpendGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a budget of `limit` over a rolling `window`. Several budgets may be
+    /// set, e.g. an hourly and a daily one: the guard trips as soon as any of them
+    /// is exceeded.
+    pub fn with_budget(self, limit: U256, window: Duration) -> Self {
+        self.state
+            .lock()
+            .unwrap()
+            .budgets
+            .push(SpendBudget { limit, window });
+        self
+    }
+
+    /// Adds a budget of `limit` per rolling hour.
+    pub fn with_hourly_budget(self, limit: U256) -> Self {
+        self.with_budget(limit, Duration::from_secs(60 * 60))
+    }
+
+    /// Adds a budget of `limit` per rolling day.
+    pub fn with_daily_budget(self, limit: U256) -> Self {
+        self.with_budget(limit, Duration::from_secs(24 * 60 * 60))
+    }
+
+    /// Records an amount spent, e.g. the gas cost of a landed transaction in wei,
+    /// and trips the guard if this exceeds a budget.
+    pub fn record(&self, amount: U256) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state.spent.push_back((now, amount));
+        state.prune(now);
+        if state.tripped {
+            return;
+        }
+        if let Some(budget) = state.exceeded(now) {
+            state.tripped = true;
+            error!(
+                "spend budget of {} per {:?} exceeded, halting execution",
+                budget.limit, budget.window
+            );
+        }
+    }
+
+    /// Returns the amount spent within the last `window`. Spend is only kept for
+    /// the longest budget window.
+    pub fn spent(&self, window: Duration) -> U256 {
+        let state = self.state.lock().unwrap();
+        state.spent_within(Instant::now(), window)
+    }
+
+    /// Returns true if execution is halted.
+    pub fn is_tripped(&self) -> bool {
+        self.state.lock().unwrap().tripped
+    }
+
+    /// Halts execution, regardless of the budgets.
+    pub fn trip(&self) {
+        self.state.lock().unwrap().tripped = true;
+        info!("spend guard tripped manually, halting execution");
+    }
+
+    /// Resumes execution. The spend recorded so far is forgiven, so execution
+    /// continues until the budgets are exceeded again.
+    pub fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.tripped = false;
+        state.spent.clear();
+        info!("spend guard resumed");
+    }
+}
+
+impl SpendState {
+    /// Forgets the spend recorded before the longest budget window.
+    fn prune(&mut self, now: Instant) {
+        let longest = self.budgets.iter().map(|b| b.window).max();
+        while let Some((at, _)) = self.spent.front() {
+            if longest.is_some_and(|window| now.duration_since(*at) <= window) {
+                break;
+            }
+            self.spent.pop_front();
+        }
+    }
+
+    fn spent_within(&self, now: Instant, window: Duration) -> U256 {
+        self.spent
+            .iter()
+            .filter(|(at, _)| now.duration_since(*at) <= window)
+            .fold(U256::zero(), |total, (_, amount)| {
+                total.saturating_add(*amount)
+            })
+    }
+
+    /// Returns the first budget exceeded at `now`, if any.
+    fn exceeded(&self, now: Instant) -> Option<SpendBudget> {
+        self.budgets
+            .iter()
+            .find(|budget| self.spent_within(now, budget.window) > budget.limit)
+            .copied()
+    }
+}
@@ -0,0 +1,875 @@
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::FutureExt;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinSet;
+use tokio::time::{interval_at, Interval, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+use super::channel::{
+    self, BackpressurePolicy, Channel, ChannelReceiver, ChannelStats, QueueReceiver,
+};
+use super::control::{ComponentRegistry, Registration};
+use super::dedup::ActionDedup;
+use super::event::{map_engine_event, EngineEvent, EngineEventMap, PublishMap};
+use super::fanout::Route;
+use super::health::{ComponentStatus, HealthRegistry, HealthTracker};
+use super::metrics::StrategyMetrics;
+use super::runtime::Spawner;
+use super::{BatchConfig, ErrorPolicy, Shard, ShutdownHandle, StrategyConfig, StrategyEntry};
+use crate::error::{ArtemisError, Result};
+use crate::types::Strategy;
+
+/// The engine state shared by all strategy tasks.
+pub(crate) struct StrategyContext<E, A> {
+    pub(crate) action_sender: Channel<A>,
+    /// Delivers engine events raised by strategies, if enabled.
+    pub(crate) internal_sender: Option<Channel<E>>,
+    pub(crate) engine_events: Option<EngineEventMap<E>>,
+    /// Turns actions into events published to the strategies, if enabled.
+    pub(crate) event_bus: Option<PublishMap<A, E>>,
+    pub(crate) dedup: Option<Arc<ActionDedup<A>>>,
+    pub(crate) shutdown: ShutdownHandle,
+    /// Whether events are delivered in a single global sequence.
+    pub(crate) strict_ordering: bool,
+    pub(crate) health: Arc<HealthRegistry>,
+}
+
+impl<E, A> Clone for StrategyContext<E, A> {
+    fn clone(&self) -> Self {
+        Self {
+            action_sender: self.action_sender.clone(),
+            internal_sender: self.internal_sender.clone(),
+            engine_events: self.engine_events.clone(),
+            event_bus: self.event_bus.clone(),
+            dedup: self.dedup.clone(),
+            shutdown: self.shutdown.clone(),
+            strict_ordering: self.strict_ordering,
+            health: self.health.clone(),
+        }
+    }
+}
+
+/// Starts strategies, along with the event queue the fan-out task delivers their
+/// events to.
+pub(crate) struct StrategyLauncher<E, A> {
+    pub(crate) ctx: StrategyContext<E, A>,
+    pub(crate) queue_capacity: usize,
+    pub(crate) queue_policy: BackpressurePolicy,
+    pub(crate) queue_stats: Arc<ChannelStats>,
+    pub(crate) event_topic: Option<fn(&E) -> &'static str>,
+    /// The strategies running in the engine, by name.
+    pub(crate) registry: Arc<ComponentRegistry>,
+    /// Hands the queues of strategies added at runtime to the fan-out task.
+    pub(crate) routes: UnboundedSender<Route<E>>,
+    pub(crate) spawner: Spawner,
+}
+
+impl<E, A> Clone for StrategyLauncher<E, A> {
+    fn clone(&self) -> Self {
+        Self {
+            ctx: self.ctx.clone(),
+            queue_capacity: self.queue_capacity,
+            queue_policy: self.queue_policy,
+            queue_stats: self.queue_stats.clone(),
+            event_topic: self.event_topic,
+            registry: self.registry.clone(),
+            routes: self.routes.clone(),
+            spawner: self.spawner.clone(),
+        }
+    }
+}
+
+impl<E, A> StrategyLauncher<E, A>
+where
+    E: Clone + Send + 'static,
+    A: Clone + Send + 'static,
+{
+    /// Starts a strategy whose state was already synced, and returns its route,
+    /// for the fan-out task to deliver events to.
+    pub(crate) fn start(
+        &self,
+        set: &mut JoinSet<()>,
+        entry: StrategyEntry<E, A>,
+        registration: Registration,
+    ) -> Route<E> {
+        let (route, events, internal) = self.route(&entry);
+        let name = entry.name.clone();
+        let task = run_registered(
+            entry,
+            registration,
+            events,
+            internal,
+            self.ctx.clone(),
+            self.registry.clone(),
+        );
+        self.spawner.spawn(set, "strategy", &name, task);
+        route
+    }
+
+    /// Starts a strategy added while the engine is running. The strategy is only
+    /// sent events once its state was synced, and is left out if the sync fails,
+    /// unless its [ErrorPolicy] is [Ignore](ErrorPolicy::Ignore).
+    pub(crate) fn add(
+        &self,
+        set: &mut JoinSet<()>,
+        mut entry: StrategyEntry<E, A>,
+        registration: Registration,
+    ) {
+        let name = entry.name.clone();
+        let launcher = self.clone();
+        let task = async move {
+            let synced = sync_strategy(&entry.name, entry.strategy.as_mut(), &entry.config).await;
+            if let Err(e) = synced {
+                if entry.config.error_policy != ErrorPolicy::Ignore {
+                    error!(
+                        "error syncing state of strategy {}, skipping it: {}",
+                        entry.name, e
+                    );
+                    launcher.registry.deregister(&entry.name, registration.id);
+                    return;
+                }
+                error!(
+                    "error syncing state of strategy {}, starting anyway: {}",
+                    entry.name, e
+                );
+            }
+
+            let (route, events, internal) = launcher.route(&entry);
+            let StrategyLauncher {
+                ctx,
+                registry,
+                routes,
+                ..
+            } = launcher;
+            // The fan-out task may have stopped along with the engine.
+            if routes.send(route).is_err() {
+                registry.deregister(&entry.name, registration.id);
+                return;
+            }
+            drop(routes);
+            run_registered(entry, registration, events, internal, ctx, registry).await;
+        };
+        self.spawner.spawn(set, "strategy", &name, task);
+    }
+
+    /// Creates the event queue of a strategy, along with its route. Strategies
+    /// subscribed to topics are never sent the events of other topics.
+    fn route(
+        &self,
+        entry: &StrategyEntry<E, A>,
+    ) -> (Route<E>, QueueReceiver<E>, Option<ChannelReceiver<E>>) {
+        let (queue, events) = channel::queue(
+            "event",
+            self.queue_capacity,
+            self.queue_policy,
+            self.queue_stats.clone(),
+        );
+        let route = Route {
+            strategy: entry.name.clone(),
+            topics: self
+                .event_topic
+                .and(entry.strategy.topics())
+                .map(|topics| topics.into_iter().collect()),
+            queue,
+        };
+        let internal = self.ctx.internal_sender.as_ref().map(Channel::subscribe);
+        (route, events, internal)
+    }
+}
+
+/// Runs a strategy until it stops, and removes it from the registry.
+async fn run_registered<E, A>(
+    entry: StrategyEntry<E, A>,
+    registration: Registration,
+    events: QueueReceiver<E>,
+    internal: Option<ChannelReceiver<E>>,
+    ctx: StrategyContext<E, A>,
+    registry: Arc<ComponentRegistry>,
+) where
+    E: Clone + Send + 'static,
+    A: Clone + Send + 'static,
+{
+    let name = entry.name.clone();
+    let Registration { id, stop } = registration;
+    run_strategy(entry, events, internal, ctx, stop).await;
+    registry.deregister(&name, id);
+}
+
+/// Feeds events, and internal events such as execution feedback if enabled, to a
+/// strategy, and sends the resulting actions to the executors. Panics raised
+/// while processing an event, or in the `on_start` and `on_tick` hooks, are
+/// caught, and the strategy is restarted according to its
+/// [RestartPolicy](super::RestartPolicy). Lifecycle hooks are invoked on
+/// start, at every tick and on exit. The strategy's state is resynced
+/// periodically, or whenever it asks for it, if configured. A sharded strategy
+/// skips the events of other shards. Exits once the event channel closes, or once
+/// the strategy is removed through `stop`.
+async fn run_strategy<E, A>(
+    entry: StrategyEntry<E, A>,
+    event_receiver: QueueReceiver<E>,
+    internal_receiver: Option<ChannelReceiver<E>>,
+    ctx: StrategyContext<E, A>,
+    stop: ShutdownHandle,
+) where
+    E: Clone + Send + 'static,
+    A: Clone + Send + 'static,
+{
+    let StrategyEntry {
+        name,
+        mut strategy,
+        config,
+        shard,
+    } = entry;
+    let metrics = StrategyMetrics::new(&name);
+    let health = ctx.health.strategy(&name);
+    let mut restarts = 0;
+    let mut resync = config.resync_interval.map(|period| {
+        let mut interval = interval_at(tokio::time::Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval
+    });
+    let mut inbox = Inbox {
+        events: event_receiver,
+        internal: internal_receiver,
+        strict: ctx.strict_ordering,
+        held_event: None,
+        held_internal: None,
+        drop_notice: config
+            .drop_notifications
+            .then(|| ctx.engine_events.clone())
+            .flatten()
+            .map(|map| DropNotice {
+                strategy: name.clone(),
+                map,
+            }),
+    };
+    let mut last_seq = 0;
+
+    let mut ticks = strategy.tick_interval().map(|period| {
+        let mut interval = interval_at(tokio::time::Instant::now() + period, period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval
+    });
+
+    info!("starting strategy {}... ", name);
+    let started = AssertUnwindSafe(strategy.on_start()).catch_unwind().await;
+    let mut running = match started {
+        Ok(()) => true,
+        Err(payload) => {
+            let recovery = recover(
+                &name,
+                strategy.as_mut(),
+                &config,
+                &ctx,
+                &metrics,
+                &health,
+                &mut restarts,
+                payload,
+            );
+            recovery.await == Recovery::Continue
+        }
+    };
+    if running {
+        health.set_status(ComponentStatus::Running);
+    }
+    while running {
+        let received = tokio::select! {
+            received = inbox.next() => received,
+            _ = stop.wait() => break,
+            _ = tick(&mut resync) => {
+                resync_strategy(&name, strategy.as_mut(), &config).await;
+                continue;
+            }
+            _ = tick(&mut ticks) => {
+                match AssertUnwindSafe(strategy.on_tick()).catch_unwind().await {
+                    Ok(actions) => send_actions(actions, &ctx, &metrics).await,
+                    Err(payload) => {
+                        let recovery = recover(
+                            &name,
+                            strategy.as_mut(),
+                            &config,
+                            &ctx,
+                            &metrics,
+                            &health,
+                            &mut restarts,
+                            payload,
+                        );
+                        running = recovery.await == Recovery::Continue;
+                    }
+                }
+                continue;
+            }
+        };
+        let (seq, event) = match received {
+            Ok(Some(event)) => event,
+            Ok(None) => break,
+            Err(skipped) => {
+                metrics.lagged.increment(skipped);
+                health.lagged(skipped);
+                warn!("strategy {} lagged, skipped {} events", name, skipped);
+                // The notice is not sequenced, so it reuses the sequence number
+                // of the last delivered event.
+                match inbox.drop_notice(skipped) {
+                    Some(notice) => (last_seq, notice),
+                    None => continue,
+                }
+            }
+        };
+        last_seq = seq;
+        if shard.is_some_and(|shard| !shard.owns(strategy.shard_key(&event))) {
+            continue;
+        }
+        if strategy.needs_resync(&event) {
+            resync_strategy(&name, strategy.as_mut(), &config).await;
+        }
+
+        let started = Instant::now();
+        let (processing, processed_events) = match &config.batch {
+            Some(batch) => {
+                let mut events = vec![event];
+                let resync = fill_batch(
+                    &mut events,
+                    &mut inbox,
+                    batch,
+                    strategy.as_ref(),
+                    shard,
+                    &metrics,
+                    &health,
+                )
+                .await;
+                if resync {
+                    resync_strategy(&name, strategy.as_mut(), &config).await;
+                }
+                let processed_events = events.len() as u64;
+                (strategy.process_batch(events), processed_events)
+            }
+            None if ctx.strict_ordering => (strategy.process_sequenced(seq, event), 1),
+            None => (strategy.process_event(event), 1),
+        };
+        let processed = AssertUnwindSafe(processing).catch_unwind().await;
+        metrics.latency.record(started.elapsed().as_secs_f64());
+        metrics.events.increment(processed_events);
+        health.processed(processed_events);
+
+        let actions = match processed {
+            Ok(actions) => {
+                // The strategy is healthy again, so the next restart starts over
+                // with the initial backoff.
+                restarts = 0;
+                actions
+            }
+            Err(payload) => {
+                let recovery = recover(
+                    &name,
+                    strategy.as_mut(),
+                    &config,
+                    &ctx,
+                    &metrics,
+                    &health,
+                    &mut restarts,
+                    payload,
+                );
+                running = recovery.await == Recovery::Continue;
+                continue;
+            }
+        };
+
+        send_actions(actions, &ctx, &metrics).await;
+    }
+
+    info!("stopping strategy {}... ", name);
+    // The strategy stops either way, so a panic is only reported.
+    if let Err(payload) = AssertUnwindSafe(strategy.on_shutdown())
+        .catch_unwind()
+        .await
+    {
+        let message = panic_message(&*payload);
+        metrics.panics.increment(1);
+        error!("strategy {} panicked while stopping: {}", name, message);
+        health.failed(&message);
+        ctx.emit(EngineEvent::StrategyPanicked {
+            strategy: name.to_string(),
+            message,
+        })
+        .await;
+    }
+    health.set_status(ComponentStatus::Stopped);
+}
+
+/// What a strategy does after it panicked.
+#[derive(Debug, PartialEq, Eq)]
+enum Recovery {
+    /// The strategy was restarted, or its error policy ignores panics.
+    Continue,
+    /// The strategy stops.
+    Stop,
+}
+
+/// Reports a panic raised by a strategy, and restarts the strategy according to
+/// its [RestartPolicy](super::RestartPolicy), resyncing its state first, since the
+/// panic may have left it inconsistent. Without a restart policy, or once the
+/// policy gives up, the strategy's [ErrorPolicy] applies.
+#[allow(clippy::too_many_arguments)]
+async fn recover<E: Clone, A>(
+    name: &str,
+    strategy: &mut dyn Strategy<E, A>,
+    config: &StrategyConfig,
+    ctx: &StrategyContext<E, A>,
+    metrics: &StrategyMetrics,
+    health: &HealthTracker,
+    restarts: &mut u32,
+    payload: Box<dyn Any + Send>,
+) -> Recovery {
+    let message = panic_message(&*payload);
+    metrics.panics.increment(1);
+    error!("strategy {} panicked: {}", name, message);
+    health.failed(&message);
+    ctx.emit(EngineEvent::StrategyPanicked {
+        strategy: name.to_string(),
+        message,
+    })
+    .await;
+
+    let Some(policy) = &config.restart_policy else {
+        return match config.error_policy {
+            ErrorPolicy::Ignore => Recovery::Continue,
+            ErrorPolicy::Stop => Recovery::Stop,
+            ErrorPolicy::Shutdown => {
+                ctx.shutdown.shutdown();
+                Recovery::Stop
+            }
+        };
+    };
+
+    if policy.max_restarts.is_some_and(|max| *restarts >= max) {
+        error!("giving up on strategy {} after {} restarts", name, restarts);
+        ctx.emit(EngineEvent::StrategyGaveUp {
+            strategy: name.to_string(),
+            attempts: *restarts,
+        })
+        .await;
+        if config.error_policy == ErrorPolicy::Shutdown {
+            ctx.shutdown.shutdown();
+        }
+        return Recovery::Stop;
+    }
+
+    health.set_status(ComponentStatus::Restarting);
+    tokio::time::sleep(policy.backoff(*restarts)).await;
+    *restarts += 1;
+    health.restarted();
+
+    info!("restarting strategy {} (attempt {})", name, restarts);
+    resync_strategy(name, strategy, config).await;
+    ctx.emit(EngineEvent::StrategyRestarted {
+        strategy: name.to_string(),
+        attempt: *restarts,
+    })
+    .await;
+    health.set_status(ComponentStatus::Running);
+    Recovery::Continue
+}
+
+/// Adds events to a batch until it holds [max_size](BatchConfig::max_size) events,
+/// or until [max_delay](BatchConfig::max_delay) has elapsed. Returns true if any
+/// added event requested a resync. Events received after the event channel closed
+/// are left to the caller.
+async fn fill_batch<E: Clone, A>(
+    batch: &mut Vec<E>,
+    inbox: &mut Inbox<E>,
+    config: &BatchConfig,
+    strategy: &dyn Strategy<E, A>,
+    shard: Option<Shard>,
+    metrics: &StrategyMetrics,
+    health: &HealthTracker,
+) -> bool {
+    let deadline = tokio::time::sleep(config.max_delay);
+    tokio::pin!(deadline);
+    let mut resync = false;
+
+    while batch.len() < config.max_size {
+        let received = tokio::select! {
+            received = inbox.next() => received,
+            _ = &mut deadline => break,
+        };
+        match received {
+            Ok(Some((_, event))) => {
+                if shard.is_some_and(|shard| !shard.owns(strategy.shard_key(&event))) {
+                    continue;
+                }
+                resync |= strategy.needs_resync(&event);
+                batch.push(event);
+            }
+            // The next call to the inbox reports the closed channel again.
+            Ok(None) => break,
+            Err(skipped) => {
+                metrics.lagged.increment(skipped);
+                health.lagged(skipped);
+                if let Some(notice) = inbox.drop_notice(skipped) {
+                    batch.push(notice);
+                }
+            }
+        }
+    }
+    resync
+}
+
+/// Sends the actions emitted by a strategy to the executors, discarding duplicates.
+/// Actions turned into events by the event bus are published to the strategies
+/// instead.
+async fn send_actions<E: Clone, A: Clone>(
+    actions: Vec<A>,
+    ctx: &StrategyContext<E, A>,
+    metrics: &StrategyMetrics,
+) {
+    metrics.actions.increment(actions.len() as u64);
+    for action in actions {
+        let action = match (&ctx.event_bus, &ctx.internal_sender) {
+            (Some(publish), Some(sender)) => match publish(action) {
+                Ok(event) => {
+                    if let Err(e) = sender.send(event).await {
+                        error!("error publishing event: {}", e);
+                    }
+                    continue;
+                }
+                Err(action) => action,
+            },
+            _ => action,
+        };
+        if ctx
+            .dedup
+            .as_ref()
+            .is_some_and(|dedup| !dedup.check(&action))
+        {
+            metrics.deduplicated.increment(1);
+            continue;
+        }
+        match ctx.action_sender.send(action).await {
+            Ok(_) => {}
+            Err(e) => error!("error sending action: {}", e),
+        }
+    }
+}
+
+/// Syncs the state of a strategy, retrying according to its
+/// [sync retry policy](StrategyConfig::sync_retry), and failing each attempt that
+/// takes longer than its [sync timeout](StrategyConfig::sync_timeout). A panic
+/// raised while syncing fails the attempt. Fatal errors are never retried.
+pub(crate) async fn sync_strategy<E, A>(
+    name: &str,
+    strategy: &mut dyn Strategy<E, A>,
+    config: &StrategyConfig,
+) -> Result<()> {
+    let mut retries = 0;
+    loop {
+        let syncing = AssertUnwindSafe(strategy.sync_state()).catch_unwind();
+        let synced = match config.sync_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, syncing)
+                .await
+                .unwrap_or_else(|_| {
+                    Ok(Err(ArtemisError::retryable(anyhow::anyhow!(
+                        "timed out after {:?}",
+                        timeout
+                    ))))
+                }),
+            None => syncing.await,
+        };
+        let synced = synced.unwrap_or_else(|payload| {
+            Err(ArtemisError::retryable(anyhow::anyhow!(
+                "panicked: {}",
+                panic_message(&*payload)
+            )))
+        });
+        let e = match synced {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+
+        let Some(policy) = &config.sync_retry else {
+            return Err(e);
+        };
+        if e.is_fatal() {
+            return Err(e);
+        }
+        if policy.max_restarts.is_some_and(|max| retries >= max) {
+            return Err(e);
+        }
+        warn!("error syncing state of strategy {}, retrying: {}", name, e);
+        tokio::time::sleep(policy.backoff(retries)).await;
+        retries += 1;
+    }
+}
+
+/// Resyncs the state of a running strategy. The strategy keeps running on failure.
+async fn resync_strategy<E, A>(
+    name: &str,
+    strategy: &mut dyn Strategy<E, A>,
+    config: &StrategyConfig,
+) {
+    info!("resyncing state of strategy {}", name);
+    if let Err(e) = sync_strategy(name, strategy, config).await {
+        error!("error resyncing state of strategy {}: {}", name, e);
+    }
+}
+
+/// Completes at the next tick of the interval, if any. Never resolves otherwise.
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+impl<E: Clone, A> StrategyContext<E, A> {
+    /// Delivers an [EngineEvent] to the strategies, if engine events are enabled.
+    async fn emit(&self, event: EngineEvent) {
+        let Some(sender) = &self.internal_sender else {
+            return;
+        };
+        let Some(event) = map_engine_event(&self.engine_events, event) else {
+            return;
+        };
+        if let Err(e) = sender.send(event).await {
+            error!("error sending engine event: {}", e);
+        }
+    }
+}
+
+/// The events delivered to a strategy, from the event channel and the internal
+/// channel.
+struct Inbox<E> {
+    events: QueueReceiver<E>,
+    internal: Option<ChannelReceiver<E>>,
+    /// Whether events are delivered by sequence number across both channels.
+    strict: bool,
+    /// In strict mode, the next event of each channel, until it is delivered.
+    held_event: Option<(u64, E)>,
+    held_internal: Option<(u64, E)>,
+    /// Notifies the strategy of skipped events, if enabled.
+    drop_notice: Option<DropNotice<E>>,
+}
+
+/// Maps the events skipped by a strategy into an [EventsDropped](EngineEvent::EventsDropped)
+/// event for it.
+struct DropNotice<E> {
+    strategy: String,
+    map: EngineEventMap<E>,
+}
+
+impl<E: Clone> Inbox<E> {
+    /// Returns the event notifying the strategy that it skipped `count` events,
+    /// if drop notifications are enabled.
+    fn drop_notice(&self, count: u64) -> Option<E> {
+        let notice = self.drop_notice.as_ref()?;
+        (notice.map)(EngineEvent::EventsDropped {
+            strategy: notice.strategy.clone(),
+            count,
+        })
+    }
+
+    /// Receives the next event along with its sequence number. Returns `Err(n)` if
+    /// `n` events were skipped because the strategy fell behind, and `Ok(None)`
+    /// once the event channel closes.
+    async fn next(&mut self) -> Result<Option<(u64, E)>, u64> {
+        if !self.strict {
+            return tokio::select! {
+                event = self.events.recv_sequenced() => event,
+                internal = recv_internal(&mut self.internal) => internal.map(Some),
+            };
+        }
+
+        if self.held_event.is_none() && self.held_internal.is_none() {
+            tokio::select! {
+                event = self.events.recv_sequenced() => match event? {
+                    Some(event) => self.held_event = Some(event),
+                    None => return Ok(None),
+                },
+                internal = recv_internal(&mut self.internal) => {
+                    self.held_internal = Some(internal?);
+                }
+            }
+        }
+
+        // Both channels share a sequencer, which only publishes an event once all
+        // earlier events are visible. Any event sequenced before the held one is
+        // therefore already available on the other channel.
+        if self.held_event.is_none() {
+            self.held_event = self.events.try_recv_sequenced()?;
+        }
+        if self.held_internal.is_none() {
+            if let Some(internal) = &mut self.internal {
+                self.held_internal = internal.try_recv_sequenced()?;
+            }
+        }
+
+        let internal_first = match (&self.held_event, &self.held_internal) {
+            (Some((event, _)), Some((internal, _))) => internal < event,
+            (None, Some(_)) => true,
+            _ => false,
+        };
+        if internal_first {
+            Ok(self.held_internal.take())
+        } else {
+            Ok(self.held_event.take())
+        }
+    }
+}
+
+/// Receives the next internal event along with its sequence number, if internal
+/// events are enabled. Never resolves once the internal channel is disabled or
+/// closed.
+async fn recv_internal<E: Clone>(
+    receiver: &mut Option<ChannelReceiver<E>>,
+) -> Result<(u64, E), u64> {
+    let Some(internal) = receiver else {
+        return std::future::pending().await;
+    };
+    match internal.recv_sequenced().await {
+        Ok(Some(event)) => Ok(event),
+        Ok(None) => {
+            *receiver = None;
+            std::future::pending().await
+        }
+        Err(skipped) => Err(skipped),
+    }
+}
+
+/// Extracts the message of a caught panic.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// A strategy keyed by event value, resyncing on event 7.
+    struct KeyedStrategy;
+
+    #[async_trait]
+    impl Strategy<u64, ()> for KeyedStrategy {
+        async fn sync_state(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn process_event(&mut self, _event: u64) -> Vec<()> {
+            vec![]
+        }
+
+        fn needs_resync(&self, event: &u64) -> bool {
+            *event == 7
+        }
+
+        fn shard_key(&self, event: &u64) -> Option<u64> {
+            Some(*event)
+        }
+    }
+
+    /// Returns an inbox holding `events`, along with the sender of its queue.
+    async fn inbox(
+        events: impl IntoIterator<Item = u64>,
+    ) -> (channel::QueueSender<u64>, Inbox<u64>) {
+        let stats = Arc::new(ChannelStats::default());
+        let (mut sender, receiver) = channel::queue("test", 16, BackpressurePolicy::Block, stats);
+        for (seq, event) in events.into_iter().enumerate() {
+            sender.send(seq as u64, event).await.unwrap();
+        }
+        let inbox = Inbox {
+            events: receiver,
+            internal: None,
+            strict: false,
+            held_event: None,
+            held_internal: None,
+            drop_notice: None,
+        };
+        (sender, inbox)
+    }
+
+    async fn fill(
+        inbox: &mut Inbox<u64>,
+        config: BatchConfig,
+        shard: Option<Shard>,
+    ) -> (Vec<u64>, bool) {
+        let mut batch = vec![];
+        let metrics = StrategyMetrics::new("test");
+        let health = HealthRegistry::default().strategy("test");
+        let resync = fill_batch(
+            &mut batch,
+            inbox,
+            &config,
+            &KeyedStrategy,
+            shard,
+            &metrics,
+            &health,
+        )
+        .await;
+        (batch, resync)
+    }
+
+    #[tokio::test]
+    async fn test_fill_batch_stops_at_max_size() {
+        let (_sender, mut inbox) = inbox(0..5).await;
+        let config = BatchConfig {
+            max_size: 3,
+            max_delay: Duration::from_secs(60),
+        };
+        assert_eq!(
+            fill(&mut inbox, config.clone(), None).await,
+            (vec![0, 1, 2], false)
+        );
+        assert_eq!(fill(&mut inbox, config.clone(), None).await.0.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fill_batch_stops_after_max_delay() {
+        let (_sender, mut inbox) = inbox([1]).await;
+        let config = BatchConfig {
+            max_size: 10,
+            max_delay: Duration::from_millis(20),
+        };
+        let started = Instant::now();
+        assert_eq!(fill(&mut inbox, config, None).await, (vec![1], false));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_fill_batch_stops_when_channel_closes() {
+        let (sender, mut inbox) = inbox([1, 2]).await;
+        drop(sender);
+        let config = BatchConfig {
+            max_size: 10,
+            max_delay: Duration::from_secs(60),
+        };
+        assert_eq!(fill(&mut inbox, config, None).await, (vec![1, 2], false));
+    }
+
+    #[tokio::test]
+    async fn test_fill_batch_skips_events_of_other_shards() {
+        let (_sender, mut inbox) = inbox(0..8).await;
+        let config = BatchConfig {
+            max_size: 4,
+            max_delay: Duration::from_secs(60),
+        };
+        let shard = Some(Shard { index: 1, count: 2 });
+        assert_eq!(
+            fill(&mut inbox, config, shard).await,
+            (vec![1, 3, 5, 7], true)
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use tokio::task::JoinSet;
+use tokio_stream::StreamExt;
+use tracing::{error, info, warn};
+
+use super::control::{ComponentRegistry, Registration};
+use super::event::{map_engine_event, EngineEvent, EngineEventMap};
+use super::fanout::EventRouter;
+use super::health::{ComponentStatus, HealthRegistry, HealthTracker};
+use super::metrics::CollectorMetrics;
+use super::pause::{PauseMode, PauseSwitch};
+use super::runtime::Spawner;
+use super::{CollectorEntry, ErrorPolicy, ShutdownHandle};
+use crate::types::EventMiddleware;
+
+/// The engine state shared by all collector tasks.
+pub(crate) struct CollectorContext<E> {
+    pub(crate) middleware: Arc<[Box<dyn EventMiddleware<E>>]>,
+    pub(crate) event_sender: EventRouter<E>,
+    pub(crate) engine_events: Option<EngineEventMap<E>>,
+    pub(crate) shutdown: ShutdownHandle,
+    pub(crate) registry: Arc<ComponentRegistry>,
+    pub(crate) pause: PauseSwitch,
+    pub(crate) pause_mode: PauseMode,
+    pub(crate) health: Arc<HealthRegistry>,
+    pub(crate) spawner: Spawner,
+}
+
+impl<E> Clone for CollectorContext<E> {
+    fn clone(&self) -> Self {
+        Self {
+            middleware: self.middleware.clone(),
+            event_sender: self.event_sender.clone(),
+            engine_events: self.engine_events.clone(),
+            shutdown: self.shutdown.clone(),
+            registry: self.registry.clone(),
+            pause: self.pause.clone(),
+            pause_mode: self.pause_mode,
+            health: self.health.clone(),
+            spawner: self.spawner.clone(),
+        }
+    }
+}
+
+/// Spawns a registered collector into the given set. The collector is removed
+/// from the registry once its task exits.
+pub(crate) fn spawn_collector<E: Clone + Send + 'static>(
+    set: &mut JoinSet<()>,
+    entry: CollectorEntry<E>,
+    registration: Registration,
+    ctx: CollectorContext<E>,
+) {
+    let spawner = ctx.spawner.clone();
+    let task_name = entry.name.clone();
+    spawner.spawn(set, "collector", &task_name, async move {
+        let name = entry.name.clone();
+        let registry = ctx.registry.clone();
+        let health = ctx.health.collector(&name);
+        supervise_collector(entry, ctx, registration.stop, &health).await;
+        health.set_status(ComponentStatus::Stopped);
+        registry.deregister(&name, registration.id);
+    });
+}
+
+/// Forwards events from a collector into the event channel, restarting the
+/// collector according to its [RestartPolicy](super::RestartPolicy) whenever
+/// its stream ends or fails with a non-fatal error. The collector exits once
+/// `stop` is shut down.
+async fn supervise_collector<E: Clone + Send + 'static>(
+    entry: CollectorEntry<E>,
+    ctx: CollectorContext<E>,
+    stop: ShutdownHandle,
+    health: &HealthTracker,
+) {
+    let CollectorEntry {
+        name,
+        collector,
+        config,
+    } = entry;
+    let CollectorContext {
+        middleware,
+        event_sender,
+        engine_events,
+        shutdown,
+        pause,
+        pause_mode,
+        ..
+    } = ctx;
+    let metrics = CollectorMetrics::new(&name);
+    let mut restarts = 0;
+    let mut paused = pause.subscribe();
+    let mut buffer = VecDeque::new();
+
+    loop {
+        info!("starting collector {}... ", name);
+        let stream = tokio::select! {
+            stream = collector.get_event_stream() => stream,
+            _ = stop.wait() => return,
+        };
+
+        let (reason, failed, fatal) = match stream {
+            Ok(mut event_stream) => {
+                health.set_status(ComponentStatus::Running);
+                loop {
+                    let event = tokio::select! {
+                        event = event_stream.next() => match event {
+                            Some(event) => event,
+                            None => break,
+                        },
+                        Ok(()) = paused.changed(), if !buffer.is_empty() => {
+                            let resumed = !*paused.borrow_and_update();
+                            if resumed {
+                                flush(&mut buffer, &event_sender).await;
+                            }
+                            continue;
+                        }
+                        _ = stop.wait() => {
+                            info!("shutting down collector {}... ", name);
+                            return;
+                        }
+                    };
+                    // The collector is healthy again, so the next restart
+                    // starts over with the initial backoff.
+                    restarts = 0;
+                    metrics.events.increment(1);
+                    health.processed(1);
+                    let Some(event) = apply_middleware(&middleware, event).await else {
+                        metrics.dropped.increment(1);
+                        continue;
+                    };
+
+                    let is_paused = *paused.borrow_and_update();
+                    if is_paused {
+                        match pause_mode {
+                            PauseMode::Drop => metrics.dropped.increment(1),
+                            PauseMode::Buffer(capacity) => {
+                                buffer.push_back(event);
+                                if buffer.len() > capacity {
+                                    buffer.pop_front();
+                                    metrics.dropped.increment(1);
+                                }
+                            }
+                        }
+                        continue;
+                    }
+
+                    // Deliver events buffered while paused first, to preserve ordering.
+                    flush(&mut buffer, &event_sender).await;
+                    match event_sender.send(event).await {
+                        Ok(_) => {}
+                        Err(e) => error!("error sending event: {}", e),
+                    }
+                }
+                warn!("collector {} stream ended", name);
+                ("stream ended".to_string(), false, false)
+            }
+            Err(e) => {
+                error!("error starting collector {}: {}", name, e);
+                health.failed(&e);
+                (e.to_string(), true, e.is_fatal())
+            }
+        };
+
+        // Fatal errors would fail again, so the collector is not restarted.
+        let policy = config.restart_policy.as_ref().filter(|_| !fatal);
+        let Some(policy) = policy else {
+            if failed && config.error_policy == ErrorPolicy::Shutdown {
+                shutdown.shutdown();
+            }
+            return;
+        };
+
+        if policy.max_restarts.is_some_and(|max| restarts >= max) {
+            error!(
+                "giving up on collector {} after {} restarts",
+                name, restarts
+            );
+            emit(
+                &engine_events,
+                &event_sender,
+                EngineEvent::CollectorGaveUp {
+                    collector: name.clone(),
+                    attempts: restarts,
+                },
+            )
+            .await;
+            if config.error_policy == ErrorPolicy::Shutdown {
+                shutdown.shutdown();
+            }
+            return;
+        }
+
+        health.set_status(ComponentStatus::Restarting);
+        tokio::select! {
+            _ = tokio::time::sleep(policy.backoff(restarts)) => {}
+            _ = stop.wait() => return,
+        }
+        restarts += 1;
+        metrics.restarts.increment(1);
+        health.restarted();
+
+        info!("restarting collector {} (attempt {})", name, restarts);
+        emit(
+            &engine_events,
+            &event_sender,
+            EngineEvent::CollectorRestarted {
+                collector: name.clone(),
+                attempt: restarts,
+                reason,
+            },
+        )
+        .await;
+    }
+}
+
+/// Sends the events buffered while the engine was paused.
+async fn flush<E: Clone>(buffer: &mut VecDeque<E>, event_sender: &EventRouter<E>) {
+    for event in buffer.drain(..) {
+        if let Err(e) = event_sender.send(event).await {
+            error!("error sending event: {}", e);
+        }
+    }
+}
+
+/// Runs an event through the middleware stack, in order. Returns `None` as soon
+/// as a middleware drops the event.
+async fn apply_middleware<E>(middleware: &[Box<dyn EventMiddleware<E>>], event: E) -> Option<E> {
+    let mut event = event;
+    for layer in middleware {
+        event = layer.process(event).await?;
+    }
+    Some(event)
+}
+
+/// Delivers an [EngineEvent] to the strategies, if engine events are mapped into
+/// the pipeline's event type.
+async fn emit<E: Clone>(
+    engine_events: &Option<EngineEventMap<E>>,
+    event_sender: &EventRouter<E>,
+    event: EngineEvent,
+) {
+    let Some(event) = map_engine_event(engine_events, event) else {
+        return;
+    };
+    if let Err(e) = event_sender.send(event).await {
+        error!("error sending engine event: {}", e);
+    }
+}
@@ -0,0 +1,107 @@
+use ethers::providers::{ProviderError, RpcError};
+use thiserror::Error;
+
+/// A result whose error is an [ArtemisError](ArtemisError).
+pub type Result<T, E = ArtemisError> = std::result::Result<T, E>;
+
+/// The error returned by [collectors](crate::types::Collector), [strategies](crate::types::Strategy)
+/// and [executors](crate::types::Executor). Errors are classified so that the
+/// [Engine](crate::engine::Engine) and callers can tell whether a failed operation
+/// is worth retrying.
+///
+/// Any [anyhow::Error] converts into an unclassified error with `?`, which the
+/// engine retries according to its policies. Use [ResultExt](ResultExt) to
+/// classify an error explicitly, e.g. `provider.get_block(n).await.retryable()?`.
+#[derive(Debug, Error)]
+pub enum ArtemisError {
+    /// A transient failure, such as a dropped connection, a timeout or a rate
+    /// limited request, which may succeed if retried.
+    #[error(transparent)]
+    Retryable(anyhow::Error),
+    /// A failure that retrying cannot fix, such as invalid configuration or a
+    /// broken invariant. The engine never retries fatal errors.
+    #[error(transparent)]
+    Fatal(anyhow::Error),
+    /// A failure that was not classified.
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl ArtemisError {
+    /// Creates a [Retryable](ArtemisError::Retryable) error.
+    pub fn retryable(error: impl Into<anyhow::Error>) -> Self {
+        Self::Retryable(error.into())
+    }
+
+    /// Creates a [Fatal](ArtemisError::Fatal) error.
+    pub fn fatal(error: impl Into<anyhow::Error>) -> Self {
+        Self::Fatal(error.into())
+    }
+
+    /// Creates an unclassified error.
+    pub fn other(error: impl Into<anyhow::Error>) -> Self {
+        Self::Other(error.into())
+    }
+
+    /// Returns true if the error is known to be transient.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Retryable(_))
+    }
+
+    /// Returns true if the error is known to be permanent.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::Fatal(_))
+    }
+
+    /// Returns the underlying error, if it is of type `T`.
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: std::fmt::Display + std::fmt::Debug + Send + Sync + 'static,
+    {
+        match self {
+            Self::Retryable(e) | Self::Fatal(e) | Self::Other(e) => e.downcast_ref(),
+        }
+    }
+
+    /// Returns the underlying error.
+    pub fn into_inner(self) -> anyhow::Error {
+        match self {
+            Self::Retryable(e) | Self::Fatal(e) | Self::Other(e) => e,
+        }
+    }
+}
+
+/// Transport failures are retryable, while errors returned by the node itself,
+/// e.g. a reverted call, are left unclassified.
+impl From<ProviderError> for ArtemisError {
+    fn from(error: ProviderError) -> Self {
+        let transport = matches!(
+            error,
+            ProviderError::HTTPError(_) | ProviderError::JsonRpcClientError(_)
+        );
+        if transport && error.as_error_response().is_none() {
+            Self::Retryable(error.into())
+        } else {
+            Self::Other(error.into())
+        }
+    }
+}
+
+/// Extension methods to classify the error of a result.
+pub trait ResultExt<T> {
+    /// Classifies the error as [Retryable](ArtemisError::Retryable).
+    fn retryable(self) -> Result<T>;
+
+    /// Classifies the error as [Fatal](ArtemisError::Fatal).
+    fn fatal(self) -> Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn retryable(self) -> Result<T> {
+        self.map_err(ArtemisError::retryable)
+    }
+
+    fn fatal(self) -> Result<T> {
+        self.map_err(ArtemisError::fatal)
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use alloy::consensus::{SidecarBuilder, SimpleCoder};
+use alloy::network::{EthereumWallet, TransactionBuilder4844};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+use crate::error::{ArtemisError, Result, ResultExt};
+use crate::executors::alloy_mempool_executor::AlloyMempoolExecutor;
+use crate::types::{ExecutionReceipt, Executor};
+
+/// A type-3 transaction carrying data in blobs.
+#[derive(Debug, Clone, Default)]
+pub struct BlobTx {
+    /// The transaction carrying the blobs, which must have a recipient. Its other
+    /// missing fields are filled in by the executor.
+    pub tx: TransactionRequest,
+    /// The data to post, encoded into as many blobs as needed.
+    pub data: Vec<u8>,
+    /// The maximum fee per blob gas. Defaults to a multiple of the current blob base
+    /// fee.
+    pub max_fee_per_blob_gas: Option<u128>,
+}
+
+impl BlobTx {
+    pub fn new(tx: TransactionRequest, data: Vec<u8>) -> Self {
+        Self {
+            tx,
+            data,
+            max_fee_per_blob_gas: None,
+        }
+    }
+}
+
+/// An executor that constructs, signs and submits EIP-4844 blob transactions to the
+/// public mempool over an alloy provider, for strategies that post data or arb the
+/// blob market.
+///
+/// The data of every transaction is encoded into blobs, whose KZG commitments and
+/// proofs are computed with c-kzg and the trusted setup of mainnet. Unless set, the
+/// maximum fee per blob gas is the current blob base fee times the fee multiplier
+/// of the executor, so the transaction stays valid while the blob base fee rises.
+/// Transactions are then sent through an [AlloyMempoolExecutor](AlloyMempoolExecutor),
+/// which fills in their nonce, gas and fees.
+pub struct BlobExecutor<P> {
+    provider: Arc<P>,
+    executor: AlloyMempoolExecutor<P>,
+    fee_multiplier: u128,
+}
+
+impl<P> BlobExecutor<P> {
+    /// Creates an executor sending blob transactions signed by the default signer of
+    /// `wallet`.
+    pub fn new(provider: Arc<P>, wallet: impl Into<EthereumWallet>) -> Self {
+        Self {
+            executor: AlloyMempoolExecutor::new(provider.clone(), wallet),
+            provider,
+            fee_multiplier: 2,
+        }
+    }
+
+    /// Sets the multiple of the blob base fee paid at most by default. Defaults to 2,
+    /// which covers several blocks of full blobs.
+    pub fn with_fee_multiplier(mut self, fee_multiplier: u128) -> Self {
+        self.fee_multiplier = fee_multiplier.max(1);
+        self
+    }
+}
+
+impl<P> BlobExecutor<P>
+where
+    P: Provider + 'static,
+{
+    /// Returns the default maximum fee per blob gas.
+    pub async fn estimate_blob_fee(&self) -> Result<u128> {
+        let blob_base_fee = self.provider.get_blob_base_fee().await.retryable()?;
+        Ok(blob_base_fee.max(1) * self.fee_multiplier)
+    }
+
+    /// Returns the transaction request of `action`, with its blob sidecar.
+    async fn request(&self, action: BlobTx) -> Result<TransactionRequest> {
+        if action.data.is_empty() {
+            return Err(ArtemisError::fatal(anyhow!(
+                "blob transaction without data"
+            )));
+        }
+        if action.tx.to.is_none() {
+            return Err(ArtemisError::fatal(anyhow!(
+                "blob transaction without recipient"
+            )));
+        }
+        let sidecar = SidecarBuilder::<SimpleCoder>::from_slice(&action.data)
+            .build()
+            .fatal()?;
+        let max_fee_per_blob_gas = match action.max_fee_per_blob_gas {
+            Some(max_fee_per_blob_gas) => max_fee_per_blob_gas,
+            None => self.estimate_blob_fee().await?,
+        };
+        Ok(action
+            .tx
+            .with_blob_sidecar(sidecar)
+            .with_max_fee_per_blob_gas(max_fee_per_blob_gas))
+    }
+}
+
+#[async_trait]
+impl<P> Executor<BlobTx> for BlobExecutor<P>
+where
+    P: Provider + 'static,
+{
+    /// Send a blob transaction to the mempool.
+    async fn execute(&self, action: BlobTx) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Send a blob transaction to the mempool, and return its hash.
+    async fn execute_with_receipt(&self, action: BlobTx) -> Result<ExecutionReceipt> {
+        let tx = self.request(action).await?;
+        self.executor.execute_with_receipt(tx).await
+    }
+}
@@ -0,0 +1,226 @@
+use std::sync::Arc;
+
+use alloy::hex;
+use alloy::primitives::{keccak256, Bytes, B256};
+use alloy::providers::Provider;
+use alloy::rpc::types::mev::{EthBundleHash, EthSendBundle};
+use alloy::signers::Signer;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethers::types::H256;
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::warn;
+
+use crate::error::{ArtemisError, Result, ResultExt};
+use crate::types::{ExecutionReceipt, Executor};
+
+/// The Flashbots relay.
+pub const FLASHBOTS_RELAY_URL: &str = "https://relay.flashbots.net";
+
+/// A bundle of signed transactions to send to the Flashbots relay.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlloyBundle {
+    /// The signed transactions of the bundle, EIP-2718 encoded, e.g. with
+    /// `TxEnvelope::encoded_2718`.
+    pub txs: Vec<Bytes>,
+    /// The first block the bundle targets. Defaults to the next block.
+    pub min_block: Option<u64>,
+    /// The last block the bundle targets. Defaults to the first one.
+    pub max_block: Option<u64>,
+    /// The hashes of the transactions allowed to revert.
+    pub reverting_tx_hashes: Vec<B256>,
+    /// A UUID identifying the bundle, so it can be replaced by a later bundle with
+    /// the same UUID, or cancelled.
+    pub replacement_uuid: Option<String>,
+}
+
+impl AlloyBundle {
+    pub fn new(txs: Vec<Bytes>) -> Self {
+        Self {
+            txs,
+            ..Default::default()
+        }
+    }
+
+    /// Targets every block from `min_block` to `max_block`, inclusive.
+    pub fn with_blocks(mut self, min_block: u64, max_block: u64) -> Self {
+        self.min_block = Some(min_block);
+        self.max_block = Some(max_block.max(min_block));
+        self
+    }
+
+    /// Sets the replacement UUID of the bundle.
+    pub fn with_replacement_uuid(mut self, replacement_uuid: impl Into<String>) -> Self {
+        self.replacement_uuid = Some(replacement_uuid.into());
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct Request<'a, T> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    params: [T; 1],
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CancelBundle<'a> {
+    replacement_uuid: &'a str,
+}
+
+/// An executor that signs and submits `eth_sendBundle` requests to the Flashbots
+/// relay, built on alloy types.
+///
+/// Requests are authenticated with the `X-Flashbots-Signature` header, signed by
+/// the searcher key of the executor, which should not hold funds. Bundles
+/// targeting several blocks are submitted once per block, concurrently, and the
+/// submission fails only if every block fails.
+pub struct AlloyFlashbotsExecutor<P, S> {
+    provider: Arc<P>,
+    signer: S,
+    client: Client,
+    relay_url: String,
+}
+
+impl<P, S> AlloyFlashbotsExecutor<P, S> {
+    /// Creates an executor signing its requests with `signer`, and fetching the
+    /// next block from `provider`.
+    pub fn new(provider: Arc<P>, signer: S) -> Self {
+        Self {
+            provider,
+            signer,
+            client: Client::new(),
+            relay_url: FLASHBOTS_RELAY_URL.to_string(),
+        }
+    }
+
+    /// Sets the URL of the relay, e.g. of a builder with a Flashbots-compatible API.
+    /// Defaults to the Flashbots relay.
+    pub fn with_relay_url(mut self, relay_url: impl Into<String>) -> Self {
+        self.relay_url = relay_url.into();
+        self
+    }
+}
+
+impl<P, S> AlloyFlashbotsExecutor<P, S>
+where
+    P: Provider + 'static,
+    S: Signer + Send + Sync + 'static,
+{
+    /// Sends a signed JSON-RPC request to the relay, and returns its result.
+    async fn request<T: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: T,
+    ) -> anyhow::Result<Option<R>> {
+        let body = serde_json::to_string(&Request {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params: [params],
+        })?;
+        let message = hex::encode_prefixed(keccak256(&body));
+        let signature = self.signer.sign_message(message.as_bytes()).await?;
+        let response: Response<R> = self
+            .client
+            .post(self.relay_url.as_str())
+            .header("Content-Type", "application/json")
+            .header(
+                "X-Flashbots-Signature",
+                format!(
+                    "{}:{}",
+                    self.signer.address(),
+                    hex::encode_prefixed(signature.as_bytes())
+                ),
+            )
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(error) = response.error {
+            return Err(anyhow!("relay error: {}", error));
+        }
+        Ok(response.result)
+    }
+
+    /// Cancels the bundles submitted with `replacement_uuid`.
+    pub async fn cancel_bundle(&self, replacement_uuid: &str) -> Result<()> {
+        self.request::<_, serde_json::Value>("eth_cancelBundle", CancelBundle { replacement_uuid })
+            .await
+            .retryable()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P, S> Executor<AlloyBundle> for AlloyFlashbotsExecutor<P, S>
+where
+    P: Provider + 'static,
+    S: Signer + Send + Sync + 'static,
+{
+    /// Send a bundle to the Flashbots relay.
+    async fn execute(&self, action: AlloyBundle) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Send a bundle to the Flashbots relay, for every targeted block, and return
+    /// its bundle hash.
+    async fn execute_with_receipt(&self, action: AlloyBundle) -> Result<ExecutionReceipt> {
+        if action.txs.is_empty() {
+            return Err(ArtemisError::fatal(anyhow!("empty bundle")));
+        }
+        let min_block = match action.min_block {
+            Some(min_block) => min_block,
+            None => self.provider.get_block_number().await.retryable()? + 1,
+        };
+        let max_block = action.max_block.unwrap_or(min_block).max(min_block);
+
+        let submissions = (min_block..=max_block).map(|block_number| {
+            let bundle = EthSendBundle {
+                txs: action.txs.clone(),
+                block_number,
+                reverting_tx_hashes: action.reverting_tx_hashes.clone(),
+                replacement_uuid: action.replacement_uuid.clone(),
+                ..Default::default()
+            };
+            async move {
+                let result = self
+                    .request::<_, EthBundleHash>("eth_sendBundle", bundle)
+                    .await;
+                (block_number, result)
+            }
+        });
+        let mut bundle_hash = None;
+        let mut last_error = None;
+        let mut sent = 0;
+        for (block_number, result) in futures::future::join_all(submissions).await {
+            match result {
+                Ok(response) => {
+                    sent += 1;
+                    // Bundles with a replacement UUID may not return a hash.
+                    bundle_hash = bundle_hash.or(response.map(|response| response.bundle_hash));
+                }
+                Err(e) => {
+                    warn!("error sending bundle for block {}: {}", block_number, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match (sent, last_error, bundle_hash) {
+            (0, Some(e), _) => Err(ArtemisError::retryable(e)),
+            (_, _, Some(bundle_hash)) => Ok(ExecutionReceipt::Bundle(H256::from(bundle_hash.0))),
+            _ => Ok(ExecutionReceipt::Executed),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::{Ethereum, EthereumWallet, NetworkWallet, TransactionBuilder};
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use anyhow::Context;
+use async_trait::async_trait;
+use ethers::types::H256;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::error::{Result, ResultExt};
+use crate::types::{ExecutionReceipt, Executor};
+
+/// The nonces assigned by a [NonceManager](NonceManager).
+#[derive(Debug)]
+struct NonceState {
+    /// The next nonce to assign, unknown until fetched from the node.
+    next: Option<u64>,
+    /// The nonces assigned to transactions that aren't sent yet.
+    in_flight: usize,
+    synced_at: Instant,
+}
+
+/// Assigns consecutive nonces to the transactions of an account, so they can be
+/// submitted concurrently without querying the node for each of them.
+///
+/// The next nonce is fetched from the pending state of the node on first use, and
+/// again after a transaction fails to be sent, since its nonce would leave a gap.
+/// It is also checked against the node every resync interval, while no transaction
+/// is in flight: the node counting more transactions means they were sent by
+/// another process, and fewer means sent transactions were dropped, e.g. by a
+/// re-org, so the nonce is reset to fill the gap.
+#[derive(Debug)]
+pub struct NonceManager {
+    address: Address,
+    state: Mutex<NonceState>,
+    resync_interval: Duration,
+}
+
+impl NonceManager {
+    /// Creates a nonce manager for `address`, checked against the node every
+    /// `resync_interval`.
+    pub fn new(address: Address, resync_interval: Duration) -> Self {
+        Self {
+            address,
+            state: Mutex::new(NonceState {
+                next: None,
+                in_flight: 0,
+                synced_at: Instant::now(),
+            }),
+            resync_interval,
+        }
+    }
+
+    /// Returns the next nonce, which must then be [released](NonceManager::release).
+    pub async fn next<P: Provider>(&self, provider: &P) -> Result<u64> {
+        let mut state = self.state.lock().await;
+        let stale = state.synced_at.elapsed() >= self.resync_interval;
+        if state.next.is_none() || (stale && state.in_flight == 0) {
+            let pending = provider
+                .get_transaction_count(self.address)
+                .pending()
+                .await
+                .retryable()?;
+            if let Some(next) = state.next.filter(|next| *next != pending) {
+                warn!(
+                    "resetting nonce of {} from {} to {}",
+                    self.address, next, pending
+                );
+            }
+            state.next = Some(pending);
+            state.synced_at = Instant::now();
+        }
+        let nonce = state.next.unwrap_or_default();
+        state.next = Some(nonce + 1);
+        state.in_flight += 1;
+        Ok(nonce)
+    }
+
+    /// Releases a nonce returned by [next](NonceManager::next), once its transaction
+    /// was sent, or failed to be. A failure resyncs the nonce with the node.
+    pub async fn release(&self, sent: bool) {
+        let mut state = self.state.lock().await;
+        state.in_flight = state.in_flight.saturating_sub(1);
+        if !sent {
+            state.next = None;
+        }
+    }
+}
+
+/// An executor that signs transactions and broadcasts them to the public mempool
+/// over an alloy provider, with a built-in [nonce manager](NonceManager).
+///
+/// Missing fields of the [transaction requests](TransactionRequest) are filled in:
+/// the sender, nonce and chain id, the gas limit from an estimate, and the EIP-1559
+/// fees from the node's estimate, unless a gas price is set. Transactions with a
+/// nonce, e.g. replacements from a [GasEscalator](super::gas_escalator::GasEscalator),
+/// bypass the nonce manager. Transactions can be executed concurrently.
+pub struct AlloyMempoolExecutor<P> {
+    provider: Arc<P>,
+    wallet: EthereumWallet,
+    nonces: NonceManager,
+}
+
+impl<P> AlloyMempoolExecutor<P> {
+    /// Creates an executor sending transactions signed by the default signer of
+    /// `wallet`, resyncing its nonce with the node every 12 seconds.
+    pub fn new(provider: Arc<P>, wallet: impl Into<EthereumWallet>) -> Self {
+        let wallet = wallet.into();
+        let address = NetworkWallet::<Ethereum>::default_signer_address(&wallet);
+        Self {
+            provider,
+            wallet,
+            nonces: NonceManager::new(address, Duration::from_secs(12)),
+        }
+    }
+
+    /// Sets how often the nonce is checked against the node. Defaults to 12 seconds.
+    pub fn with_resync_interval(mut self, resync_interval: Duration) -> Self {
+        self.nonces.resync_interval = resync_interval;
+        self
+    }
+
+    /// Returns the nonce manager of the executor.
+    pub fn nonces(&self) -> &NonceManager {
+        &self.nonces
+    }
+}
+
+impl<P> AlloyMempoolExecutor<P>
+where
+    P: Provider + 'static,
+{
+    /// Fills in the missing fields of `tx`, other than its nonce.
+    async fn fill(&self, mut tx: TransactionRequest) -> Result<TransactionRequest> {
+        tx.from = Some(self.nonces.address);
+        if tx.chain_id.is_none() {
+            tx.chain_id = Some(self.provider.get_chain_id().await.retryable()?);
+        }
+        if tx.gas.is_none() {
+            tx.gas = Some(
+                self.provider
+                    .estimate_gas(tx.clone())
+                    .await
+                    .context("error estimating gas")?,
+            );
+        }
+        if tx.gas_price.is_none() && tx.max_fee_per_gas.is_none() {
+            let fees = self.provider.estimate_eip1559_fees().await.retryable()?;
+            tx.max_fee_per_gas = Some(fees.max_fee_per_gas);
+            tx.max_priority_fee_per_gas = Some(fees.max_priority_fee_per_gas);
+        }
+        Ok(tx)
+    }
+}
+
+#[async_trait]
+impl<P> Executor<TransactionRequest> for AlloyMempoolExecutor<P>
+where
+    P: Provider + 'static,
+{
+    /// Send a transaction to the mempool.
+    async fn execute(&self, action: TransactionRequest) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Send a transaction to the mempool, and return its hash.
+    async fn execute_with_receipt(&self, action: TransactionRequest) -> Result<ExecutionReceipt> {
+        let mut tx = self.fill(action).await?;
+        // A set nonce, e.g. of a replacement transaction, is kept.
+        let managed = tx.nonce.is_none();
+        if managed {
+            tx.nonce = Some(self.nonces.next(self.provider.as_ref()).await?);
+        }
+
+        let sent: Result<_> = async {
+            let envelope = tx.build(&self.wallet).await.fatal()?;
+            let pending = self
+                .provider
+                .send_raw_transaction(&envelope.encoded_2718())
+                .await
+                .retryable()?;
+            Ok(*pending.tx_hash())
+        }
+        .await;
+        if managed {
+            self.nonces.release(sent.is_ok()).await;
+        }
+        sent.map(|hash| ExecutionReceipt::Transaction(H256::from(hash.0)))
+    }
+}
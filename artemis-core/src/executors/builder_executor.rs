@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethers::{
+    signers::Signer,
+    types::{Bytes, H256, U64},
+    utils::keccak256,
+};
+use metrics::{counter, histogram};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::engine::metrics::{BUILDER_LATENCY, BUILDER_SUBMISSIONS};
+use crate::error::{ArtemisError, Result};
+use crate::types::{ExecutionReceipt, Executor};
+
+/// A block builder accepting `eth_sendBundle` requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Builder {
+    /// The name of the builder, used in stats and metrics.
+    pub name: String,
+    pub url: String,
+}
+
+impl Builder {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+        }
+    }
+
+    pub fn flashbots() -> Self {
+        Self::new("flashbots", "https://relay.flashbots.net")
+    }
+
+    pub fn titan() -> Self {
+        Self::new("titan", "https://rpc.titanbuilder.xyz")
+    }
+
+    pub fn beaverbuild() -> Self {
+        Self::new("beaverbuild", "https://rpc.beaverbuild.org")
+    }
+
+    pub fn rsync() -> Self {
+        Self::new("rsync", "https://rsync-builder.xyz")
+    }
+}
+
+/// A bundle of signed transactions to broadcast to builders.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuilderBundle {
+    /// The signed transactions of the bundle, RLP encoded.
+    pub txs: Vec<Bytes>,
+    /// The block the bundle targets.
+    pub block_number: U64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_timestamp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_timestamp: Option<u64>,
+    /// The hashes of the transactions allowed to revert.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reverting_tx_hashes: Vec<H256>,
+    /// A UUID identifying the bundle, so it can be replaced by a later bundle with
+    /// the same UUID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement_uuid: Option<String>,
+}
+
+/// The submissions of bundles to a builder, since the executor was created.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuilderStats {
+    /// The bundles submitted to the builder.
+    pub submitted: u64,
+    /// The bundles the builder accepted.
+    pub accepted: u64,
+    /// The bundles that failed to be submitted, or that the builder rejected.
+    pub failed: u64,
+    /// The error of the last failed submission.
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    params: [&'a BuilderBundle; 1],
+}
+
+#[derive(Deserialize)]
+struct Response {
+    result: Option<serde_json::Value>,
+    error: Option<serde_json::Value>,
+}
+
+/// An executor that broadcasts every bundle concurrently to a list of builders,
+/// since submitting to a single relay leaves inclusion probability on the table.
+///
+/// Requests are signed with the `X-Flashbots-Signature` header by the searcher key
+/// of the executor, which should not hold funds; builders that don't require it
+/// ignore it. The outcome of every submission is tracked per builder, in
+/// [stats](BuilderExecutor::stats) and in the `artemis_builder_submissions_total`
+/// and `artemis_builder_latency_seconds` metrics. A bundle fails only if every
+/// builder fails.
+pub struct BuilderExecutor<S> {
+    client: Client,
+    signer: S,
+    builders: Vec<Builder>,
+    stats: Mutex<HashMap<String, BuilderStats>>,
+}
+
+impl<S> BuilderExecutor<S> {
+    /// Creates an executor broadcasting to `builders`, e.g.
+    /// `vec![Builder::flashbots(), Builder::titan(), Builder::beaverbuild()]`.
+    pub fn new(signer: S, builders: Vec<Builder>) -> Self {
+        Self {
+            client: Client::new(),
+            signer,
+            builders,
+            stats: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the submission stats of every builder, by name.
+    pub fn stats(&self) -> HashMap<String, BuilderStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    fn record(&self, builder: &Builder, result: &anyhow::Result<Option<H256>>) {
+        let mut stats = self.stats.lock().unwrap();
+        let stats = stats.entry(builder.name.clone()).or_default();
+        stats.submitted += 1;
+        match result {
+            Ok(_) => stats.accepted += 1,
+            Err(e) => {
+                stats.failed += 1;
+                stats.last_error = Some(e.to_string());
+            }
+        }
+        let outcome = if result.is_ok() { "accepted" } else { "failed" };
+        counter!(BUILDER_SUBMISSIONS, "builder" => builder.name.clone(), "outcome" => outcome)
+            .increment(1);
+    }
+}
+
+impl<S> BuilderExecutor<S>
+where
+    S: Signer + 'static,
+{
+    /// Sends a bundle to a builder, and returns its bundle hash, if the builder
+    /// returns one.
+    async fn send(
+        &self,
+        builder: &Builder,
+        bundle: &BuilderBundle,
+    ) -> anyhow::Result<Option<H256>> {
+        let body = serde_json::to_string(&Request {
+            jsonrpc: "2.0",
+            id: 1,
+            method: "eth_sendBundle",
+            params: [bundle],
+        })?;
+        let hash = H256::from(keccak256(&body));
+        let signature = self
+            .signer
+            .sign_message(format!("{:?}", hash))
+            .await
+            .map_err(|e| anyhow!("error signing request: {}", e))?;
+        let response: Response = self
+            .client
+            .post(builder.url.as_str())
+            .header("Content-Type", "application/json")
+            .header(
+                "X-Flashbots-Signature",
+                format!("{:?}:0x{}", self.signer.address(), signature),
+            )
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(error) = response.error {
+            return Err(anyhow!("builder error: {}", error));
+        }
+        // Builders don't agree on the shape of the result.
+        let bundle_hash = response
+            .result
+            .as_ref()
+            .and_then(|result| result.get("bundleHash"))
+            .and_then(|hash| serde_json::from_value(hash.clone()).ok());
+        Ok(bundle_hash)
+    }
+}
+
+#[async_trait]
+impl<S> Executor<BuilderBundle> for BuilderExecutor<S>
+where
+    S: Signer + 'static,
+{
+    /// Broadcast a bundle to every builder.
+    async fn execute(&self, action: BuilderBundle) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Broadcast a bundle to every builder, and return the first bundle hash
+    /// returned.
+    async fn execute_with_receipt(&self, action: BuilderBundle) -> Result<ExecutionReceipt> {
+        let results = futures::future::join_all(self.builders.iter().map(|builder| {
+            let action = &action;
+            async move {
+                let start = Instant::now();
+                let result = self.send(builder, action).await;
+                histogram!(BUILDER_LATENCY, "builder" => builder.name.clone())
+                    .record(start.elapsed().as_secs_f64());
+                self.record(builder, &result);
+                (builder, result)
+            }
+        }))
+        .await;
+
+        let mut bundle_hash = None;
+        let mut errors = vec![];
+        for (builder, result) in results {
+            match result {
+                Ok(hash) => bundle_hash = bundle_hash.or(hash),
+                Err(e) => {
+                    warn!("error sending bundle to {}: {}", builder.name, e);
+                    errors.push(format!("{}: {}", builder.name, e));
+                }
+            }
+        }
+        if !errors.is_empty() && errors.len() == self.builders.len() {
+            return Err(ArtemisError::retryable(anyhow!(
+                "bundle broadcast failed: {}",
+                errors.join("; ")
+            )));
+        }
+        Ok(bundle_hash.map_or(ExecutionReceipt::Executed, ExecutionReceipt::Bundle))
+    }
+}
@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use anyhow::Result;
 use async_trait::async_trait;
 use ethers::{
     providers::Middleware, signers::Signer, types::transaction::eip2718::TypedTransaction,
@@ -9,6 +8,7 @@ use ethers_flashbots::{BundleRequest, FlashbotsMiddleware};
 use reqwest::Url;
 use tracing::error;
 
+use crate::error::{Result, ResultExt};
 use crate::types::Executor;
 
 /// A Flashbots executor that sends transactions to the Flashbots relay.
@@ -47,12 +47,12 @@ where
 
         // Sign each transaction in bundle.
         for tx in action {
-            let signature = self.tx_signer.sign_transaction(&tx).await?;
+            let signature = self.tx_signer.sign_transaction(&tx).await.fatal()?;
             bundle.add_transaction(tx.rlp_signed(&signature));
         }
 
         // Simulate bundle.
-        let block_number = self.fb_client.get_block_number().await?;
+        let block_number = self.fb_client.get_block_number().await.retryable()?;
         let bundle = bundle
             .set_block(block_number + 1)
             .set_simulation_block(block_number)
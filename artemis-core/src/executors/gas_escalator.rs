@@ -0,0 +1,565 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethers::{
+    providers::Middleware,
+    types::{transaction::eip2718::TypedTransaction, Transaction, H256, U256},
+};
+use tokio::sync::broadcast;
+use tokio::task::{JoinError, JoinSet};
+use tokio::time::{sleep, Instant};
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use tracing::{error, warn};
+
+use crate::engine::ShutdownHandle;
+use crate::error::{ArtemisError, Result};
+use crate::executors::mempool_executor::SubmitTxToMempool;
+use crate::types::{Collector, CollectorStream, ExecutionReceipt, Executor};
+
+/// The fees of a transaction, in wei per gas. Legacy transactions only have a gas
+/// price, and EIP-1559 ones only the max fees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fees {
+    pub gas_price: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+}
+
+impl Fees {
+    fn of(tx: &Transaction) -> Self {
+        Self {
+            gas_price: tx
+                .max_fee_per_gas
+                .is_none()
+                .then_some(tx.gas_price)
+                .flatten(),
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+        }
+    }
+
+    /// Returns the fees bumped by `percent`, at least by one wei.
+    fn bumped(&self, percent: u64) -> Self {
+        let bump = |fee: U256| {
+            (fee.saturating_mul((100 + percent).into()) / 100).max(fee.saturating_add(1.into()))
+        };
+        Self {
+            gas_price: self.gas_price.map(bump),
+            max_fee_per_gas: self.max_fee_per_gas.map(bump),
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas.map(bump),
+        }
+    }
+
+    /// Returns the highest fee per gas the transaction may pay.
+    fn max(&self) -> U256 {
+        self.max_fee_per_gas.or(self.gas_price).unwrap_or_default()
+    }
+}
+
+/// An action submitting a transaction that can be replaced by a copy with the same
+/// nonce and higher fees, as done by the [GasEscalator](GasEscalator).
+pub trait Replaceable: Clone + Send + Sync + 'static {
+    /// Sets the nonce and fees of the transaction. Fails if they can't be
+    /// represented by the transaction.
+    fn replace(&mut self, nonce: U256, fees: &Fees) -> Result<()>;
+}
+
+impl Replaceable for TypedTransaction {
+    fn replace(&mut self, nonce: U256, fees: &Fees) -> Result<()> {
+        self.set_nonce(nonce);
+        match self {
+            TypedTransaction::Eip1559(tx) => {
+                tx.max_fee_per_gas = fees.max_fee_per_gas.or(fees.gas_price);
+                tx.max_priority_fee_per_gas = fees.max_priority_fee_per_gas.or(fees.gas_price);
+            }
+            _ => {
+                if let Some(gas_price) = fees.gas_price.or(fees.max_fee_per_gas) {
+                    self.set_gas_price(gas_price);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Replaceable for SubmitTxToMempool {
+    fn replace(&mut self, nonce: U256, fees: &Fees) -> Result<()> {
+        self.tx.replace(nonce, fees)
+    }
+}
+
+#[cfg(feature = "alloy")]
+impl Replaceable for alloy::rpc::types::TransactionRequest {
+    fn replace(&mut self, nonce: U256, fees: &Fees) -> Result<()> {
+        let narrow = |value: U256, what: &str| -> Result<u128> {
+            value
+                .try_into()
+                .map_err(|_| ArtemisError::fatal(anyhow!("{} {} out of range", what, value)))
+        };
+        let nonce = u64::try_from(nonce)
+            .map_err(|_| ArtemisError::fatal(anyhow!("nonce {} out of range", nonce)))?;
+        let (max_fee, max_priority_fee, gas_price) = (
+            fees.max_fee_per_gas
+                .map(|fee| narrow(fee, "max fee"))
+                .transpose()?,
+            fees.max_priority_fee_per_gas
+                .map(|fee| narrow(fee, "max priority fee"))
+                .transpose()?,
+            fees.gas_price
+                .map(|fee| narrow(fee, "gas price"))
+                .transpose()?,
+        );
+        self.nonce = Some(nonce);
+        if self.max_fee_per_gas.is_some() || max_fee.is_some() {
+            self.max_fee_per_gas = max_fee;
+            self.max_priority_fee_per_gas = max_priority_fee;
+        } else {
+            self.gas_price = gas_price;
+        }
+        Ok(())
+    }
+}
+
+/// How the [GasEscalator](GasEscalator) bumps the fees of a pending transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscalationSchedule {
+    /// How long a transaction may stay pending before it is replaced.
+    pub interval: Duration,
+    /// The percentage by which fees are bumped at each replacement. Nodes reject
+    /// replacements bumping fees by less than 10%.
+    pub bump_percent: u64,
+    /// The maximum fee per gas, never exceeded by a replacement.
+    pub max_fee_per_gas: Option<U256>,
+    /// How long after the first submission the transaction is abandoned.
+    pub deadline: Duration,
+}
+
+impl EscalationSchedule {
+    /// Returns the fees of the next replacement of a transaction paying `fees`,
+    /// or `None` if they would exceed the cap of the schedule.
+    fn next_fees(&self, fees: &Fees) -> Option<Fees> {
+        let bumped = fees.bumped(self.bump_percent);
+        match self.max_fee_per_gas {
+            Some(cap) if bumped.max() > cap => None,
+            _ => Some(bumped),
+        }
+    }
+}
+
+impl Default for EscalationSchedule {
+    /// Bumps fees by 12% every 12 seconds, for up to 2 minutes.
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(12),
+            bump_percent: 12,
+            max_fee_per_gas: None,
+            deadline: Duration::from_secs(120),
+        }
+    }
+}
+
+/// The progress of a transaction escalated by the [GasEscalator](GasEscalator).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EscalationEvent {
+    /// The pending transaction was replaced with one paying higher fees.
+    Replaced {
+        replaced: H256,
+        replacement: H256,
+        fees: Fees,
+    },
+    /// One of the submitted transactions was included.
+    Confirmed { hash: H256, replacements: u32 },
+    /// The transaction was abandoned, since its deadline passed, its fees reached
+    /// the cap of the schedule, or it couldn't be tracked or replaced. It may still
+    /// be included.
+    GaveUp {
+        hashes: Vec<H256>,
+        replacements: u32,
+        reason: String,
+    },
+}
+
+/// A wrapper around an [Executor](Executor) submitting transactions that tracks
+/// them until they are included, and rebroadcasts them with bumped fees according
+/// to an [escalation schedule](EscalationSchedule), until they are confirmed or
+/// their deadline passes.
+///
+/// The wrapped executor must return [Transaction](ExecutionReceipt::Transaction)
+/// receipts, whose nonce and fees are looked up with the provider; replacements
+/// pin them, so the executor must keep the nonce and fees of its actions when set.
+/// Escalations run in the background until the [shutdown handle](GasEscalator::with_shutdown)
+/// of the escalator is triggered, and can be awaited with [join](GasEscalator::join).
+/// Their [events](EscalationEvent) are streamed by the [collector](EscalationEvents)
+/// returned by [events](GasEscalator::events), so strategies can act when a
+/// transaction is abandoned. Actions are returned the receipt of their first
+/// submission.
+pub struct GasEscalator<M, E> {
+    executor: Arc<E>,
+    provider: Arc<M>,
+    schedule: EscalationSchedule,
+    events: broadcast::Sender<EscalationEvent>,
+    shutdown: ShutdownHandle,
+    /// The running escalations.
+    tasks: Mutex<JoinSet<()>>,
+}
+
+impl<M, E> GasEscalator<M, E> {
+    pub fn new(executor: E, provider: Arc<M>) -> Self {
+        Self {
+            executor: Arc::new(executor),
+            provider,
+            schedule: EscalationSchedule::default(),
+            events: broadcast::channel(512).0,
+            shutdown: ShutdownHandle::new(),
+            tasks: Mutex::new(JoinSet::new()),
+        }
+    }
+
+    /// Sets the escalation schedule.
+    pub fn with_schedule(mut self, schedule: EscalationSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Abandons the pending escalations once `shutdown` is triggered, e.g. the
+    /// [shutdown handle](crate::engine::Engine::shutdown_handle) of the engine
+    /// running the escalator. Defaults to a handle that is never triggered.
+    pub fn with_shutdown(mut self, shutdown: ShutdownHandle) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Waits until the pending escalations are confirmed or abandoned, e.g. after
+    /// the shutdown handle was triggered.
+    pub async fn join(&self) {
+        let mut tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        while let Some(result) = tasks.join_next().await {
+            report_failure(result);
+        }
+    }
+
+    /// Returns a collector streaming the escalation events of the transactions
+    /// executed from now on.
+    pub fn events(&self) -> EscalationEvents {
+        EscalationEvents {
+            events: self.events.clone(),
+        }
+    }
+}
+
+/// Logs an escalation task that panicked.
+fn report_failure(result: std::result::Result<(), JoinError>) {
+    if let Err(e) = result {
+        error!("gas escalation task failed: {}", e);
+    }
+}
+
+/// Returns the hash of the first of `hashes` that was included, if any.
+async fn included<M: Middleware>(provider: &M, hashes: &[H256]) -> Option<H256> {
+    for hash in hashes {
+        match provider.get_transaction_receipt(*hash).await {
+            Ok(Some(_)) => return Some(*hash),
+            Ok(None) => {}
+            Err(e) => warn!("error fetching receipt of {:?}: {}", hash, e),
+        }
+    }
+    None
+}
+
+/// Escalates the transaction submitted by `action` as `hash` until it is
+/// confirmed or abandoned, and returns the final event.
+async fn escalate<M, E, A>(
+    provider: &M,
+    executor: &E,
+    schedule: EscalationSchedule,
+    mut action: A,
+    hash: H256,
+    events: &broadcast::Sender<EscalationEvent>,
+    shutdown: &ShutdownHandle,
+) -> EscalationEvent
+where
+    M: Middleware,
+    E: Executor<A>,
+    A: Replaceable,
+{
+    let deadline = Instant::now() + schedule.deadline;
+    let mut hashes = vec![hash];
+    let give_up = |hashes: Vec<H256>, reason: String| EscalationEvent::GaveUp {
+        replacements: hashes.len() as u32 - 1,
+        hashes,
+        reason,
+    };
+
+    let (nonce, mut fees) = match provider.get_transaction(hash).await {
+        Ok(Some(tx)) => (tx.nonce, Fees::of(&tx)),
+        Ok(None) => return give_up(hashes, "transaction not found".to_string()),
+        Err(e) => return give_up(hashes, format!("error fetching transaction: {}", e)),
+    };
+    loop {
+        let interval = schedule
+            .interval
+            .min(deadline.saturating_duration_since(Instant::now()));
+        tokio::select! {
+            _ = sleep(interval) => {}
+            _ = shutdown.wait() => return give_up(hashes, "shutting down".to_string()),
+        }
+        if let Some(hash) = included(provider, &hashes).await {
+            return EscalationEvent::Confirmed {
+                hash,
+                replacements: hashes.len() as u32 - 1,
+            };
+        }
+        if Instant::now() >= deadline {
+            return give_up(hashes, "deadline passed".to_string());
+        }
+
+        let Some(bumped) = schedule.next_fees(&fees) else {
+            return give_up(hashes, "fee cap reached".to_string());
+        };
+        if let Err(e) = action.replace(nonce, &bumped) {
+            return give_up(hashes, format!("error replacing transaction: {}", e));
+        }
+        match executor.execute_with_receipt(action.clone()).await {
+            Ok(ExecutionReceipt::Transaction(replacement)) => {
+                let replaced = *hashes.last().unwrap_or(&hash);
+                hashes.push(replacement);
+                fees = bumped;
+                let _ = events.send(EscalationEvent::Replaced {
+                    replaced,
+                    replacement,
+                    fees,
+                });
+            }
+            Ok(receipt) => {
+                return give_up(hashes, format!("unexpected receipt: {:?}", receipt));
+            }
+            // The nonce may have been used by one of the transactions in the
+            // meantime, which the next round finds.
+            Err(e) => warn!("error replacing transaction {:?}: {}", hash, e),
+        }
+    }
+}
+
+/// Implementation of the [Executor](Executor) trait for the [GasEscalator](GasEscalator).
+#[async_trait]
+impl<M, E, A> Executor<A> for GasEscalator<M, E>
+where
+    M: Middleware + 'static,
+    E: Executor<A> + 'static,
+    A: Replaceable,
+{
+    async fn execute(&self, action: A) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    async fn execute_with_receipt(&self, action: A) -> Result<ExecutionReceipt> {
+        let receipt = self.executor.execute_with_receipt(action.clone()).await?;
+        let ExecutionReceipt::Transaction(hash) = receipt else {
+            return Err(ArtemisError::fatal(anyhow!(
+                "cannot escalate a non-transaction receipt: {:?}",
+                receipt
+            )));
+        };
+        let (provider, executor) = (self.provider.clone(), self.executor.clone());
+        let (schedule, events) = (self.schedule, self.events.clone());
+        let shutdown = self.shutdown.clone();
+        let mut tasks = self.tasks.lock().unwrap();
+        while let Some(result) = tasks.try_join_next() {
+            report_failure(result);
+        }
+        tasks.spawn(async move {
+            let event = escalate(
+                provider.as_ref(),
+                executor.as_ref(),
+                schedule,
+                action,
+                hash,
+                &events,
+                &shutdown,
+            )
+            .await;
+            if let EscalationEvent::GaveUp { reason, .. } = &event {
+                warn!("abandoned transaction {:?}: {}", hash, reason);
+            }
+            let _ = events.send(event);
+        });
+        Ok(receipt)
+    }
+
+    fn spent(&self, action: &A) -> Option<U256> {
+        self.executor.spent(action)
+    }
+}
+
+/// A collector streaming the [events](EscalationEvent) of a [GasEscalator](GasEscalator).
+/// Events missed by a lagging stream are skipped.
+pub struct EscalationEvents {
+    events: broadcast::Sender<EscalationEvent>,
+}
+
+#[async_trait]
+impl Collector<EscalationEvent> for EscalationEvents {
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, EscalationEvent>> {
+        let stream = BroadcastStream::new(self.events.subscribe()).filter_map(|event| event.ok());
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::providers::Provider;
+    use ethers::types::{Eip1559TransactionRequest, TransactionRequest};
+
+    use super::*;
+
+    /// An executor submitting every transaction under the same hash.
+    struct StubExecutor;
+
+    #[async_trait]
+    impl Executor<TypedTransaction> for StubExecutor {
+        async fn execute(&self, _action: TypedTransaction) -> Result<()> {
+            Ok(())
+        }
+
+        async fn execute_with_receipt(
+            &self,
+            _action: TypedTransaction,
+        ) -> Result<ExecutionReceipt> {
+            Ok(ExecutionReceipt::Transaction(H256::zero()))
+        }
+    }
+
+    fn legacy(gas_price: u64) -> Fees {
+        Fees {
+            gas_price: Some(gas_price.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bumped_fees() {
+        assert_eq!(legacy(100).bumped(12), legacy(112));
+        // Small fees are bumped by at least one wei.
+        assert_eq!(legacy(1).bumped(12), legacy(2));
+        assert_eq!(legacy(0).bumped(12), legacy(1));
+
+        let fees = Fees {
+            gas_price: None,
+            max_fee_per_gas: Some(200.into()),
+            max_priority_fee_per_gas: Some(5.into()),
+        };
+        let bumped = fees.bumped(10);
+        assert_eq!(bumped.max_fee_per_gas, Some(220.into()));
+        assert_eq!(bumped.max_priority_fee_per_gas, Some(6.into()));
+        assert_eq!(bumped.gas_price, None);
+    }
+
+    #[test]
+    fn test_fee_cap_stops_escalation() {
+        let schedule = EscalationSchedule {
+            bump_percent: 10,
+            max_fee_per_gas: Some(120.into()),
+            ..Default::default()
+        };
+        assert_eq!(schedule.next_fees(&legacy(100)), Some(legacy(110)));
+        assert_eq!(schedule.next_fees(&legacy(110)), None);
+        let uncapped = EscalationSchedule::default();
+        assert!(uncapped.next_fees(&legacy(u64::MAX)).is_some());
+        // Uncapped escalation saturates instead of overflowing.
+        let max = Fees {
+            gas_price: Some(U256::MAX),
+            ..Default::default()
+        };
+        assert_eq!(uncapped.next_fees(&max), Some(max));
+    }
+
+    #[cfg(feature = "alloy")]
+    #[test]
+    fn test_replace_alloy_transaction_out_of_range() {
+        let mut tx = alloy::rpc::types::TransactionRequest::default();
+        let fees = Fees {
+            gas_price: Some(U256::from(u128::MAX) + 1),
+            ..Default::default()
+        };
+        let error = tx.replace(1.into(), &fees).unwrap_err();
+        assert!(error.is_fatal());
+        assert_eq!(tx.gas_price, None);
+
+        let error = tx
+            .replace(U256::from(u64::MAX) + 1, &legacy(1))
+            .unwrap_err();
+        assert!(error.is_fatal());
+        tx.replace(1.into(), &legacy(1)).unwrap();
+        assert_eq!((tx.nonce, tx.gas_price), (Some(1), Some(1)));
+    }
+
+    #[test]
+    fn test_replace_legacy_transaction() {
+        let mut tx: TypedTransaction = TransactionRequest::new().gas_price(100).into();
+        tx.replace(7.into(), &legacy(112)).unwrap();
+        assert_eq!(tx.nonce(), Some(&7.into()));
+        assert_eq!(tx.gas_price(), Some(112.into()));
+    }
+
+    #[test]
+    fn test_replace_eip1559_transaction() {
+        let mut tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .max_fee_per_gas(200)
+            .max_priority_fee_per_gas(5)
+            .into();
+        let fees = Fees {
+            gas_price: None,
+            max_fee_per_gas: Some(220.into()),
+            max_priority_fee_per_gas: Some(6.into()),
+        };
+        tx.replace(7.into(), &fees).unwrap();
+        let TypedTransaction::Eip1559(tx) = tx else {
+            panic!("not an EIP-1559 transaction");
+        };
+        assert_eq!(tx.nonce, Some(7.into()));
+        assert_eq!(tx.max_fee_per_gas, Some(220.into()));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(6.into()));
+    }
+
+    #[test]
+    fn test_replace_mempool_submission() {
+        let mut action = SubmitTxToMempool {
+            tx: TransactionRequest::new().into(),
+            gas_bid_info: None,
+        };
+        action.replace(3.into(), &legacy(50)).unwrap();
+        assert_eq!(action.tx.nonce(), Some(&3.into()));
+        assert_eq!(action.tx.gas_price(), Some(50.into()));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_abandons_escalations() {
+        let (provider, mock) = Provider::mocked();
+        mock.push(Transaction::default()).unwrap();
+        let shutdown = ShutdownHandle::new();
+        let escalator = GasEscalator::new(StubExecutor, Arc::new(provider))
+            .with_schedule(EscalationSchedule {
+                interval: Duration::from_secs(3600),
+                deadline: Duration::from_secs(3600),
+                ..Default::default()
+            })
+            .with_shutdown(shutdown.clone());
+        let mut events = escalator.events.subscribe();
+
+        let tx: TypedTransaction = TransactionRequest::new().into();
+        escalator.execute(tx).await.unwrap();
+        shutdown.shutdown();
+        escalator.join().await;
+        assert_eq!(
+            events.recv().await.unwrap(),
+            EscalationEvent::GaveUp {
+                hashes: vec![H256::zero()],
+                replacements: 0,
+                reason: "shutting down".to_string(),
+            }
+        );
+    }
+}
@@ -3,15 +3,20 @@ use std::{
     sync::Arc,
 };
 
-use crate::types::Executor;
-use anyhow::{Context, Result};
+use crate::error::{ArtemisError, Result};
+use crate::types::{ExecutionReceipt, Executor};
+use anyhow::Context;
 use async_trait::async_trait;
 use ethers::{
     providers::Middleware,
     types::{transaction::eip2718::TypedTransaction, U256},
 };
 
-/// An executor that sends transactions to the mempool.
+/// An executor that sends transactions to the mempool. The gas price is bid from
+/// the [GasBidInfo](GasBidInfo) of the transaction, or the node's gas price,
+/// unless the transaction already has one, or EIP-1559 fees. The amount reported to
+/// a [SpendGuard](crate::engine::SpendGuard) is the value of the transaction plus
+/// its gas bid, or its gas limit times its gas price, when either is known.
 pub struct MempoolExecutor<M> {
     client: Arc<M>,
 }
@@ -45,30 +50,60 @@ where
     M::Error: 'static,
 {
     /// Send a transaction to the mempool.
-    async fn execute(&self, mut action: SubmitTxToMempool) -> Result<()> {
+    async fn execute(&self, action: SubmitTxToMempool) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Send a transaction to the mempool, and return its hash.
+    async fn execute_with_receipt(
+        &self,
+        mut action: SubmitTxToMempool,
+    ) -> Result<ExecutionReceipt> {
         let gas_usage = self
             .client
             .estimate_gas(&action.tx, None)
             .await
             .context("Error estimating gas usage: {}")?;
 
-        let bid_gas_price;
-        if let Some(gas_bid_info) = action.gas_bid_info {
-            // gas price at which we'd break even, meaning 100% of profit goes to validator
-            let breakeven_gas_price = gas_bid_info.total_profit / gas_usage;
-            // gas price corresponding to bid percentage
-            bid_gas_price = breakeven_gas_price
-                .mul(gas_bid_info.bid_percentage)
-                .div(100);
-        } else {
-            bid_gas_price = self
-                .client
-                .get_gas_price()
-                .await
-                .context("Error getting gas price: {}")?;
+        // Fees already set on the transaction, e.g. the bumped fees of a
+        // replacement from a GasEscalator, are kept.
+        if action.tx.gas_price().is_none() {
+            let bid_gas_price;
+            if let Some(gas_bid_info) = action.gas_bid_info {
+                // gas price at which we'd break even, meaning 100% of profit goes to validator
+                let breakeven_gas_price = gas_bid_info.total_profit / gas_usage;
+                // gas price corresponding to bid percentage
+                bid_gas_price = breakeven_gas_price
+                    .mul(gas_bid_info.bid_percentage)
+                    .div(100);
+            } else {
+                bid_gas_price = self
+                    .client
+                    .get_gas_price()
+                    .await
+                    .context("Error getting gas price: {}")?;
+            }
+            action.tx.set_gas_price(bid_gas_price);
         }
-        action.tx.set_gas_price(bid_gas_price);
-        self.client.send_transaction(action.tx, None).await?;
-        Ok(())
+        let pending_tx = self
+            .client
+            .send_transaction(action.tx, None)
+            .await
+            .map_err(ArtemisError::other)?;
+        Ok(ExecutionReceipt::Transaction(pending_tx.tx_hash()))
+    }
+
+    fn spent(&self, action: &SubmitTxToMempool) -> Option<U256> {
+        let gas_cost = match &action.gas_bid_info {
+            Some(gas_bid_info) => {
+                gas_bid_info
+                    .total_profit
+                    .saturating_mul(gas_bid_info.bid_percentage.into())
+                    / 100
+            }
+            None => action.tx.gas()?.saturating_mul(action.tx.gas_price()?),
+        };
+        let value = action.tx.value().copied().unwrap_or_default();
+        Some(gas_cost.saturating_add(value))
     }
 }
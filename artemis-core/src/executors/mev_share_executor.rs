@@ -1,15 +1,23 @@
-use crate::types::Executor;
-use anyhow::Result;
+use crate::error::{ArtemisError, Result, ResultExt};
+use crate::types::{ExecutionReceipt, Executor};
+use anyhow::anyhow;
 use async_trait::async_trait;
 use ethers::signers::Signer;
+use ethers::types::{Address, Bytes, H256, U64};
 use jsonrpsee::http_client::{
     transport::{self},
     HttpClientBuilder,
 };
-use mev_share::rpc::{FlashbotsSignerLayer, MevApiClient, SendBundleRequest};
+use mev_share::rpc::{
+    BundleItem, FlashbotsSignerLayer, Inclusion, MevApiClient, Privacy, PrivacyHint, Refund,
+    RefundConfig, SendBundleRequest, Validity,
+};
 
 use tracing::{error, info};
 
+/// The Flashbots MEV-Share matchmaker on mainnet.
+pub const MEV_SHARE_URL: &str = "https://relay.flashbots.net:443";
+
 /// An executor that sends bundles to the MEV-share Matchmaker.
 pub struct MevshareExecutor {
     mev_share_client: Box<dyn MevApiClient + Send + Sync>,
@@ -17,6 +25,11 @@ pub struct MevshareExecutor {
 
 impl MevshareExecutor {
     pub fn new(signer: impl Signer + Clone + 'static) -> Self {
+        Self::with_url(signer, MEV_SHARE_URL)
+    }
+
+    /// Creates an executor sending bundles to the matchmaker at `url`.
+    pub fn with_url(signer: impl Signer + Clone + 'static, url: &str) -> Self {
         // Set up flashbots-style auth middleware
         let http = HttpClientBuilder::default()
             .set_middleware(
@@ -24,7 +37,7 @@ impl MevshareExecutor {
                     .map_err(transport::Error::Http)
                     .layer(FlashbotsSignerLayer::new(signer)),
             )
-            .build("https://relay.flashbots.net:443")
+            .build(url)
             .expect("failed to build HTTP client");
         Self {
             mev_share_client: Box::new(http),
@@ -44,3 +57,171 @@ impl Executor<SendBundleRequest> for MevshareExecutor {
         Ok(())
     }
 }
+
+/// A `mev_sendBundle` request, composed of transactions and hashes of transactions
+/// shared on MEV-Share, e.g. by the [MevShareCollector](crate::collectors::mevshare_collector::MevShareCollector),
+/// in order.
+///
+/// ```ignore
+/// let bundle = MevShareBundle::backrun(event.hash, block + 1)
+///     .with_tx(signed_backrun)
+///     .with_max_block(block + 25)
+///     .with_refund(0, 90);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MevShareBundle {
+    body: Vec<BundleItem>,
+    block: U64,
+    max_block: Option<U64>,
+    hints: Option<PrivacyHint>,
+    builders: Option<Vec<String>>,
+    refunds: Vec<Refund>,
+    refund_config: Vec<RefundConfig>,
+}
+
+impl MevShareBundle {
+    /// Creates an empty bundle targeting `block`.
+    pub fn new(block: U64) -> Self {
+        Self {
+            body: vec![],
+            block,
+            max_block: None,
+            hints: None,
+            builders: None,
+            refunds: vec![],
+            refund_config: vec![],
+        }
+    }
+
+    /// Creates a bundle targeting `block` that backruns the transaction or bundle
+    /// shared on MEV-Share with the hash `tx_hash`.
+    pub fn backrun(tx_hash: H256, block: U64) -> Self {
+        Self::new(block).with_tx_hash(tx_hash)
+    }
+
+    /// Appends a transaction shared on MEV-Share, by hash.
+    pub fn with_tx_hash(mut self, hash: H256) -> Self {
+        self.body.push(BundleItem::Hash { hash });
+        self
+    }
+
+    /// Appends a signed transaction, which must not revert.
+    pub fn with_tx(mut self, tx: Bytes) -> Self {
+        self.body.push(BundleItem::Tx {
+            tx,
+            can_revert: false,
+        });
+        self
+    }
+
+    /// Appends a signed transaction that may revert without invalidating the
+    /// bundle.
+    pub fn with_revertible_tx(mut self, tx: Bytes) -> Self {
+        self.body.push(BundleItem::Tx {
+            tx,
+            can_revert: true,
+        });
+        self
+    }
+
+    /// Keeps the bundle valid until `max_block`, inclusive.
+    pub fn with_max_block(mut self, max_block: U64) -> Self {
+        self.max_block = Some(max_block);
+        self
+    }
+
+    /// Shares the bundle on MEV-Share with `hints`, so it can itself be backrun.
+    /// Bundles are not shared by default.
+    pub fn with_hints(mut self, hints: PrivacyHint) -> Self {
+        self.hints = Some(hints);
+        self
+    }
+
+    /// Only sends the bundle to `builders`, by name.
+    pub fn with_builders(mut self, builders: Vec<String>) -> Self {
+        self.builders = Some(builders);
+        self
+    }
+
+    /// Refunds `percent` of the MEV of the bundle to the sender of its transaction
+    /// at `body_idx`, usually the backrun one at index 0.
+    pub fn with_refund(mut self, body_idx: u64, percent: u64) -> Self {
+        self.refunds.push(Refund { body_idx, percent });
+        self
+    }
+
+    /// Sends `percent` of the refunds paid to the bundle to `address`, instead of
+    /// to the sender of its first transaction.
+    pub fn with_refund_recipient(mut self, address: Address, percent: u64) -> Self {
+        self.refund_config.push(RefundConfig { address, percent });
+        self
+    }
+
+    /// Returns an error if the bundle is empty, or its refunds are invalid.
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.body.is_empty() {
+            return Err(anyhow!("empty bundle"));
+        }
+        if self
+            .refunds
+            .iter()
+            .any(|refund| refund.body_idx as usize >= self.body.len())
+        {
+            return Err(anyhow!("refund of a transaction outside the bundle"));
+        }
+        let percents = [
+            self.refunds
+                .iter()
+                .map(|refund| refund.percent)
+                .sum::<u64>(),
+            self.refund_config.iter().map(|config| config.percent).sum(),
+        ];
+        if percents.iter().any(|percent| *percent > 100) {
+            return Err(anyhow!("refunds exceed 100 percent"));
+        }
+        Ok(())
+    }
+}
+
+impl From<MevShareBundle> for SendBundleRequest {
+    fn from(bundle: MevShareBundle) -> Self {
+        let validity =
+            (!bundle.refunds.is_empty() || !bundle.refund_config.is_empty()).then(|| Validity {
+                refund: Some(bundle.refunds).filter(|refunds| !refunds.is_empty()),
+                refund_config: Some(bundle.refund_config).filter(|config| !config.is_empty()),
+            });
+        let privacy = (bundle.hints.is_some() || bundle.builders.is_some()).then(|| Privacy {
+            hints: bundle.hints,
+            builders: bundle.builders,
+        });
+        SendBundleRequest {
+            bundle_body: bundle.body,
+            inclusion: Inclusion {
+                block: bundle.block,
+                max_block: bundle.max_block,
+            },
+            validity,
+            privacy,
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl Executor<MevShareBundle> for MevshareExecutor {
+    /// Send a bundle to the matchmaker.
+    async fn execute(&self, action: MevShareBundle) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Send a bundle to the matchmaker, and return its bundle hash.
+    async fn execute_with_receipt(&self, action: MevShareBundle) -> Result<ExecutionReceipt> {
+        action.validate().map_err(ArtemisError::fatal)?;
+        let response = self
+            .mev_share_client
+            .send_bundle(action.into())
+            .await
+            .retryable()?;
+        Ok(ExecutionReceipt::Bundle(response.bundle_hash))
+    }
+}
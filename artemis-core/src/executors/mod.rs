@@ -5,8 +5,41 @@
 /// This executor submits transactions to the flashbots relay.
 pub mod flashbots_executor;
 
+/// This executor submits bundles to the flashbots relay, built on alloy types.
+#[cfg(feature = "alloy")]
+pub mod alloy_flashbots_executor;
+
+/// This executor broadcasts bundles to several block builders.
+pub mod builder_executor;
+
 /// This executor submits transactions to the public mempool.
 pub mod mempool_executor;
 
+/// This executor signs and submits transactions to the public mempool over an alloy
+/// provider, managing their nonces.
+#[cfg(feature = "alloy")]
+pub mod alloy_mempool_executor;
+
+/// This executor signs and submits EIP-4844 blob transactions over an alloy provider.
+#[cfg(feature = "alloy")]
+pub mod alloy_blob_executor;
+
 /// This executor submits bundles to the flashbots matchmaker.
 pub mod mev_share_executor;
+
+/// This executor submits ERC-4337 user operations to a bundler.
+pub mod user_operation_executor;
+
+/// This executor proposes transactions to a Safe multisig.
+pub mod safe_executor;
+
+/// This executor wraps another executor and replaces its pending transactions with
+/// higher fees.
+pub mod gas_escalator;
+
+/// This executor wraps another executor and simulates its bundles before
+/// submission.
+pub mod simulation_gate;
+
+/// This executor wraps another executor and limits the rate of its submissions.
+pub mod rate_limiter;
@@ -0,0 +1,187 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::error::{ArtemisError, Result};
+use crate::types::{ExecutionReceipt, Executor};
+use async_trait::async_trait;
+use ethers::types::U256;
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+/// A wrapper around an [Executor](Executor) that limits the rate at which actions
+/// are submitted, protecting relays and the mempool from a runaway strategy.
+///
+/// Submissions are limited per second with a token bucket, and per block with a
+/// fixed budget that resets whenever the block number changes. Both limits are
+/// optional; without any limit the wrapper is a no-op.
+pub struct ExecutorRateLimiter<E> {
+    executor: E,
+    per_second: Option<u32>,
+    per_block: Option<(u32, watch::Receiver<u64>)>,
+    mode: RateLimitMode,
+    state: Mutex<LimiterState>,
+}
+
+/// What the [ExecutorRateLimiter](ExecutorRateLimiter) does with an action that
+/// exceeds the limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitMode {
+    /// Fail the action with a retryable [RateLimited](RateLimited) error. Stale
+    /// actions are usually worthless, so this is the default.
+    #[default]
+    Reject,
+    /// Wait until the limit allows the action to be submitted.
+    Wait,
+}
+
+/// The error returned for actions rejected by the [ExecutorRateLimiter](ExecutorRateLimiter).
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("executor rate limit exceeded")]
+pub struct RateLimited;
+
+struct LimiterState {
+    /// Tokens left in the per-second bucket.
+    tokens: f64,
+    refilled_at: Instant,
+    /// The block the per-block budget applies to.
+    block: u64,
+    /// Submissions made in the current block.
+    used: u32,
+}
+
+/// Why an action could not be submitted yet.
+enum Limited {
+    /// The per-second bucket refills after the given delay.
+    For(Duration),
+    /// The per-block budget of the given block is exhausted.
+    UntilBlock(u64),
+}
+
+impl<E> ExecutorRateLimiter<E> {
+    pub fn new(executor: E) -> Self {
+        Self {
+            executor,
+            per_second: None,
+            per_block: None,
+            mode: RateLimitMode::default(),
+            state: Mutex::new(LimiterState {
+                tokens: 0.0,
+                refilled_at: Instant::now(),
+                block: 0,
+                used: 0,
+            }),
+        }
+    }
+
+    /// Limits submissions to `limit` per second, allowing bursts of up to `limit`
+    /// submissions at once.
+    pub fn with_per_second(mut self, limit: u32) -> Self {
+        self.per_second = Some(limit);
+        self.state.get_mut().unwrap().tokens = limit as f64;
+        self
+    }
+
+    /// Limits submissions to `limit` per block. `blocks` tracks the latest block
+    /// number, e.g. fed from a [BlockCollector](crate::collectors::block_collector::BlockCollector).
+    pub fn with_per_block(mut self, limit: u32, blocks: watch::Receiver<u64>) -> Self {
+        self.state.get_mut().unwrap().block = *blocks.borrow();
+        self.per_block = Some((limit, blocks));
+        self
+    }
+
+    /// Sets what happens to actions that exceed the limit.
+    pub fn with_mode(mut self, mode: RateLimitMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Waits until an action may be submitted, or fails with [RateLimited](RateLimited)
+    /// in [Reject](RateLimitMode::Reject) mode.
+    async fn acquire(&self) -> Result<()> {
+        loop {
+            let limited = match self.try_acquire() {
+                Ok(()) => return Ok(()),
+                Err(limited) => limited,
+            };
+            if self.mode == RateLimitMode::Reject {
+                return Err(ArtemisError::retryable(RateLimited));
+            }
+            match limited {
+                Limited::For(delay) => tokio::time::sleep(delay).await,
+                Limited::UntilBlock(block) => {
+                    let Some((_, blocks)) = &self.per_block else {
+                        continue;
+                    };
+                    let mut blocks = blocks.clone();
+                    if blocks.wait_for(|latest| *latest != block).await.is_err() {
+                        return Err(ArtemisError::fatal(anyhow::anyhow!(
+                            "block number source closed"
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Takes a submission from both limits, or neither.
+    fn try_acquire(&self) -> Result<(), Limited> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some((limit, blocks)) = &self.per_block {
+            let latest = *blocks.borrow();
+            if latest != state.block {
+                state.block = latest;
+                state.used = 0;
+            }
+            if state.used >= *limit {
+                return Err(Limited::UntilBlock(state.block));
+            }
+        }
+
+        if let Some(limit) = self.per_second {
+            let rate = limit as f64;
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+            state.tokens = (state.tokens + elapsed * rate).min(rate);
+            state.refilled_at = now;
+            if state.tokens < 1.0 {
+                // A zero rate never refills; check back after a second.
+                let delay = if rate > 0.0 {
+                    (1.0 - state.tokens) / rate
+                } else {
+                    1.0
+                };
+                return Err(Limited::For(Duration::from_secs_f64(delay)));
+            }
+            state.tokens -= 1.0;
+        }
+
+        if self.per_block.is_some() {
+            state.used += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Implementation of the [Executor](Executor) trait for the [ExecutorRateLimiter](ExecutorRateLimiter).
+#[async_trait]
+impl<A, E> Executor<A> for ExecutorRateLimiter<E>
+where
+    A: Send + Sync + 'static,
+    E: Executor<A>,
+{
+    async fn execute(&self, action: A) -> Result<()> {
+        self.acquire().await?;
+        self.executor.execute(action).await
+    }
+
+    async fn execute_with_receipt(&self, action: A) -> Result<ExecutionReceipt> {
+        self.acquire().await?;
+        self.executor.execute_with_receipt(action).await
+    }
+
+    fn spent(&self, action: &A) -> Option<U256> {
+        self.executor.spent(action)
+    }
+}
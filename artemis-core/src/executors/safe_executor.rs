@@ -0,0 +1,241 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, Token},
+    providers::Middleware,
+    signers::Signer,
+    types::{Address, Bytes, TransactionRequest, H256, U256},
+    utils::{id, keccak256},
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ArtemisError, Result, ResultExt};
+use crate::types::{ExecutionReceipt, Executor};
+
+/// How a Safe executes a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Operation {
+    #[default]
+    Call = 0,
+    DelegateCall = 1,
+}
+
+/// A transaction to propose to a Safe.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SafeProposal {
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub operation: Operation,
+}
+
+impl SafeProposal {
+    /// Creates a proposal calling `to` with `value` and `data`.
+    pub fn call(to: Address, value: U256, data: Bytes) -> Self {
+        Self {
+            to,
+            value,
+            data,
+            operation: Operation::Call,
+        }
+    }
+}
+
+/// A proposed multisig transaction, in the format of the Safe Transaction Service.
+/// Gas refunds are not used.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MultisigTransaction {
+    to: Address,
+    value: String,
+    data: Option<Bytes>,
+    operation: u8,
+    safe_tx_gas: u64,
+    base_gas: u64,
+    gas_price: String,
+    gas_token: Address,
+    refund_receiver: Address,
+    nonce: u64,
+    contract_transaction_hash: H256,
+    sender: Address,
+    signature: Bytes,
+    origin: &'static str,
+}
+
+#[derive(Deserialize)]
+struct Page<T> {
+    results: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct QueuedTransaction {
+    nonce: u64,
+}
+
+/// An executor that, instead of broadcasting transactions, proposes them to a Safe
+/// through the Safe Transaction Service, signed by one of its owners, for teams
+/// whose strategy output must pass multisig approval.
+///
+/// Every proposal takes the next nonce of the Safe, after the transactions already
+/// queued in the service, and is signed over its EIP-712 Safe transaction hash,
+/// which is returned as a [Proposed](ExecutionReceipt::Proposed) receipt. The other
+/// owners confirm and execute it from the Safe interface.
+pub struct SafeExecutor<M, S> {
+    provider: Arc<M>,
+    signer: S,
+    client: Client,
+    safe: Address,
+    service_url: String,
+}
+
+impl<M, S> SafeExecutor<M, S> {
+    /// Creates an executor proposing transactions to the Safe at `safe`, signed by
+    /// the owner `signer`, through the Safe Transaction Service of the chain at
+    /// `service_url`, e.g. `https://safe-transaction-mainnet.safe.global`.
+    pub fn new(provider: Arc<M>, signer: S, safe: Address, service_url: impl Into<String>) -> Self {
+        Self {
+            provider,
+            signer,
+            client: Client::new(),
+            safe,
+            service_url: service_url.into(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/api/v1/safes/{}/{}",
+            self.service_url.trim_end_matches('/'),
+            ethers::utils::to_checksum(&self.safe, None),
+            path
+        )
+    }
+}
+
+/// Returns the EIP-712 hash of a Safe transaction without gas refunds, signed by
+/// the owners of `safe` on the chain `chain_id`.
+pub fn safe_tx_hash(safe: Address, chain_id: U256, proposal: &SafeProposal, nonce: u64) -> H256 {
+    let domain_separator = keccak256(abi::encode(&[
+        Token::FixedBytes(
+            keccak256("EIP712Domain(uint256 chainId,address verifyingContract)").to_vec(),
+        ),
+        Token::Uint(chain_id),
+        Token::Address(safe),
+    ]));
+    let struct_hash = keccak256(abi::encode(&[
+        Token::FixedBytes(
+            keccak256(
+                "SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,\
+                 uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,\
+                 uint256 nonce)",
+            )
+            .to_vec(),
+        ),
+        Token::Address(proposal.to),
+        Token::Uint(proposal.value),
+        Token::FixedBytes(keccak256(&proposal.data).to_vec()),
+        Token::Uint((proposal.operation as u8).into()),
+        Token::Uint(U256::zero()),
+        Token::Uint(U256::zero()),
+        Token::Uint(U256::zero()),
+        Token::Address(Address::zero()),
+        Token::Address(Address::zero()),
+        Token::Uint(nonce.into()),
+    ]));
+    H256(keccak256(
+        [&[0x19, 0x01][..], &domain_separator, &struct_hash].concat(),
+    ))
+}
+
+impl<M, S> SafeExecutor<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    /// Returns the next nonce of the Safe, after its queued transactions.
+    async fn next_nonce(&self) -> Result<u64> {
+        let tx = TransactionRequest::new()
+            .to(self.safe)
+            .data(id("nonce()").to_vec());
+        let output = self.provider.call(&tx.into(), None).await.retryable()?;
+        let nonce = output
+            .get(..32)
+            .map(U256::from_big_endian)
+            .ok_or_else(|| ArtemisError::fatal(anyhow!("{:?} is not a Safe", self.safe)))?
+            .as_u64();
+        let queued: Page<QueuedTransaction> = self
+            .client
+            .get(self.url(&format!(
+                "multisig-transactions/?executed=false&nonce__gte={}&ordering=-nonce&limit=1",
+                nonce
+            )))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .retryable()?
+            .json()
+            .await
+            .retryable()?;
+        Ok(queued
+            .results
+            .first()
+            .map_or(nonce, |queued| queued.nonce + 1))
+    }
+}
+
+#[async_trait]
+impl<M, S> Executor<SafeProposal> for SafeExecutor<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    /// Propose a transaction to the Safe.
+    async fn execute(&self, action: SafeProposal) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Propose a transaction to the Safe, and return its Safe transaction hash.
+    async fn execute_with_receipt(&self, action: SafeProposal) -> Result<ExecutionReceipt> {
+        let chain_id = self.provider.get_chainid().await.retryable()?;
+        let nonce = self.next_nonce().await?;
+        let hash = safe_tx_hash(self.safe, chain_id, &action, nonce);
+
+        // Safes accept `eth_sign` signatures of the hash, flagged by adding 4 to v.
+        let mut signature = self
+            .signer
+            .sign_message(hash.as_bytes())
+            .await
+            .map_err(|e| ArtemisError::fatal(anyhow!("error signing Safe transaction: {}", e)))?;
+        signature.v += 4;
+
+        let proposal = MultisigTransaction {
+            to: action.to,
+            value: action.value.to_string(),
+            data: (!action.data.is_empty()).then_some(action.data),
+            operation: action.operation as u8,
+            safe_tx_gas: 0,
+            base_gas: 0,
+            gas_price: "0".to_string(),
+            gas_token: Address::zero(),
+            refund_receiver: Address::zero(),
+            nonce,
+            contract_transaction_hash: hash,
+            sender: self.signer.address(),
+            signature: signature.to_vec().into(),
+            origin: "artemis",
+        };
+        self.client
+            .post(self.url("multisig-transactions/"))
+            .json(&proposal)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .retryable()?;
+        Ok(ExecutionReceipt::Proposed(hash))
+    }
+}
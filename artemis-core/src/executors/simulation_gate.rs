@@ -0,0 +1,494 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethers::{
+    providers::Middleware,
+    signers::Signer,
+    types::{Bytes, H256, U256, U64},
+    utils::keccak256,
+};
+use metrics::counter;
+use mev_share::rpc::SendBundleRequest;
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::collectors::bundle_stats_collector::FLASHBOTS_RELAY_URL;
+use crate::engine::metrics::BUNDLE_SIMULATIONS;
+use crate::error::{ArtemisError, Result, ResultExt};
+use crate::executors::builder_executor::BuilderBundle;
+use crate::executors::mev_share_executor::MevShareBundle;
+use crate::types::{ExecutionReceipt, Executor};
+
+/// The result of a bundle simulation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulationResult {
+    /// Whether every transaction of the bundle succeeded, other than those allowed
+    /// to revert.
+    pub success: bool,
+    /// The profit of the bundle: the value paid to the block builder for an
+    /// `eth_callBundle` simulation, or the MEV-Share profit for `mev_simBundle`.
+    pub profit: U256,
+    pub gas_used: u64,
+    /// Why the bundle failed, if it did.
+    pub error: Option<String>,
+}
+
+/// Simulates actions of type `A` before they are executed, e.g. against a relay or
+/// a local EVM.
+#[async_trait]
+pub trait BundleSimulator<A>: Send + Sync {
+    /// Simulates `action` on top of the latest block.
+    async fn simulate(&self, action: &A) -> Result<SimulationResult>;
+}
+
+#[derive(Serialize)]
+struct Request<'a, T> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    params: T,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallBundle<'a> {
+    txs: &'a [Bytes],
+    block_number: U64,
+    state_block_number: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CallBundleResponse {
+    /// A decimal string, in wei.
+    coinbase_diff: String,
+    total_gas_used: u64,
+    results: Vec<CallBundleTx>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CallBundleTx {
+    tx_hash: H256,
+    error: Option<String>,
+    revert: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimBundleResponse {
+    success: bool,
+    error: Option<String>,
+    profit: U256,
+    gas_used: U64,
+}
+
+/// A [bundle simulator](BundleSimulator) running signed simulation requests on the
+/// Flashbots relay: `eth_callBundle` for [BuilderBundle](BuilderBundle)s, and
+/// `mev_simBundle` for [MevShareBundle](MevShareBundle)s.
+///
+/// Bundles are simulated on top of the latest block, as part of their target block,
+/// or of the next block if it has passed. Requests are signed with the
+/// `X-Flashbots-Signature` header by the searcher key of the simulator.
+pub struct RelaySimulator<M, S> {
+    provider: Arc<M>,
+    signer: S,
+    client: Client,
+    relay_url: String,
+}
+
+impl<M, S> RelaySimulator<M, S> {
+    pub fn new(provider: Arc<M>, signer: S) -> Self {
+        Self {
+            provider,
+            signer,
+            client: Client::new(),
+            relay_url: FLASHBOTS_RELAY_URL.to_string(),
+        }
+    }
+
+    /// Sets the relay the bundles are simulated on. Defaults to
+    /// [FLASHBOTS_RELAY_URL](FLASHBOTS_RELAY_URL).
+    pub fn with_relay_url(mut self, relay_url: impl Into<String>) -> Self {
+        self.relay_url = relay_url.into();
+        self
+    }
+}
+
+impl<M, S> RelaySimulator<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    /// Sends a signed JSON-RPC request to the relay, and returns its result.
+    async fn request<T: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: T,
+    ) -> anyhow::Result<R> {
+        let body = serde_json::to_string(&Request {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params,
+        })?;
+        let hash = H256::from(keccak256(&body));
+        let signature = self
+            .signer
+            .sign_message(format!("{:?}", hash))
+            .await
+            .map_err(|e| anyhow!("error signing request: {}", e))?;
+        let response: Response<R> = self
+            .client
+            .post(self.relay_url.as_str())
+            .header("Content-Type", "application/json")
+            .header(
+                "X-Flashbots-Signature",
+                format!("{:?}:0x{}", self.signer.address(), signature),
+            )
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(error) = response.error {
+            return Err(anyhow!("relay error: {}", error));
+        }
+        response
+            .result
+            .ok_or_else(|| anyhow!("empty relay response"))
+    }
+}
+
+#[async_trait]
+impl<M, S> BundleSimulator<BuilderBundle> for RelaySimulator<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    async fn simulate(&self, action: &BuilderBundle) -> Result<SimulationResult> {
+        let latest = self.provider.get_block_number().await.retryable()?;
+        let call = CallBundle {
+            txs: &action.txs,
+            block_number: action.block_number.max(latest + 1),
+            state_block_number: "latest",
+        };
+        let response: CallBundleResponse = self
+            .request("eth_callBundle", [call])
+            .await
+            .map_err(ArtemisError::retryable)?;
+        let error = response
+            .results
+            .iter()
+            .filter(|tx| !action.reverting_tx_hashes.contains(&tx.tx_hash))
+            .find_map(|tx| {
+                let reason = tx.revert.as_ref().or(tx.error.as_ref())?;
+                Some(format!("{:?} reverted: {}", tx.tx_hash, reason))
+            });
+        let profit = U256::from_dec_str(&response.coinbase_diff).map_err(|e| {
+            ArtemisError::retryable(anyhow!(
+                "invalid coinbaseDiff {:?}: {}",
+                response.coinbase_diff,
+                e
+            ))
+        })?;
+        Ok(SimulationResult {
+            success: error.is_none(),
+            profit,
+            gas_used: response.total_gas_used,
+            error,
+        })
+    }
+}
+
+#[async_trait]
+impl<M, S> BundleSimulator<MevShareBundle> for RelaySimulator<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    async fn simulate(&self, action: &MevShareBundle) -> Result<SimulationResult> {
+        let bundle = SendBundleRequest::from(action.clone());
+        let response: SimBundleResponse = self
+            .request("mev_simBundle", (bundle, serde_json::json!({})))
+            .await
+            .map_err(ArtemisError::retryable)?;
+        Ok(SimulationResult {
+            success: response.success,
+            profit: response.profit,
+            gas_used: response.gas_used.as_u64(),
+            error: response.error,
+        })
+    }
+}
+
+/// Why the [SimulationGate](SimulationGate) rejected an action. Returned as a fatal
+/// error, since simulating the same action again would give the same result.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SimulationRejected {
+    #[error("bundle simulation failed: {0}")]
+    Reverted(String),
+    #[error("bundle profit {profit} below threshold {min_profit}")]
+    Unprofitable { profit: U256, min_profit: U256 },
+}
+
+/// The outcome of a simulation run by the [SimulationGate](SimulationGate).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationOutcome {
+    /// The action passed the gate, and was forwarded to the executor.
+    Passed,
+    /// The action failed in simulation, and was rejected.
+    Reverted,
+    /// The action was less profitable than the threshold, and was rejected.
+    Unprofitable,
+    /// The action could not be simulated, and was not executed.
+    Failed(String),
+}
+
+/// A simulation run by the [SimulationGate](SimulationGate).
+#[derive(Debug, Clone)]
+pub struct SimulationRecord {
+    pub outcome: SimulationOutcome,
+    /// The result of the simulation, unless it failed to run.
+    pub result: Option<SimulationResult>,
+    pub simulated_at: SystemTime,
+}
+
+/// A wrapper around an [Executor](Executor) that simulates every action with a
+/// [BundleSimulator](BundleSimulator) before forwarding it, and rejects those that
+/// fail or fall below a profit threshold, so the wrapped executor only submits
+/// bundles that land as expected.
+///
+/// Rejected actions fail with a fatal [SimulationRejected](SimulationRejected)
+/// error, and actions that couldn't be simulated with a retryable error. The most
+/// recent simulations are kept in the [history](SimulationGate::history) of the
+/// gate, and counted in the `artemis_bundle_simulations_total` metric, by outcome.
+pub struct SimulationGate<E, Sim> {
+    executor: E,
+    simulator: Sim,
+    min_profit: U256,
+    capacity: usize,
+    history: Mutex<VecDeque<SimulationRecord>>,
+}
+
+impl<E, Sim> SimulationGate<E, Sim> {
+    pub fn new(executor: E, simulator: Sim) -> Self {
+        Self {
+            executor,
+            simulator,
+            min_profit: U256::zero(),
+            capacity: 256,
+            history: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Rejects actions whose simulated profit is below `min_profit`, in wei.
+    /// Defaults to 0.
+    pub fn with_min_profit(mut self, min_profit: U256) -> Self {
+        self.min_profit = min_profit;
+        self
+    }
+
+    /// Sets how many simulations are kept in the history. Defaults to 256.
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns the most recent simulations, oldest first.
+    pub fn history(&self) -> Vec<SimulationRecord> {
+        self.history.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn record(&self, outcome: SimulationOutcome, result: Option<SimulationResult>) {
+        let label = match outcome {
+            SimulationOutcome::Passed => "passed",
+            SimulationOutcome::Reverted => "reverted",
+            SimulationOutcome::Unprofitable => "unprofitable",
+            SimulationOutcome::Failed(_) => "failed",
+        };
+        counter!(BUNDLE_SIMULATIONS, "outcome" => label).increment(1);
+        let mut history = self.history.lock().unwrap();
+        if history.len() >= self.capacity {
+            history.pop_front();
+        }
+        if self.capacity > 0 {
+            history.push_back(SimulationRecord {
+                outcome,
+                result,
+                simulated_at: SystemTime::now(),
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl<A, E, Sim> Executor<A> for SimulationGate<E, Sim>
+where
+    A: Send + Sync + 'static,
+    E: Executor<A>,
+    Sim: BundleSimulator<A>,
+{
+    /// Simulate an action, and execute it if it passes the gate.
+    async fn execute(&self, action: A) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Simulate an action, and execute it if it passes the gate, returning the
+    /// receipt of the wrapped executor.
+    async fn execute_with_receipt(&self, action: A) -> Result<ExecutionReceipt> {
+        let result = match self.simulator.simulate(&action).await {
+            Ok(result) => result,
+            Err(e) => {
+                self.record(SimulationOutcome::Failed(e.to_string()), None);
+                return Err(e);
+            }
+        };
+        if !result.success {
+            let error = result.error.clone().unwrap_or_default();
+            warn!("rejecting bundle: simulation failed: {}", error);
+            self.record(SimulationOutcome::Reverted, Some(result));
+            return Err(ArtemisError::fatal(SimulationRejected::Reverted(error)));
+        }
+        if result.profit < self.min_profit {
+            let profit = result.profit;
+            self.record(SimulationOutcome::Unprofitable, Some(result));
+            return Err(ArtemisError::fatal(SimulationRejected::Unprofitable {
+                profit,
+                min_profit: self.min_profit,
+            }));
+        }
+        self.record(SimulationOutcome::Passed, Some(result));
+        self.executor.execute_with_receipt(action).await
+    }
+
+    fn spent(&self, action: &A) -> Option<U256> {
+        self.executor.spent(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// A simulator returning the same result for every action.
+    struct StubSimulator(std::result::Result<SimulationResult, String>);
+
+    #[async_trait]
+    impl BundleSimulator<u64> for StubSimulator {
+        async fn simulate(&self, _action: &u64) -> Result<SimulationResult> {
+            self.0
+                .clone()
+                .map_err(|e| ArtemisError::retryable(anyhow!(e)))
+        }
+    }
+
+    /// An executor counting the actions it executed.
+    #[derive(Default)]
+    struct CountingExecutor(AtomicUsize);
+
+    #[async_trait]
+    impl Executor<u64> for CountingExecutor {
+        async fn execute(&self, _action: u64) -> Result<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn simulated(success: bool, profit: u64) -> StubSimulator {
+        StubSimulator(Ok(SimulationResult {
+            success,
+            profit: profit.into(),
+            gas_used: 21_000,
+            error: (!success).then(|| "execution reverted".to_string()),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_profitable_action_passes() {
+        let gate = SimulationGate::new(CountingExecutor::default(), simulated(true, 100))
+            .with_min_profit(100.into());
+        gate.execute(1).await.unwrap();
+        assert_eq!(gate.executor.0.load(Ordering::SeqCst), 1);
+        assert_eq!(gate.history()[0].outcome, SimulationOutcome::Passed);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_action_is_rejected() {
+        let gate = SimulationGate::new(CountingExecutor::default(), simulated(false, 100));
+        let error = gate.execute(1).await.unwrap_err();
+        assert!(error.is_fatal());
+        assert_eq!(
+            error.downcast_ref::<SimulationRejected>(),
+            Some(&SimulationRejected::Reverted(
+                "execution reverted".to_string()
+            ))
+        );
+        assert_eq!(gate.executor.0.load(Ordering::SeqCst), 0);
+        assert_eq!(gate.history()[0].outcome, SimulationOutcome::Reverted);
+    }
+
+    #[tokio::test]
+    async fn test_unprofitable_action_is_rejected() {
+        let gate = SimulationGate::new(CountingExecutor::default(), simulated(true, 99))
+            .with_min_profit(100.into());
+        let error = gate.execute(1).await.unwrap_err();
+        assert!(error.is_fatal());
+        assert_eq!(
+            error.downcast_ref::<SimulationRejected>(),
+            Some(&SimulationRejected::Unprofitable {
+                profit: 99.into(),
+                min_profit: 100.into(),
+            })
+        );
+        assert_eq!(gate.executor.0.load(Ordering::SeqCst), 0);
+        assert_eq!(gate.history()[0].outcome, SimulationOutcome::Unprofitable);
+    }
+
+    #[tokio::test]
+    async fn test_simulator_error_is_retryable() {
+        let simulator = StubSimulator(Err("relay unavailable".to_string()));
+        let gate = SimulationGate::new(CountingExecutor::default(), simulator);
+        let error = gate.execute(1).await.unwrap_err();
+        assert!(error.is_retryable());
+        assert_eq!(gate.executor.0.load(Ordering::SeqCst), 0);
+        let record = &gate.history()[0];
+        assert_eq!(
+            record.outcome,
+            SimulationOutcome::Failed("relay unavailable".to_string())
+        );
+        assert_eq!(record.result, None);
+    }
+
+    #[tokio::test]
+    async fn test_history_keeps_most_recent_simulations() {
+        let gate =
+            SimulationGate::new(CountingExecutor::default(), simulated(true, 50)).with_history(2);
+        for action in 0..3 {
+            gate.execute(action).await.unwrap();
+        }
+        assert_eq!(gate.history().len(), 2);
+
+        let gate =
+            SimulationGate::new(CountingExecutor::default(), simulated(true, 50)).with_history(0);
+        gate.execute(1).await.unwrap();
+        assert!(gate.history().is_empty());
+    }
+}
@@ -0,0 +1,322 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethers::{
+    abi::{self, Token},
+    providers::Middleware,
+    signers::Signer,
+    types::{Address, Bytes, TransactionRequest, H256, U256},
+    utils::{id, keccak256},
+};
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error::{ArtemisError, Result, ResultExt};
+use crate::types::{ExecutionReceipt, Executor};
+
+/// The ERC-4337 EntryPoint v0.7, at the same address on every chain.
+pub const ENTRY_POINT_V07: &str = "0x0000000071727De22E5E9d8BAf0edAc6f37da032";
+
+/// A placeholder signature of the right shape, used to estimate gas before the
+/// user operation is signed.
+const DUMMY_SIGNATURE: &str = "0xfffffffffffffffffffffffffffffff0000000000000000000000000000000007aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa1c";
+
+/// An ERC-4337 user operation for the EntryPoint v0.7, in the format of the bundler
+/// RPC.
+///
+/// Zero gas limits, fees and nonce, and an empty signature, are filled in by the
+/// [UserOperationExecutor](UserOperationExecutor).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    /// The smart account executing the operation.
+    pub sender: Address,
+    pub nonce: U256,
+    /// The factory deploying the account, if it isn't deployed yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub factory: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub factory_data: Option<Bytes>,
+    /// The call the account executes.
+    pub call_data: Bytes,
+    pub call_gas_limit: U256,
+    pub verification_gas_limit: U256,
+    pub pre_verification_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    /// The paymaster sponsoring the operation, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paymaster: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paymaster_verification_gas_limit: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paymaster_post_op_gas_limit: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paymaster_data: Option<Bytes>,
+    pub signature: Bytes,
+}
+
+impl UserOperation {
+    /// Creates an operation executing `call_data` from the account `sender`.
+    pub fn new(sender: Address, call_data: Bytes) -> Self {
+        Self {
+            sender,
+            call_data,
+            ..Default::default()
+        }
+    }
+
+    /// Deploys the account with `factory` and `factory_data` first.
+    pub fn with_factory(mut self, factory: Address, factory_data: Bytes) -> Self {
+        self.factory = Some(factory);
+        self.factory_data = Some(factory_data);
+        self
+    }
+
+    /// Has the operation sponsored by `paymaster`, with `paymaster_data`. Its gas
+    /// limits are estimated unless set.
+    pub fn with_paymaster(mut self, paymaster: Address, paymaster_data: Bytes) -> Self {
+        self.paymaster = Some(paymaster);
+        self.paymaster_data = Some(paymaster_data);
+        self
+    }
+
+    /// Returns the hash of the operation signed by the account, for `entry_point`
+    /// on the chain `chain_id`.
+    pub fn hash(&self, entry_point: Address, chain_id: U256) -> H256 {
+        let init_code = match self.factory {
+            Some(factory) => [
+                factory.as_bytes(),
+                self.factory_data.as_deref().unwrap_or_default(),
+            ]
+            .concat(),
+            None => vec![],
+        };
+        let paymaster_and_data = match self.paymaster {
+            Some(paymaster) => [
+                paymaster.as_bytes(),
+                &u128_bytes(self.paymaster_verification_gas_limit.unwrap_or_default())[..],
+                &u128_bytes(self.paymaster_post_op_gas_limit.unwrap_or_default())[..],
+                self.paymaster_data.as_deref().unwrap_or_default(),
+            ]
+            .concat(),
+            None => vec![],
+        };
+        let packed = abi::encode(&[
+            Token::Address(self.sender),
+            Token::Uint(self.nonce),
+            Token::FixedBytes(keccak256(init_code).to_vec()),
+            Token::FixedBytes(keccak256(&self.call_data).to_vec()),
+            Token::FixedBytes(pack_u128s(self.verification_gas_limit, self.call_gas_limit)),
+            Token::Uint(self.pre_verification_gas),
+            Token::FixedBytes(pack_u128s(
+                self.max_priority_fee_per_gas,
+                self.max_fee_per_gas,
+            )),
+            Token::FixedBytes(keccak256(paymaster_and_data).to_vec()),
+        ]);
+        H256(keccak256(abi::encode(&[
+            Token::FixedBytes(keccak256(packed).to_vec()),
+            Token::Address(entry_point),
+            Token::Uint(chain_id),
+        ])))
+    }
+}
+
+/// Returns the low 128 bits of `value`, big-endian.
+fn u128_bytes(value: U256) -> [u8; 16] {
+    value.low_u128().to_be_bytes()
+}
+
+/// Packs two 128-bit values into a word, `high` first.
+fn pack_u128s(high: U256, low: U256) -> Vec<u8> {
+    [u128_bytes(high), u128_bytes(low)].concat()
+}
+
+/// The gas limits of a user operation estimated by a bundler.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GasEstimate {
+    pre_verification_gas: U256,
+    verification_gas_limit: U256,
+    call_gas_limit: U256,
+    paymaster_verification_gas_limit: Option<U256>,
+    paymaster_post_op_gas_limit: Option<U256>,
+}
+
+#[derive(Serialize)]
+struct Request<'a, T> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    params: T,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<serde_json::Value>,
+}
+
+/// An executor that submits ERC-4337 [user operations](UserOperation) to a bundler
+/// with `eth_sendUserOperation`, so account-abstraction flows can be automated from
+/// strategies.
+///
+/// Before submission, missing fields are filled in: the nonce from the EntryPoint,
+/// the fees from the provider's EIP-1559 estimate, the gas limits, including those
+/// of the paymaster, with `eth_estimateUserOperationGas`, and the signature, signed
+/// by the owner of the account over the hash of the operation, as expected by
+/// ECDSA-validated accounts such as `SimpleAccount`. Operations are returned their
+/// [user operation hash](ExecutionReceipt::UserOperation).
+pub struct UserOperationExecutor<M, S> {
+    provider: Arc<M>,
+    signer: S,
+    client: Client,
+    bundler_url: String,
+    entry_point: Address,
+}
+
+impl<M, S> UserOperationExecutor<M, S> {
+    /// Creates an executor submitting operations signed by `signer` to the bundler at
+    /// `bundler_url`, for the EntryPoint v0.7.
+    pub fn new(provider: Arc<M>, signer: S, bundler_url: impl Into<String>) -> Self {
+        Self {
+            provider,
+            signer,
+            client: Client::new(),
+            bundler_url: bundler_url.into(),
+            entry_point: ENTRY_POINT_V07.parse().expect("valid address"),
+        }
+    }
+
+    /// Sets the EntryPoint the operations are submitted to. Its version must match
+    /// the format of [UserOperation](UserOperation).
+    pub fn with_entry_point(mut self, entry_point: Address) -> Self {
+        self.entry_point = entry_point;
+        self
+    }
+}
+
+impl<M, S> UserOperationExecutor<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    /// Sends a JSON-RPC request to the bundler, and returns its result.
+    async fn request<T: Serialize + Send + Sync, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: T,
+    ) -> anyhow::Result<R> {
+        let response: Response<R> = self
+            .client
+            .post(self.bundler_url.as_str())
+            .json(&Request {
+                jsonrpc: "2.0",
+                id: 1,
+                method,
+                params,
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(error) = response.error {
+            return Err(anyhow!("bundler error: {}", error));
+        }
+        response
+            .result
+            .ok_or_else(|| anyhow!("empty bundler response"))
+    }
+
+    /// Returns the next nonce of `sender` at the EntryPoint, with key 0.
+    async fn nonce(&self, sender: Address) -> Result<U256> {
+        let mut data = id("getNonce(address,uint192)").to_vec();
+        data.extend(abi::encode(&[
+            Token::Address(sender),
+            Token::Uint(U256::zero()),
+        ]));
+        let tx = TransactionRequest::new().to(self.entry_point).data(data);
+        let output = self.provider.call(&tx.into(), None).await.retryable()?;
+        Ok(output
+            .get(..32)
+            .map(U256::from_big_endian)
+            .unwrap_or_default())
+    }
+
+    /// Fills in the missing fields of `op`, and signs it.
+    async fn fill(&self, mut op: UserOperation) -> Result<UserOperation> {
+        if op.nonce.is_zero() {
+            op.nonce = self.nonce(op.sender).await?;
+        }
+        if op.max_fee_per_gas.is_zero() {
+            let (max_fee_per_gas, max_priority_fee_per_gas) = self
+                .provider
+                .estimate_eip1559_fees(None)
+                .await
+                .retryable()?;
+            op.max_fee_per_gas = max_fee_per_gas;
+            op.max_priority_fee_per_gas = max_priority_fee_per_gas;
+        }
+
+        let signature = std::mem::take(&mut op.signature);
+        if op.call_gas_limit.is_zero() || op.verification_gas_limit.is_zero() {
+            op.signature = DUMMY_SIGNATURE.parse().expect("valid signature");
+            let estimate: GasEstimate = self
+                .request("eth_estimateUserOperationGas", (&op, self.entry_point))
+                .await
+                .map_err(ArtemisError::other)?;
+            op.pre_verification_gas = estimate.pre_verification_gas;
+            op.verification_gas_limit = estimate.verification_gas_limit;
+            op.call_gas_limit = estimate.call_gas_limit;
+            if op.paymaster.is_some() {
+                op.paymaster_verification_gas_limit = op
+                    .paymaster_verification_gas_limit
+                    .or(estimate.paymaster_verification_gas_limit);
+                op.paymaster_post_op_gas_limit = op
+                    .paymaster_post_op_gas_limit
+                    .or(estimate.paymaster_post_op_gas_limit);
+            }
+        }
+
+        op.signature = if signature.is_empty() {
+            let chain_id = self.provider.get_chainid().await.retryable()?;
+            let hash = op.hash(self.entry_point, chain_id);
+            let signature = self
+                .signer
+                .sign_message(hash.as_bytes())
+                .await
+                .map_err(|e| ArtemisError::fatal(anyhow!("error signing user operation: {}", e)))?;
+            signature.to_vec().into()
+        } else {
+            signature
+        };
+        Ok(op)
+    }
+}
+
+#[async_trait]
+impl<M, S> Executor<UserOperation> for UserOperationExecutor<M, S>
+where
+    M: Middleware,
+    M::Error: 'static,
+    S: Signer + 'static,
+{
+    /// Submit a user operation to the bundler.
+    async fn execute(&self, action: UserOperation) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Submit a user operation to the bundler, and return its hash.
+    async fn execute_with_receipt(&self, action: UserOperation) -> Result<ExecutionReceipt> {
+        let op = self.fill(action).await?;
+        let hash: H256 = self
+            .request("eth_sendUserOperation", (&op, self.entry_point))
+            .await
+            .map_err(ArtemisError::other)?;
+        Ok(ExecutionReceipt::UserOperation(hash))
+    }
+}
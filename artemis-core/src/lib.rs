@@ -31,8 +31,17 @@ pub mod collectors;
 /// This module contains the [Engine](engine::Engine) struct, which is responsible
 /// for orchestrating data flows between components
 pub mod engine;
+/// This module contains the [error](error::ArtemisError) returned by the core traits.
+pub mod error;
 /// This module contains [executor](types::Executor) implementations.
 pub mod executors;
+/// This module contains the [PluginStrategy](plugin::PluginStrategy), which loads
+/// strategies compiled separately as dynamic libraries.
+#[cfg(feature = "plugins")]
+pub mod plugin;
+/// This module contains [state store](state::StateStore) implementations, used to
+/// checkpoint [persistent strategies](types::PersistentStrategy).
+pub mod state;
 /// This module contains the core type definitions for Artemis.
 pub mod types;
 /// This module contains utilities for working with Artemis.
@@ -0,0 +1,121 @@
+use std::ffi::OsStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use libloading::{Library, Symbol};
+
+use crate::error::Result;
+use crate::types::Strategy;
+
+/// The name of the symbol exported by strategy plugins, see
+/// [declare_strategy_plugin](crate::declare_strategy_plugin).
+const CREATE_STRATEGY: &[u8] = b"_artemis_create_strategy";
+
+/// The constructor exported by strategy plugins.
+type CreateStrategy<E, A> = fn() -> Box<dyn Strategy<E, A>>;
+
+/// A strategy loaded from a dynamic library, e.g. a `cdylib` crate declaring it
+/// with [declare_strategy_plugin](crate::declare_strategy_plugin), so it can be
+/// compiled separately from the bot and swapped into a running
+/// [Engine](crate::engine::Engine) with a [ControlHandle](crate::engine::ControlHandle),
+/// without restarting the collectors.
+///
+/// Strategies are passed across the library boundary as Rust trait objects, which
+/// have no stable ABI: the plugin must be built with the same compiler version,
+/// the same version of `artemis-core` and the same event and action types as the
+/// bot loading it. The library stays loaded until the strategy is dropped.
+pub struct PluginStrategy<E, A> {
+    // Declared before the library, so the strategy is dropped while its code is
+    // still loaded.
+    strategy: Box<dyn Strategy<E, A>>,
+    _library: Library,
+}
+
+impl<E, A> PluginStrategy<E, A> {
+    /// Loads the dynamic library at `path`, and creates the strategy it declares.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization code, and the strategy it
+    /// declares is trusted to match the [Strategy](Strategy) trait of this crate,
+    /// for these event and action types. See [PluginStrategy](PluginStrategy).
+    pub unsafe fn load(path: impl AsRef<OsStr>) -> std::result::Result<Self, libloading::Error> {
+        let library = Library::new(path)?;
+        let strategy = {
+            let create: Symbol<'_, CreateStrategy<E, A>> = library.get(CREATE_STRATEGY)?;
+            create()
+        };
+        Ok(Self {
+            strategy,
+            _library: library,
+        })
+    }
+}
+
+#[async_trait]
+impl<E, A> Strategy<E, A> for PluginStrategy<E, A>
+where
+    E: Send + 'static,
+    A: Send + 'static,
+{
+    async fn sync_state(&mut self) -> Result<()> {
+        self.strategy.sync_state().await
+    }
+
+    async fn process_event(&mut self, event: E) -> Vec<A> {
+        self.strategy.process_event(event).await
+    }
+
+    async fn process_sequenced(&mut self, seq: u64, event: E) -> Vec<A> {
+        self.strategy.process_sequenced(seq, event).await
+    }
+
+    async fn process_batch(&mut self, events: Vec<E>) -> Vec<A> {
+        self.strategy.process_batch(events).await
+    }
+
+    async fn on_start(&mut self) {
+        self.strategy.on_start().await
+    }
+
+    async fn on_shutdown(&mut self) {
+        self.strategy.on_shutdown().await
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        self.strategy.tick_interval()
+    }
+
+    async fn on_tick(&mut self) -> Vec<A> {
+        self.strategy.on_tick().await
+    }
+
+    fn needs_resync(&self, event: &E) -> bool {
+        self.strategy.needs_resync(event)
+    }
+
+    fn topics(&self) -> Option<Vec<&'static str>> {
+        self.strategy.topics()
+    }
+
+    fn shard_key(&self, event: &E) -> Option<u64> {
+        self.strategy.shard_key(event)
+    }
+}
+
+/// Declares the strategy exported by a plugin crate, built as a `cdylib`, so it
+/// can be loaded with [PluginStrategy::load](crate::plugin::PluginStrategy::load).
+/// Takes the event and action types of the strategy, and an expression creating it.
+///
+/// ```ignore
+/// artemis_core::declare_strategy_plugin!(Event, Action, MyStrategy::new());
+/// ```
+#[macro_export]
+macro_rules! declare_strategy_plugin {
+    ($event:ty, $action:ty, $constructor:expr) => {
+        #[no_mangle]
+        pub fn _artemis_create_strategy() -> Box<dyn $crate::types::Strategy<$event, $action>> {
+            Box::new($constructor)
+        }
+    };
+}
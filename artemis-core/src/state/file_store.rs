@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::StateStore;
+
+/// A [StateStore](StateStore) that keeps each checkpoint in its own file, in a
+/// directory. Checkpoints are written to a temporary file first, then renamed,
+/// so a crash never leaves a partially written checkpoint behind.
+#[derive(Debug, Clone)]
+pub struct FileStateStore {
+    dir: PathBuf,
+}
+
+impl FileStateStore {
+    /// Creates a store keeping checkpoints in `dir`, which is created on the
+    /// first save if it does not exist.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the path of the checkpoint saved under `key`. Characters that are
+    /// not safe in file names are replaced.
+    fn path(&self, key: &str) -> PathBuf {
+        let name: String = key
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+                _ => '_',
+            })
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+#[async_trait]
+impl StateStore for FileStateStore {
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)).await {
+            Ok(checkpoint) => Ok(Some(checkpoint)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save(&self, key: &str, checkpoint: Vec<u8>) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.path(key);
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, checkpoint).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+}
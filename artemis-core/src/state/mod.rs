@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{error, info, warn};
+
+use crate::types::{PersistentStrategy, Strategy};
+
+/// This module contains a [StateStore](StateStore) that keeps checkpoints in files.
+pub mod file_store;
+/// This module contains a [StateStore](StateStore) backed by a Redis server.
+#[cfg(feature = "redis")]
+pub mod redis_store;
+/// This module contains a [StateStore](StateStore) backed by an embedded sled database.
+#[cfg(feature = "sled")]
+pub mod sled_store;
+
+/// Storage for the checkpoints of [persistent strategies](PersistentStrategy),
+/// keyed by strategy name.
+#[async_trait]
+pub trait StateStore: Send + Sync {
+    /// Loads the last checkpoint saved under `key`, if any.
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Saves a checkpoint under `key`, replacing the previous one.
+    async fn save(&self, key: &str, checkpoint: Vec<u8>) -> Result<()>;
+}
+
+/// A [StateStore](StateStore) that keeps checkpoints in memory, e.g. for tests.
+/// Checkpoints are lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryStateStore {
+    checkpoints: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StateStore for MemoryStateStore {
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.checkpoints.lock().unwrap().get(key).cloned())
+    }
+
+    async fn save(&self, key: &str, checkpoint: Vec<u8>) -> Result<()> {
+        self.checkpoints
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), checkpoint);
+        Ok(())
+    }
+}
+
+/// PersistentStrategyAdapter is a wrapper around a [PersistentStrategy](PersistentStrategy)
+/// that checkpoints its state into a [StateStore](StateStore), and implements
+/// [Strategy](Strategy), so it can be added to the [Engine](crate::engine::Engine).
+///
+/// On startup, the strategy is restored from its last checkpoint, if any, and
+/// only synced from scratch if there is none or restoring it fails. Later syncs,
+/// e.g. after a panic, always sync from scratch. The state is checkpointed as
+/// JSON after a full sync, at most once per checkpoint interval while events are
+/// processed, and on shutdown.
+pub struct PersistentStrategyAdapter<S> {
+    strategy: S,
+    key: String,
+    store: Arc<dyn StateStore>,
+    interval: Duration,
+    checkpointed_at: Option<Instant>,
+    started: bool,
+}
+
+impl<S> PersistentStrategyAdapter<S> {
+    /// Creates an adapter checkpointing `strategy` under `key`, usually the name
+    /// of the strategy in the engine.
+    pub fn new(strategy: S, key: impl Into<String>, store: Arc<dyn StateStore>) -> Self {
+        Self {
+            strategy,
+            key: key.into(),
+            store,
+            interval: Duration::from_secs(30),
+            checkpointed_at: None,
+            started: false,
+        }
+    }
+
+    /// Sets the minimum interval between two checkpoints while events are
+    /// processed. Defaults to 30 seconds.
+    pub fn with_checkpoint_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the wrapped strategy.
+    pub fn into_inner(self) -> S {
+        self.strategy
+    }
+
+    /// Loads the last checkpoint and restores the strategy from it. Returns true
+    /// if the strategy was restored.
+    async fn restore<E, A>(&mut self) -> bool
+    where
+        S: PersistentStrategy<E, A>,
+    {
+        let checkpoint = match self.store.load(&self.key).await {
+            Ok(Some(checkpoint)) => checkpoint,
+            Ok(None) => return false,
+            Err(e) => {
+                warn!("error loading checkpoint of strategy {}: {}", self.key, e);
+                return false;
+            }
+        };
+        let state = match serde_json::from_slice(&checkpoint) {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("error decoding checkpoint of strategy {}: {}", self.key, e);
+                return false;
+            }
+        };
+        match self.strategy.restore(state).await {
+            Ok(()) => {
+                info!("restored strategy {} from its checkpoint", self.key);
+                true
+            }
+            Err(e) => {
+                warn!("error restoring strategy {}, syncing: {}", self.key, e);
+                false
+            }
+        }
+    }
+
+    /// Checkpoints the state of the strategy. Failures are logged, since the
+    /// strategy can still sync from scratch on the next start.
+    async fn checkpoint<E, A>(&mut self)
+    where
+        S: PersistentStrategy<E, A>,
+    {
+        self.checkpointed_at = Some(Instant::now());
+        let checkpoint = match serde_json::to_vec(&self.strategy.checkpoint()) {
+            Ok(checkpoint) => checkpoint,
+            Err(e) => {
+                error!("error encoding checkpoint of strategy {}: {}", self.key, e);
+                return;
+            }
+        };
+        if let Err(e) = self.store.save(&self.key, checkpoint).await {
+            error!("error saving checkpoint of strategy {}: {}", self.key, e);
+        }
+    }
+
+    /// Checkpoints the state of the strategy if the checkpoint interval elapsed.
+    async fn maybe_checkpoint<E, A>(&mut self)
+    where
+        S: PersistentStrategy<E, A>,
+    {
+        let due = self
+            .checkpointed_at
+            .is_none_or(|at| at.elapsed() >= self.interval);
+        if due {
+            self.checkpoint::<E, A>().await;
+        }
+    }
+}
+
+#[async_trait]
+impl<E, A, S> Strategy<E, A> for PersistentStrategyAdapter<S>
+where
+    E: Send + 'static,
+    A: Send + 'static,
+    S: PersistentStrategy<E, A>,
+{
+    async fn sync_state(&mut self) -> crate::error::Result<()> {
+        if !self.started {
+            self.started = true;
+            if self.restore::<E, A>().await {
+                return Ok(());
+            }
+        }
+        self.strategy.sync_state().await?;
+        self.checkpoint::<E, A>().await;
+        Ok(())
+    }
+
+    async fn process_event(&mut self, event: E) -> Vec<A> {
+        let actions = self.strategy.process_event(event).await;
+        self.maybe_checkpoint::<E, A>().await;
+        actions
+    }
+
+    async fn process_sequenced(&mut self, seq: u64, event: E) -> Vec<A> {
+        let actions = self.strategy.process_sequenced(seq, event).await;
+        self.maybe_checkpoint::<E, A>().await;
+        actions
+    }
+
+    async fn process_batch(&mut self, events: Vec<E>) -> Vec<A> {
+        let actions = self.strategy.process_batch(events).await;
+        self.maybe_checkpoint::<E, A>().await;
+        actions
+    }
+
+    async fn on_start(&mut self) {
+        self.strategy.on_start().await
+    }
+
+    async fn on_shutdown(&mut self) {
+        self.strategy.on_shutdown().await;
+        self.checkpoint::<E, A>().await;
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        self.strategy.tick_interval()
+    }
+
+    async fn on_tick(&mut self) -> Vec<A> {
+        let actions = self.strategy.on_tick().await;
+        self.maybe_checkpoint::<E, A>().await;
+        actions
+    }
+
+    fn needs_resync(&self, event: &E) -> bool {
+        self.strategy.needs_resync(event)
+    }
+
+    fn topics(&self) -> Option<Vec<&'static str>> {
+        self.strategy.topics()
+    }
+
+    fn shard_key(&self, event: &E) -> Option<u64> {
+        self.strategy.shard_key(event)
+    }
+}
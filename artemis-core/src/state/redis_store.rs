@@ -0,0 +1,56 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+
+use super::StateStore;
+
+/// A [StateStore](StateStore) backed by a Redis server, so checkpoints survive
+/// the loss of the machine running the bot. Checkpoints are stored as plain
+/// string values, under the store's key prefix.
+#[derive(Clone)]
+pub struct RedisStateStore {
+    connection: MultiplexedConnection,
+    prefix: String,
+}
+
+impl RedisStateStore {
+    /// Connects to the Redis server at `url`, e.g. `redis://127.0.0.1/`.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = client.get_multiplexed_async_connection().await?;
+        Ok(Self::new(connection))
+    }
+
+    /// Creates a store on top of an existing connection.
+    pub fn new(connection: MultiplexedConnection) -> Self {
+        Self {
+            connection,
+            prefix: "artemis:state:".to_string(),
+        }
+    }
+
+    /// Sets the prefix of the keys checkpoints are stored under, e.g. to run
+    /// several bots against the same server. Defaults to `artemis:state:`.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+#[async_trait]
+impl StateStore for RedisStateStore {
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let mut connection = self.connection.clone();
+        let checkpoint: Option<Vec<u8>> = connection.get(format!("{}{}", self.prefix, key)).await?;
+        Ok(checkpoint)
+    }
+
+    async fn save(&self, key: &str, checkpoint: Vec<u8>) -> Result<()> {
+        let mut connection = self.connection.clone();
+        connection
+            .set::<_, _, ()>(format!("{}{}", self.prefix, key), checkpoint)
+            .await?;
+        Ok(())
+    }
+}
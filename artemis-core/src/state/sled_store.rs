@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::StateStore;
+
+/// A [StateStore](StateStore) backed by an embedded [sled](sled) database.
+/// Every save is flushed to disk before it completes.
+#[derive(Debug, Clone)]
+pub struct SledStateStore {
+    db: sled::Db,
+}
+
+impl SledStateStore {
+    /// Opens, or creates, the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            db: sled::open(path)?,
+        })
+    }
+
+    /// Creates a store on top of an already opened database, e.g. to share it
+    /// with the rest of the application.
+    pub fn new(db: sled::Db) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl StateStore for SledStateStore {
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.db.get(key)?.map(|checkpoint| checkpoint.to_vec()))
+    }
+
+    async fn save(&self, key: &str, checkpoint: Vec<u8>) -> Result<()> {
+        self.db.insert(key, checkpoint)?;
+        self.db.flush_async().await?;
+        Ok(())
+    }
+}
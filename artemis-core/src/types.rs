@@ -1,12 +1,15 @@
-use anyhow::Result;
 use async_trait::async_trait;
-use ethers::types::Transaction;
+use ethers::types::{Transaction, H256, U256};
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
 use tokio_stream::Stream;
 use tokio_stream::StreamExt;
 
 use crate::collectors::block_collector::NewBlock;
 use crate::collectors::opensea_order_collector::OpenseaOrder;
+use crate::error::{ArtemisError, Result};
 use crate::executors::flashbots_executor::FlashbotsBundle;
 use crate::executors::mempool_executor::SubmitTxToMempool;
 
@@ -29,6 +32,78 @@ pub trait Strategy<E, A>: Send + Sync {
 
     /// Process an event, and return an action if needed.
     async fn process_event(&mut self, event: E) -> Vec<A>;
+
+    /// Process an event along with its sequence number, when the
+    /// [Engine](crate::engine::Engine) runs with strict ordering. Sequence numbers
+    /// increase monotonically across all events, in the order in which every
+    /// strategy observes them, and skip the events the strategy did not receive,
+    /// e.g. because it fell behind. Defaults to [process_event](Strategy::process_event).
+    async fn process_sequenced(&mut self, seq: u64, event: E) -> Vec<A>
+    where
+        E: Send + 'static,
+    {
+        let _ = seq;
+        self.process_event(event).await
+    }
+
+    /// Process a batch of events, when the strategy is configured for batching in
+    /// the [Engine](crate::engine::Engine), and return actions if needed. Defaults
+    /// to processing the events one at a time with [process_event](Strategy::process_event).
+    async fn process_batch(&mut self, events: Vec<E>) -> Vec<A>
+    where
+        E: Send + 'static,
+        A: Send + 'static,
+    {
+        let mut actions = vec![];
+        for event in events {
+            actions.extend(self.process_event(event).await);
+        }
+        actions
+    }
+
+    /// Called by the [Engine](crate::engine::Engine) once the strategy's state is
+    /// synced, before it processes any event, e.g. to warm caches.
+    async fn on_start(&mut self) {}
+
+    /// Called by the [Engine](crate::engine::Engine) once the strategy has
+    /// processed its last event, e.g. to flush caches.
+    async fn on_shutdown(&mut self) {}
+
+    /// Returns how often the [Engine](crate::engine::Engine) calls
+    /// [on_tick](Strategy::on_tick). `None`, the default, never ticks.
+    fn tick_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Runs periodic housekeeping, at the strategy's [tick interval](Strategy::tick_interval),
+    /// and returns actions if needed.
+    async fn on_tick(&mut self) -> Vec<A> {
+        vec![]
+    }
+
+    /// Returns true if the strategy's state should be resynced before the event is
+    /// processed, e.g. every 100 blocks. The [Engine](crate::engine::Engine) then
+    /// calls [sync_state](Strategy::sync_state) according to the strategy's
+    /// configuration.
+    fn needs_resync(&self, _event: &E) -> bool {
+        false
+    }
+
+    /// Returns the [topics](EventTopic) the strategy subscribes to, when the
+    /// [Engine](crate::engine::Engine) routes events by topic. `None`, the
+    /// default, subscribes to every event.
+    fn topics(&self) -> Option<Vec<&'static str>> {
+        None
+    }
+
+    /// Returns the shard key of an event, used when the strategy is sharded
+    /// across several copies in the [Engine](crate::engine::Engine). Each copy
+    /// only processes the events whose key falls into its partition, e.g. the
+    /// hash of the pool a transaction touches. Events without a key, such as new
+    /// blocks, are processed by every copy.
+    fn shard_key(&self, _event: &E) -> Option<u64> {
+        None
+    }
 }
 
 /// Executor trait, responsible for executing actions returned by strategies.
@@ -36,6 +111,156 @@ pub trait Strategy<E, A>: Send + Sync {
 pub trait Executor<A>: Send + Sync {
     /// Execute an action.
     async fn execute(&self, action: A) -> Result<()>;
+
+    /// Execute an action, and return a [receipt](ExecutionReceipt) describing
+    /// its outcome. The engine uses this method instead of [execute](Executor::execute)
+    /// when execution feedback is enabled, and feeds the receipts back to the
+    /// strategies. The default implementation calls [execute](Executor::execute)
+    /// and returns [Executed](ExecutionReceipt::Executed).
+    async fn execute_with_receipt(&self, action: A) -> Result<ExecutionReceipt>
+    where
+        A: Send + 'static,
+    {
+        self.execute(action).await?;
+        Ok(ExecutionReceipt::Executed)
+    }
+
+    /// Returns the most the executor spends executing an action, e.g. the maximum
+    /// gas cost and value of a transaction in wei, if known. The engine reports it
+    /// to its [SpendGuard](crate::engine::SpendGuard) once the action was executed.
+    /// Defaults to `None`, i.e. nothing is reported.
+    fn spent(&self, _action: &A) -> Option<U256> {
+        None
+    }
+}
+
+/// EventTopic trait, implemented by events that are routed to strategies by
+/// topic, so each strategy only receives the events it subscribed to. See
+/// [Engine::with_event_topics](crate::engine::Engine::with_event_topics).
+pub trait EventTopic {
+    /// Returns the topic of the event, e.g. `"block"` or `"transaction"`.
+    fn topic(&self) -> &'static str;
+}
+
+/// ActionPriority trait, implemented by actions that should be executed by
+/// priority rather than in the order they were emitted, e.g. so bundle
+/// submissions jump ahead of notifications. See
+/// [Engine::with_action_priorities](crate::engine::Engine::with_action_priorities).
+pub trait ActionPriority {
+    /// Returns the priority of the action. Actions with a higher priority are
+    /// executed first.
+    fn priority(&self) -> u8;
+}
+
+/// The outcome of an action successfully executed by an [Executor](Executor).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExecutionReceipt {
+    /// The action was executed, without further information.
+    Executed,
+    /// The action did not apply to the executor, e.g. because it was filtered
+    /// out by an [ExecutorMap](ExecutorMap).
+    Skipped,
+    /// A transaction was submitted, with the given hash.
+    Transaction(H256),
+    /// A bundle was submitted, with the given bundle hash.
+    Bundle(H256),
+    /// A user operation was submitted to a bundler, with the given user operation
+    /// hash.
+    UserOperation(H256),
+    /// A transaction was proposed to a Safe for its owners' approval, with the given
+    /// Safe transaction hash.
+    Proposed(H256),
+    /// The action was captured by an [Engine](crate::engine::Engine) in dry-run
+    /// mode, and not executed.
+    DryRun,
+}
+
+/// Middleware trait, which intercepts events between collectors and strategies.
+///
+/// Middleware is stacked in the [Engine](crate::engine::Engine), and applied in
+/// order to every event emitted by a collector, before it reaches the strategies.
+/// Each middleware may pass the event on unchanged, transform or enrich it, or
+/// drop it by returning `None`. Closures of type `Fn(E) -> Option<E>` implement
+/// this trait.
+#[async_trait]
+pub trait EventMiddleware<E>: Send + Sync {
+    /// Process an event, and return the event to pass on, if any.
+    async fn process(&self, event: E) -> Option<E>;
+}
+
+#[async_trait]
+impl<E, F> EventMiddleware<E> for F
+where
+    E: Send + 'static,
+    F: Fn(E) -> Option<E> + Send + Sync,
+{
+    async fn process(&self, event: E) -> Option<E> {
+        self(event)
+    }
+}
+
+/// Batch strategy trait, for strategies that process events in batches to cut
+/// the per-event overhead of very high-throughput feeds.
+#[async_trait]
+pub trait BatchStrategy<E, A>: Send + Sync {
+    /// Sync the initial state of the strategy if needed, usually by fetching
+    /// onchain data.
+    async fn sync_state(&mut self) -> Result<()>;
+
+    /// Process a batch of events, and return actions if needed.
+    async fn process_batch(&mut self, events: Vec<E>) -> Vec<A>;
+}
+
+/// Persistent strategy trait, an extension of [Strategy](Strategy) for strategies
+/// whose state can be checkpointed, e.g. tracked pools or inflight bundles, so
+/// they resume after a restart without a full [sync](Strategy::sync_state) from
+/// scratch. Wrap the strategy in a
+/// [PersistentStrategyAdapter](crate::state::PersistentStrategyAdapter) to
+/// checkpoint it into a [StateStore](crate::state::StateStore).
+#[async_trait]
+pub trait PersistentStrategy<E, A>: Strategy<E, A> {
+    /// The checkpointed state of the strategy.
+    type State: Serialize + DeserializeOwned + Send;
+
+    /// Returns the current state of the strategy, to be checkpointed.
+    fn checkpoint(&self) -> Self::State;
+
+    /// Restores the strategy from its last checkpoint, instead of syncing its
+    /// state. Returning an error falls back to [sync_state](Strategy::sync_state),
+    /// e.g. if the checkpoint is too old to catch up from.
+    async fn restore(&mut self, state: Self::State) -> Result<()>;
+}
+
+/// BatchStrategyAdapter is a wrapper around a [BatchStrategy](BatchStrategy) that
+/// implements [Strategy](Strategy), so it can be added to the [Engine](crate::engine::Engine).
+pub struct BatchStrategyAdapter<E, A> {
+    strategy: Box<dyn BatchStrategy<E, A>>,
+}
+
+impl<E, A> BatchStrategyAdapter<E, A> {
+    pub fn new(strategy: Box<dyn BatchStrategy<E, A>>) -> Self {
+        Self { strategy }
+    }
+}
+
+#[async_trait]
+impl<E, A> Strategy<E, A> for BatchStrategyAdapter<E, A>
+where
+    E: Send + 'static,
+    A: Send + 'static,
+{
+    async fn sync_state(&mut self) -> Result<()> {
+        self.strategy.sync_state().await
+    }
+
+    async fn process_event(&mut self, event: E) -> Vec<A> {
+        self.strategy.process_batch(vec![event]).await
+    }
+
+    async fn process_batch(&mut self, events: Vec<E>) -> Vec<A> {
+        self.strategy.process_batch(events).await
+    }
 }
 
 /// CollectorMap is a wrapper around a [Collector](Collector) that maps outgoing
@@ -65,6 +290,147 @@ where
     }
 }
 
+/// CollectorFilter is a wrapper around a [Collector](Collector) that only emits
+/// the events matching a predicate, e.g. transactions touching specific addresses.
+pub struct CollectorFilter<E, F> {
+    collector: Box<dyn Collector<E>>,
+    f: F,
+}
+
+impl<E, F> CollectorFilter<E, F> {
+    pub fn new(collector: Box<dyn Collector<E>>, f: F) -> Self {
+        Self { collector, f }
+    }
+}
+
+#[async_trait]
+impl<E, F> Collector<E> for CollectorFilter<E, F>
+where
+    E: Send + Sync + 'static,
+    F: Fn(&E) -> bool + Send + Sync + Clone + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, E>> {
+        let stream = self.collector.get_event_stream().await?;
+        let f = self.f.clone();
+        let stream = stream.filter(f);
+        Ok(Box::pin(stream))
+    }
+}
+
+/// CollectorFilterMap is a wrapper around a [Collector](Collector) that maps
+/// outgoing events to a different type, and drops the events for which the
+/// function returns `None`.
+pub struct CollectorFilterMap<E, F> {
+    collector: Box<dyn Collector<E>>,
+    f: F,
+}
+
+impl<E, F> CollectorFilterMap<E, F> {
+    pub fn new(collector: Box<dyn Collector<E>>, f: F) -> Self {
+        Self { collector, f }
+    }
+}
+
+#[async_trait]
+impl<E1, E2, F> Collector<E2> for CollectorFilterMap<E1, F>
+where
+    E1: Send + Sync + 'static,
+    E2: Send + Sync + 'static,
+    F: Fn(E1) -> Option<E2> + Send + Sync + Clone + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, E2>> {
+        let stream = self.collector.get_event_stream().await?;
+        let f = self.f.clone();
+        let stream = stream.filter_map(f);
+        Ok(Box::pin(stream))
+    }
+}
+
+/// CollectorTryMap is a wrapper around a [Collector](Collector) that maps outgoing
+/// events to a different type with a fallible function. Events that fail to map
+/// are logged and dropped.
+pub struct CollectorTryMap<E, F> {
+    collector: Box<dyn Collector<E>>,
+    f: F,
+}
+
+impl<E, F> CollectorTryMap<E, F> {
+    pub fn new(collector: Box<dyn Collector<E>>, f: F) -> Self {
+        Self { collector, f }
+    }
+}
+
+#[async_trait]
+impl<E1, E2, F> Collector<E2> for CollectorTryMap<E1, F>
+where
+    E1: Send + Sync + 'static,
+    E2: Send + Sync + 'static,
+    F: Fn(E1) -> anyhow::Result<E2> + Send + Sync + Clone + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, E2>> {
+        let stream = self.collector.get_event_stream().await?;
+        let f = self.f.clone();
+        let stream = stream.filter_map(move |event| match f(event) {
+            Ok(event) => Some(event),
+            Err(e) => {
+                tracing::warn!("dropping event that failed to map: {}", e);
+                None
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
+
+/// CollectorAsyncMap is a wrapper around a [Collector](Collector) that maps
+/// outgoing events to a different type with a fallible async function, e.g. to
+/// enrich events with an RPC lookup. Events that fail to map are logged and
+/// dropped. Up to `concurrency` events are mapped at once, and events are
+/// emitted in the order they were collected.
+pub struct CollectorAsyncMap<E, F> {
+    collector: Box<dyn Collector<E>>,
+    f: F,
+    concurrency: usize,
+}
+
+impl<E, F> CollectorAsyncMap<E, F> {
+    pub fn new(collector: Box<dyn Collector<E>>, f: F) -> Self {
+        Self {
+            collector,
+            f,
+            concurrency: 1,
+        }
+    }
+
+    /// Sets the maximum number of events mapped at once. Defaults to 1.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+}
+
+#[async_trait]
+impl<E1, E2, F, Fut> Collector<E2> for CollectorAsyncMap<E1, F>
+where
+    E1: Send + Sync + 'static,
+    E2: Send + Sync + 'static,
+    F: Fn(E1) -> Fut + Send + Sync + Clone + 'static,
+    Fut: Future<Output = anyhow::Result<E2>> + Send + 'static,
+{
+    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, E2>> {
+        let stream = self.collector.get_event_stream().await?;
+        let f = self.f.clone();
+        let mapped = futures::StreamExt::buffered(stream.map(f), self.concurrency);
+        let stream = mapped.filter_map(|event| match event {
+            Ok(event) => Some(event),
+            Err(e) => {
+                tracing::warn!("dropping event that failed to map: {}", e);
+                None
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
+
 /// ExecutorMap is a wrapper around an [Executor](Executor) that maps incoming
 /// actions to a different type.
 pub struct ExecutorMap<A, F> {
@@ -81,7 +447,7 @@ impl<A, F> ExecutorMap<A, F> {
 #[async_trait]
 impl<A1, A2, F> Executor<A1> for ExecutorMap<A2, F>
 where
-    A1: Send + Sync + 'static,
+    A1: Clone + Send + Sync + 'static,
     A2: Send + Sync + 'static,
     F: Fn(A1) -> Option<A2> + Send + Sync + Clone + 'static,
 {
@@ -92,6 +458,86 @@ where
             None => Ok(()),
         }
     }
+
+    async fn execute_with_receipt(&self, action: A1) -> Result<ExecutionReceipt> {
+        let action = (self.f)(action);
+        match action {
+            Some(action) => self.executor.execute_with_receipt(action).await,
+            None => Ok(ExecutionReceipt::Skipped),
+        }
+    }
+
+    fn spent(&self, action: &A1) -> Option<U256> {
+        let action = (self.f)(action.clone())?;
+        self.executor.spent(&action)
+    }
+}
+
+/// FanoutExecutor is a wrapper around several [Executors](Executor) that forwards
+/// each action to all of them concurrently, e.g. to submit the same bundle to
+/// several builders. The action fails if any executor fails, once all of them
+/// have completed. The failure is retryable if every executor's failure is.
+pub struct FanoutExecutor<A> {
+    executors: Vec<Box<dyn Executor<A>>>,
+}
+
+impl<A> FanoutExecutor<A> {
+    pub fn new(executors: Vec<Box<dyn Executor<A>>>) -> Self {
+        Self { executors }
+    }
+}
+
+#[async_trait]
+impl<A> Executor<A> for FanoutExecutor<A>
+where
+    A: Clone + Send + Sync + 'static,
+{
+    async fn execute(&self, action: A) -> Result<()> {
+        self.execute_with_receipt(action).await.map(|_| ())
+    }
+
+    /// Returns the receipt of the first executor.
+    async fn execute_with_receipt(&self, action: A) -> Result<ExecutionReceipt> {
+        let results = futures::future::join_all(
+            self.executors
+                .iter()
+                .map(|executor| executor.execute_with_receipt(action.clone())),
+        )
+        .await;
+
+        let errors: Vec<String> = results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, result)| {
+                let e = result.as_ref().err()?;
+                Some(format!("executor {}: {}", i, e))
+            })
+            .collect();
+        if !errors.is_empty() {
+            let e = anyhow::anyhow!("fan-out execution failed: {}", errors.join("; "));
+            // Worth retrying only if every failure is.
+            let retryable = results
+                .iter()
+                .all(|result| result.as_ref().err().is_none_or(ArtemisError::is_retryable));
+            return Err(if retryable {
+                ArtemisError::Retryable(e)
+            } else {
+                ArtemisError::Other(e)
+            });
+        }
+        results
+            .into_iter()
+            .next()
+            .unwrap_or(Ok(ExecutionReceipt::Skipped))
+    }
+
+    /// Returns the total spent by the executors, since each executes the action.
+    fn spent(&self, action: &A) -> Option<U256> {
+        self.executors
+            .iter()
+            .filter_map(|executor| executor.spent(action))
+            .reduce(|total, spent| total.saturating_add(spent))
+    }
 }
 
 /// Convenience enum containing all the events that can be emitted by collectors.
@@ -1,14 +1,35 @@
 use artemis_core::{
-    collectors::{block_collector::BlockCollector, mempool_collector::MempoolCollector},
-    executors::mempool_executor::{MempoolExecutor, SubmitTxToMempool},
-    types::{Collector, Executor},
+    collectors::{
+        blob_gas_collector::{BlobSchedule, GAS_PER_BLOB},
+        block_collector::{BlockCollector, NewBlock},
+        file_replay_collector::FileReplayCollector,
+        gas_market_collector::next_base_fee,
+        interval_collector::{IntervalCollector, Tick},
+        mempool_collector::MempoolCollector,
+        multi_chain_collector::MultiChainCollector,
+        seaport_order_collector::{OrderParameters, SeaportOrder, SEAPORT_1_5},
+        uniswapx_order_collector::UniswapXOrder,
+    },
+    engine::{
+        BackpressurePolicy, BuildError, ComponentStatus, DryRun, Engine, ExecutorConfig,
+        RecordedEvent, Replay, ReplaySpeed, RestartPolicy, SpendGuard, StrategyConfig,
+    },
+    error::Result,
+    executors::{
+        mempool_executor::{MempoolExecutor, SubmitTxToMempool},
+        rate_limiter::{ExecutorRateLimiter, RateLimited},
+    },
+    state::{MemoryStateStore, PersistentStrategyAdapter},
+    types::{Collector, Executor, PersistentStrategy, Strategy},
 };
+use async_trait::async_trait;
 use ethers::providers::StreamExt;
 use ethers::{
     providers::{Middleware, Provider, Ws},
     types::{BlockNumber, TransactionRequest, U256},
     utils::{Anvil, AnvilInstance},
 };
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::{sync::Arc, time::Duration};
 use tokio::time::sleep;
 
@@ -38,6 +59,35 @@ async fn test_block_collector_sends_blocks() {
     assert_eq!(block_a.hash, block_b.hash.unwrap());
 }
 
+/// Test that interval collector emits ticks at its interval.
+#[tokio::test]
+async fn test_interval_collector_emits_ticks() {
+    let interval_collector = IntervalCollector::new(Duration::from_millis(50));
+    let mut tick_stream = interval_collector.get_event_stream().await.unwrap();
+    let tick_a = tick_stream.next().await.unwrap();
+    let tick_b = tick_stream.next().await.unwrap();
+    assert!(tick_b.0.duration_since(tick_a.0) >= Duration::from_millis(50));
+}
+
+/// Test that the multi-chain collector tags the events of every chain.
+#[tokio::test]
+async fn test_multi_chain_collector_tags_events() {
+    let collector = MultiChainCollector::new()
+        .with_chain(
+            1,
+            Box::new(IntervalCollector::new(Duration::from_millis(50))),
+        )
+        .with_chain(
+            10,
+            Box::new(IntervalCollector::new(Duration::from_millis(50))),
+        );
+    let stream = collector.get_event_stream().await.unwrap();
+    let mut chain_ids: Vec<_> = stream.take(4).map(|event| event.chain_id).collect().await;
+    chain_ids.sort();
+    chain_ids.dedup();
+    assert_eq!(chain_ids, vec![1, 10]);
+}
+
 /// Test that mempool collector correctly emits blocks.
 #[tokio::test]
 async fn test_mempool_collector_sends_txs() {
@@ -85,3 +135,616 @@ async fn test_mempool_executor_sends_tx_simple() {
     let tx = provider.get_transaction_count(account, None).await.unwrap();
     assert_eq!(tx, 1.into());
 }
+
+/// A strategy that never produces actions.
+struct NoopStrategy;
+
+#[async_trait]
+impl Strategy<NewBlock, SubmitTxToMempool> for NoopStrategy {
+    async fn sync_state(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn process_event(&mut self, _event: NewBlock) -> Vec<SubmitTxToMempool> {
+        vec![]
+    }
+}
+
+/// Test that the engine builder rejects misconfigured pipelines.
+#[tokio::test]
+async fn test_engine_builder_validates_pipeline() {
+    let (provider, _anvil) = spawn_anvil().await;
+    let provider = Arc::new(provider);
+
+    let result = Engine::<NewBlock, SubmitTxToMempool>::builder()
+        .with_collector("blocks", Box::new(BlockCollector::new(provider.clone())))
+        .with_strategy("noop", Box::new(NoopStrategy))
+        .build();
+    assert_eq!(result.err(), Some(BuildError::NoExecutors));
+
+    let result = Engine::builder()
+        .with_collector("blocks", Box::new(BlockCollector::new(provider.clone())))
+        .with_strategy("blocks", Box::new(NoopStrategy))
+        .with_executor("mempool", Box::new(MempoolExecutor::new(provider.clone())))
+        .build();
+    assert_eq!(
+        result.err(),
+        Some(BuildError::DuplicateName("blocks".to_string()))
+    );
+
+    let result = Engine::builder()
+        .with_collector("blocks", Box::new(BlockCollector::new(provider.clone())))
+        .with_strategy("noop", Box::new(NoopStrategy))
+        .with_executor_config(
+            "mempool",
+            Box::new(MempoolExecutor::new(provider.clone())),
+            ExecutorConfig::default().with_concurrency(0),
+        )
+        .build();
+    assert_eq!(
+        result.err(),
+        Some(BuildError::ZeroConcurrency("mempool".to_string()))
+    );
+
+    let result = Engine::builder()
+        .with_collector("blocks", Box::new(BlockCollector::new(provider.clone())))
+        .with_sharded_strategy(
+            "noop",
+            0,
+            |_| Box::new(NoopStrategy),
+            StrategyConfig::default(),
+        )
+        .with_executor("mempool", Box::new(MempoolExecutor::new(provider.clone())))
+        .build();
+    assert_eq!(
+        result.err(),
+        Some(BuildError::ZeroShards("noop".to_string()))
+    );
+
+    let result = Engine::builder()
+        .with_collector("blocks", Box::new(BlockCollector::new(provider.clone())))
+        .with_strategy("noop", Box::new(NoopStrategy))
+        .with_executor("mempool", Box::new(MempoolExecutor::new(provider.clone())))
+        .with_strict_ordering()
+        .with_event_channel_policy(BackpressurePolicy::Block)
+        .build();
+    assert_eq!(result.err(), Some(BuildError::BlockingStrictOrdering));
+
+    let result = Engine::builder()
+        .with_collector("blocks", Box::new(BlockCollector::new(provider.clone())))
+        .with_strategy("noop", Box::new(NoopStrategy))
+        .with_executor("mempool", Box::new(MempoolExecutor::new(provider)))
+        .build();
+    assert!(result.is_ok());
+}
+
+/// Test that an engine configured without the builder refuses to run with strict
+/// ordering and the Block event channel policy.
+#[tokio::test]
+async fn test_engine_rejects_blocking_strict_ordering() {
+    let mut engine: Engine<Tick, u64> = Engine::new()
+        .with_strict_ordering()
+        .with_event_channel_policy(BackpressurePolicy::Block);
+    engine.add_collector(Box::new(IntervalCollector::new(Duration::from_millis(10))));
+    engine.add_strategy(Box::new(TickStrategy));
+    engine.add_executor(Box::new(NoopExecutor));
+    let error = engine.run().await.unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<BuildError>(),
+        Some(&BuildError::BlockingStrictOrdering)
+    );
+}
+
+/// An executor that accepts every action.
+struct NoopExecutor;
+
+#[async_trait]
+impl Executor<u64> for NoopExecutor {
+    async fn execute(&self, _action: u64) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that the executor rate limiter rejects submissions over the limit.
+#[tokio::test]
+async fn test_executor_rate_limiter_rejects_excess_submissions() {
+    let limiter = ExecutorRateLimiter::new(NoopExecutor).with_per_second(2);
+    assert!(limiter.execute(1).await.is_ok());
+    assert!(limiter.execute(2).await.is_ok());
+    let err = limiter.execute(3).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<RateLimited>(), Some(&RateLimited));
+
+    let (blocks, block_receiver) = tokio::sync::watch::channel(1u64);
+    let limiter = ExecutorRateLimiter::new(NoopExecutor).with_per_block(1, block_receiver);
+    assert!(limiter.execute(1).await.is_ok());
+    assert!(limiter.execute(2).await.is_err());
+    blocks.send(2).unwrap();
+    assert!(limiter.execute(3).await.is_ok());
+}
+
+/// A strategy that never produces actions from ticks.
+struct TickStrategy;
+
+#[async_trait]
+impl Strategy<Tick, u64> for TickStrategy {
+    async fn sync_state(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn process_event(&mut self, _event: Tick) -> Vec<u64> {
+        vec![]
+    }
+}
+
+/// Test that the engine reports the health of its components.
+#[tokio::test]
+async fn test_engine_reports_health() {
+    let engine = Engine::builder()
+        .with_collector(
+            "ticks",
+            Box::new(IntervalCollector::new(Duration::from_millis(10))),
+        )
+        .with_strategy("noop", Box::new(TickStrategy))
+        .with_executor("noop", Box::new(NoopExecutor))
+        .build()
+        .unwrap();
+    let health = engine.health_handle();
+    let shutdown = engine.shutdown_handle();
+    let mut set = engine.run().await.unwrap();
+
+    sleep(Duration::from_millis(100)).await;
+    let snapshot = health.health();
+    assert!(snapshot.is_healthy());
+    assert_eq!(snapshot.collectors[0].name, "ticks");
+    assert!(snapshot.collectors[0].processed > 0);
+    assert!(snapshot.strategies[0].last_event_at.is_some());
+
+    shutdown.shutdown();
+    while set.join_next().await.is_some() {}
+    let snapshot = health.health();
+    assert!(!snapshot.is_healthy());
+    assert!(snapshot
+        .collectors
+        .iter()
+        .chain(&snapshot.strategies)
+        .chain(&snapshot.executors)
+        .all(|component| component.status == ComponentStatus::Stopped));
+}
+
+/// A strategy that panics on its first tick, and counts the ticks it processed.
+struct PanickingStrategy {
+    ticks: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl Strategy<Tick, u64> for PanickingStrategy {
+    async fn sync_state(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn process_event(&mut self, _event: Tick) -> Vec<u64> {
+        if self.ticks.fetch_add(1, Ordering::SeqCst) == 0 {
+            panic!("first tick");
+        }
+        vec![]
+    }
+}
+
+/// A strategy that panics when it stops.
+struct PanickingShutdownStrategy;
+
+#[async_trait]
+impl Strategy<Tick, u64> for PanickingShutdownStrategy {
+    async fn sync_state(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn process_event(&mut self, _event: Tick) -> Vec<u64> {
+        vec![]
+    }
+
+    async fn on_shutdown(&mut self) {
+        panic!("shutdown");
+    }
+}
+
+/// Test that a panic in the shutdown hook of a strategy is reported, and doesn't
+/// abort the strategy task.
+#[tokio::test]
+async fn test_panicking_shutdown_is_reported() {
+    let engine = Engine::builder()
+        .with_collector(
+            "ticks",
+            Box::new(IntervalCollector::new(Duration::from_millis(10))),
+        )
+        .with_strategy("panicking", Box::new(PanickingShutdownStrategy))
+        .with_executor("noop", Box::new(NoopExecutor))
+        .build()
+        .unwrap();
+    let health = engine.health_handle();
+    let shutdown = engine.shutdown_handle();
+    let mut set = engine.run().await.unwrap();
+
+    sleep(Duration::from_millis(50)).await;
+    shutdown.shutdown();
+    while let Some(joined) = set.join_next().await {
+        assert!(joined.is_ok());
+    }
+    let strategy = &health.health().strategies[0];
+    assert_eq!(strategy.status, ComponentStatus::Stopped);
+    assert_eq!(strategy.errors, 1);
+    assert_eq!(strategy.last_error.as_deref(), Some("shutdown"));
+}
+
+/// Test that a panicking strategy is restarted while the other strategies keep
+/// running.
+#[tokio::test]
+async fn test_panicking_strategy_is_restarted() {
+    let (panicking, steady) = (Arc::new(AtomicU64::new(0)), Arc::new(AtomicU64::new(0)));
+    let engine = Engine::builder()
+        .with_collector(
+            "ticks",
+            Box::new(IntervalCollector::new(Duration::from_millis(10))),
+        )
+        .with_strategy_config(
+            "panicking",
+            Box::new(PanickingStrategy {
+                ticks: panicking.clone(),
+            }),
+            StrategyConfig::default().with_restart_policy(RestartPolicy {
+                initial_backoff: Duration::from_millis(10),
+                ..Default::default()
+            }),
+        )
+        .with_strategy(
+            "steady",
+            Box::new(PanickingStrategy {
+                ticks: steady.clone(),
+            }),
+        )
+        .with_executor("noop", Box::new(NoopExecutor))
+        .build()
+        .unwrap();
+    // The steady strategy starts past the tick it panics on.
+    steady.store(1, Ordering::SeqCst);
+    let health = engine.health_handle();
+    let shutdown = engine.shutdown_handle();
+    let mut set = engine.run().await.unwrap();
+
+    sleep(Duration::from_millis(200)).await;
+    let snapshot = health.health();
+    let panicked = snapshot
+        .strategies
+        .iter()
+        .find(|strategy| strategy.name == "panicking")
+        .unwrap();
+    assert_eq!(panicked.restarts, 1);
+    assert_eq!(panicked.errors, 1);
+    assert_eq!(panicked.status, ComponentStatus::Running);
+    assert!(panicking.load(Ordering::SeqCst) > 2);
+    assert!(steady.load(Ordering::SeqCst) > 2);
+
+    shutdown.shutdown();
+    while set.join_next().await.is_some() {}
+}
+
+/// A strategy counting the ticks it processed.
+#[derive(Default)]
+struct CountingStrategy {
+    synced: bool,
+    ticks: u64,
+}
+
+#[async_trait]
+impl Strategy<Tick, u64> for CountingStrategy {
+    async fn sync_state(&mut self) -> Result<()> {
+        self.synced = true;
+        Ok(())
+    }
+
+    async fn process_event(&mut self, _event: Tick) -> Vec<u64> {
+        self.ticks += 1;
+        vec![]
+    }
+}
+
+#[async_trait]
+impl PersistentStrategy<Tick, u64> for CountingStrategy {
+    type State = u64;
+
+    fn checkpoint(&self) -> u64 {
+        self.ticks
+    }
+
+    async fn restore(&mut self, ticks: u64) -> Result<()> {
+        self.ticks = ticks;
+        Ok(())
+    }
+}
+
+/// Test that a persistent strategy resumes from its checkpoint.
+#[tokio::test]
+async fn test_persistent_strategy_resumes_from_checkpoint() {
+    let store = Arc::new(MemoryStateStore::new());
+    let tick = Tick(std::time::Instant::now());
+
+    let mut strategy: Box<dyn Strategy<Tick, u64>> = Box::new(PersistentStrategyAdapter::new(
+        CountingStrategy::default(),
+        "counter",
+        store.clone(),
+    ));
+    strategy.sync_state().await.unwrap();
+    strategy.process_event(tick).await;
+    strategy.process_event(tick).await;
+    strategy.on_shutdown().await;
+
+    let mut strategy =
+        PersistentStrategyAdapter::new(CountingStrategy::default(), "counter", store);
+    Strategy::<Tick, u64>::sync_state(&mut strategy)
+        .await
+        .unwrap();
+    let restored = strategy.into_inner();
+    assert!(!restored.synced);
+    assert_eq!(restored.ticks, 2);
+}
+
+/// A strategy that emits every event it receives as an action.
+struct EchoStrategy;
+
+#[async_trait]
+impl Strategy<u64, u64> for EchoStrategy {
+    async fn sync_state(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn process_event(&mut self, event: u64) -> Vec<u64> {
+        vec![event]
+    }
+}
+
+/// Test that a replayed event log is delivered in order, and that the engine
+/// stops once it has been replayed.
+#[tokio::test]
+async fn test_engine_replays_recorded_events() {
+    let events = (0..5)
+        .map(|i| RecordedEvent {
+            recorded_at: 1_000 + i * 10,
+            event: i,
+        })
+        .collect();
+    let dry_run = DryRun::new(10);
+    let engine = Engine::builder()
+        .with_replay(Replay::new(events).with_speed(ReplaySpeed::Accelerated(10.0)))
+        .with_strategy("echo", Box::new(EchoStrategy))
+        .with_executor("noop", Box::new(NoopExecutor))
+        .with_dry_run(dry_run.clone())
+        .build()
+        .unwrap();
+    let mut set = engine.run().await.unwrap();
+    while set.join_next().await.is_some() {}
+
+    let actions: Vec<u64> = dry_run.drain().into_iter().map(|c| c.action).collect();
+    assert_eq!(actions, vec![0, 1, 2, 3, 4]);
+}
+
+/// Test that the file replay collector streams a recorded event log in order.
+#[tokio::test]
+async fn test_file_replay_collector_streams_recorded_events() {
+    let path = std::env::temp_dir().join(format!("artemis-replay-{}.jsonl", std::process::id()));
+    let lines: Vec<String> = (0..3)
+        .map(|i| format!(r#"{{"recorded_at":{},"event":{}}}"#, 1_000 + i, i))
+        .collect();
+    std::fs::write(&path, lines.join("\n\n")).unwrap();
+
+    let collector = FileReplayCollector::<u64>::new(&path);
+    let events: Vec<u64> = collector.get_event_stream().await.unwrap().collect().await;
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(events, vec![0, 1, 2]);
+}
+
+/// A strategy that spends 60 wei on every tick.
+struct SpendingStrategy;
+
+#[async_trait]
+impl Strategy<Tick, u64> for SpendingStrategy {
+    async fn sync_state(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn process_event(&mut self, _event: Tick) -> Vec<u64> {
+        vec![60]
+    }
+}
+
+/// An executor that counts its executions, and spends the amount of each action.
+struct SpendingExecutor {
+    executed: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl Executor<u64> for SpendingExecutor {
+    async fn execute(&self, _action: u64) -> Result<()> {
+        self.executed.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn spent(&self, action: &u64) -> Option<U256> {
+        Some(U256::from(*action))
+    }
+}
+
+/// Test that the engine records the spend of executed actions, and halts
+/// execution once the spend guard trips.
+#[tokio::test]
+async fn test_engine_halts_once_spend_guard_trips() {
+    let executed = Arc::new(AtomicU64::new(0));
+    let guard = SpendGuard::new().with_hourly_budget(U256::from(100));
+    let engine = Engine::builder()
+        .with_collector(
+            "ticks",
+            Box::new(IntervalCollector::new(Duration::from_millis(10))),
+        )
+        .with_strategy("spending", Box::new(SpendingStrategy))
+        .with_executor(
+            "spending",
+            Box::new(SpendingExecutor {
+                executed: executed.clone(),
+            }),
+        )
+        .with_spend_guard(guard.clone())
+        .build()
+        .unwrap();
+    let shutdown = engine.shutdown_handle();
+    let mut set = engine.run().await.unwrap();
+
+    sleep(Duration::from_millis(150)).await;
+    shutdown.shutdown();
+    while set.join_next().await.is_some() {}
+    assert!(guard.is_tripped());
+    assert_eq!(executed.load(Ordering::SeqCst), 2);
+    assert_eq!(guard.spent(Duration::from_secs(60)), U256::from(120));
+}
+
+/// Test that the spend guard trips once a budget is exceeded, until resumed.
+#[tokio::test]
+async fn test_spend_guard_trips_over_budget() {
+    let guard = SpendGuard::new().with_hourly_budget(U256::from(100));
+    guard.record(U256::from(60));
+    assert!(!guard.is_tripped());
+    guard.record(U256::from(60));
+    assert!(guard.is_tripped());
+    assert_eq!(guard.spent(Duration::from_secs(60)), U256::from(120));
+
+    guard.resume();
+    assert!(!guard.is_tripped());
+    assert_eq!(guard.spent(Duration::from_secs(60)), U256::zero());
+}
+
+/// Test that the gas market helpers follow EIP-1559 and EIP-4844.
+#[test]
+fn test_gas_market_fees() {
+    let base_fee = U256::from(100_000_000_000u64);
+    let gas_limit = U256::from(30_000_000);
+    assert_eq!(
+        next_base_fee(base_fee, gas_limit, gas_limit),
+        U256::from(112_500_000_000u64)
+    );
+    assert_eq!(
+        next_base_fee(base_fee, U256::zero(), gas_limit),
+        U256::from(87_500_000_000u64)
+    );
+    assert_eq!(next_base_fee(base_fee, gas_limit / 2, gas_limit), base_fee);
+    assert_eq!(
+        BlobSchedule::CANCUN.blob_base_fee(U256::zero()),
+        U256::one()
+    );
+}
+
+/// Test that the blob schedule tracks the excess blob gas around its target.
+#[test]
+fn test_blob_schedule() {
+    let schedule = BlobSchedule::PRAGUE;
+    let target = U256::from(schedule.target_blobs * GAS_PER_BLOB);
+    let blob = U256::from(GAS_PER_BLOB);
+    assert_eq!(
+        schedule.next_excess_blob_gas(U256::zero(), target),
+        U256::zero()
+    );
+    assert_eq!(
+        schedule.next_excess_blob_gas(U256::zero(), target + blob),
+        blob
+    );
+    assert_eq!(
+        schedule.next_excess_blob_gas(blob, U256::zero()),
+        U256::zero()
+    );
+    assert_eq!(schedule.blob_base_fee(U256::zero()), U256::one());
+    assert!(schedule.blob_base_fee(target * 10) > U256::one());
+}
+
+/// Test that Seaport orders are only valid while active and signed for Seaport.
+#[test]
+fn test_seaport_order_validation() {
+    let parameters: OrderParameters = serde_json::from_value(serde_json::json!({
+        "offerer": "0x0000000000000000000000000000000000000001",
+        "zone": "0x0000000000000000000000000000000000000000",
+        "offer": [{
+            "itemType": 2,
+            "token": "0x0000000000000000000000000000000000000002",
+            "identifierOrCriteria": "42",
+            "startAmount": "1",
+            "endAmount": "1",
+        }],
+        "consideration": [{
+            "itemType": 0,
+            "token": "0x0000000000000000000000000000000000000000",
+            "identifierOrCriteria": "0",
+            "startAmount": "975000000000000000",
+            "endAmount": "975000000000000000",
+            "recipient": "0x0000000000000000000000000000000000000001",
+        }, {
+            "itemType": 0,
+            "token": "0x0000000000000000000000000000000000000000",
+            "identifierOrCriteria": "0",
+            "startAmount": "25000000000000000",
+            "endAmount": "25000000000000000",
+            "recipient": "0x0000a26b00c1F0DF003000390027140000fAa719",
+        }],
+        "orderType": 0,
+        "startTime": "1000",
+        "endTime": "2000",
+        "zoneHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        "salt": "0x1",
+        "conduitKey": "0x0000007b02230091a7ed01230072f7006a004d60a8d4e71d599b8104250f0000",
+        "totalOriginalConsiderationItems": 2,
+        "counter": 0,
+    }))
+    .unwrap();
+    let seaport = vec![SEAPORT_1_5.parse().unwrap()];
+    let order = SeaportOrder {
+        order_hash: Default::default(),
+        collection: "example".to_string(),
+        protocol_address: seaport[0],
+        parameters,
+        signature: vec![1u8; 65].into(),
+    };
+
+    assert_eq!(order.native_price(), U256::exp10(18));
+    assert_eq!(order.validate(&seaport, 1500), Ok(()));
+    assert!(order.validate(&seaport, 2000).is_err());
+    assert!(order.validate(&[], 1500).is_err());
+}
+
+/// Test that UniswapX order amounts decay linearly over the decay period.
+#[test]
+fn test_uniswapx_order_decay() {
+    let order: UniswapXOrder = serde_json::from_value(serde_json::json!({
+        "orderHash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "chainId": 1,
+        "orderType": "Dutch_V2",
+        "swapper": "0x0000000000000000000000000000000000000001",
+        "reactor": "0x00000011F84B9aa48e5f8aA8B9897600006289Be",
+        "encodedOrder": "0x",
+        "signature": "0x",
+        "decayStartTime": 1000,
+        "decayEndTime": 1100,
+        "deadline": 1200,
+        "input": {
+            "token": "0x0000000000000000000000000000000000000002",
+            "startAmount": "1000",
+            "endAmount": "1000",
+        },
+        "outputs": [{
+            "token": "0x0000000000000000000000000000000000000003",
+            "startAmount": "2000",
+            "endAmount": "1000",
+            "recipient": "0x0000000000000000000000000000000000000001",
+        }],
+    }))
+    .unwrap();
+
+    assert_eq!(order.input_amount(1050), U256::from(1000));
+    assert_eq!(order.output_amounts(900), vec![U256::from(2000)]);
+    assert_eq!(order.output_amounts(1025), vec![U256::from(1750)]);
+    assert_eq!(order.output_amounts(1100), vec![U256::from(1000)]);
+}
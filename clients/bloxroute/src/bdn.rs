@@ -0,0 +1,588 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use ethers::types::{Address, Transaction, H256, U256, U64};
+use futures::{SinkExt, StreamExt};
+use metrics::{counter, histogram};
+use serde::Deserialize;
+use serde_json::json;
+use thiserror::Error;
+use tokio::{net::TcpStream, sync::mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+use tracing::{error, warn};
+
+use artemis_core::error;
+use artemis_core::types::{Collector, CollectorStream};
+
+const BLOXROUTE_DEFAULT_URL: &str = "wss://api.blxrbdn.com/ws";
+
+/// Number of events buffered between the bloXroute subscription task and the collector stream.
+const EVENT_BUFFER_SIZE: usize = 512;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Possible events emitted by the bloXroute collector.
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+#[allow(missing_docs)]
+pub enum Event {
+    Transaction(Transaction),
+    Block(BdnBlock),
+}
+
+/// A block propagated through the BDN, as emitted by the `bdnBlocks` stream.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BdnBlock {
+    /// The block hash
+    pub hash: H256,
+    /// The block header
+    pub header: BdnBlockHeader,
+    /// The transactions included in the block
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+/// The header of a [BdnBlock].
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BdnBlockHeader {
+    /// The hash of the parent block
+    pub parent_hash: H256,
+    /// The fee recipient of the block
+    pub miner: Address,
+    /// The block number
+    pub number: U64,
+    /// The gas limit of the block
+    pub gas_limit: U256,
+    /// The gas used by the block
+    pub gas_used: U256,
+    /// The block timestamp, in seconds
+    pub timestamp: U256,
+    /// The base fee of the block, if any
+    #[serde(default)]
+    pub base_fee_per_gas: Option<U256>,
+}
+
+/// bloXroute collector stream type, used to specify which stream to subscribe to.
+#[derive(Debug, Clone)]
+pub enum StreamType {
+    /// Subscribe to new pending transactions as seen by the BDN (`newTxs`).
+    Transactions,
+    /// Subscribe to new blocks with their transactions, as propagated by the BDN
+    /// (`bdnBlocks`).
+    Blocks,
+    /// Subscribe to both new pending transactions and new blocks, multiplexed over
+    /// a single connection into one stream.
+    All,
+}
+
+impl StreamType {
+    /// Returns the subscriptions to open, along with the fields they include.
+    fn subscriptions(&self) -> Vec<(&'static str, &'static [&'static str])> {
+        const TXS: (&str, &[&str]) = ("newTxs", &["tx_hash", "tx_contents"]);
+        const BLOCKS: (&str, &[&str]) = ("bdnBlocks", &["hash", "header", "transactions"]);
+        match self {
+            StreamType::Transactions => vec![TXS],
+            StreamType::Blocks => vec![BLOCKS],
+            StreamType::All => vec![TXS, BLOCKS],
+        }
+    }
+}
+
+/// Errors returned by the [BloxrouteCollector].
+#[derive(Debug, Error)]
+pub enum BloxrouteError {
+    /// Thrown when the collector fails to connect or subscribe to a bloXroute endpoint.
+    #[error("failed to connect to bloXroute at {endpoint}: {reason}")]
+    Connection {
+        /// The endpoint that could not be reached
+        endpoint: String,
+        /// The underlying connection error
+        reason: String,
+    },
+    /// Thrown when the collector is configured without any bloXroute endpoint.
+    #[error("no bloXroute endpoints configured")]
+    NoEndpoints,
+    /// Thrown when the collector is built without an authorization header.
+    #[error("missing bloXroute authorization header")]
+    MissingAuthHeader,
+}
+
+/// Connects to the given endpoint and opens the subscriptions of the stream type.
+async fn connect(
+    endpoint: &str,
+    auth_header: &str,
+    ty: &StreamType,
+) -> Result<Socket, BloxrouteError> {
+    let reason = |e: &dyn std::fmt::Display| BloxrouteError::Connection {
+        endpoint: endpoint.to_string(),
+        reason: e.to_string(),
+    };
+
+    let mut request = endpoint.into_client_request().map_err(|e| reason(&e))?;
+    let auth = HeaderValue::from_str(auth_header).map_err(|e| reason(&e))?;
+    request.headers_mut().insert("Authorization", auth);
+    let (mut socket, _) = connect_async(request).await.map_err(|e| reason(&e))?;
+
+    for (id, (stream, include)) in ty.subscriptions().into_iter().enumerate() {
+        let subscribe = json!({
+            "jsonrpc": "2.0",
+            "id": id + 1,
+            "method": "subscribe",
+            "params": [stream, { "include": include }],
+        });
+        socket
+            .send(Message::Text(subscribe.to_string()))
+            .await
+            .map_err(|e| reason(&e))?;
+    }
+    Ok(socket)
+}
+
+/// Connects to the first reachable endpoint, trying them in order starting at `start`
+/// and wrapping around. Returns the index of the connected endpoint along with the socket.
+async fn connect_any(
+    endpoints: &[String],
+    start: usize,
+    auth_header: &str,
+    ty: &StreamType,
+) -> Result<(usize, Socket), BloxrouteError> {
+    let mut last_err = BloxrouteError::NoEndpoints;
+    for offset in 0..endpoints.len() {
+        let index = (start + offset) % endpoints.len();
+        match connect(&endpoints[index], auth_header, ty).await {
+            Ok(socket) => return Ok((index, socket)),
+            Err(e) => {
+                warn!("{}", e);
+                last_err = e;
+            }
+        }
+    }
+    Err(last_err)
+}
+
+/// Reconnection policy applied by the [BloxrouteCollector] when its subscription ends.
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// Delay before the first reconnection attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between two reconnection attempts.
+    pub max_backoff: Duration,
+    /// Maximum number of consecutive reconnection attempts before the stream
+    /// is terminated. `None` retries forever.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// Returns the delay to wait before the given (zero-indexed) reconnection attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// A bloXroute collector that subscribes to the specified BDN streams over the
+/// websocket API.
+pub struct BloxrouteCollector {
+    /// The subscribed socket, if already connected. Collectors created through the
+    /// [BloxrouteCollectorBuilder] connect lazily, once the event stream is requested.
+    socket: Mutex<Option<Socket>>,
+    /// The ordered list of bloXroute endpoints to connect to
+    endpoints: Vec<String>,
+    /// The index of the endpoint the socket is connected to
+    active: usize,
+    /// The authorization header of the bloXroute account
+    auth_header: String,
+    /// The type of stream to subscribe to
+    ty: StreamType,
+    /// The reconnection policy used when the subscription ends
+    reconnect: ReconnectConfig,
+}
+
+impl BloxrouteCollector {
+    /// Initialize a new bloXroute collector.
+    ///
+    /// ## Arguments
+    /// - `auth_header`: The authorization header of the bloXroute account
+    /// - `ty`: The type of stream to subscribe to
+    ///
+    /// Returns a [BloxrouteError] if the initial connection to bloXroute fails.
+    pub async fn new(auth_header: String, ty: StreamType) -> Result<Self, BloxrouteError> {
+        Self::with_endpoints(auth_header, vec![BLOXROUTE_DEFAULT_URL.to_string()], ty).await
+    }
+
+    /// Initialize a new bloXroute collector with an ordered list of endpoints, e.g.
+    /// regional cloud API endpoints or a local gateway.
+    ///
+    /// The collector connects to the first reachable endpoint. Whenever the active
+    /// connection drops, it transparently fails over to the next endpoint in the list,
+    /// wrapping around once the end of the list is reached.
+    ///
+    /// Returns a [BloxrouteError] if none of the endpoints can be reached.
+    pub async fn with_endpoints(
+        auth_header: String,
+        endpoints: Vec<String>,
+        ty: StreamType,
+    ) -> Result<Self, BloxrouteError> {
+        let (active, socket) = connect_any(&endpoints, 0, &auth_header, &ty).await?;
+
+        Ok(Self {
+            socket: Mutex::new(Some(socket)),
+            endpoints,
+            active,
+            auth_header,
+            ty,
+            reconnect: ReconnectConfig::default(),
+        })
+    }
+
+    /// Returns a [BloxrouteCollectorBuilder] to configure a lazily-connected collector.
+    pub fn builder() -> BloxrouteCollectorBuilder {
+        BloxrouteCollectorBuilder::default()
+    }
+
+    /// Optionally set the reconnection policy, overriding the default
+    /// (exponential backoff from 100ms up to 30s, retrying forever).
+    pub fn set_reconnect_config(&mut self, reconnect: ReconnectConfig) {
+        self.reconnect = reconnect;
+    }
+
+    /// Get the event stream for the specified stream type.
+    ///
+    /// The subscription runs in a background task: whenever the websocket closes,
+    /// the task fails over to the next configured endpoint with exponential backoff
+    /// and resumes emitting events. The returned stream only terminates once the
+    /// configured maximum number of consecutive reconnection attempts is exhausted.
+    pub async fn get_event_stream(&self) -> error::Result<CollectorStream<'_, Event>> {
+        let (tx, rx) = mpsc::channel(EVENT_BUFFER_SIZE);
+
+        let mut socket = self.socket.lock().unwrap().take();
+        let endpoints = self.endpoints.clone();
+        let mut active = self.active;
+        let auth_header = self.auth_header.clone();
+        let ty = self.ty.clone();
+        let reconnect = self.reconnect.clone();
+
+        tokio::spawn(async move {
+            let mut attempt = 0;
+            // Lazily-connected collectors start from the first endpoint, and the ones
+            // that were already connected fail over to the next endpoint.
+            let mut next = active;
+
+            loop {
+                let current = match socket.take() {
+                    Some(socket) => socket,
+                    None => match connect_with_backoff(
+                        &endpoints,
+                        next,
+                        &auth_header,
+                        &ty,
+                        &reconnect,
+                        &mut attempt,
+                    )
+                    .await
+                    {
+                        Some((index, socket)) => {
+                            if index != active {
+                                warn!(
+                                    "Failed over from bloXroute endpoint {} to {}",
+                                    endpoints[active], endpoints[index]
+                                );
+                            }
+                            active = index;
+                            socket
+                        }
+                        None => {
+                            error!("bloXroute reconnection attempts exhausted, terminating stream");
+                            return;
+                        }
+                    },
+                };
+
+                match forward_events(current, &tx).await {
+                    Forwarded::ReceiverDropped => return,
+                    Forwarded::StreamEnded { received } => {
+                        if received {
+                            attempt = 0;
+                        }
+                        warn!(
+                            "bloXroute {:?} stream from {} ended, reconnecting",
+                            ty, endpoints[active]
+                        );
+                        next = (active + 1) % endpoints.len();
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+}
+
+/// Connects to one of the endpoints, starting at `start`, retrying with exponential
+/// backoff. The first attempt after a healthy stream (`attempt == 0`) is immediate.
+///
+/// Returns `None` once the maximum number of consecutive attempts is exhausted.
+async fn connect_with_backoff(
+    endpoints: &[String],
+    start: usize,
+    auth_header: &str,
+    ty: &StreamType,
+    reconnect: &ReconnectConfig,
+    attempt: &mut u32,
+) -> Option<(usize, Socket)> {
+    loop {
+        if reconnect.max_retries.is_some_and(|max| *attempt >= max) {
+            return None;
+        }
+
+        if *attempt > 0 {
+            tokio::time::sleep(reconnect.backoff(*attempt - 1)).await;
+        }
+        *attempt += 1;
+
+        match connect_any(endpoints, start, auth_header, ty).await {
+            Ok(connected) => return Some(connected),
+            Err(e) => warn!("Failed to connect (attempt {}): {}", attempt, e),
+        }
+    }
+}
+
+/// A builder for a lazily-connected [BloxrouteCollector].
+///
+/// No connection is made until the engine requests the event stream, so the
+/// collector can be fully configured in synchronous wiring code.
+///
+/// ```ignore
+/// let collector = BloxrouteCollector::builder()
+///     .auth_header(auth_header)
+///     .endpoint("wss://virginia.eth.blxrbdn.com/ws")
+///     .endpoint("wss://uk.eth.blxrbdn.com/ws")
+///     .stream_type(StreamType::All)
+///     .build()?;
+/// ```
+#[derive(Debug, Default)]
+pub struct BloxrouteCollectorBuilder {
+    auth_header: Option<String>,
+    endpoints: Vec<String>,
+    ty: Option<StreamType>,
+    reconnect: ReconnectConfig,
+}
+
+impl BloxrouteCollectorBuilder {
+    /// Set the authorization header of the bloXroute account (required).
+    pub fn auth_header(mut self, auth_header: impl Into<String>) -> Self {
+        self.auth_header = Some(auth_header.into());
+        self
+    }
+
+    /// Add a bloXroute endpoint. Endpoints are tried in the order they are added.
+    /// Defaults to the public cloud API endpoint if none is set.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoints.push(endpoint.into());
+        self
+    }
+
+    /// Set the type of stream to subscribe to. Defaults to [StreamType::Transactions].
+    pub fn stream_type(mut self, ty: StreamType) -> Self {
+        self.ty = Some(ty);
+        self
+    }
+
+    /// Set the reconnection policy.
+    pub fn reconnect_config(mut self, reconnect: ReconnectConfig) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// Validate the configuration and build the collector, without connecting.
+    pub fn build(self) -> Result<BloxrouteCollector, BloxrouteError> {
+        let auth_header = self.auth_header.ok_or(BloxrouteError::MissingAuthHeader)?;
+
+        let endpoints = if self.endpoints.is_empty() {
+            vec![BLOXROUTE_DEFAULT_URL.to_string()]
+        } else {
+            self.endpoints
+        };
+
+        Ok(BloxrouteCollector {
+            socket: Mutex::new(None),
+            endpoints,
+            active: 0,
+            auth_header,
+            ty: self.ty.unwrap_or(StreamType::Transactions),
+            reconnect: self.reconnect,
+        })
+    }
+}
+
+/// A message received from bloXroute: either a subscription notification, the
+/// response to a subscription request, or an error.
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    params: Option<Params>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Params {
+    result: Notification,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+enum Notification {
+    Transaction {
+        #[serde(rename = "txContents")]
+        tx_contents: Transaction,
+    },
+    Block(BdnBlock),
+}
+
+impl From<Notification> for Event {
+    fn from(notification: Notification) -> Self {
+        match notification {
+            Notification::Transaction { tx_contents } => Event::Transaction(tx_contents),
+            Notification::Block(block) => Event::Block(block),
+        }
+    }
+}
+
+/// Outcome of forwarding a single bloXroute subscription into the collector channel.
+enum Forwarded {
+    /// The websocket closed. `received` is set if at least one event was forwarded.
+    StreamEnded { received: bool },
+    /// The collector stream was dropped, so there is no one left to forward to.
+    ReceiverDropped,
+}
+
+/// Forwards every notification received on the socket into `tx` until either side
+/// of the pipe is closed, or bloXroute rejects a subscription.
+async fn forward_events(mut socket: Socket, tx: &mpsc::Sender<Event>) -> Forwarded {
+    let mut received = false;
+    while let Some(message) = socket.next().await {
+        let text = match message {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(e) => {
+                warn!("bloXroute websocket error: {}", e);
+                break;
+            }
+        };
+        let event: Event = match serde_json::from_str::<Response>(&text) {
+            Ok(Response {
+                params: Some(params),
+                ..
+            }) => params.result.into(),
+            Ok(Response { error: Some(e), .. }) => {
+                error!("bloXroute subscription error: {}", e);
+                break;
+            }
+            // The confirmation of a subscription request.
+            Ok(_) => continue,
+            Err(e) => {
+                warn!("Failed to decode bloXroute message: {}", e);
+                continue;
+            }
+        };
+
+        record_metrics(&event);
+        if tx.send(event).await.is_err() {
+            return Forwarded::ReceiverDropped;
+        }
+        received = true;
+    }
+    Forwarded::StreamEnded { received }
+}
+
+impl Event {
+    /// Returns a short, static name for the event kind, used as a metric label.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Event::Transaction(_) => "transaction",
+            Event::Block(_) => "block",
+        }
+    }
+}
+
+/// Records per-event metrics through the [metrics] facade.
+///
+/// For blocks, the propagation latency is measured as the delta between the block
+/// timestamp and the local receipt time. Pending transactions are only counted.
+fn record_metrics(event: &Event) {
+    let kind = event.kind();
+    counter!("bloxroute_events_received_total", "event" => kind).increment(1);
+
+    let Event::Block(block) = event else {
+        return;
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let latency = now.saturating_sub(Duration::from_secs(block.header.timestamp.low_u64()));
+    histogram!("bloxroute_propagation_latency_seconds", "event" => kind)
+        .record(latency.as_secs_f64());
+}
+
+#[async_trait]
+impl Collector<Event> for BloxrouteCollector {
+    async fn get_event_stream<'a>(&'a self) -> error::Result<CollectorStream<'a, Event>> {
+        self.get_event_stream().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, Response};
+
+    #[test]
+    fn test_decode_notifications() {
+        let tx = r#"{"jsonrpc":"2.0","id":null,"method":"subscribe","params":{"subscription":"a1","result":{
+            "txHash":"0x6a4c3b9b2ac3a4cf5c5e8f25c85b2bd9739c0871b4f7d2b2d0b3e3a7c0b0c1d2",
+            "txContents":{
+                "hash":"0x6a4c3b9b2ac3a4cf5c5e8f25c85b2bd9739c0871b4f7d2b2d0b3e3a7c0b0c1d2",
+                "from":"0x8ba1f109551bd432803012645ac136ddd64dba72","to":null,"nonce":"0x1",
+                "gas":"0x5208","gasPrice":"0x3b9aca00","value":"0x0","input":"0x",
+                "v":"0x1b","r":"0x1","s":"0x1"}}}}"#;
+        let block = r#"{"jsonrpc":"2.0","id":null,"method":"subscribe","params":{"subscription":"b2","result":{
+            "hash":"0x1d59ff54b1eb26b013ce3cb5fc9dab3705b415a67127a003c3e61eb445bb8df2",
+            "header":{
+                "parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000",
+                "miner":"0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5","number":"0x1312d00",
+                "gasLimit":"0x1c9c380","gasUsed":"0x0","timestamp":"0x6553f100",
+                "baseFeePerGas":"0x3b9aca00"},
+            "transactions":[]}}}"#;
+
+        let response: Response = serde_json::from_str(tx).unwrap();
+        let event: Event = response.params.unwrap().result.into();
+        assert!(matches!(event, Event::Transaction(tx) if tx.to.is_none()));
+
+        let response: Response = serde_json::from_str(block).unwrap();
+        let event: Event = response.params.unwrap().result.into();
+        assert!(matches!(event, Event::Block(block) if block.header.number.as_u64() == 20_000_000));
+    }
+}
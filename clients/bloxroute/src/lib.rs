@@ -0,0 +1,27 @@
+#![warn(missing_docs, unreachable_pub)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! # bloXroute Artemis
+//!
+//! This crate gives you access to the [bloXroute][bloxroute] Blockchain Distribution Network
+//! (BDN) from the [Artemis][artemis] framework, so its orderflow can be compared or combined
+//! with other sources in the same engine.
+//!
+//! - BDN Collector: a `newTxs` and `bdnBlocks` stream over the bloXroute websocket API,
+//!   following the API of the Fiber collector of the `chainbound-artemis` crate.
+//!
+//! Please refer to the crate README file for an example on how to use these components.
+//!
+//! [bloxroute]: https://bloxroute.com/
+//! [artemis]: https://github.com/paradigmxyz/artemis
+
+/// bloXroute BDN client module
+pub mod bdn;
+pub use bdn::{
+    BdnBlock, BdnBlockHeader, BloxrouteCollector, BloxrouteCollectorBuilder, BloxrouteError, Event,
+    ReconnectConfig, StreamType,
+};
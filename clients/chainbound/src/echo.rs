@@ -1,6 +1,6 @@
 use std::{sync::Arc, time::Duration};
 
-use anyhow::{anyhow, Result};
+use anyhow::anyhow;
 use async_trait::async_trait;
 use ethers::{providers::Middleware, signers::Signer};
 use reqwest::{
@@ -9,16 +9,27 @@ use reqwest::{
 };
 use tracing::{debug, error};
 
+use alloy::primitives::Bytes;
+use alloy_consensus::TxEnvelope;
+
+use artemis_core::error::{ArtemisError, Result, ResultExt};
 use artemis_core::types::Executor;
 
 use crate::SendBundleArgs;
 
-/// Possible actions that can be executed by the Echo executor
+/// Possible actions that can be executed by the Echo and Fiber executors
 #[derive(Debug, Clone)]
 #[allow(clippy::large_enum_variant)]
-#[allow(missing_docs)]
 pub enum Action {
+    /// Send a bundle to block builders through Echo.
     SendBundle(SendBundleArgs),
+    /// Send a signed transaction through Fiber.
+    SendTransaction(TxEnvelope),
+    /// Send a signed, RLP-encoded transaction through Fiber.
+    SendRawTransaction(Bytes),
+    /// Send a sequence of signed, RLP-encoded transactions through Fiber, which are
+    /// propagated atomically in the given order (e.g. a target tx followed by a backrun).
+    SendRawTransactionSequence(Vec<Bytes>),
 }
 
 const ECHO_RPC_URL: &str = "https://echo-rpc.chainbound.io";
@@ -86,22 +97,26 @@ where
     /// Send a bundle to transactions to the specified builders
     async fn execute(&self, mut action: SendBundleArgs) -> Result<()> {
         if action.unsigned_txs.is_empty() {
-            return Err(anyhow!(
+            return Err(ArtemisError::fatal(anyhow!(
                 "Bundle must contain at least one transaction. 
                 To cancel a bundle, use the `eth_cancelBundle` method."
-            ));
+            )));
         }
 
         // Sign each transaction in bundle
         for tx in action.unsigned_txs.iter() {
-            let signature = self.tx_signer.sign_transaction(&tx.clone().into()).await?;
+            let signature = self
+                .tx_signer
+                .sign_transaction(&tx.clone().into())
+                .await
+                .fatal()?;
             let signed = tx.rlp_signed(&signature).to_string();
             action.standard_features.txs.push(signed);
         }
 
         // Set block number to the next block if not specified
         if action.standard_features.block_number.is_none() {
-            let block_number = self.inner.get_block_number().await?;
+            let block_number = self.inner.get_block_number().await.retryable()?;
             let next_block_number_hex = format!("0x{:#x}", block_number.as_u64() + 1);
             action.standard_features.block_number = Some(next_block_number_hex);
         }
@@ -109,15 +124,21 @@ where
         // TODO: Simulate bundle
 
         // Sign bundle payload (without the Echo-specific features)
-        let signable_payload = serde_json::to_string(&action.standard_features)?;
-        let flashbots_signature = self.auth_signer.sign_message(&signable_payload).await?;
+        let signable_payload = serde_json::to_string(&action.standard_features).fatal()?;
+        let flashbots_signature = self
+            .auth_signer
+            .sign_message(&signable_payload)
+            .await
+            .fatal()?;
 
         // Create the `X-Flashbots-Signature` header
         let flashbots_signature_header: HeaderValue =
-            format!("{:#x}:{}", self.auth_signer.address(), flashbots_signature).parse()?;
+            format!("{:#x}:{}", self.auth_signer.address(), flashbots_signature)
+                .parse()
+                .fatal()?;
 
         // Prepare the full JSON-RPC request body
-        let bundle_json = serde_json::to_string(&action)?;
+        let bundle_json = serde_json::to_string(&action).fatal()?;
 
         let request_body = format!(
             r#"{{"id":1,"jsonrpc":"2.0","method":"eth_sendBundle","params":[{}]}}"#,
@@ -136,10 +157,7 @@ where
         match echo_response {
             Ok(send_response) => {
                 let status = send_response.status();
-                let body = send_response.text().await?;
-
-                dbg!(body.clone());
-
+                let body = send_response.text().await.retryable()?;
                 if status.is_success() {
                     debug!("Echo bundle response: {:?}", body);
                 } else {
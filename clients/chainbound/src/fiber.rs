@@ -1,15 +1,33 @@
-use anyhow::Result;
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::{Bytes, B256};
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use fiber::Client;
-use futures::StreamExt;
+use futures::{future, stream, StreamExt};
+use metrics::{counter, histogram};
+use thiserror::Error;
+use tokio::sync::{mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, error, info, warn};
 
 // Use the actual types returned by fiber streams
-use alloy_consensus::{Block, TxEnvelope};
+use alloy_consensus::{Block, Header, Transaction, TxEnvelope};
+use fiber::ethereum_consensus::types::mainnet::SignedBeaconBlock;
+
+use artemis_core::error::{self, ArtemisError};
+use artemis_core::types::{Collector, CollectorStream, Executor};
 
-use artemis_core::types::{Collector, CollectorStream};
+use crate::{Action, TxFilter};
 
 const FIBER_DEFAULT_URL: &str = "beta.fiberapi.io:8080";
 
+/// Number of events buffered between the Fiber subscription task and the collector stream.
+const EVENT_BUFFER_SIZE: usize = 512;
+
 /// Possible events emitted by the Fiber collector.
 #[derive(Clone, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -17,24 +35,172 @@ const FIBER_DEFAULT_URL: &str = "beta.fiberapi.io:8080";
 pub enum Event {
     Transaction(TxEnvelope),
     ExecutionPayload(Block<TxEnvelope>),
+    Header(Header),
+    BeaconBlock(SignedBeaconBlock),
+    BlobTransaction(BlobTransaction),
+}
+
+/// A blob-carrying (EIP-4844) transaction, along with the versioned hashes of its blobs.
+#[derive(Clone, Debug)]
+pub struct BlobTransaction {
+    /// The signed transaction envelope
+    pub tx: TxEnvelope,
+    /// The versioned hashes of the blobs carried by the transaction
+    pub versioned_hashes: Vec<B256>,
+}
+
+impl BlobTransaction {
+    /// Returns the blob transaction contained in the envelope, or `None` if the
+    /// envelope is not an EIP-4844 transaction.
+    pub fn from_envelope(tx: TxEnvelope) -> Option<Self> {
+        let versioned_hashes = tx.blob_versioned_hashes()?.to_vec();
+        Some(Self {
+            tx,
+            versioned_hashes,
+        })
+    }
 }
 
 /// Fiber collector stream type, used to specify which stream to subscribe to.
+#[derive(Debug, Clone)]
 pub enum StreamType {
     /// Subscribe to new pending transactions as seen by the Fiber network.
     Transactions,
+    /// Subscribe to new pending transactions matching the given [TxFilter].
+    FilteredTransactions(TxFilter),
     /// Subscribe to new execution payloads (blocks with full transaction data).
     ExecutionPayloads,
+    /// Subscribe to new execution headers (blocks without the transactions).
+    /// This is much lighter than [StreamType::ExecutionPayloads] for strategies
+    /// that only need block timing.
+    Headers,
+    /// Subscribe to new beacon blocks (consensus-layer blocks).
+    BeaconBlocks,
+    /// Subscribe to new pending blob-carrying (EIP-4844) transactions. These are
+    /// selected from the full transaction stream, so every other transaction type is
+    /// dropped before reaching the engine.
+    BlobTransactions,
+    /// Subscribe to both new pending transactions and new execution payloads,
+    /// multiplexed over a single client into one stream. Events from each
+    /// subscription are emitted in the order they are received.
+    All,
+}
+
+/// Errors returned by the [FiberCollector].
+#[derive(Debug, Error)]
+pub enum FiberError {
+    /// Thrown when the client fails to connect to a Fiber endpoint.
+    #[error("failed to connect to Fiber at {endpoint}: {reason}")]
+    Connection {
+        /// The endpoint that could not be reached
+        endpoint: String,
+        /// The underlying connection error
+        reason: String,
+    },
+    /// Thrown when the collector is configured without any Fiber endpoint.
+    #[error("no Fiber endpoints configured")]
+    NoEndpoints,
+    /// Thrown when the collector is built without an API key.
+    #[error("missing Fiber API key")]
+    MissingApiKey,
+    /// Thrown when the collector is built with an invalid configuration.
+    #[error("invalid Fiber collector configuration: {0}")]
+    InvalidConfig(String),
+}
+
+/// Connects a new Fiber client to the given endpoint.
+async fn connect(endpoint: &str, api_key: &str) -> Result<Client, FiberError> {
+    Client::connect(endpoint, api_key)
+        .await
+        .map_err(|e| FiberError::Connection {
+            endpoint: endpoint.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Connects to the first reachable endpoint, trying them in order starting at `start`
+/// and wrapping around. Returns the index of the connected endpoint along with the client.
+async fn connect_any(
+    endpoints: &[String],
+    start: usize,
+    api_key: &str,
+) -> Result<(usize, Client), FiberError> {
+    let mut last_err = FiberError::NoEndpoints;
+    for offset in 0..endpoints.len() {
+        let index = (start + offset) % endpoints.len();
+        match connect(&endpoints[index], api_key).await {
+            Ok(client) => return Ok((index, client)),
+            Err(e) => {
+                warn!("{}", e);
+                last_err = e;
+            }
+        }
+    }
+    Err(last_err)
+}
+
+/// Reconnection policy applied by the [FiberCollector] when its subscription ends.
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// Delay before the first reconnection attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between two reconnection attempts.
+    pub max_backoff: Duration,
+    /// Maximum number of consecutive reconnection attempts before the stream
+    /// is terminated. `None` retries forever.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// Returns the delay to wait before the given (zero-indexed) reconnection attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
 }
 
 /// A Fiber collector that subscribes to the specified stream type.
 pub struct FiberCollector {
-    /// The Fiber-rs client
-    client: Client,
-    /// The Fiber API key
-    api_key: String,
+    /// The Fiber-rs client, if already connected. Collectors created through the
+    /// [FiberCollectorBuilder] connect lazily, once the event stream is requested.
+    client: Option<Arc<Client>>,
+    /// The ordered list of Fiber endpoints to connect to
+    endpoints: Vec<String>,
+    /// The index of the endpoint the client is connected to
+    active: usize,
+    /// The Fiber API key, which can be rotated at runtime through an [ApiKeyHandle]
+    api_key: Arc<watch::Sender<String>>,
     /// The type of stream to subscribe to
     ty: StreamType,
+    /// The reconnection policy used when the subscription ends
+    reconnect: ReconnectConfig,
+}
+
+/// A handle to rotate the API key of a running [FiberCollector] without restarting
+/// the engine.
+#[derive(Debug, Clone)]
+pub struct ApiKeyHandle {
+    sender: Arc<watch::Sender<String>>,
+}
+
+impl ApiKeyHandle {
+    /// Swap the API key. Active subscriptions are transparently re-established with
+    /// the new key, and every later reconnection uses it.
+    pub fn rotate(&self, api_key: impl Into<String>) {
+        self.sender.send_replace(api_key.into());
+    }
 }
 
 impl FiberCollector {
@@ -43,63 +209,536 @@ impl FiberCollector {
     /// ## Arguments
     /// - `api_key`: The Fiber API key to use
     /// - `ty`: The type of stream to subscribe to
-    pub async fn new(api_key: String, ty: StreamType) -> Self {
-        let client = Client::connect(FIBER_DEFAULT_URL, api_key.clone())
-            .await
-            .expect("failed to connect to Fiber");
+    ///
+    /// Returns a [FiberError] if the initial connection to Fiber fails.
+    pub async fn new(api_key: String, ty: StreamType) -> Result<Self, FiberError> {
+        Self::with_endpoints(api_key, vec![FIBER_DEFAULT_URL.to_string()], ty).await
+    }
 
-        Self {
-            client,
-            api_key,
+    /// Initialize a new Fiber collector with an ordered list of endpoints
+    /// (e.g. regional gateways).
+    ///
+    /// The collector connects to the first reachable endpoint. Whenever the active
+    /// connection drops, it transparently fails over to the next endpoint in the list,
+    /// wrapping around once the end of the list is reached.
+    ///
+    /// Returns a [FiberError] if none of the endpoints can be reached.
+    pub async fn with_endpoints(
+        api_key: String,
+        endpoints: Vec<String>,
+        ty: StreamType,
+    ) -> Result<Self, FiberError> {
+        if let StreamType::FilteredTransactions(filter) = &ty {
+            filter.validate()?;
+        }
+        let (active, client) = connect_any(&endpoints, 0, &api_key).await?;
+
+        Ok(Self {
+            client: Some(Arc::new(client)),
+            endpoints,
+            active,
+            api_key: Arc::new(watch::Sender::new(api_key)),
             ty,
+            reconnect: ReconnectConfig::default(),
+        })
+    }
+
+    /// Returns a [FiberCollectorBuilder] to configure a lazily-connected collector.
+    pub fn builder() -> FiberCollectorBuilder {
+        FiberCollectorBuilder::default()
+    }
+
+    /// Optionally set the Fiber endpoint, overriding the default (or the list of
+    /// endpoints the collector was created with).
+    ///
+    /// The current connection is kept if the new endpoint cannot be reached.
+    pub async fn set_fiber_endpoint(
+        &mut self,
+        endpoint: impl Into<String>,
+    ) -> Result<(), FiberError> {
+        let endpoint = endpoint.into();
+        let api_key = self.api_key.borrow().clone();
+        let client = connect(&endpoint, &api_key).await?;
+
+        self.client = Some(Arc::new(client));
+        self.endpoints = vec![endpoint];
+        self.active = 0;
+        Ok(())
+    }
+
+    /// Returns a handle that can be used to rotate the API key at runtime, even after
+    /// the collector has been moved into the engine.
+    pub fn api_key_handle(&self) -> ApiKeyHandle {
+        ApiKeyHandle {
+            sender: self.api_key.clone(),
         }
     }
 
-    /// Optionally set the Fiber endpoint, overriding the default
-    pub async fn set_fiber_endpoint(&mut self, endpoint: impl Into<String>) {
-        self.client = Client::connect(endpoint, self.api_key.clone())
-            .await
-            .expect("failed to connect to Fiber");
+    /// Swap the API key at runtime. See [ApiKeyHandle::rotate].
+    pub fn rotate_api_key(&self, api_key: impl Into<String>) {
+        self.api_key_handle().rotate(api_key);
+    }
+
+    /// Optionally set the reconnection policy, overriding the default
+    /// (exponential backoff from 100ms up to 30s, retrying forever).
+    pub fn set_reconnect_config(&mut self, reconnect: ReconnectConfig) {
+        self.reconnect = reconnect;
     }
 
     /// Get the event stream for the specified stream type.
-    pub async fn get_event_stream(&self) -> Result<CollectorStream<'_, Event>> {
-        match self.ty {
-            StreamType::Transactions => {
-                let stream = self.client.subscribe_new_transactions(None).await;
-                let stream = stream.map(|tx| Event::Transaction(tx.into_inner()));
-                Ok(Box::pin(stream))
+    ///
+    /// The subscription runs in a background task: whenever the underlying Fiber
+    /// stream ends, the task fails over to the next configured endpoint with
+    /// exponential backoff and resumes emitting events. The returned stream only
+    /// terminates once the configured maximum number of consecutive reconnection
+    /// attempts is exhausted.
+    pub async fn get_event_stream(&self) -> error::Result<CollectorStream<'_, Event>> {
+        let (tx, rx) = mpsc::channel(EVENT_BUFFER_SIZE);
+
+        let mut client = self.client.clone();
+        let endpoints = self.endpoints.clone();
+        let mut active = self.active;
+        let mut api_key = self.api_key.subscribe();
+        let ty = self.ty.clone();
+        let reconnect = self.reconnect.clone();
+
+        tokio::spawn(async move {
+            let mut attempt = 0;
+            // Lazily-connected collectors start from the first endpoint, and the ones
+            // that were already connected fail over to the next endpoint.
+            let mut next = active;
+
+            loop {
+                let current = match client.take() {
+                    Some(client) => client,
+                    None => {
+                        let key = api_key.borrow_and_update().clone();
+                        match connect_with_backoff(&endpoints, next, &key, &reconnect, &mut attempt)
+                            .await
+                        {
+                            Some((index, new_client)) => {
+                                if index != active {
+                                    warn!(
+                                        "Failed over from Fiber endpoint {} to {}",
+                                        endpoints[active], endpoints[index]
+                                    );
+                                }
+                                active = index;
+                                Arc::new(new_client)
+                            }
+                            None => {
+                                error!("Fiber reconnection attempts exhausted, terminating stream");
+                                return;
+                            }
+                        }
+                    }
+                };
+
+                match forward_events(&current, &ty, &tx, &mut api_key).await {
+                    Forwarded::ReceiverDropped => return,
+                    Forwarded::StreamEnded { received } => {
+                        if received {
+                            attempt = 0;
+                        }
+                        warn!(
+                            "Fiber {:?} stream from {} ended, reconnecting",
+                            ty, endpoints[active]
+                        );
+                        next = (active + 1) % endpoints.len();
+                    }
+                    Forwarded::ApiKeyRotated => {
+                        info!("Fiber API key rotated, re-authenticating");
+                        attempt = 0;
+                        next = active;
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+}
+
+/// Connects to one of the endpoints, starting at `start`, retrying with exponential
+/// backoff. The first attempt after a healthy stream (`attempt == 0`) is immediate.
+///
+/// Returns `None` once the maximum number of consecutive attempts is exhausted.
+async fn connect_with_backoff(
+    endpoints: &[String],
+    start: usize,
+    api_key: &str,
+    reconnect: &ReconnectConfig,
+    attempt: &mut u32,
+) -> Option<(usize, Client)> {
+    loop {
+        if reconnect.max_retries.is_some_and(|max| *attempt >= max) {
+            return None;
+        }
+
+        if *attempt > 0 {
+            tokio::time::sleep(reconnect.backoff(*attempt - 1)).await;
+        }
+        *attempt += 1;
+
+        match connect_any(endpoints, start, api_key).await {
+            Ok(connected) => return Some(connected),
+            Err(e) => warn!("Failed to connect (attempt {}): {}", attempt, e),
+        }
+    }
+}
+
+/// A builder for a lazily-connected [FiberCollector].
+///
+/// No connection is made until the engine requests the event stream, so the
+/// collector can be fully configured in synchronous wiring code.
+///
+/// ```ignore
+/// let collector = FiberCollector::builder()
+///     .api_key(api_key)
+///     .endpoint("fiber-eu.chainbound.io:8080")
+///     .endpoint("fiber-us.chainbound.io:8080")
+///     .stream_type(StreamType::Transactions)
+///     .tx_filter(TxFilter::new().to(router))
+///     .build()?;
+/// ```
+#[derive(Debug, Default)]
+pub struct FiberCollectorBuilder {
+    api_key: Option<String>,
+    endpoints: Vec<String>,
+    ty: Option<StreamType>,
+    filter: Option<TxFilter>,
+    reconnect: ReconnectConfig,
+}
+
+impl FiberCollectorBuilder {
+    /// Set the Fiber API key (required).
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Add a Fiber endpoint. Endpoints are tried in the order they are added.
+    /// Defaults to the public Fiber endpoint if none is set.
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoints.push(endpoint.into());
+        self
+    }
+
+    /// Set the type of stream to subscribe to. Defaults to [StreamType::Transactions].
+    pub fn stream_type(mut self, ty: StreamType) -> Self {
+        self.ty = Some(ty);
+        self
+    }
+
+    /// Set a filter for the transaction stream. This is only valid together with
+    /// [StreamType::Transactions] (the default stream type).
+    pub fn tx_filter(mut self, filter: TxFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Set the reconnection policy.
+    pub fn reconnect_config(mut self, reconnect: ReconnectConfig) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// Validate the configuration and build the collector, without connecting.
+    pub fn build(self) -> Result<FiberCollector, FiberError> {
+        let api_key = self.api_key.ok_or(FiberError::MissingApiKey)?;
+
+        let ty = match (self.ty.unwrap_or(StreamType::Transactions), self.filter) {
+            (ty, None) => ty,
+            (StreamType::Transactions, Some(filter)) => {
+                filter.validate()?;
+                StreamType::FilteredTransactions(filter)
             }
-            StreamType::ExecutionPayloads => {
-                let stream = self.client.subscribe_new_execution_payloads().await;
-                let stream = stream.map(Event::ExecutionPayload);
-                Ok(Box::pin(stream))
+            (ty, Some(_)) => {
+                return Err(FiberError::InvalidConfig(format!(
+                    "transaction filters cannot be applied to {:?} streams",
+                    ty
+                )))
+            }
+        };
+
+        let endpoints = if self.endpoints.is_empty() {
+            vec![FIBER_DEFAULT_URL.to_string()]
+        } else {
+            self.endpoints
+        };
+
+        Ok(FiberCollector {
+            client: None,
+            endpoints,
+            active: 0,
+            api_key: Arc::new(watch::Sender::new(api_key)),
+            ty,
+            reconnect: self.reconnect,
+        })
+    }
+}
+
+/// Outcome of forwarding a single Fiber subscription into the collector channel.
+enum Forwarded {
+    /// The Fiber stream ended. `received` is set if at least one event was forwarded.
+    StreamEnded { received: bool },
+    /// The collector stream was dropped, so there is no one left to forward to.
+    ReceiverDropped,
+    /// The API key was rotated, so the subscription must be re-established.
+    ApiKeyRotated,
+}
+
+/// Subscribes to the given stream type and forwards every event into `tx`
+/// until either side of the pipe is closed, or the API key is rotated.
+async fn forward_events(
+    client: &Client,
+    ty: &StreamType,
+    tx: &mpsc::Sender<Event>,
+    api_key: &mut watch::Receiver<String>,
+) -> Forwarded {
+    let mut stream: CollectorStream<'_, Event> = match ty {
+        StreamType::Transactions => {
+            let stream = client.subscribe_new_transactions(None).await;
+            Box::pin(stream.map(|tx| Event::Transaction(tx.into_inner())))
+        }
+        StreamType::FilteredTransactions(filter) => {
+            let stream = client.subscribe_new_transactions(filter.build()).await;
+            let filter = filter.clone();
+            Box::pin(stream.filter_map(move |tx| {
+                let tx = tx.into_inner();
+                future::ready(filter.matches(&tx).then_some(Event::Transaction(tx)))
+            }))
+        }
+        StreamType::ExecutionPayloads => {
+            let stream = client.subscribe_new_execution_payloads().await;
+            Box::pin(stream.map(Event::ExecutionPayload))
+        }
+        StreamType::Headers => {
+            let stream = client.subscribe_new_execution_headers().await;
+            Box::pin(stream.map(Event::Header))
+        }
+        StreamType::BeaconBlocks => {
+            let stream = client.subscribe_new_beacon_blocks().await;
+            Box::pin(stream.map(Event::BeaconBlock))
+        }
+        StreamType::BlobTransactions => {
+            let stream = client.subscribe_new_transactions(None).await;
+            Box::pin(stream.filter_map(|tx| {
+                let blob_tx = BlobTransaction::from_envelope(tx.into_inner());
+                future::ready(blob_tx.map(Event::BlobTransaction))
+            }))
+        }
+        StreamType::All => {
+            let txs = client.subscribe_new_transactions(None).await;
+            let txs = txs.map(|tx| Some(Event::Transaction(tx.into_inner())));
+            let payloads = client.subscribe_new_execution_payloads().await;
+            let payloads = payloads.map(|block| Some(Event::ExecutionPayload(block)));
+
+            // Terminate the merged stream as soon as either subscription ends, so that
+            // both are re-established together on reconnection.
+            let txs = txs.chain(stream::once(future::ready(None)));
+            let payloads = payloads.chain(stream::once(future::ready(None)));
+            let merged = stream::select(txs, payloads)
+                .take_while(|event| future::ready(event.is_some()))
+                .filter_map(future::ready);
+            Box::pin(merged)
+        }
+    };
+
+    let mut received = false;
+    loop {
+        tokio::select! {
+            event = stream.next() => {
+                let Some(event) = event else {
+                    return Forwarded::StreamEnded { received };
+                };
+                record_metrics(&event);
+                if tx.send(event).await.is_err() {
+                    return Forwarded::ReceiverDropped;
+                }
+                received = true;
+            }
+            changed = api_key.changed() => {
+                // The collector owning the key was dropped, so no one is listening anymore.
+                if changed.is_err() {
+                    return Forwarded::ReceiverDropped;
+                }
+                return Forwarded::ApiKeyRotated;
             }
         }
     }
 }
 
+impl Event {
+    /// Returns a short, static name for the event kind, used as a metric label.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Event::Transaction(_) => "transaction",
+            Event::ExecutionPayload(_) => "execution_payload",
+            Event::Header(_) => "header",
+            Event::BeaconBlock(_) => "beacon_block",
+            Event::BlobTransaction(_) => "blob_transaction",
+        }
+    }
+}
+
+/// Records per-event metrics through the [metrics] facade.
+///
+/// For block-carrying events, the slot delay is measured as the delta between the block
+/// timestamp, i.e. the start of its slot, and the local receipt time. It includes the
+/// time the proposer took to publish the block, so it is not a propagation latency.
+/// The transaction stream of Fiber carries no server-side seen-time, so pending
+/// transactions are only counted.
+fn record_metrics(event: &Event) {
+    let kind = event.kind();
+    counter!("fiber_events_received_total", "event" => kind).increment(1);
+
+    let block_timestamp = match event {
+        Event::ExecutionPayload(block) => block.header.timestamp,
+        Event::Header(header) => header.timestamp,
+        _ => return,
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let delay = now.saturating_sub(Duration::from_secs(block_timestamp));
+    histogram!("fiber_block_slot_delay_seconds", "event" => kind).record(delay.as_secs_f64());
+}
+
 #[async_trait]
 impl Collector<Event> for FiberCollector {
-    async fn get_event_stream<'a>(&'a self) -> Result<CollectorStream<'a, Event>> {
+    async fn get_event_stream<'a>(&'a self) -> error::Result<CollectorStream<'a, Event>> {
         self.get_event_stream().await
     }
 }
 
+/// A Fiber executor that submits signed transactions through the Fiber network.
+///
+/// This lets a strategy receive transactions from the [FiberCollector] and submit
+/// its own through the same low-latency network.
+pub struct FiberExecutor {
+    /// The Fiber-rs client
+    client: Client,
+}
+
+impl FiberExecutor {
+    /// Initialize a new Fiber executor connected to the default Fiber endpoint.
+    ///
+    /// ## Arguments
+    /// - `api_key`: The Fiber API key to use
+    pub async fn new(api_key: impl Into<String>) -> Result<Self, FiberError> {
+        Self::with_endpoint(FIBER_DEFAULT_URL, api_key).await
+    }
+
+    /// Initialize a new Fiber executor connected to the given endpoint.
+    pub async fn with_endpoint(
+        endpoint: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Result<Self, FiberError> {
+        let client = connect(&endpoint.into(), &api_key.into()).await?;
+        Ok(Self { client })
+    }
+
+    /// Send a signed transaction through Fiber.
+    pub async fn send_transaction(&self, tx: TxEnvelope) -> Result<()> {
+        let (hash, timestamp) = self
+            .client
+            .send_transaction(tx)
+            .await
+            .map_err(|e| anyhow!("Error sending transaction to Fiber: {}", e))?;
+        debug!("Fiber transaction {} sent at {}", hash, timestamp);
+        Ok(())
+    }
+
+    /// Send a signed, RLP-encoded transaction through Fiber.
+    pub async fn send_raw_transaction(&self, raw_tx: Bytes) -> Result<()> {
+        let (hash, timestamp) = self
+            .client
+            .send_raw_transaction(raw_tx.to_vec())
+            .await
+            .map_err(|e| anyhow!("Error sending raw transaction to Fiber: {}", e))?;
+        debug!("Fiber raw transaction {} sent at {}", hash, timestamp);
+        Ok(())
+    }
+
+    /// Send a sequence of signed, RLP-encoded transactions through Fiber.
+    ///
+    /// The transactions are propagated atomically and in order, which makes this
+    /// suitable for backruns: the target transaction followed by the backrun.
+    pub async fn send_raw_transaction_sequence(&self, raw_txs: Vec<Bytes>) -> Result<()> {
+        if raw_txs.is_empty() {
+            return Err(anyhow!(
+                "Transaction sequence must contain at least one transaction"
+            ));
+        }
+
+        let raw_txs = raw_txs.into_iter().map(|tx| tx.to_vec()).collect();
+        let (hashes, timestamp) = self
+            .client
+            .send_raw_transaction_sequence(raw_txs)
+            .await
+            .map_err(|e| anyhow!("Error sending transaction sequence to Fiber: {}", e))?;
+        debug!(
+            "Fiber transaction sequence {:?} sent at {}",
+            hashes, timestamp
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Executor<Action> for FiberExecutor {
+    /// Submit a transaction through Fiber.
+    async fn execute(&self, action: Action) -> error::Result<()> {
+        let sent = match action {
+            Action::SendTransaction(tx) => self.send_transaction(tx).await,
+            Action::SendRawTransaction(raw_tx) => self.send_raw_transaction(raw_tx).await,
+            Action::SendRawTransactionSequence(raw_txs) => {
+                self.send_raw_transaction_sequence(raw_txs).await
+            }
+            Action::SendBundle(_) => {
+                return Err(ArtemisError::fatal(anyhow!(
+                    "Bundles cannot be sent through Fiber, use the Echo executor instead"
+                )))
+            }
+        };
+        Ok(sent?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use anyhow::Result;
     use artemis_core::engine::Engine;
     use ethers::types::Action;
 
     use crate::Event;
     use crate::FiberCollector;
+    use crate::ReconnectConfig;
     use crate::StreamType;
 
+    #[test]
+    fn test_reconnect_backoff_is_capped() {
+        let config = ReconnectConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            max_retries: None,
+        };
+
+        assert_eq!(config.backoff(0), Duration::from_millis(100));
+        assert_eq!(config.backoff(1), Duration::from_millis(200));
+        assert_eq!(config.backoff(3), Duration::from_millis(800));
+        assert_eq!(config.backoff(4), Duration::from_secs(1));
+        assert_eq!(config.backoff(64), Duration::from_secs(1));
+    }
+
     #[tokio::test]
     async fn test_fiber_collector_txs() -> Result<()> {
         if let Ok(api_key) = std::env::var("FIBER_TEST_KEY") {
-            let fiber_collector = FiberCollector::new(api_key, StreamType::Transactions).await;
+            let fiber_collector = FiberCollector::new(api_key, StreamType::Transactions).await?;
 
             let mut engine: Engine<Event, Action> = Engine::default();
             engine.add_collector(Box::new(fiber_collector));
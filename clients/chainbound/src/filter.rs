@@ -0,0 +1,220 @@
+use alloy::primitives::{Address, FixedBytes, U256};
+use alloy_consensus::{Transaction, TxEnvelope};
+use fiber::filter::{Filter, FilterBuilder};
+
+use crate::fiber::FiberError;
+
+/// A filter for the Fiber transaction stream.
+///
+/// Address and method selector criteria are evaluated server-side by Fiber, which
+/// reduces bandwidth and deserialization cost for high-volume subscriptions.
+/// Criteria of the same kind are combined with OR (e.g. "to A or to B"), while
+/// different kinds are combined with AND (e.g. "to A and calling `transfer`").
+///
+/// Value ranges are not supported by the Fiber filter language, so they are
+/// applied locally on every received transaction.
+#[derive(Debug, Clone, Default)]
+pub struct TxFilter {
+    to: Vec<Address>,
+    from: Vec<Address>,
+    method_ids: Vec<FixedBytes<4>>,
+    min_value: Option<U256>,
+    max_value: Option<U256>,
+}
+
+impl TxFilter {
+    /// Create a new filter that matches every transaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match transactions sent to the given address.
+    pub fn to(mut self, address: Address) -> Self {
+        self.to.push(address);
+        self
+    }
+
+    /// Only match transactions sent from the given address.
+    pub fn from(mut self, address: Address) -> Self {
+        self.from.push(address);
+        self
+    }
+
+    /// Only match transactions calling the given 4-byte method selector.
+    pub fn method_id(mut self, selector: impl Into<FixedBytes<4>>) -> Self {
+        self.method_ids.push(selector.into());
+        self
+    }
+
+    /// Only match transactions transferring at least `value` wei.
+    pub fn min_value(mut self, value: U256) -> Self {
+        self.min_value = Some(value);
+        self
+    }
+
+    /// Only match transactions transferring at most `value` wei.
+    pub fn max_value(mut self, value: U256) -> Self {
+        self.max_value = Some(value);
+        self
+    }
+
+    /// Returns an error if the filter can never match, i.e. if its minimum value
+    /// is above its maximum value.
+    pub(crate) fn validate(&self) -> Result<(), FiberError> {
+        match (self.min_value, self.max_value) {
+            (Some(min), Some(max)) if min > max => Err(FiberError::InvalidConfig(format!(
+                "transaction filter minimum value {} is above its maximum value {}",
+                min, max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the server-side criteria of the filter, as groups that are ANDed
+    /// together, each matching any of its criteria. Empty groups are left out.
+    pub(crate) fn groups(&self) -> Vec<Vec<Criterion>> {
+        let to = self.to.iter().copied().map(Criterion::To).collect();
+        let from = self.from.iter().copied().map(Criterion::From).collect();
+        let method_ids = self
+            .method_ids
+            .iter()
+            .copied()
+            .map(Criterion::MethodId)
+            .collect();
+        [to, from, method_ids]
+            .into_iter()
+            .filter(|group: &Vec<Criterion>| !group.is_empty())
+            .collect()
+    }
+
+    /// Build the server-side Fiber filter, if any server-side criteria are set.
+    pub(crate) fn build(&self) -> Option<Filter> {
+        let groups = self.groups();
+        if groups.is_empty() {
+            return None;
+        }
+        let builder = groups
+            .iter()
+            .fold(FilterBuilder::new().and(), |builder, group| {
+                push_group(builder, group)
+            });
+        Some(builder.build())
+    }
+
+    /// Returns true if the transaction satisfies the locally-applied criteria.
+    pub(crate) fn matches(&self, tx: &TxEnvelope) -> bool {
+        let value = tx.value();
+        self.min_value.is_none_or(|min| value >= min)
+            && self.max_value.is_none_or(|max| value <= max)
+    }
+}
+
+/// A single server-side criterion of a [TxFilter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Criterion {
+    To(Address),
+    From(Address),
+    MethodId(FixedBytes<4>),
+}
+
+impl Criterion {
+    fn push(&self, builder: FilterBuilder) -> FilterBuilder {
+        match self {
+            Criterion::To(address) => builder.to(&address.to_string()),
+            Criterion::From(address) => builder.from(&address.to_string()),
+            Criterion::MethodId(selector) => builder.method_id(&selector.to_string()),
+        }
+    }
+}
+
+/// Adds a group of criteria to the builder, nesting them under an OR level when
+/// there is more than one. The level is exited afterwards, so the next group is
+/// ANDed with this one rather than nested inside it.
+fn push_group(builder: FilterBuilder, group: &[Criterion]) -> FilterBuilder {
+    match group {
+        [criterion] => criterion.push(builder),
+        _ => group
+            .iter()
+            .fold(builder.or(), |builder, criterion| criterion.push(builder))
+            .exit(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, Bytes, Signature, TxKind};
+    use alloy_consensus::{Signed, TxLegacy};
+
+    use super::*;
+
+    fn tx(value: u64) -> TxEnvelope {
+        let tx = TxLegacy {
+            to: TxKind::Call(Address::ZERO),
+            value: U256::from(value),
+            input: Bytes::new(),
+            ..Default::default()
+        };
+        let signature = Signature::new(U256::from(1), U256::from(1), false);
+        TxEnvelope::Legacy(Signed::new_unchecked(tx, signature, Default::default()))
+    }
+
+    #[test]
+    fn test_build_ands_groups() {
+        let (a, b) = (
+            address!("0000000000000000000000000000000000000001"),
+            address!("0000000000000000000000000000000000000002"),
+        );
+        let selector = FixedBytes::new([0xa9, 0x05, 0x9c, 0xbb]);
+        let filter = TxFilter::new().to(a).to(b).from(a).method_id(selector);
+        assert_eq!(
+            filter.groups(),
+            vec![
+                vec![Criterion::To(a), Criterion::To(b)],
+                vec![Criterion::From(a)],
+                vec![Criterion::MethodId(selector)],
+            ]
+        );
+        assert!(filter.build().is_some());
+
+        // Criteria of the same kind stay in one group, whatever the call order.
+        let filter = TxFilter::new().from(b).to(a).from(a);
+        assert_eq!(
+            filter.groups(),
+            vec![
+                vec![Criterion::To(a)],
+                vec![Criterion::From(b), Criterion::From(a)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_without_server_side_criteria() {
+        let filter = TxFilter::new().min_value(U256::from(1));
+        assert!(filter.groups().is_empty());
+        assert!(filter.build().is_none());
+    }
+
+    #[test]
+    fn test_matches_value_bounds() {
+        let filter = TxFilter::new()
+            .min_value(U256::from(10))
+            .max_value(U256::from(20));
+        assert!(!filter.matches(&tx(9)));
+        assert!(filter.matches(&tx(10)));
+        assert!(filter.matches(&tx(20)));
+        assert!(!filter.matches(&tx(21)));
+        assert!(TxFilter::new().matches(&tx(0)));
+    }
+
+    #[test]
+    fn test_validate_rejects_inverted_bounds() {
+        let filter = TxFilter::new()
+            .min_value(U256::from(20))
+            .max_value(U256::from(10));
+        assert!(filter.validate().is_err());
+        let filter = TxFilter::new()
+            .min_value(U256::from(10))
+            .max_value(U256::from(10));
+        assert!(filter.validate().is_ok());
+    }
+}
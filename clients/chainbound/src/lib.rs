@@ -11,16 +11,24 @@
 //! It is built directly into the [Artemis][artemis] framework for seamless integration with your existing
 //! trading strategies.
 //!
-//! This crate offers two main components, which are implemented following the standard Artemis traits:
+//! This crate offers three main components, which are implemented following the standard Artemis traits:
 //!
 //! - Fiber Collector: a low-latency, reliable `mempool` and `new_blocks` stream for Ethereum.
 //! - Echo Executor: a feature-rich RPC endpoint to propagate your MEV bundles to block builders.
+//! - Fiber Executor: low-latency submission of signed transactions through the Fiber network.
 //!
 //! Please refer to the crate README file for an example on how to use these components.
 
 /// Fiber Network client module
 pub mod fiber;
-pub use fiber::{Event, FiberCollector, StreamType};
+pub use fiber::{
+    ApiKeyHandle, BlobTransaction, Event, FiberCollector, FiberCollectorBuilder, FiberError,
+    FiberExecutor, ReconnectConfig, StreamType,
+};
+
+/// Fiber transaction filter module
+pub mod filter;
+pub use filter::TxFilter;
 
 /// Echo RPC client module
 pub mod echo;
@@ -51,7 +59,7 @@ mod tests {
             // ==== Open a Fiber transaction stream, and verify that we receive transactions ====
 
             let ty = StreamType::Transactions;
-            let fiber_tx_collector = FiberCollector::new(api_key.clone(), ty).await;
+            let fiber_tx_collector = FiberCollector::new(api_key.clone(), ty).await.unwrap();
             let fiber_tx_stream = fiber_tx_collector.get_event_stream().await.unwrap();
             let fiber_tx = fiber_tx_stream.into_future().await.0.unwrap();
             assert!(matches!(fiber_tx, Event::Transaction(_)));
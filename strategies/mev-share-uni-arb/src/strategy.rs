@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use anyhow::Result;
+use artemis_core::error::{Result, ResultExt};
 use artemis_core::types::Strategy;
 
 use ethers::signers::Signer;
@@ -65,11 +65,11 @@ impl<M: Middleware + 'static, S: Signer + 'static> Strategy<Event, Action>
         // Read pool information from csv file.
         let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         path.push("resources/v3_v2_pools.csv");
-        let mut reader = csv::Reader::from_path(path)?;
+        let mut reader = csv::Reader::from_path(path).fatal()?;
 
         for record in reader.deserialize() {
             // Parse records into PoolRecord struct.
-            let record: V2V3PoolRecord = record?;
+            let record: V2V3PoolRecord = record.fatal()?;
             self.pool_map.insert(
                 record.v3_pool,
                 V2PoolInfo {
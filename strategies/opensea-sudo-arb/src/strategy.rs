@@ -14,6 +14,7 @@ use crate::types::Config;
 use anyhow::Result;
 use artemis_core::collectors::block_collector::NewBlock;
 use artemis_core::collectors::opensea_order_collector::OpenseaOrder;
+use artemis_core::error::{self, ResultExt};
 use artemis_core::executors::mempool_executor::{GasBidInfo, SubmitTxToMempool};
 use artemis_core::types::Strategy;
 use artemis_core::utilities::state_override_middleware::StateOverrideMiddleware;
@@ -81,11 +82,11 @@ impl<M: Middleware + 'static> OpenseaSudoArb<M> {
 #[async_trait]
 impl<M: Middleware + 'static> Strategy<Event, Action> for OpenseaSudoArb<M> {
     // In order to sync this strategy, we need to get the current bid for all Sudo pools.
-    async fn sync_state(&mut self) -> Result<()> {
+    async fn sync_state(&mut self) -> error::Result<()> {
         // Block in which the pool factory was deployed.
         let start_block = FACTORY_DEPLOYMENT_BLOCK;
 
-        let current_block = self.client.get_block_number().await?.as_u64();
+        let current_block = self.client.get_block_number().await.retryable()?.as_u64();
 
         // Get all Sudo pool addresses deployed in the block range.
         let pool_addresses = self.get_new_pools(start_block, current_block).await?;